@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use topk_rs::{
+    proto::v1::control::{Collection, FieldSpec},
+    Client, Error,
+};
+
+use crate::dataset_region_cache::{collection_region_cache_path, DatasetRegionCache};
+
+#[async_trait(?Send)]
+pub trait CollectionsClient {
+    async fn list(&mut self) -> Result<Vec<Collection>, Error>;
+    async fn get(&mut self, name: &str) -> Result<Collection, Error>;
+    async fn create(
+        &mut self,
+        name: &str,
+        schema: HashMap<String, FieldSpec>,
+        region: &str,
+    ) -> Result<Collection, Error>;
+    async fn delete(&mut self, name: &str) -> Result<(), Error>;
+}
+
+#[async_trait(?Send)]
+pub trait CollectionRegionResolver {
+    async fn get_region(&mut self, name: &str) -> Result<String, Error>;
+}
+
+struct RealCollectionsClient {
+    client: Client,
+}
+
+impl RealCollectionsClient {
+    fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait(?Send)]
+impl CollectionsClient for RealCollectionsClient {
+    async fn list(&mut self) -> Result<Vec<Collection>, Error> {
+        Ok(self.client.collections().list().await?)
+    }
+
+    async fn get(&mut self, name: &str) -> Result<Collection, Error> {
+        Ok(self.client.collections().get(name).await?)
+    }
+
+    async fn create(
+        &mut self,
+        name: &str,
+        schema: HashMap<String, FieldSpec>,
+        region: &str,
+    ) -> Result<Collection, Error> {
+        Ok(self
+            .client
+            .collections()
+            .create(name, schema, Some(region.to_string()))
+            .await?)
+    }
+
+    async fn delete(&mut self, name: &str) -> Result<(), Error> {
+        self.client.collections().delete(name).await?;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl CollectionRegionResolver for RealCollectionsClient {
+    async fn get_region(&mut self, name: &str) -> Result<String, Error> {
+        Ok(self.get(name).await?.region)
+    }
+}
+
+struct CachedCollectionsClient<B> {
+    client: B,
+    cache: DatasetRegionCache,
+}
+
+impl<B> CachedCollectionsClient<B> {
+    fn new(client: B, cache: DatasetRegionCache) -> Self {
+        Self { client, cache }
+    }
+
+    fn persist(&mut self) {
+        if let Err(err) = self.cache.save() {
+            eprintln!("warning: failed to persist collection index: {err}");
+        }
+    }
+
+    fn cache_collection_region(&mut self, name: &str, region: &str) {
+        self.cache.insert(name, region);
+        self.persist();
+    }
+}
+
+#[async_trait(?Send)]
+impl<B> CollectionsClient for CachedCollectionsClient<B>
+where
+    B: CollectionsClient,
+{
+    async fn list(&mut self) -> Result<Vec<Collection>, Error> {
+        let collections = self.client.list().await?;
+        self.cache.set_all(
+            collections
+                .iter()
+                .map(|collection| (collection.name.clone(), collection.region.clone())),
+        );
+        self.persist();
+        Ok(collections)
+    }
+
+    async fn get(&mut self, name: &str) -> Result<Collection, Error> {
+        let collection = self.client.get(name).await?;
+        self.cache_collection_region(&collection.name, &collection.region);
+        Ok(collection)
+    }
+
+    async fn create(
+        &mut self,
+        name: &str,
+        schema: HashMap<String, FieldSpec>,
+        region: &str,
+    ) -> Result<Collection, Error> {
+        let collection = self.client.create(name, schema, region).await?;
+        self.cache_collection_region(&collection.name, &collection.region);
+        Ok(collection)
+    }
+
+    async fn delete(&mut self, name: &str) -> Result<(), Error> {
+        self.client.delete(name).await?;
+        self.cache.remove(name);
+        self.persist();
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl<B> CollectionRegionResolver for CachedCollectionsClient<B>
+where
+    B: CollectionsClient + CollectionRegionResolver,
+{
+    async fn get_region(&mut self, name: &str) -> Result<String, Error> {
+        if let Some(region) = self.cache.get(name) {
+            return Ok(region.to_string());
+        }
+
+        let region = self.client.get_region(name).await?;
+        self.cache_collection_region(name, &region);
+        Ok(region)
+    }
+}
+
+pub fn make_cached_collections_client(
+    client: Client,
+    profile: &str,
+) -> impl CollectionsClient + CollectionRegionResolver {
+    CachedCollectionsClient::new(
+        RealCollectionsClient::new(client),
+        DatasetRegionCache::new(collection_region_cache_path(profile)),
+    )
+}
+
+pub async fn get_region<C: CollectionRegionResolver + ?Sized>(
+    client: &mut C,
+    name: &str,
+) -> Result<String, Error> {
+    client.get_region(name).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Default)]
+    struct FakeCollectionsClient {
+        collections: BTreeMap<String, Collection>,
+        get_calls: usize,
+        delete_calls: usize,
+    }
+
+    impl FakeCollectionsClient {
+        fn with_collection(collection: Collection) -> Self {
+            let mut remote = Self::default();
+            remote
+                .collections
+                .insert(collection.name.clone(), collection);
+            remote
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl CollectionsClient for FakeCollectionsClient {
+        async fn list(&mut self) -> Result<Vec<Collection>, Error> {
+            Ok(self.collections.values().cloned().collect())
+        }
+
+        async fn get(&mut self, name: &str) -> Result<Collection, Error> {
+            self.get_calls += 1;
+            self.collections
+                .get(name)
+                .cloned()
+                .ok_or(Error::CollectionNotFound)
+        }
+
+        async fn create(
+            &mut self,
+            name: &str,
+            schema: HashMap<String, FieldSpec>,
+            region: &str,
+        ) -> Result<Collection, Error> {
+            let mut collection = collection(name, region);
+            collection.schema = schema;
+            self.collections
+                .insert(name.to_string(), collection.clone());
+            Ok(collection)
+        }
+
+        async fn delete(&mut self, name: &str) -> Result<(), Error> {
+            self.delete_calls += 1;
+            self.collections
+                .remove(name)
+                .ok_or(Error::CollectionNotFound)?;
+            Ok(())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl CollectionRegionResolver for FakeCollectionsClient {
+        async fn get_region(&mut self, name: &str) -> Result<String, Error> {
+            Ok(self.get(name).await?.region)
+        }
+    }
+
+    fn collection(name: &str, region: &str) -> Collection {
+        Collection {
+            name: name.to_string(),
+            org_id: "org".to_string(),
+            project_id: "project".to_string(),
+            schema: HashMap::new(),
+            region: region.to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn indexed(name: &str, region: &str) -> DatasetRegionCache {
+        let mut cache = DatasetRegionCache::default();
+        cache.insert(name, region);
+        cache
+    }
+
+    #[tokio::test]
+    async fn list_repopulates_index_from_remote() {
+        let backend = FakeCollectionsClient::with_collection(collection("books", "us-east-1"));
+        let mut client = CachedCollectionsClient::new(backend, indexed("stale", "eu-west-1"));
+
+        assert_eq!(client.list().await.unwrap().len(), 1);
+        assert_eq!(client.cache.get("books"), Some("us-east-1"));
+        assert_eq!(client.cache.get("stale"), None);
+    }
+
+    #[tokio::test]
+    async fn get_region_uses_cache_before_remote() {
+        let backend = FakeCollectionsClient::with_collection(collection("books", "eu-west-1"));
+        let mut client = CachedCollectionsClient::new(backend, indexed("books", "us-east-1"));
+
+        assert_eq!(get_region(&mut client, "books").await.unwrap(), "us-east-1");
+        assert_eq!(client.client.get_calls, 0);
+    }
+
+    #[tokio::test]
+    async fn get_region_fetches_remote_and_updates_cache_on_miss() {
+        let backend = FakeCollectionsClient::with_collection(collection("books", "us-east-1"));
+        let mut client = CachedCollectionsClient::new(backend, DatasetRegionCache::default());
+
+        assert_eq!(get_region(&mut client, "books").await.unwrap(), "us-east-1");
+        assert_eq!(client.client.get_calls, 1);
+        assert_eq!(client.cache.get("books"), Some("us-east-1"));
+    }
+
+    #[tokio::test]
+    async fn delete_removes_collection_from_index_after_remote_success() {
+        let mut client = CachedCollectionsClient::new(
+            FakeCollectionsClient::with_collection(collection("books", "us-east-1")),
+            indexed("books", "us-east-1"),
+        );
+
+        client.delete("books").await.unwrap();
+
+        assert_eq!(client.client.delete_calls, 1);
+        assert_eq!(client.cache.get("books"), None);
+    }
+
+    #[tokio::test]
+    async fn get_region_returns_new_region_after_delete_and_recreate_with_same_name() {
+        let backend = FakeCollectionsClient::with_collection(collection("books", "us-east-1"));
+        let mut client = CachedCollectionsClient::new(backend, DatasetRegionCache::default());
+
+        assert_eq!(get_region(&mut client, "books").await.unwrap(), "us-east-1");
+
+        client.delete("books").await.unwrap();
+        client
+            .create("books", HashMap::new(), "sunflower")
+            .await
+            .unwrap();
+
+        assert_eq!(get_region(&mut client, "books").await.unwrap(), "sunflower");
+    }
+}
@@ -1,25 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::io::{IsTerminal, Read};
 use std::path::PathBuf;
 
+use clap::ValueEnum;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use tokio_stream::StreamExt;
 use topk_rs::{
     proto::v1::ctx::{
         ask_result::{self, Answer},
-        Fact,
+        Fact, Source,
     },
+    proto::v1::data::LogicalExpr,
     Client, Error,
 };
 
-use crate::util::read_query_from_stdin;
+use crate::client::make_client;
+use crate::datasets::{ensure_unique_region, DatasetRegionResolver, DatasetsClient};
+use crate::output::{is_broken_pipe, OutputFormat};
+use crate::util::repl::{Lines, Repl, Script};
+use crate::util::{filter, plural, read_query_from_stdin};
 use crate::{
-    commands::search::{render_search_result, SearchResult},
+    commands::search::{render_search_result, save_search_results, Content, SearchResult},
     output::Output,
 };
 
-#[derive(Debug, Clone, clap::ValueEnum)]
+#[derive(Debug, Clone, PartialEq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Mode {
     Auto,
     Summarize,
@@ -47,7 +55,7 @@ pub struct AskResult {
 }
 
 impl AskResult {
-    fn from_answer(a: Answer, show_refs: bool) -> Self {
+    pub(crate) fn from_answer(a: Answer, show_refs: bool) -> Self {
         Self {
             facts: a.facts,
             refs: a.refs.into_iter().map(|(k, v)| (k, v.into())).collect(),
@@ -114,6 +122,12 @@ pub struct AskArgs {
     /// Dataset to search (repeatable)
     #[arg(short = 'd', long = "dataset")]
     pub datasets: Vec<String>,
+    /// Only search documents matching a SQL condition (e.g. "year >= 2023 and team = 'ml'")
+    #[arg(long, value_name = "FILTER", value_parser = filter::parse_filter)]
+    pub filter: Option<LogicalExpr>,
+    /// Filter of a single dataset, as DATASET=FILTER (repeatable)
+    #[arg(long, value_name = "DATASET=FILTER", value_parser = filter::parse_dataset_filter)]
+    pub dataset_filter: Vec<(String, LogicalExpr)>,
     /// Query mode
     #[arg(short = 'm', long)]
     pub mode: Option<Mode>,
@@ -126,6 +140,9 @@ pub struct AskArgs {
     /// Save search result content (images, text chunks) to a directory
     #[arg(long, value_name = "DIR")]
     pub output_dir: Option<PathBuf>,
+    /// Ask follow-up questions in an interactive session
+    #[arg(short = 'i', long)]
+    pub interactive: bool,
 }
 
 /// `topk ask`
@@ -135,15 +152,41 @@ pub async fn run(client: &Client, args: &AskArgs, output: &Output) -> Result<Ask
         None => read_query_from_stdin()?,
     };
 
+    let sources = filter::sources(&args.datasets, &args.dataset_filter)?;
+
+    let answer = answer(
+        client,
+        query,
+        sources,
+        args.filter.clone(),
+        args.mode.clone(),
+        args.fields.clone(),
+        output,
+    )
+    .await?;
+
+    Ok(AskResult::from_answer(answer, args.show_refs))
+}
+
+/// Answer to `query`, printing progress updates while it is prepared.
+async fn answer(
+    client: &Client,
+    query: String,
+    sources: Vec<Source>,
+    filter: Option<LogicalExpr>,
+    mode: Option<Mode>,
+    fields: Option<Vec<String>>,
+    output: &Output,
+) -> Result<Answer, Error> {
     let spinner = output.spinner("Answering...");
 
     let mut stream = client
         .ask(
             query,
-            args.datasets.clone(),
-            None,
-            args.mode.clone().map(Into::into),
-            args.fields.clone(),
+            sources,
+            filter,
+            mode.map(Into::into),
+            fields,
             Some(true),
         )
         .await?;
@@ -164,14 +207,485 @@ pub async fn run(client: &Client, args: &AskArgs, output: &Output) -> Result<Ask
 
     spinner.finish();
 
-    let answer = answer.ok_or_else(|| Error::Internal("No answer found".to_string()))?;
+    answer.ok_or_else(|| Error::Internal("No answer found".to_string()))
+}
 
-    Ok(AskResult::from_answer(answer, args.show_refs))
+const INTERACTIVE_HELP: &str = ":mode auto|summarize|research  set the query mode
+:dataset                       list the datasets asked
+:dataset +NAME | -NAME         add or remove a dataset
+:open N                        save and show the content of reference N
+:export FILE                   export the transcript as Markdown, or JSON for *.json files
+:new                           ask the next question without the previous ones as context
+:help                          show this help
+:quit                          quit
+
+Other lines are questions. Questions follow up on the previous answers of the session.";
+
+/// Previous turns sent as context of a follow-up question.
+const FOLLOW_UP_TURNS: usize = 3;
+
+/// A question of an interactive session, with its answer.
+///
+/// References are numbered across the session, so that `:open N` and exported citations are
+/// unambiguous.
+#[derive(Serialize, Deserialize)]
+pub struct Turn {
+    pub query: String,
+    pub datasets: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<Mode>,
+    pub facts: Vec<Fact>,
+    pub refs: BTreeMap<u32, SearchResult>,
+    pub confidence: f32,
+}
+
+impl Turn {
+    fn answer(&self) -> String {
+        self.facts
+            .iter()
+            .map(|fact| fact.fact.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn to_result(&self, show_refs: bool) -> AskResult {
+        AskResult {
+            facts: self.facts.clone(),
+            refs: self
+                .refs
+                .iter()
+                .map(|(n, result)| (n.to_string(), result.clone()))
+                .collect(),
+            confidence: self.confidence,
+            show_refs,
+        }
+    }
+}
+
+/// Questions and answers of an interactive session, exported with `:export`.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Transcript {
+    pub turns: Vec<Turn>,
+}
+
+impl Transcript {
+    /// Adds the answer to `query`, renumbering its references after those of earlier turns.
+    fn push(
+        &mut self,
+        query: String,
+        datasets: Vec<String>,
+        mode: Option<Mode>,
+        result: AskResult,
+    ) -> &Turn {
+        let mut next = self.turns.iter().map(|t| t.refs.len() as u32).sum::<u32>() + 1;
+
+        let mut ref_ids = result.refs.keys().cloned().collect::<Vec<_>>();
+        ref_ids.sort_by_key(|id| (id.parse::<u64>().unwrap_or(u64::MAX), id.clone()));
+        let mut numbers = HashMap::new();
+        let mut refs = BTreeMap::new();
+        let mut results = result.refs;
+        for id in ref_ids {
+            if let Some(result) = results.remove(&id) {
+                numbers.insert(id, next);
+                refs.insert(next, result);
+                next += 1;
+            }
+        }
+
+        let facts = result
+            .facts
+            .into_iter()
+            .map(|fact| Fact {
+                ref_ids: fact
+                    .ref_ids
+                    .iter()
+                    .filter_map(|id| numbers.get(id).map(u32::to_string))
+                    .collect(),
+                ..fact
+            })
+            .collect();
+
+        self.turns.push(Turn {
+            query,
+            datasets,
+            mode,
+            facts,
+            refs,
+            confidence: result.confidence,
+        });
+        self.turns.last().unwrap()
+    }
+
+    fn reference(&self, n: u32) -> Option<&SearchResult> {
+        self.turns.iter().find_map(|turn| turn.refs.get(&n))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# topk ask\n");
+        for turn in &self.turns {
+            md.push_str(&format!("\n## {}\n\n", turn.query));
+
+            if turn.facts.is_empty() {
+                md.push_str("No answer found.\n");
+                continue;
+            }
+            let answer = turn
+                .facts
+                .iter()
+                .map(|fact| {
+                    let refs = fact
+                        .ref_ids
+                        .iter()
+                        .map(|id| format!(" [{id}]"))
+                        .collect::<String>();
+                    format!("{}{refs}", fact.fact)
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            md.push_str(&answer);
+            md.push('\n');
+
+            if !turn.refs.is_empty() {
+                md.push_str("\n**References**\n\n");
+            }
+            for (n, result) in &turn.refs {
+                md.push_str(&format!(
+                    "{n}. {} ({}, `{}`)\n",
+                    result.doc_name, result.dataset, result.doc_id
+                ));
+                if let Some(Content::Chunk { text, .. }) = &result.content {
+                    let excerpt = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    md.push_str(&format!("   > {}\n", truncate(&excerpt, 200)));
+                }
+            }
+        }
+        md
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+/// `query` with the last of the `previous` turns, for the question to follow up on them.
+fn follow_up(previous: &[Turn], query: &str) -> String {
+    let context = &previous[previous.len().saturating_sub(FOLLOW_UP_TURNS)..];
+    if context.is_empty() {
+        return query.to_string();
+    }
+
+    let mut text = String::from("Previous questions and answers:\n");
+    for turn in context {
+        text.push_str(&format!("Q: {}\nA: {}\n", turn.query, turn.answer()));
+    }
+    text.push_str(&format!("\nFollow-up question: {query}"));
+    text
+}
+
+/// A `:` command of an interactive session.
+#[derive(Debug, PartialEq)]
+enum SessionCommand {
+    Help,
+    Quit,
+    New,
+    Mode(Mode),
+    Datasets,
+    AddDataset(String),
+    RemoveDataset(String),
+    Open(u32),
+    Export(PathBuf),
+}
+
+fn parse_command(line: &str) -> Result<SessionCommand, String> {
+    let line = line.trim().trim_start_matches(':');
+    let (name, arg) = match line.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (line, ""),
+    };
+
+    let command = match (name, arg) {
+        ("help" | "h" | "?", "") => SessionCommand::Help,
+        ("quit" | "q" | "exit", "") => SessionCommand::Quit,
+        ("new", "") => SessionCommand::New,
+        ("mode", mode) => SessionCommand::Mode(Mode::from_str(mode, true).map_err(|_| {
+            format!("invalid mode '{mode}', use one of: auto, summarize, research")
+        })?),
+        ("dataset" | "datasets", "") => SessionCommand::Datasets,
+        ("dataset" | "datasets", arg) => match arg.split_at(1) {
+            ("+", name) if !name.trim().is_empty() => {
+                SessionCommand::AddDataset(name.trim().to_string())
+            }
+            ("-", name) if !name.trim().is_empty() => {
+                SessionCommand::RemoveDataset(name.trim().to_string())
+            }
+            _ => return Err(format!("expected +NAME or -NAME, got '{arg}'")),
+        },
+        ("open", n) => SessionCommand::Open(
+            n.trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse()
+                .map_err(|_| format!("expected a reference number, got '{n}'"))?,
+        ),
+        ("export", "") => return Err("expected a file to export to".to_string()),
+        ("export", path) => SessionCommand::Export(PathBuf::from(path)),
+        _ => return Err(format!("unknown command ':{line}', type :help for help")),
+    };
+    Ok(command)
+}
+
+/// State of `topk ask --interactive`.
+///
+/// Datasets can be added during the session, so the client is resolved for the region of the
+/// current datasets before each question.
+pub struct AskSession<D> {
+    datasets_client: D,
+    api_key: String,
+    host: String,
+    https: bool,
+    /// Client of the last region asked, with the region.
+    client: Option<(String, Client)>,
+    datasets: Vec<String>,
+    filter: Option<LogicalExpr>,
+    dataset_filters: Vec<(String, LogicalExpr)>,
+    mode: Option<Mode>,
+    fields: Option<Vec<String>>,
+    show_refs: bool,
+    /// Directory of content saved with `:open`.
+    output_dir: PathBuf,
+    transcript: Transcript,
+    /// First turn sent as context of follow-up questions, set by `:new`.
+    context_start: usize,
+}
+
+impl<D> AskSession<D>
+where
+    D: DatasetsClient + DatasetRegionResolver,
+{
+    pub fn new(datasets_client: D, api_key: &str, host: &str, https: bool, args: &AskArgs) -> Self {
+        Self {
+            datasets_client,
+            api_key: api_key.to_string(),
+            host: host.to_string(),
+            https,
+            client: None,
+            datasets: args.datasets.clone(),
+            filter: args.filter.clone(),
+            dataset_filters: args.dataset_filter.clone(),
+            mode: args.mode.clone(),
+            fields: args.fields.clone(),
+            show_refs: args.show_refs,
+            output_dir: args
+                .output_dir
+                .clone()
+                .unwrap_or_else(|| std::env::temp_dir().join("topk-ask")),
+            transcript: Transcript::default(),
+            context_start: 0,
+        }
+    }
+
+    async fn client(&mut self) -> Result<Client, Error> {
+        let region = ensure_unique_region(&mut self.datasets_client, self.datasets.clone()).await?;
+        match &self.client {
+            Some((cached, client)) if *cached == region => Ok(client.clone()),
+            _ => {
+                let client = make_client(&self.api_key, &region, &self.host, self.https);
+                self.client = Some((region, client.clone()));
+                Ok(client)
+            }
+        }
+    }
+
+    async fn ask(&mut self, query: &str, output: &Output) -> Result<(), Error> {
+        let client = self.client().await?;
+        let sources = filter::sources(&self.datasets, &self.dataset_filters)?;
+
+        let answer = answer(
+            &client,
+            follow_up(&self.transcript.turns[self.context_start..], query),
+            sources,
+            self.filter.clone(),
+            self.mode.clone(),
+            self.fields.clone(),
+            output,
+        )
+        .await?;
+
+        let turn = self.transcript.push(
+            query.to_string(),
+            self.datasets.clone(),
+            self.mode.clone(),
+            AskResult::from_answer(answer, true),
+        );
+
+        match output.format {
+            OutputFormat::Text => {
+                let result = turn.to_result(true);
+                output.print(&result)?;
+                if !result.facts.is_empty() {
+                    output.meta(&format!(
+                        "{} {}",
+                        "Confidence:".dimmed(),
+                        format!("{:.2}%", result.confidence).dimmed().bold()
+                    ));
+                }
+                if self.show_refs {
+                    let refs_text = turn
+                        .refs
+                        .iter()
+                        .map(|(n, result)| render_search_result(&n.to_string(), result, None))
+                        .collect::<Vec<_>>()
+                        .join("\n\n");
+                    if !refs_text.is_empty() {
+                        output.print(&format!("\n{}\n{refs_text}", "References:".bold()))?;
+                    }
+                }
+            }
+            OutputFormat::Json => output.print_json_line(turn)?,
+        }
+
+        Ok(())
+    }
+
+    /// Asks the questions and runs the commands read from `lines`. Errors only fail the line
+    /// that caused them.
+    async fn run(&mut self, lines: &mut dyn Lines, output: &Output) -> Result<(), Error> {
+        while let Some(line) = lines.read_line("ask> ")? {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            lines.add_history(line);
+
+            let result = if line.starts_with(':') {
+                self.command(line, output).await
+            } else {
+                self.ask(line, output).await.map(|()| false)
+            };
+            match result {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(err) if is_broken_pipe(&err) => return Err(err),
+                Err(err) => output.error(&err),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs a `:` command, returning whether the session ends.
+    async fn command(&mut self, line: &str, output: &Output) -> Result<bool, Error> {
+        match parse_command(line).map_err(Error::InvalidArgument)? {
+            SessionCommand::Help => output.meta(INTERACTIVE_HELP),
+            SessionCommand::Quit => return Ok(true),
+            SessionCommand::New => {
+                self.context_start = self.transcript.turns.len();
+                output.meta("Next question starts a new conversation.");
+            }
+            SessionCommand::Mode(mode) => {
+                if let Some(name) = mode.to_possible_value() {
+                    output.meta(&format!("Mode set to {}.", name.get_name()));
+                }
+                self.mode = Some(mode);
+            }
+            SessionCommand::Datasets => output.meta(&self.datasets.join(", ")),
+            SessionCommand::AddDataset(name) => {
+                if !self.datasets.contains(&name) {
+                    let mut datasets = self.datasets.clone();
+                    datasets.push(name.clone());
+                    // Datasets of a question must share a region.
+                    ensure_unique_region(&mut self.datasets_client, datasets.clone()).await?;
+                    self.datasets = datasets;
+                }
+                output.meta(&format!("Asking {}.", self.datasets.join(", ")));
+            }
+            SessionCommand::RemoveDataset(name) => {
+                if !self.datasets.contains(&name) {
+                    return Err(Error::InvalidArgument(format!(
+                        "dataset '{name}' is not asked"
+                    )));
+                }
+                if self.datasets.len() == 1 {
+                    return Err(Error::InvalidArgument(
+                        "cannot remove the last dataset".to_string(),
+                    ));
+                }
+                self.datasets.retain(|d| *d != name);
+                self.dataset_filters.retain(|(d, _)| *d != name);
+                output.meta(&format!("Asking {}.", self.datasets.join(", ")));
+            }
+            SessionCommand::Open(n) => {
+                let result = self.transcript.reference(n).ok_or_else(|| {
+                    Error::InvalidArgument(format!("no reference [{n}] in this session"))
+                })?;
+                let refs = HashMap::from([(n.to_string(), result.clone())]);
+                let paths = save_search_results(&self.output_dir, &refs)?;
+                let path = paths.get(&n.to_string()).map(PathBuf::as_path);
+                output.print(&render_search_result(&n.to_string(), result, path))?;
+            }
+            SessionCommand::Export(path) => {
+                let is_json = path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+                let text = if is_json {
+                    serde_json::to_string_pretty(&self.transcript)
+                        .map_err(|e| Error::Internal(e.to_string()))?
+                } else {
+                    self.transcript.to_markdown()
+                };
+                std::fs::write(&path, text)?;
+                output.success(&format!(
+                    "Exported {} {} to '{}'.",
+                    self.transcript.turns.len(),
+                    plural(self.transcript.turns.len(), "question", "questions"),
+                    path.display()
+                ));
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// `topk ask --interactive`
+///
+/// Reads questions and commands from the prompt, or from stdin when it is not a terminal.
+pub async fn interactive<D>(
+    mut session: AskSession<D>,
+    args: &AskArgs,
+    output: &Output,
+) -> Result<(), Error>
+where
+    D: DatasetsClient + DatasetRegionResolver,
+{
+    // Fail before the first question if the datasets can't be asked together.
+    session.client().await?;
+
+    if let Some(query) = &args.query {
+        if let Err(err) = session.ask(query, output).await {
+            output.error(&err);
+        }
+    }
+
+    if !std::io::stdin().is_terminal() {
+        let mut script = String::new();
+        std::io::stdin().read_to_string(&mut script)?;
+        return session.run(&mut Script::new(&script), output).await;
+    }
+
+    let mut repl = Repl::new("ask_history")?;
+    output.meta("Type :help for help, :quit to quit.");
+    let result = session.run(&mut repl, output).await;
+    repl.save();
+    result
 }
 
 #[cfg(test)]
 mod tests {
-    use super::AskResult;
+    use super::*;
     use crate::commands::test_context::{CliTestContext, OutputJsonExt};
     use assert_cmd::Command;
     use tempfile::tempdir;
@@ -181,6 +695,125 @@ mod tests {
         Command::cargo_bin("topk").unwrap()
     }
 
+    fn search_result(doc_id: &str, text: &str) -> SearchResult {
+        SearchResult {
+            doc_id: doc_id.to_string(),
+            doc_type: "text".to_string(),
+            dataset: "docs".to_string(),
+            content_id: format!("{doc_id}-0"),
+            doc_name: format!("{doc_id}.md"),
+            content: Some(Content::Chunk {
+                text: text.to_string(),
+                doc_pages: vec![],
+            }),
+            metadata: Default::default(),
+        }
+    }
+
+    fn ask_result(facts: &[(&str, &[&str])], refs: &[(&str, &str)]) -> AskResult {
+        AskResult {
+            facts: facts
+                .iter()
+                .map(|(fact, ref_ids)| Fact {
+                    fact: fact.to_string(),
+                    ref_ids: ref_ids.iter().map(|id| id.to_string()).collect(),
+                })
+                .collect(),
+            refs: refs
+                .iter()
+                .map(|(id, doc_id)| (id.to_string(), search_result(doc_id, "Some text.")))
+                .collect(),
+            confidence: 90.0,
+            show_refs: true,
+        }
+    }
+
+    #[test]
+    fn transcript_numbers_refs_across_turns() {
+        let mut transcript = Transcript::default();
+        transcript.push(
+            "What is TopK?".to_string(),
+            vec!["docs".to_string()],
+            None,
+            ask_result(
+                &[("A search engine.", &["2", "1"])],
+                &[("1", "intro"), ("2", "faq")],
+            ),
+        );
+        let turn = transcript.push(
+            "Who builds it?".to_string(),
+            vec!["docs".to_string()],
+            Some(Mode::Research),
+            ask_result(
+                &[("A team.", &["1"]), ("In Berlin.", &["2", "3"])],
+                &[("1", "about"), ("2", "jobs")],
+            ),
+        );
+
+        assert_eq!(turn.refs.keys().copied().collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(turn.facts[1].ref_ids, vec!["4"]);
+        assert_eq!(transcript.reference(2).unwrap().doc_id, "faq");
+        assert_eq!(transcript.reference(3).unwrap().doc_id, "about");
+        assert!(transcript.reference(5).is_none());
+
+        let md = transcript.to_markdown();
+        assert!(md.contains("## What is TopK?\n\nA search engine. [2] [1]\n"));
+        assert!(md.contains("4. jobs.md (docs, `jobs`)\n   > Some text.\n"));
+
+        let json = serde_json::to_value(&transcript).unwrap();
+        assert_eq!(json[1]["mode"], "research");
+        assert_eq!(json[1]["refs"]["3"]["doc_id"], "about");
+    }
+
+    #[test]
+    fn follow_ups_include_recent_turns() {
+        let mut transcript = Transcript::default();
+        assert_eq!(follow_up(&transcript.turns, "Why?"), "Why?");
+
+        for i in 0..4 {
+            transcript.push(
+                format!("Question {i}"),
+                vec!["docs".to_string()],
+                None,
+                ask_result(&[(&format!("Answer {i}."), &[])], &[]),
+            );
+        }
+
+        let query = follow_up(&transcript.turns, "Why?");
+        assert!(!query.contains("Question 0"));
+        assert!(query.contains("Q: Question 3\nA: Answer 3.\n"));
+        assert!(query.ends_with("Follow-up question: Why?"));
+        assert_eq!(follow_up(&transcript.turns[4..], "Why?"), "Why?");
+    }
+
+    #[test]
+    fn session_commands() {
+        assert_eq!(parse_command(":q"), Ok(SessionCommand::Quit));
+        assert_eq!(
+            parse_command(":mode Research"),
+            Ok(SessionCommand::Mode(Mode::Research))
+        );
+        assert_eq!(
+            parse_command(":dataset +wiki"),
+            Ok(SessionCommand::AddDataset("wiki".to_string()))
+        );
+        assert_eq!(
+            parse_command(":dataset -wiki"),
+            Ok(SessionCommand::RemoveDataset("wiki".to_string()))
+        );
+        assert_eq!(parse_command(":open [3]"), Ok(SessionCommand::Open(3)));
+        assert_eq!(
+            parse_command(":export chat.md"),
+            Ok(SessionCommand::Export(PathBuf::from("chat.md")))
+        );
+
+        assert!(parse_command(":mode fast").is_err());
+        assert!(parse_command(":dataset wiki").is_err());
+        assert!(parse_command(":open x").is_err());
+        assert!(parse_command(":export").is_err());
+        assert!(parse_command(":frobnicate").is_err());
+    }
+
     #[test_context(CliTestContext)]
     #[tokio::test]
     async fn ask_returns_result(ctx: &mut CliTestContext) {
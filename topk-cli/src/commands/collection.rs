@@ -0,0 +1,607 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use comfy_table::{
+    presets, Attribute, Cell, Color, ColumnConstraint, ContentArrangement, Table, Width,
+};
+use serde::{Deserialize, Serialize};
+use terminal_size::{terminal_size, Width as TermWidth};
+use topk_rs::{
+    proto::v1::control::{Collection as CollectionPb, FieldSpec},
+    Error,
+};
+
+use crate::collections::CollectionsClient;
+use crate::output::Output;
+use crate::util::format_timestamp;
+
+#[derive(Debug, clap::Args)]
+pub struct CreateCollectionArgs {
+    /// Collection name, defaults to the table name of a `CREATE TABLE` schema
+    #[arg(value_name = "COLLECTION")]
+    pub collection: Option<String>,
+    /// Region to create the collection in. List available regions at https://docs.topk.io/regions
+    #[arg(long, short = 'r', required = true)]
+    pub region: String,
+    /// Schema file (.toml, .json, .yaml, or .sql with a `CREATE TABLE` statement)
+    #[arg(long, short = 's', value_name = "FILE", conflicts_with = "sql")]
+    pub schema: Option<PathBuf>,
+    /// Schema as a `CREATE TABLE` statement
+    #[arg(long, value_name = "STATEMENT")]
+    pub sql: Option<String>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct DeleteCollectionArgs {
+    /// Collection name
+    #[arg(value_name = "COLLECTION")]
+    pub collection: String,
+    /// Skip confirmation prompt
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+}
+
+/// `topk collection`
+#[derive(Debug, clap::Subcommand)]
+pub enum CollectionAction {
+    /// List all collections
+    List,
+    /// Get a collection and its schema
+    Get {
+        /// Collection name
+        #[arg(value_name = "COLLECTION")]
+        collection: String,
+    },
+    /// Create a collection
+    Create(CreateCollectionArgs),
+    /// Delete a collection
+    Delete(DeleteCollectionArgs),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Collection {
+    pub name: String,
+    pub region: String,
+    // RFC3339 formatted timestamp
+    pub(crate) created_at: String,
+    pub schema: BTreeMap<String, FieldSpec>,
+}
+
+impl From<CollectionPb> for Collection {
+    fn from(collection: CollectionPb) -> Self {
+        Self {
+            name: collection.name,
+            region: collection.region,
+            created_at: collection.created_at,
+            schema: collection.schema.into_iter().collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ListCollectionsResult {
+    pub collections: Vec<Collection>,
+}
+
+impl From<Vec<CollectionPb>> for ListCollectionsResult {
+    fn from(collections: Vec<CollectionPb>) -> Self {
+        Self {
+            collections: collections.into_iter().map(|c| c.into()).collect(),
+        }
+    }
+}
+
+impl fmt::Display for ListCollectionsResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.collections.is_empty() {
+            return f.write_str("No collections found.");
+        }
+
+        let mut table = table(["NAME", "REGION", "FIELDS", "CREATED"]);
+        table.set_constraints([
+            ColumnConstraint::LowerBoundary(Width::Fixed(10)),
+            ColumnConstraint::ContentWidth,
+            ColumnConstraint::ContentWidth,
+            ColumnConstraint::ContentWidth,
+        ]);
+
+        for c in &self.collections {
+            table.add_row([
+                Cell::new(&c.name),
+                Cell::new(&c.region),
+                Cell::new(c.schema.len()),
+                Cell::new(format_timestamp(&c.created_at).unwrap_or_default())
+                    .add_attribute(Attribute::Dim),
+            ]);
+        }
+
+        f.write_str(&table.to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GetCollectionResult {
+    pub(crate) collection: Collection,
+}
+
+impl From<CollectionPb> for GetCollectionResult {
+    fn from(collection: CollectionPb) -> Self {
+        Self {
+            collection: collection.into(),
+        }
+    }
+}
+
+impl fmt::Display for GetCollectionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Name:    {}\nRegion:  {}\nCreated: {}\n\n{}",
+            self.collection.name,
+            self.collection.region,
+            format_timestamp(&self.collection.created_at).unwrap_or_default(),
+            SchemaTable(&self.collection.schema),
+        )
+    }
+}
+
+/// Renders a collection schema as a `FIELD | TYPE | REQUIRED | INDEX` table, using the
+/// `CREATE TABLE` spelling of types and indexes.
+pub struct SchemaTable<'a>(pub &'a BTreeMap<String, FieldSpec>);
+
+impl fmt::Display for SchemaTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "{}", "No fields.".dimmed());
+        }
+
+        let mut table = table(["FIELD", "TYPE", "REQUIRED", "INDEX"]);
+        table.set_constraints([
+            ColumnConstraint::LowerBoundary(Width::Fixed(10)),
+            ColumnConstraint::ContentWidth,
+            ColumnConstraint::ContentWidth,
+            ColumnConstraint::LowerBoundary(Width::Fixed(10)),
+        ]);
+
+        for (name, spec) in self.0 {
+            let index = spec.index.as_ref().and_then(topk_sql::index_sql);
+            table.add_row([
+                Cell::new(name),
+                match topk_sql::data_type_sql(spec) {
+                    Ok(data_type) => Cell::new(data_type),
+                    Err(_) => Cell::new("unknown").add_attribute(Attribute::Dim),
+                },
+                Cell::new(if spec.required { "yes" } else { "no" }),
+                match index {
+                    Some(index) => Cell::new(index),
+                    None => Cell::new("-").add_attribute(Attribute::Dim),
+                },
+            ]);
+        }
+
+        f.write_str(&table.to_string())
+    }
+}
+
+fn table<const N: usize>(header: [&str; N]) -> Table {
+    let term_width = terminal_size().map(|(TermWidth(w), _)| w).unwrap_or(80);
+
+    let mut table = Table::new();
+    table
+        .load_preset(presets::NOTHING)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_width(term_width)
+        .set_header(
+            header
+                .iter()
+                .map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Cyan)),
+        );
+    table
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CreateCollectionResult {
+    pub(crate) collection: Collection,
+}
+
+impl From<CollectionPb> for CreateCollectionResult {
+    fn from(collection: CollectionPb) -> Self {
+        Self {
+            collection: collection.into(),
+        }
+    }
+}
+
+impl fmt::Display for CreateCollectionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Collection '{}' created.", self.collection.name)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteCollectionResult {
+    pub deleted: bool,
+}
+
+impl fmt::Display for DeleteCollectionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.deleted {
+            f.write_str("Collection deleted.")
+        } else {
+            f.write_str("Deletion skipped.")
+        }
+    }
+}
+
+/// Schema file field definition, one of:
+///
+/// - `title = "TEXT NOT NULL INDEX keyword_index()"`
+/// - `title = { type = "TEXT", required = true, index = "keyword_index()" }`
+/// - a `FieldSpec` as printed by `topk collection get -o json`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FieldDef {
+    Sql(String),
+    Column(ColumnDef),
+    Spec(FieldSpec),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColumnDef {
+    #[serde(rename = "type")]
+    data_type: String,
+    #[serde(default)]
+    required: bool,
+    index: Option<String>,
+}
+
+impl ColumnDef {
+    fn sql(&self) -> String {
+        let mut sql = self.data_type.clone();
+        if self.required {
+            sql.push_str(" NOT NULL");
+        }
+        if let Some(index) = &self.index {
+            sql.push_str(" INDEX ");
+            sql.push_str(index);
+        }
+        sql
+    }
+}
+
+/// Loads a collection schema from a `.toml`, `.json`, `.yaml` / `.yml` or `.sql` file.
+///
+/// Returns the table name for `.sql` files.
+pub fn load_schema(path: &Path) -> Result<(Option<String>, HashMap<String, FieldSpec>), Error> {
+    let content = std::fs::read_to_string(path)?;
+    let invalid = |e: &dyn fmt::Display| {
+        Error::InvalidArgument(format!("invalid schema file '{}': {e}", path.display()))
+    };
+
+    let fields: BTreeMap<String, FieldDef> = match path.extension().and_then(|e| e.to_str()) {
+        Some("sql") => {
+            let (table, schema) = parse_create_table(&content)?;
+            return Ok((Some(table), schema));
+        }
+        Some("toml") => toml::from_str(&content).map_err(|e| invalid(&e))?,
+        Some("json") => serde_json::from_str(&content).map_err(|e| invalid(&e))?,
+        Some("yaml" | "yml") => serde_yaml::from_str(&content).map_err(|e| invalid(&e))?,
+        _ => {
+            return Err(Error::InvalidArgument(format!(
+                "unsupported schema file '{}', expected .toml, .json, .yaml or .sql",
+                path.display()
+            )))
+        }
+    };
+
+    let schema = fields
+        .into_iter()
+        .map(|(name, def)| {
+            let spec = match def {
+                FieldDef::Sql(sql) => parse_column(&name, &sql)?,
+                FieldDef::Column(column) => parse_column(&name, &column.sql())?,
+                FieldDef::Spec(spec) => spec,
+            };
+            Ok((name, spec))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok((None, schema))
+}
+
+/// Parses a single column definition, eg. `TEXT NOT NULL INDEX keyword_index()`.
+fn parse_column(name: &str, sql: &str) -> Result<FieldSpec, Error> {
+    let ident = format!("\"{}\"", name.replace('"', "\"\""));
+    let (_, mut schema) = parse_create_table(&format!("CREATE TABLE t ({ident} {sql})"))
+        .map_err(|e| Error::InvalidArgument(format!("field '{name}': {e}")))?;
+
+    schema
+        .remove(name)
+        .ok_or_else(|| Error::InvalidArgument(format!("field '{name}': missing definition")))
+}
+
+/// Parses a `CREATE TABLE` statement into its table name and schema.
+pub fn parse_create_table(sql: &str) -> Result<(String, HashMap<String, FieldSpec>), Error> {
+    let invalid = |e: topk_sql::Error| Error::InvalidArgument(e.to_string());
+
+    let mut stmts =
+        topk_sql::convert_sql(topk_sql::parse_sql(sql).map_err(invalid)?).map_err(invalid)?;
+    if stmts.len() != 1 {
+        return Err(Error::InvalidArgument(
+            "expected a single CREATE TABLE statement".into(),
+        ));
+    }
+
+    match stmts.remove(0).0 {
+        topk_sql::Statement::CreateTable {
+            table: topk_sql::Table::Collection(table),
+            schema,
+            ..
+        } => Ok((table, schema)),
+        stmt => Err(Error::InvalidArgument(format!(
+            "expected a CREATE TABLE statement, got `{}`",
+            stmt.as_str()
+        ))),
+    }
+}
+
+/// `topk collection list`
+pub async fn list<C: CollectionsClient>(mut client: C) -> Result<ListCollectionsResult, Error> {
+    Ok(client.list().await?.into())
+}
+
+/// `topk collection get`
+pub async fn get<C: CollectionsClient>(
+    mut client: C,
+    name: &str,
+) -> Result<GetCollectionResult, Error> {
+    Ok(client.get(name).await?.into())
+}
+
+/// `topk collection create`
+pub async fn create<C: CollectionsClient>(
+    mut client: C,
+    args: &CreateCollectionArgs,
+) -> Result<CreateCollectionResult, Error> {
+    let (table, schema) = match (&args.schema, &args.sql) {
+        (Some(path), _) => load_schema(path)?,
+        (None, Some(sql)) => {
+            let (table, schema) = parse_create_table(sql)?;
+            (Some(table), schema)
+        }
+        (None, None) => (None, HashMap::new()),
+    };
+
+    let name = match (&args.collection, table) {
+        (Some(name), Some(table)) if *name != table => {
+            return Err(Error::InvalidArgument(format!(
+                "collection name '{name}' does not match CREATE TABLE name '{table}'"
+            )))
+        }
+        (Some(name), _) => name.clone(),
+        (None, Some(table)) => table,
+        (None, None) => return Err(Error::InvalidArgument("collection name is required".into())),
+    };
+
+    Ok(client.create(&name, schema, &args.region).await?.into())
+}
+
+/// `topk collection delete`
+pub async fn delete<C: CollectionsClient>(
+    mut client: C,
+    args: &DeleteCollectionArgs,
+    output: &Output,
+) -> Result<DeleteCollectionResult, Error> {
+    if !output.confirm_or_yes(
+        &format!("Delete collection '{}'? ", args.collection),
+        args.yes,
+    )? {
+        return Ok(DeleteCollectionResult { deleted: false });
+    }
+
+    client.delete(&args.collection).await?;
+
+    Ok(DeleteCollectionResult { deleted: true })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_context::{CliTestContext, OutputJsonExt};
+    use assert_cmd::Command;
+    use test_context::test_context;
+
+    fn cmd() -> Command {
+        Command::cargo_bin("topk").unwrap()
+    }
+
+    fn schema_file(name: &str, content: &str) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    fn books_schema() -> HashMap<String, FieldSpec> {
+        parse_create_table(
+            "CREATE TABLE books (
+                title TEXT NOT NULL INDEX keyword_index(),
+                embedding f32_vector(4) INDEX vector_index(metric = 'cosine'),
+                rating FLOAT
+            )",
+        )
+        .unwrap()
+        .1
+    }
+
+    #[test]
+    fn load_schema_formats() {
+        let files = [
+            (
+                "schema.toml",
+                r#"
+                title = { type = "TEXT", required = true, index = "keyword_index()" }
+                embedding = "f32_vector(4) INDEX vector_index(metric = 'cosine')"
+                rating = { type = "FLOAT" }
+                "#,
+            ),
+            (
+                "schema.json",
+                r#"{
+                    "title": "TEXT NOT NULL INDEX keyword_index()",
+                    "embedding": { "type": "f32_vector(4)", "index": "vector_index(metric = 'cosine')" },
+                    "rating": "FLOAT"
+                }"#,
+            ),
+            (
+                "schema.yaml",
+                "title: TEXT NOT NULL INDEX keyword_index()\n\
+                 embedding:\n  type: f32_vector(4)\n  index: vector_index(metric = 'cosine')\n\
+                 rating: FLOAT\n",
+            ),
+        ];
+
+        for (name, content) in files {
+            let (_dir, path) = schema_file(name, content);
+            let (table, schema) = load_schema(&path).unwrap();
+            assert_eq!(table, None, "{name}");
+            assert_eq!(schema, books_schema(), "{name}");
+        }
+    }
+
+    #[test]
+    fn load_schema_round_trips_json_output() {
+        let schema = books_schema().into_iter().collect::<BTreeMap<_, _>>();
+        let (_dir, path) = schema_file("schema.json", &serde_json::to_string(&schema).unwrap());
+
+        let (_, loaded) = load_schema(&path).unwrap();
+        assert_eq!(loaded, books_schema());
+    }
+
+    #[test]
+    fn load_schema_from_create_table() {
+        let (_dir, path) = schema_file(
+            "books.sql",
+            "CREATE TABLE books (title TEXT NOT NULL INDEX keyword_index());",
+        );
+
+        let (table, schema) = load_schema(&path).unwrap();
+        assert_eq!(table.as_deref(), Some("books"));
+        assert!(schema["title"].required);
+    }
+
+    #[test]
+    fn load_schema_errors() {
+        let (_dir, path) = schema_file("schema.toml", "title = \"NOPE\"");
+        let err = load_schema(&path).unwrap_err().to_string();
+        assert!(err.contains("field 'title'"), "{err}");
+
+        let (_dir, path) = schema_file("schema.toml", "title = { type = \"TEXT\", indx = \"\" }");
+        let err = load_schema(&path).unwrap_err().to_string();
+        assert!(err.contains("invalid schema file"), "{err}");
+
+        let (_dir, path) = schema_file("schema.csv", "");
+        let err = load_schema(&path).unwrap_err().to_string();
+        assert!(err.contains("unsupported schema file"), "{err}");
+
+        let err = parse_create_table("DROP TABLE books")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected a CREATE TABLE statement"), "{err}");
+    }
+
+    #[test]
+    fn schema_table_renders_sql_types() {
+        let schema = books_schema().into_iter().collect::<BTreeMap<_, _>>();
+        colored::control::set_override(false);
+        let rendered = SchemaTable(&schema).to_string();
+
+        assert!(rendered.contains("f32_vector(4)"), "{rendered}");
+        assert!(rendered.contains("keyword_index()"), "{rendered}");
+        assert!(rendered.contains("DOUBLE PRECISION"), "{rendered}");
+    }
+
+    #[test_context(CliTestContext)]
+    #[tokio::test]
+    async fn create_get_list_delete(ctx: &mut CliTestContext) {
+        let name = ctx.wrap("books");
+        let (_dir, path) = schema_file(
+            "schema.toml",
+            "title = \"TEXT NOT NULL INDEX keyword_index()\"",
+        );
+
+        let out = cmd()
+            .args([
+                "-o",
+                "json",
+                "collection",
+                "create",
+                "--region",
+                &ctx.region,
+            ])
+            .args(["--schema", path.to_str().unwrap(), &name])
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let result: CreateCollectionResult = out.json().unwrap();
+        assert_eq!(result.collection.name, name);
+        assert!(result.collection.schema["title"].required);
+
+        let out = cmd()
+            .args(["-o", "json", "collection", "get", &name])
+            .output()
+            .unwrap();
+        let result: GetCollectionResult = out.json().unwrap();
+        assert_eq!(result.collection.region, ctx.region);
+
+        let out = cmd()
+            .args(["-o", "json", "collection", "list"])
+            .output()
+            .unwrap();
+        let collections: Vec<Collection> = out.json_lines().unwrap();
+        assert!(collections.iter().any(|c| c.name == name));
+
+        let out = cmd()
+            .args(["-o", "json", "collection", "delete", &name, "-y"])
+            .output()
+            .unwrap();
+        let result: DeleteCollectionResult = out.json().unwrap();
+        assert!(result.deleted);
+    }
+
+    #[test_context(CliTestContext)]
+    #[tokio::test]
+    async fn create_from_sql(ctx: &mut CliTestContext) {
+        let name = ctx.wrap("books");
+        let out = cmd()
+            .args([
+                "-o",
+                "json",
+                "collection",
+                "create",
+                "--region",
+                &ctx.region,
+            ])
+            .args(["--sql", &format!("CREATE TABLE \"{name}\" (rating FLOAT)")])
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let result: CreateCollectionResult = out.json().unwrap();
+        assert_eq!(result.collection.name, name);
+        assert!(result.collection.schema.contains_key("rating"));
+    }
+}
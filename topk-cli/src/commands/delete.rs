@@ -45,7 +45,7 @@ pub async fn run(
 
     let handle = client
         .dataset(&args.dataset)
-        .delete(args.id.clone())
+        .delete(args.id.clone(), None)
         .await?;
 
     Ok(DeleteResult {
@@ -76,12 +76,12 @@ mod tests {
         let upload = ctx
             .client
             .dataset(&dataset)
-            .upsert_file("doc-to-delete", input, Vec::<(String, Value)>::new())
+            .upsert_file("doc-to-delete", input, Vec::<(String, Value)>::new(), None)
             .await
             .unwrap();
         ctx.client
             .dataset(&dataset)
-            .wait_for_handle(&upload, None)
+            .wait_for_handle(&upload, None, None)
             .await
             .unwrap();
 
@@ -118,12 +118,12 @@ mod tests {
         let upload = ctx
             .client
             .dataset(&dataset)
-            .upsert_file("doc-to-keep", input, Vec::<(String, Value)>::new())
+            .upsert_file("doc-to-keep", input, Vec::<(String, Value)>::new(), None)
             .await
             .unwrap();
         ctx.client
             .dataset(&dataset)
-            .wait_for_handle(&upload, None)
+            .wait_for_handle(&upload, None, None)
             .await
             .unwrap();
 
@@ -0,0 +1,734 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand, ValueEnum};
+use colored::Colorize;
+use futures::future::LocalBoxFuture;
+use futures::stream::{FuturesUnordered, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use topk_rs::arrow::DocumentConverter;
+use topk_rs::parquet::{ExportOptions, ImportOptions};
+use topk_rs::proto::v1::control::FieldSpec;
+use topk_rs::proto::v1::data::{Document, LogicalExpr, Query, Stage};
+use topk_rs::{Client, CollectionClient, Error};
+use topk_sql::{CopyDecoder, CopyEncoder, CopyError, CopyFormat, CopyOptions, Table};
+
+use crate::output::{is_broken_pipe, Output};
+use crate::sql::sql_error;
+use crate::util::plural;
+
+/// Size of the chunks read from the input file.
+const READ_CHUNK: usize = 256 * 1024;
+
+/// Number of exported documents encoded at a time.
+const EXPORT_BATCH: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DocsFormat {
+    /// One JSON object per line
+    Ndjson,
+    /// CSV with a header row
+    Csv,
+    /// A single JSON array of objects
+    Json,
+    /// Apache Parquet
+    Parquet,
+}
+
+impl DocsFormat {
+    /// Format of `path` by extension: `.ndjson` / `.jsonl`, `.csv` or `.json`.
+    fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "parquet" => Some(Self::Parquet),
+            _ => None,
+        }
+    }
+
+    fn copy_options(self) -> CopyOptions {
+        let format = match self {
+            Self::Ndjson => CopyFormat::Ndjson,
+            Self::Csv => CopyFormat::Csv,
+            Self::Json => CopyFormat::Json,
+            Self::Parquet => unreachable!("Parquet files are not read with COPY"),
+        };
+        CopyOptions {
+            format,
+            header: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// Collection to import into
+    #[arg(short = 'c', long, value_name = "COLLECTION")]
+    pub collection: String,
+    /// Partition to import into
+    #[arg(short = 'p', long)]
+    pub partition: Option<String>,
+    /// Input format, inferred from the file extension by default
+    #[arg(long, value_enum)]
+    pub format: Option<DocsFormat>,
+    /// Maximum number of documents per upsert
+    #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(1..=10000))]
+    pub batch_size: u64,
+    /// Number of concurrent upserts (1–64)
+    #[arg(long, default_value = "4", value_parser = clap::value_parser!(u64).range(1..=64))]
+    pub concurrency: u64,
+    /// Column holding the document `_id` (Parquet only)
+    #[arg(long, value_name = "COLUMN")]
+    pub id_column: Option<String>,
+    /// Import a column as a differently named field (Parquet only, repeatable)
+    #[arg(long = "map", value_name = "COLUMN=FIELD", value_parser = parse_mapping)]
+    pub columns: Vec<(String, String)>,
+    /// Record progress in a file and resume from it when it exists (Parquet only)
+    #[arg(long, value_name = "FILE")]
+    pub checkpoint: Option<PathBuf>,
+    /// File to import (`.ndjson`, `.jsonl`, `.csv`, `.json` or `.parquet`), or `-` for stdin
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Collection to export
+    #[arg(short = 'c', long, value_name = "COLLECTION")]
+    pub collection: String,
+    /// Partition to export
+    #[arg(short = 'p', long)]
+    pub partition: Option<String>,
+    /// Export only documents matching a SQL condition (e.g. "year >= 2023 AND team = 'ml'")
+    #[arg(long, value_name = "CONDITION")]
+    pub filter: Option<String>,
+    /// Output format, inferred from the output file extension by default (ndjson otherwise)
+    #[arg(long, value_enum)]
+    pub format: Option<DocsFormat>,
+    /// Write documents to a file instead of stdout
+    #[arg(long, value_name = "FILE")]
+    pub output_file: Option<PathBuf>,
+    /// Maximum number of rows per row group (Parquet only)
+    #[arg(long, value_name = "ROWS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub row_group_size: Option<u64>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DocsAction {
+    /// Import documents from an NDJSON, CSV, JSON or Parquet file
+    Import(ImportArgs),
+    /// Export documents as NDJSON, CSV, JSON or Parquet
+    Export(ExportArgs),
+}
+
+impl ImportArgs {
+    fn table(&self) -> Table {
+        table(&self.collection, self.partition.as_deref())
+    }
+}
+
+impl ExportArgs {
+    fn table(&self) -> Table {
+        table(&self.collection, self.partition.as_deref())
+    }
+}
+
+/// Parses a `--map` flag: `COLUMN=FIELD`.
+fn parse_mapping(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((column, field)) if !column.is_empty() && !field.is_empty() => {
+            Ok((column.to_string(), field.to_string()))
+        }
+        _ => Err(format!("expected COLUMN=FIELD, got '{s}'")),
+    }
+}
+
+/// Fails if one of the Parquet only `flags` is set for another format.
+fn parquet_only(format: DocsFormat, flags: &[(&str, bool)]) -> Result<(), Error> {
+    match flags.iter().find(|(_, set)| *set) {
+        Some((flag, _)) if format != DocsFormat::Parquet => Err(Error::InvalidArgument(format!(
+            "{flag} is only supported for Parquet files"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn table(collection: &str, partition: Option<&str>) -> Table {
+    match partition {
+        Some(partition) => Table::Partition(collection.to_string(), partition.to_string()),
+        None => Table::Collection(collection.to_string()),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportError {
+    /// Line of the rejected record (element of a JSON array), if the record was invalid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub error: String,
+}
+
+impl From<CopyError> for ImportError {
+    fn from(err: CopyError) -> Self {
+        Self {
+            line: Some(err.line),
+            error: err.message,
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.error),
+            None => f.write_str(&self.error),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportError>,
+}
+
+impl fmt::Display for ImportResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let docs = plural(self.imported, "document", "documents");
+        match self.failed {
+            0 => write!(f, "Imported {} {docs}.", self.imported),
+            failed => write!(f, "Imported {} {docs} ({failed} failed).", self.imported),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub exported: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+impl fmt::Display for ExportResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let docs = plural(self.exported, "document", "documents");
+        match &self.path {
+            Some(path) => write!(
+                f,
+                "Exported {} {docs} to {}.",
+                self.exported,
+                path.display()
+            ),
+            None => write!(f, "Exported {} {docs}.", self.exported),
+        }
+    }
+}
+
+/// Upserted (or failed) batch of documents.
+struct BatchUpsert {
+    docs: usize,
+    result: Result<(), ImportError>,
+}
+
+/// Upserts batches with at most `concurrency` requests in flight.
+struct Upserts {
+    collection: CollectionClient,
+    concurrency: usize,
+    pending: FuturesUnordered<LocalBoxFuture<'static, BatchUpsert>>,
+    imported: usize,
+    failed: usize,
+    errors: Vec<ImportError>,
+}
+
+impl Upserts {
+    fn new(collection: CollectionClient, concurrency: usize) -> Self {
+        Self {
+            collection,
+            concurrency,
+            pending: FuturesUnordered::new(),
+            imported: 0,
+            failed: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    async fn push(&mut self, batch: Vec<Document>) {
+        while self.pending.len() >= self.concurrency {
+            self.next().await;
+        }
+
+        let collection = self.collection.clone();
+        self.pending.push(Box::pin(async move {
+            let docs = batch.len();
+            let ids = match (batch.first(), batch.last()) {
+                (Some(first), Some(last)) => format!(
+                    "documents `{}`..`{}`",
+                    first.id().unwrap_or_default(),
+                    last.id().unwrap_or_default()
+                ),
+                _ => "documents".to_string(),
+            };
+            let result = collection
+                .upsert(batch, None)
+                .await
+                .map(|_| ())
+                .map_err(|err| ImportError {
+                    line: None,
+                    error: format!("{ids}: {err}"),
+                });
+            BatchUpsert { docs, result }
+        }));
+    }
+
+    /// Waits for the next upsert to complete. Returns `false` if none is pending.
+    async fn next(&mut self) -> bool {
+        let Some(upsert) = self.pending.next().await else {
+            return false;
+        };
+        match upsert.result {
+            Ok(()) => self.imported += upsert.docs,
+            Err(err) => {
+                self.failed += upsert.docs;
+                self.errors.push(err);
+            }
+        }
+        true
+    }
+
+    fn invalid(&mut self, errors: Vec<CopyError>) {
+        self.failed += errors.len();
+        self.errors
+            .extend(errors.into_iter().map(ImportError::from));
+    }
+
+    fn progress(&self) -> String {
+        let imported = format!("{} imported", self.imported);
+        match self.failed {
+            0 => imported,
+            failed => format!("{imported} ({})", format!("{failed} failed").red()),
+        }
+    }
+}
+
+/// `topk docs import`
+pub async fn import(
+    client: &Client,
+    schema: HashMap<String, FieldSpec>,
+    args: &ImportArgs,
+    output: &Output,
+) -> Result<ImportResult, Error> {
+    let stdin = args.file.as_os_str() == "-";
+    let format = match args.format {
+        Some(format) => format,
+        None => DocsFormat::from_path(&args.file).ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "cannot infer the format of '{}', use --format",
+                args.file.display()
+            ))
+        })?,
+    };
+    parquet_only(
+        format,
+        &[
+            ("--id-column", args.id_column.is_some()),
+            ("--map", !args.columns.is_empty()),
+            ("--checkpoint", args.checkpoint.is_some()),
+        ],
+    )?;
+    if format == DocsFormat::Parquet {
+        if stdin {
+            return Err(Error::InvalidArgument(
+                "Parquet files cannot be read from stdin".to_string(),
+            ));
+        }
+        return import_parquet(client, schema, args, output).await;
+    }
+
+    let (mut reader, progress): (Box<dyn AsyncRead + Unpin>, _) = if stdin {
+        (
+            Box::new(tokio::io::stdin()),
+            output.spinner("Importing documents"),
+        )
+    } else {
+        let file = tokio::fs::File::open(&args.file).await?;
+        let size = file.metadata().await?.len();
+        (Box::new(file), output.progress_bytes(size, "0 imported"))
+    };
+
+    let mut decoder = CopyDecoder::new(format.copy_options(), vec![], schema)
+        .map_err(sql_error)?
+        .with_batch_size(args.batch_size as usize, topk_sql::COPY_BATCH_BYTES)
+        .skip_invalid();
+    let mut upserts = Upserts::new(
+        args.table().configure(client.clone()),
+        args.concurrency as usize,
+    );
+
+    let mut buf = vec![0; READ_CHUNK];
+    let mut read = 0;
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        read += n as u64;
+
+        let batches = decoder.push(&buf[..n]).map_err(sql_error)?;
+        upserts.invalid(decoder.take_errors());
+        for batch in batches {
+            upserts.push(batch).await;
+        }
+
+        progress.set_position(read);
+        progress.set_message(upserts.progress());
+    }
+
+    let (batches, errors) = decoder.finish_with_errors().map_err(sql_error)?;
+    upserts.invalid(errors);
+    for batch in batches {
+        upserts.push(batch).await;
+    }
+    while upserts.next().await {
+        progress.set_message(upserts.progress());
+    }
+
+    progress.finish();
+    for err in &upserts.errors {
+        output.warn(&format!("  {err}"));
+    }
+
+    Ok(ImportResult {
+        imported: upserts.imported,
+        failed: upserts.failed,
+        errors: upserts.errors,
+    })
+}
+
+async fn import_parquet(
+    client: &Client,
+    schema: HashMap<String, FieldSpec>,
+    args: &ImportArgs,
+    output: &Output,
+) -> Result<ImportResult, Error> {
+    let converter = DocumentConverter::new(&schema)?;
+    let options = ImportOptions {
+        id_column: args.id_column.clone(),
+        columns: args.columns.iter().cloned().collect(),
+        batch_size: args.batch_size as usize,
+        concurrency: args.concurrency as usize,
+        checkpoint: args.checkpoint.clone(),
+    };
+
+    let progress = output.spinner("0 imported");
+    let result = args
+        .table()
+        .configure(client.clone())
+        .import_parquet(&converter, &args.file, options, |p| {
+            let total = p.total_rows.unwrap_or_default();
+            progress.set_message(format!("{}/{total} imported", p.rows));
+        })
+        .await;
+    progress.finish();
+
+    match result {
+        Ok(imported) => Ok(ImportResult {
+            imported: imported as usize,
+            failed: 0,
+            errors: Vec::new(),
+        }),
+        Err(err) => {
+            if let Some(checkpoint) = &args.checkpoint {
+                output.warn(&format!(
+                    "Progress is saved in {}, run the same command to resume.",
+                    checkpoint.display()
+                ));
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Query returning the documents matching `filter`, with all `schema` fields.
+fn export_query(filter: Option<&str>, schema: &HashMap<String, FieldSpec>) -> Result<Query, Error> {
+    let mut stages = match filter {
+        Some(filter) => topk_sql::parse_filter(filter)
+            .map_err(sql_error)?
+            .into_iter()
+            .map(Stage::filter)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    if !schema.is_empty() {
+        let mut fields = schema.keys().collect::<Vec<_>>();
+        fields.sort();
+        stages.push(Stage::select(
+            fields
+                .into_iter()
+                .map(|name| (name.clone(), LogicalExpr::field(name))),
+        ));
+    }
+
+    Ok(Query::new(stages))
+}
+
+/// Columns of the export: `_id`, then the schema fields by name. Empty for collections without
+/// a schema, in which case they are taken from the first document.
+fn export_columns(schema: &HashMap<String, FieldSpec>) -> Vec<String> {
+    if schema.is_empty() {
+        return Vec::new();
+    }
+
+    let mut columns = schema.keys().cloned().collect::<Vec<_>>();
+    columns.sort();
+    columns.insert(0, "_id".to_string());
+    columns
+}
+
+/// `topk docs export`
+pub async fn export(
+    client: &Client,
+    schema: HashMap<String, FieldSpec>,
+    args: &ExportArgs,
+    output: &Output,
+) -> Result<ExportResult, Error> {
+    let format = args
+        .format
+        .or_else(|| args.output_file.as_deref().and_then(DocsFormat::from_path))
+        .unwrap_or(DocsFormat::Ndjson);
+    parquet_only(
+        format,
+        &[("--row-group-size", args.row_group_size.is_some())],
+    )?;
+    let query = export_query(args.filter.as_deref(), &schema)?;
+    if format == DocsFormat::Parquet {
+        return export_parquet(client, schema, query, args, output).await;
+    }
+
+    let mut writer: Box<dyn Write> = match &args.output_file {
+        Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+    };
+    // Don't draw progress over documents written to the terminal.
+    let progress = if args.output_file.is_some() || !std::io::stdout().is_terminal() {
+        output.spinner("0 exported")
+    } else {
+        crate::util::progress::Spinner::disabled()
+    };
+
+    let mut encoder =
+        CopyEncoder::new(format.copy_options(), export_columns(&schema)).with_schema(schema);
+    let mut docs = args
+        .table()
+        .configure(client.clone())
+        .query_stream(query, None)
+        .await?
+        .try_chunks(EXPORT_BATCH);
+
+    let result = async {
+        while let Some(batch) = docs.next().await {
+            let batch = batch.map_err(|err| err.1)?;
+            writer.write_all(&encoder.encode(&batch).map_err(sql_error)?)?;
+            progress.set_message(format!("{} exported", encoder.rows()));
+        }
+        let exported = encoder.rows();
+        writer.write_all(&encoder.finish())?;
+        writer.flush()?;
+        Ok::<_, Error>(exported)
+    }
+    .await;
+
+    progress.finish();
+    let exported = match result {
+        Ok(exported) => exported,
+        // Eg. `topk docs export … | head`
+        Err(err) if is_broken_pipe(&err) => 0,
+        Err(err) => return Err(err),
+    };
+
+    Ok(ExportResult {
+        exported,
+        path: args.output_file.clone(),
+    })
+}
+
+async fn export_parquet(
+    client: &Client,
+    schema: HashMap<String, FieldSpec>,
+    query: Query,
+    args: &ExportArgs,
+    output: &Output,
+) -> Result<ExportResult, Error> {
+    let Some(path) = &args.output_file else {
+        return Err(Error::InvalidArgument(
+            "Parquet exports need --output-file".to_string(),
+        ));
+    };
+    // Columns are typed by the schema.
+    if schema.is_empty() {
+        return Err(Error::InvalidArgument(
+            "Parquet exports need a collection with a schema".to_string(),
+        ));
+    }
+
+    let mut options = ExportOptions {
+        query: Some(query),
+        batch_size: EXPORT_BATCH,
+        ..Default::default()
+    };
+    if let Some(row_group_size) = args.row_group_size {
+        options.row_group_size = row_group_size as usize;
+    }
+
+    let progress = output.spinner("0 exported");
+    let result = args
+        .table()
+        .configure(client.clone())
+        .export_parquet(DocumentConverter::new(&schema)?, path, options, |p| {
+            progress.set_message(format!("{} exported", p.rows))
+        })
+        .await;
+    progress.finish();
+
+    Ok(ExportResult {
+        exported: result? as usize,
+        path: Some(path.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_context::CliTestContext;
+    use assert_cmd::Command;
+    use test_context::test_context;
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            DocsFormat::from_path(Path::new("books.ndjson")),
+            Some(DocsFormat::Ndjson)
+        );
+        assert_eq!(
+            DocsFormat::from_path(Path::new("books.JSONL")),
+            Some(DocsFormat::Ndjson)
+        );
+        assert_eq!(
+            DocsFormat::from_path(Path::new("books.csv")),
+            Some(DocsFormat::Csv)
+        );
+        assert_eq!(
+            DocsFormat::from_path(Path::new("books.json")),
+            Some(DocsFormat::Json)
+        );
+        assert_eq!(
+            DocsFormat::from_path(Path::new("books.parquet")),
+            Some(DocsFormat::Parquet)
+        );
+        assert_eq!(DocsFormat::from_path(Path::new("books.txt")), None);
+        assert_eq!(DocsFormat::from_path(Path::new("books")), None);
+    }
+
+    #[test]
+    fn export_query_selects_schema_fields() {
+        let schema = HashMap::from([
+            ("year".to_string(), FieldSpec::integer(false)),
+            ("title".to_string(), FieldSpec::text(true)),
+        ]);
+
+        let query = export_query(Some("year >= 2023"), &schema).unwrap();
+        assert_eq!(query.stages.len(), 2);
+        assert_eq!(export_columns(&schema), ["_id", "title", "year"]);
+
+        assert!(export_query(None, &HashMap::new())
+            .unwrap()
+            .stages
+            .is_empty());
+        assert!(export_query(Some("year >="), &schema).is_err());
+    }
+
+    #[test]
+    fn parquet_flags() {
+        assert_eq!(
+            parse_mapping("emb=embedding").unwrap(),
+            ("emb".to_string(), "embedding".to_string())
+        );
+        assert!(parse_mapping("emb").is_err());
+        assert!(parse_mapping("=embedding").is_err());
+
+        assert!(parquet_only(DocsFormat::Parquet, &[("--map", true)]).is_ok());
+        assert!(parquet_only(DocsFormat::Csv, &[("--map", false)]).is_ok());
+        let err = parquet_only(DocsFormat::Csv, &[("--map", true)]).unwrap_err();
+        assert!(err.to_string().contains("--map"));
+    }
+
+    #[test]
+    fn import_result_summary() {
+        let result = ImportResult {
+            imported: 2,
+            failed: 1,
+            errors: vec![ImportError {
+                line: Some(3),
+                error: "missing `_id`".to_string(),
+            }],
+        };
+        assert_eq!(result.to_string(), "Imported 2 documents (1 failed).");
+        assert_eq!(result.errors[0].to_string(), "line 3: missing `_id`");
+    }
+
+    #[test_context(CliTestContext)]
+    #[tokio::test]
+    async fn import_and_export(ctx: &mut CliTestContext) {
+        let name = ctx.wrap("docs");
+        Command::cargo_bin("topk")
+            .unwrap()
+            .args([
+                "collection",
+                "create",
+                "--region",
+                &ctx.region,
+                "--sql",
+                &format!("CREATE TABLE \"{name}\" (title TEXT NOT NULL, year INTEGER)"),
+            ])
+            .assert()
+            .success();
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("books.ndjson");
+        std::fs::write(
+            &input,
+            "{\"_id\": \"1\", \"title\": \"Dune\", \"year\": 1965}\n\
+             {\"_id\": \"2\", \"year\": 1815}\n",
+        )
+        .unwrap();
+
+        let out = Command::cargo_bin("topk")
+            .unwrap()
+            .args(["-o", "json", "docs", "import", "-c", &name])
+            .arg(&input)
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        let result: ImportResult = serde_json::from_slice(&out.stdout).unwrap();
+        assert_eq!((result.imported, result.failed), (1, 1));
+        assert_eq!(result.errors[0].line, Some(2));
+
+        let out = Command::cargo_bin("topk")
+            .unwrap()
+            .args(["docs", "export", "-c", &name, "--filter", "year > 1900"])
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        assert_eq!(
+            String::from_utf8(out.stdout).unwrap(),
+            "{\"_id\":\"1\",\"title\":\"Dune\",\"year\":1965}\n"
+        );
+    }
+}
@@ -0,0 +1,733 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use colored::Colorize;
+use comfy_table::{presets, Attribute, Cell, CellAlignment, Color, ContentArrangement, Table};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use terminal_size::{terminal_size, Width as TermWidth};
+use topk_rs::proto::v1::data::LogicalExpr;
+use topk_rs::{Client, Error};
+use topk_sql::Statement;
+
+use crate::collections::{CollectionRegionResolver, CollectionsClient};
+use crate::output::Output;
+use crate::sql::{projection, sql_error, SqlOutput, SqlSession};
+use crate::util::filter;
+
+#[derive(Debug, clap::Args)]
+pub struct EvalArgs {
+    /// Judgments file, JSON lines or a JSON array of {"query": ..., "relevant": [...]}
+    #[arg(value_name = "JUDGMENTS", required_unless_present = "compare")]
+    pub judgments: Option<PathBuf>,
+    /// Dataset to search (repeatable)
+    #[arg(short = 'd', long = "dataset", conflicts_with = "query_template")]
+    pub datasets: Vec<String>,
+    /// SQL query run for each judgment instead of search, with `{query}` and `{k}` placeholders
+    #[arg(long, value_name = "SQL")]
+    pub query_template: Option<String>,
+    /// Only search documents matching a SQL condition (e.g. "year >= 2023 and team = 'ml'")
+    #[arg(
+        long,
+        value_name = "FILTER",
+        value_parser = filter::parse_filter,
+        conflicts_with = "query_template"
+    )]
+    pub filter: Option<LogicalExpr>,
+    /// Number of results to evaluate per query
+    #[arg(short = 'k', long, default_value = "10")]
+    pub top_k: u32,
+    /// Compare with a previous `-o json` run, or compare two previous runs
+    #[arg(long, value_name = "RUN", num_args = 1..=2)]
+    pub compare: Vec<PathBuf>,
+    /// Mean metric drop tolerated by --compare before failing
+    #[arg(long, default_value = "0", requires = "compare")]
+    pub tolerance: f64,
+}
+
+/// A query with the relevance grades of its relevant documents or contents.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Judgment {
+    pub query: String,
+    pub relevant: Relevance,
+}
+
+/// Relevant ids, as a list of equally relevant ids or a map of ids to grades.
+///
+/// Ids are document ids, or content ids for finer-grained judgments.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Relevance {
+    Ids(Vec<String>),
+    Graded(BTreeMap<String, f64>),
+}
+
+impl Relevance {
+    fn grades(&self) -> HashMap<&str, f64> {
+        match self {
+            Relevance::Ids(ids) => ids.iter().map(|id| (id.as_str(), 1.0)).collect(),
+            Relevance::Graded(grades) => grades
+                .iter()
+                .filter(|(_, grade)| **grade > 0.0)
+                .map(|(id, grade)| (id.as_str(), *grade))
+                .collect(),
+        }
+    }
+}
+
+/// A retrieved document, in rank order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub doc_id: String,
+    pub content_id: Option<String>,
+}
+
+/// Retrieves the results evaluated for a query.
+#[async_trait(?Send)]
+pub trait Retriever {
+    async fn retrieve(&mut self, query: &str, top_k: u32) -> Result<Vec<Hit>, Error>;
+}
+
+/// Retrieves with `Client::search`.
+pub struct SearchRetriever {
+    pub client: Client,
+    pub datasets: Vec<String>,
+    pub filter: Option<LogicalExpr>,
+}
+
+#[async_trait(?Send)]
+impl Retriever for SearchRetriever {
+    async fn retrieve(&mut self, query: &str, top_k: u32) -> Result<Vec<Hit>, Error> {
+        self.client
+            .search(
+                query,
+                self.datasets.clone(),
+                top_k,
+                self.filter.clone(),
+                Vec::<String>::new(),
+            )
+            .await?
+            .map_ok(|result| Hit {
+                doc_id: result.doc_id,
+                content_id: Some(result.content_id),
+            })
+            .try_collect()
+            .await
+            .map_err(Error::from)
+    }
+}
+
+/// Retrieves the `_id`s of a collection query, see `EvalArgs::query_template`.
+pub struct SqlRetriever<C> {
+    session: SqlSession<C>,
+    template: String,
+}
+
+impl<C> SqlRetriever<C>
+where
+    C: CollectionsClient + CollectionRegionResolver,
+{
+    /// Errors unless `template` is a single `SELECT`.
+    pub fn new(session: SqlSession<C>, template: &str) -> Result<Self, Error> {
+        if !template.contains("{query}") {
+            return Err(Error::InvalidArgument(
+                "query template has no {query} placeholder".to_string(),
+            ));
+        }
+        render_template(template, "", 10)?;
+
+        Ok(Self {
+            session,
+            template: template.to_string(),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl<C> Retriever for SqlRetriever<C>
+where
+    C: CollectionsClient + CollectionRegionResolver,
+{
+    async fn retrieve(&mut self, query: &str, top_k: u32) -> Result<Vec<Hit>, Error> {
+        let (stmt, columns) = render_template(&self.template, query, top_k)?;
+
+        match self.session.execute(stmt, columns).await? {
+            SqlOutput::Rows { rows, .. } => rows
+                .iter()
+                .map(|doc| {
+                    let doc_id = doc
+                        .id()
+                        .map_err(|e| Error::InvalidArgument(e.to_string()))?;
+                    Ok(Hit {
+                        doc_id: doc_id.to_string(),
+                        content_id: None,
+                    })
+                })
+                .collect(),
+            _ => Err(Error::InvalidArgument(
+                "query template must be a SELECT".to_string(),
+            )),
+        }
+    }
+}
+
+/// The statement of `template` for `query`, with its output columns.
+fn render_template(
+    template: &str,
+    query: &str,
+    top_k: u32,
+) -> Result<(Statement, Vec<String>), Error> {
+    let sql = template
+        .replace("{query}", &format!("'{}'", query.replace('\'', "''")))
+        .replace("{k}", &top_k.to_string());
+
+    let mut stmts =
+        topk_sql::convert_sql(topk_sql::parse_sql(&sql).map_err(sql_error)?).map_err(sql_error)?;
+    match (stmts.pop(), stmts.is_empty()) {
+        (Some((stmt @ Statement::Select { .. }, raw)), true) => {
+            let columns = projection(raw.as_ref());
+            Ok((stmt, columns))
+        }
+        _ => Err(Error::InvalidArgument(
+            "query template must be a single SELECT".to_string(),
+        )),
+    }
+}
+
+/// Reads judgments from JSON lines or a JSON array.
+pub fn read_judgments(path: &Path) -> Result<Vec<Judgment>, Error> {
+    let text = std::fs::read_to_string(path)?;
+    let invalid = |e: serde_json::Error| {
+        Error::InvalidArgument(format!("invalid judgments in {}: {e}", path.display()))
+    };
+
+    let judgments: Vec<Judgment> = if text.trim_start().starts_with('[') {
+        serde_json::from_str(&text).map_err(invalid)?
+    } else {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(invalid)?
+    };
+
+    if judgments.is_empty() {
+        return Err(Error::InvalidArgument(format!(
+            "no judgments in {}",
+            path.display()
+        )));
+    }
+    if let Some(judgment) = judgments.iter().find(|j| j.relevant.grades().is_empty()) {
+        return Err(Error::InvalidArgument(format!(
+            "no relevant ids for query '{}'",
+            judgment.query
+        )));
+    }
+
+    Ok(judgments)
+}
+
+/// Retrieval metrics at the evaluated `k`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+    pub recall: f64,
+    pub precision: f64,
+    pub mrr: f64,
+    pub ndcg: f64,
+}
+
+impl Metrics {
+    const NAMES: [&'static str; 4] = ["recall", "precision", "mrr", "ndcg"];
+
+    fn values(&self) -> [f64; 4] {
+        [self.recall, self.precision, self.mrr, self.ndcg]
+    }
+
+    /// Metrics of `hits`, the top `k` results of a query judged by `relevance`.
+    ///
+    /// A hit matches a judgment by content id, or else by document id. Each relevant id counts
+    /// once, at its best rank, so that chunks of the same document aren't credited twice.
+    pub fn of(hits: &[Hit], relevance: &Relevance, k: u32) -> Self {
+        let grades = relevance.grades();
+        let k = k as usize;
+
+        let mut found = HashSet::new();
+        let mut first_rank = None;
+        let mut dcg = 0.0;
+        for (rank, hit) in hits.iter().take(k).enumerate() {
+            let id = match &hit.content_id {
+                Some(content_id) if grades.contains_key(content_id.as_str()) => content_id,
+                _ => &hit.doc_id,
+            };
+            let Some(grade) = grades.get(id.as_str()) else {
+                continue;
+            };
+            if !found.insert(id.as_str()) {
+                continue;
+            }
+
+            first_rank.get_or_insert(rank + 1);
+            dcg += gain(*grade, rank);
+        }
+
+        let mut ideal = grades.values().copied().collect::<Vec<_>>();
+        ideal.sort_by(|a, b| b.total_cmp(a));
+        let idcg = ideal
+            .iter()
+            .take(k)
+            .enumerate()
+            .map(|(rank, grade)| gain(*grade, rank))
+            .sum::<f64>();
+
+        Self {
+            recall: found.len() as f64 / grades.len() as f64,
+            precision: found.len() as f64 / k as f64,
+            mrr: first_rank.map_or(0.0, |rank| 1.0 / rank as f64),
+            ndcg: if idcg > 0.0 { dcg / idcg } else { 0.0 },
+        }
+    }
+
+    fn mean<'a>(metrics: impl IntoIterator<Item = &'a Metrics>) -> Self {
+        let mut sum = Self::default();
+        let mut n = 0;
+        for m in metrics {
+            sum.recall += m.recall;
+            sum.precision += m.precision;
+            sum.mrr += m.mrr;
+            sum.ndcg += m.ndcg;
+            n += 1;
+        }
+        if n == 0 {
+            return sum;
+        }
+
+        let n = n as f64;
+        Self {
+            recall: sum.recall / n,
+            precision: sum.precision / n,
+            mrr: sum.mrr / n,
+            ndcg: sum.ndcg / n,
+        }
+    }
+}
+
+/// Discounted gain of a result of `grade` at 0-based `rank`.
+fn gain(grade: f64, rank: usize) -> f64 {
+    (2f64.powf(grade) - 1.0) / (rank as f64 + 2.0).log2()
+}
+
+fn metric_header(name: &str, k: u32) -> String {
+    match name {
+        "mrr" => "MRR".to_string(),
+        "precision" => format!("P@{k}"),
+        name => format!("{}@{k}", name.to_uppercase()),
+    }
+}
+
+fn header(cells: impl IntoIterator<Item = String>) -> Vec<Cell> {
+    cells
+        .into_iter()
+        .map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Cyan))
+        .collect()
+}
+
+fn new_table() -> Table {
+    let term_width = terminal_size().map(|(TermWidth(w), _)| w).unwrap_or(80);
+
+    let mut table = Table::new();
+    table
+        .load_preset(presets::NOTHING)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_width(term_width);
+    table
+}
+
+fn number(value: f64) -> Cell {
+    Cell::new(format!("{value:.3}")).set_alignment(CellAlignment::Right)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryEval {
+    pub query: String,
+    #[serde(flatten)]
+    pub metrics: Metrics,
+    /// Document ids of the results, in rank order.
+    pub retrieved: Vec<String>,
+}
+
+/// Result of `topk eval`, and the baseline of `--compare`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EvalRun {
+    pub k: u32,
+    pub mean: Metrics,
+    pub queries: Vec<QueryEval>,
+}
+
+impl EvalRun {
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(|e| {
+            Error::InvalidArgument(format!(
+                "{} is not the output of `topk eval -o json`: {e}",
+                path.display()
+            ))
+        })
+    }
+}
+
+impl fmt::Display for EvalRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = new_table();
+        table.set_header(header(
+            std::iter::once("QUERY".to_string()).chain(
+                Metrics::NAMES
+                    .iter()
+                    .map(|name| metric_header(name, self.k)),
+            ),
+        ));
+
+        for query in &self.queries {
+            let mut row = vec![Cell::new(&query.query)];
+            row.extend(query.metrics.values().map(number));
+            table.add_row(row);
+        }
+        let mut row = vec![Cell::new("mean").add_attribute(Attribute::Bold)];
+        row.extend(
+            self.mean
+                .values()
+                .map(|v| number(v).add_attribute(Attribute::Bold)),
+        );
+        table.add_row(row);
+
+        f.write_str(&table.to_string())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryComparison {
+    pub query: String,
+    pub baseline: Option<Metrics>,
+    pub current: Option<Metrics>,
+}
+
+/// Result of `topk eval --compare`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EvalComparison {
+    pub k: u32,
+    pub baseline: Metrics,
+    pub current: Metrics,
+    /// Queries whose metrics changed, or that only one run has.
+    pub changed: Vec<QueryComparison>,
+    /// Mean metrics that dropped by more than the tolerance.
+    pub regressions: Vec<String>,
+}
+
+impl EvalComparison {
+    pub fn new(baseline: &EvalRun, current: &EvalRun, tolerance: f64) -> Result<Self, Error> {
+        if baseline.k != current.k {
+            return Err(Error::InvalidArgument(format!(
+                "cannot compare runs at k={} and k={}",
+                baseline.k, current.k
+            )));
+        }
+
+        let baseline_queries = baseline
+            .queries
+            .iter()
+            .map(|q| (q.query.as_str(), q.metrics))
+            .collect::<HashMap<_, _>>();
+        let current_queries = current
+            .queries
+            .iter()
+            .map(|q| (q.query.as_str(), q.metrics))
+            .collect::<HashMap<_, _>>();
+
+        let mut changed = Vec::new();
+        for query in baseline.queries.iter().chain(
+            current
+                .queries
+                .iter()
+                .filter(|q| !baseline_queries.contains_key(q.query.as_str())),
+        ) {
+            let before = baseline_queries.get(query.query.as_str()).copied();
+            let after = current_queries.get(query.query.as_str()).copied();
+            if before != after {
+                changed.push(QueryComparison {
+                    query: query.query.clone(),
+                    baseline: before,
+                    current: after,
+                });
+            }
+        }
+
+        let regressions = Metrics::NAMES
+            .iter()
+            .zip(baseline.mean.values().iter().zip(current.mean.values()))
+            .filter(|(_, (before, after))| after - *before < -tolerance - f64::EPSILON)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        Ok(Self {
+            k: current.k,
+            baseline: baseline.mean,
+            current: current.mean,
+            changed,
+            regressions,
+        })
+    }
+}
+
+fn delta(before: f64, after: f64) -> Cell {
+    let delta = after - before;
+    let text = format!("{delta:+.3}");
+    let cell = Cell::new(text).set_alignment(CellAlignment::Right);
+    if delta.abs() <= f64::EPSILON {
+        cell.add_attribute(Attribute::Dim)
+    } else if delta > 0.0 {
+        cell.fg(Color::Green)
+    } else {
+        cell.fg(Color::Red)
+    }
+}
+
+impl fmt::Display for EvalComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = new_table();
+        table.set_header(header(
+            ["METRIC", "BASELINE", "CURRENT", "DELTA"].map(String::from),
+        ));
+        for ((name, before), after) in Metrics::NAMES
+            .iter()
+            .zip(self.baseline.values())
+            .zip(self.current.values())
+        {
+            table.add_row([
+                Cell::new(metric_header(name, self.k)),
+                number(before),
+                number(after),
+                delta(before, after),
+            ]);
+        }
+        f.write_str(&table.to_string())?;
+
+        if !self.changed.is_empty() {
+            let ndcg = metric_header("ndcg", self.k);
+            let mut table = new_table();
+            table.set_header(header([
+                "QUERY".to_string(),
+                format!("BASELINE {ndcg}"),
+                format!("CURRENT {ndcg}"),
+                "DELTA".to_string(),
+            ]));
+            let na = || Cell::new("-").add_attribute(Attribute::Dim);
+            for query in &self.changed {
+                let before = query.baseline.map(|m| m.ndcg);
+                let after = query.current.map(|m| m.ndcg);
+                table.add_row([
+                    Cell::new(&query.query),
+                    before.map(number).unwrap_or_else(na),
+                    after.map(number).unwrap_or_else(na),
+                    match (before, after) {
+                        (Some(before), Some(after)) => delta(before, after),
+                        _ => na(),
+                    },
+                ]);
+            }
+            write!(f, "\n\n{}\n{table}", "Changed queries".bold())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// `topk eval`
+pub async fn run(
+    retriever: &mut impl Retriever,
+    judgments: &[Judgment],
+    top_k: u32,
+    output: &Output,
+) -> Result<EvalRun, Error> {
+    if top_k == 0 {
+        return Err(Error::InvalidArgument("-k must be at least 1".to_string()));
+    }
+
+    let spinner = output.spinner("Evaluating...");
+
+    let mut queries = Vec::with_capacity(judgments.len());
+    for (i, judgment) in judgments.iter().enumerate() {
+        spinner.set_message(format!("Evaluating... {}/{}", i + 1, judgments.len()));
+
+        let hits = retriever.retrieve(&judgment.query, top_k).await?;
+        queries.push(QueryEval {
+            query: judgment.query.clone(),
+            metrics: Metrics::of(&hits, &judgment.relevant, top_k),
+            retrieved: hits.into_iter().map(|hit| hit.doc_id).collect(),
+        });
+    }
+
+    spinner.finish();
+
+    Ok(EvalRun {
+        k: top_k,
+        mean: Metrics::mean(queries.iter().map(|q| &q.metrics)),
+        queries,
+    })
+}
+
+/// Errors with the regressed metrics of `comparison`, after it has been printed.
+pub fn check_regressions(comparison: &EvalComparison) -> Result<(), Error> {
+    if comparison.regressions.is_empty() {
+        return Ok(());
+    }
+
+    Err(Error::Input(anyhow::anyhow!(
+        "retrieval quality regressed: {}",
+        comparison
+            .regressions
+            .iter()
+            .map(|name| metric_header(name, comparison.k))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hits(ids: &[&str]) -> Vec<Hit> {
+        ids.iter()
+            .map(|id| Hit {
+                doc_id: id.to_string(),
+                content_id: None,
+            })
+            .collect()
+    }
+
+    fn ids(ids: &[&str]) -> Relevance {
+        Relevance::Ids(ids.iter().map(|id| id.to_string()).collect())
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn binary_metrics() {
+        let m = Metrics::of(&hits(&["a", "x", "b", "y"]), &ids(&["a", "b", "c"]), 4);
+
+        assert_close(m.recall, 2.0 / 3.0);
+        assert_close(m.precision, 0.5);
+        assert_close(m.mrr, 1.0);
+        let dcg = 1.0 + 1.0 / 4f64.log2();
+        let idcg = 1.0 + 1.0 / 3f64.log2() + 1.0 / 4f64.log2();
+        assert_close(m.ndcg, dcg / idcg);
+
+        let m = Metrics::of(&hits(&["x", "y", "a"]), &ids(&["a"]), 2);
+        assert_eq!(m, Metrics::default());
+    }
+
+    #[test]
+    fn graded_metrics_credit_each_id_once() {
+        let relevance = Relevance::Graded(BTreeMap::from([
+            ("a".to_string(), 1.0),
+            ("b".to_string(), 3.0),
+            ("c".to_string(), 0.0),
+        ]));
+        let m = Metrics::of(&hits(&["b", "b", "c", "a"]), &relevance, 4);
+
+        assert_close(m.recall, 1.0);
+        assert_close(m.precision, 0.5);
+        assert_close(m.mrr, 1.0);
+        let dcg = 7.0 + 1.0 / 5f64.log2();
+        let idcg = 7.0 + 1.0 / 3f64.log2();
+        assert_close(m.ndcg, dcg / idcg);
+
+        // Content ids take precedence over document ids.
+        let hit = Hit {
+            doc_id: "doc".to_string(),
+            content_id: Some("chunk-2".to_string()),
+        };
+        assert_eq!(Metrics::of(&[hit], &ids(&["chunk-1"]), 1).recall, 0.0);
+    }
+
+    fn run_of(k: u32, queries: &[(&str, f64)]) -> EvalRun {
+        let queries = queries
+            .iter()
+            .map(|(query, ndcg)| QueryEval {
+                query: query.to_string(),
+                metrics: Metrics {
+                    ndcg: *ndcg,
+                    ..Default::default()
+                },
+                retrieved: vec![],
+            })
+            .collect::<Vec<_>>();
+        EvalRun {
+            k,
+            mean: Metrics::mean(queries.iter().map(|q| &q.metrics)),
+            queries,
+        }
+    }
+
+    #[test]
+    fn compare_reports_changes_and_regressions() {
+        let baseline = run_of(10, &[("a", 1.0), ("b", 0.5), ("c", 0.2)]);
+        let current = run_of(10, &[("a", 1.0), ("b", 0.25), ("d", 0.0)]);
+
+        let comparison = EvalComparison::new(&baseline, &current, 0.0).unwrap();
+        let changed = comparison
+            .changed
+            .iter()
+            .map(|q| q.query.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(changed, vec!["b", "c", "d"]);
+        assert_eq!(comparison.regressions, vec!["ndcg"]);
+        assert!(check_regressions(&comparison).is_err());
+
+        let comparison = EvalComparison::new(&baseline, &current, 0.5).unwrap();
+        assert!(comparison.regressions.is_empty());
+
+        assert!(EvalComparison::new(&baseline, &run_of(5, &[]), 0.0).is_err());
+    }
+
+    #[test]
+    fn judgments_from_json_lines_and_arrays() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let lines = dir.path().join("judgments.jsonl");
+        std::fs::write(
+            &lines,
+            "{\"query\": \"q1\", \"relevant\": [\"a\"]}\n\n{\"query\": \"q2\", \"relevant\": {\"b\": 2}}\n",
+        )
+        .unwrap();
+        let judgments = read_judgments(&lines).unwrap();
+        assert_eq!(judgments.len(), 2);
+        assert_eq!(judgments[1].relevant.grades(), HashMap::from([("b", 2.0)]));
+
+        let array = dir.path().join("judgments.json");
+        std::fs::write(&array, r#"[{"query": "q1", "relevant": ["a"]}]"#).unwrap();
+        assert_eq!(read_judgments(&array).unwrap().len(), 1);
+
+        std::fs::write(&array, r#"[{"query": "q1", "relevant": {"a": 0}}]"#).unwrap();
+        assert!(read_judgments(&array).is_err());
+    }
+
+    #[test]
+    fn query_template_must_be_a_select() {
+        let (stmt, _) = render_template(
+            "SELECT _id, bm25_score() AS s FROM books WHERE match({query}) ORDER BY s DESC LIMIT {k}",
+            "it's",
+            5,
+        )
+        .unwrap();
+        assert!(matches!(stmt, Statement::Select { .. }));
+
+        assert!(render_template("DELETE FROM books WHERE title = {query}", "q", 5).is_err());
+    }
+}
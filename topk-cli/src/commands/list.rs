@@ -11,7 +11,9 @@ use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use terminal_size::{terminal_size, Width as TermWidth};
 use topk_rs::json::Value;
-use topk_rs::{Client, Error};
+use topk_rs::{proto::v1::data::LogicalExpr, Client, Error};
+
+use crate::util::filter;
 
 #[derive(Serialize, Deserialize)]
 pub struct ListEntry {
@@ -190,6 +192,9 @@ pub struct ListArgs {
     /// Dataset to list documents from
     #[arg(short = 'd', long, value_name = "DATASET_NAME")]
     pub dataset: String,
+    /// Only list documents matching a SQL condition (e.g. "year >= 2023 and team = 'ml'")
+    #[arg(long, value_name = "FILTER", value_parser = filter::parse_filter)]
+    pub filter: Option<LogicalExpr>,
     /// Metadata fields to include (repeatable)
     #[arg(short = 'f', long = "field")]
     pub fields: Option<Vec<String>>,
@@ -202,7 +207,7 @@ pub async fn run(
 ) -> Result<impl Stream<Item = Result<ListEntry, Error>>, Error> {
     Ok(client
         .dataset(&args.dataset)
-        .list(args.fields.clone(), None)
+        .list(args.fields.clone(), args.filter.clone(), None)
         .await?
         .map(|entry| entry.map_err(Error::from).map(ListEntry::from)))
 }
@@ -0,0 +1,23 @@
+use tokio::io::{stdin, stdout, BufReader};
+use topk_rs::Error;
+
+use crate::datasets::{DatasetRegionResolver, DatasetsClient};
+use crate::mcp::McpServer;
+
+#[derive(Debug, clap::Args)]
+pub struct McpArgs {
+    /// Don't expose the `upload` tool
+    #[arg(long)]
+    pub read_only: bool,
+}
+
+/// `topk mcp`
+///
+/// Serves MCP over stdin and stdout until the client closes stdin. Nothing else may be written to
+/// stdout while serving.
+pub async fn run<D>(mut server: McpServer<D>) -> Result<(), Error>
+where
+    D: DatasetsClient + DatasetRegionResolver,
+{
+    server.serve(BufReader::new(stdin()), stdout()).await
+}
@@ -1,9 +1,16 @@
 pub mod ask;
+pub mod collection;
 pub mod dataset;
 pub mod delete;
+pub mod docs;
+pub mod eval;
 pub mod list;
 pub mod login;
+pub mod mcp;
+pub mod profile;
 pub mod search;
+pub mod sql;
+pub mod sync;
 pub mod upload;
 
 #[cfg(test)]
@@ -92,6 +99,25 @@ pub mod test_context {
                 }
             }
         }
+
+        async fn cleanup_collections(&self) {
+            let client = self.client.collections();
+            let collections = match client.list().await {
+                Ok(r) => r,
+                Err(e) => {
+                    println!("Failed to list collections on teardown: {}", e);
+                    return;
+                }
+            };
+            for collection in &collections {
+                if collection.name.starts_with(&self.scope) {
+                    println!("Deleting collection: {}", collection.name);
+                    if let Err(e) = client.delete(&collection.name).await {
+                        println!("Failed to delete collection {}: {}", collection.name, e);
+                    }
+                }
+            }
+        }
     }
 
     impl AsyncTestContext for CliTestContext {
@@ -115,6 +141,7 @@ pub mod test_context {
 
         async fn teardown(self) {
             self.cleanup_datasets().await;
+            self.cleanup_collections().await;
         }
     }
 }
@@ -0,0 +1,360 @@
+use std::fmt;
+
+use colored::Colorize;
+use comfy_table::{presets, Attribute, Cell, Color, ContentArrangement, Table};
+use serde::{Deserialize, Serialize};
+use terminal_size::{terminal_size, Width as TermWidth};
+use topk_rs::Error;
+
+use crate::config::{Config, Profile};
+use crate::output::Output;
+
+#[derive(Debug, clap::Args)]
+pub struct AddProfileArgs {
+    /// Profile name
+    #[arg(value_name = "PROFILE", value_parser = parse_profile_name)]
+    pub name: String,
+    /// Host of the TopK API (default: topk.io)
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Whether to connect over HTTPS (default: true)
+    #[arg(long)]
+    pub https: Option<bool>,
+    /// Dataset of commands run without --dataset
+    #[arg(short = 'd', long)]
+    pub dataset: Option<String>,
+    /// Region of datasets and collections created without --region
+    #[arg(short = 'r', long)]
+    pub region: Option<String>,
+    /// Make this profile the default for later commands
+    #[arg(long = "use")]
+    pub use_profile: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct RemoveProfileArgs {
+    /// Profile name
+    #[arg(value_name = "PROFILE")]
+    pub name: String,
+    /// Skip confirmation prompt
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+}
+
+/// `topk profile`
+#[derive(Debug, clap::Subcommand)]
+pub enum ProfileAction {
+    /// Add a profile, or update the settings of an existing one
+    Add(AddProfileArgs),
+    /// List profiles
+    List,
+    /// Use a profile for later commands run without --profile
+    Use {
+        /// Profile name
+        #[arg(value_name = "PROFILE")]
+        name: String,
+    },
+    /// Remove a profile and its cached data
+    Remove(RemoveProfileArgs),
+}
+
+/// Profile names are used as directory names for cached data.
+fn parse_profile_name(name: &str) -> Result<String, String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "invalid profile name '{name}', use letters, digits, '-' and '_'"
+        ));
+    }
+    Ok(name.to_string())
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProfileEntry {
+    pub name: String,
+    pub active: bool,
+    pub authenticated: bool,
+    pub host: Option<String>,
+    pub https: Option<bool>,
+    pub dataset: Option<String>,
+    pub region: Option<String>,
+}
+
+impl ProfileEntry {
+    fn new(name: &str, profile: Profile, active: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            active,
+            authenticated: profile.api_key.is_some(),
+            host: profile.host,
+            https: profile.https,
+            dataset: profile.dataset,
+            region: profile.region,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ListProfilesResult {
+    pub profiles: Vec<ProfileEntry>,
+}
+
+impl fmt::Display for ListProfilesResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.profiles.is_empty() {
+            return f.write_str("No profiles found. Run `topk profile add` or `topk login`.");
+        }
+
+        let term_width = terminal_size().map(|(TermWidth(w), _)| w).unwrap_or(80);
+
+        let mut table = Table::new();
+        table
+            .load_preset(presets::NOTHING)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(term_width)
+            .set_header(
+                ["", "NAME", "HOST", "DATASET", "REGION"]
+                    .iter()
+                    .map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Cyan)),
+            );
+
+        let na = || Cell::new("-").add_attribute(Attribute::Dim);
+        for p in &self.profiles {
+            let mut name = Cell::new(&p.name);
+            if !p.authenticated {
+                name = Cell::new(format!("{} {}", p.name, "(logged out)".dimmed()));
+            }
+            table.add_row([
+                Cell::new(if p.active { "*" } else { "" }).fg(Color::Green),
+                name,
+                p.host.as_deref().map(Cell::new).unwrap_or_else(na),
+                p.dataset.as_deref().map(Cell::new).unwrap_or_else(na),
+                p.region.as_deref().map(Cell::new).unwrap_or_else(na),
+            ]);
+        }
+
+        f.write_str(&table.to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AddProfileResult {
+    pub name: String,
+    pub created: bool,
+    pub active: bool,
+}
+
+impl fmt::Display for AddProfileResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = if self.created { "added" } else { "updated" };
+        write!(f, "Profile '{}' {action}.", self.name)?;
+        if self.active {
+            write!(f, " Now using profile '{}'.", self.name)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UseProfileResult {
+    pub name: String,
+}
+
+impl fmt::Display for UseProfileResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Now using profile '{}'.", self.name)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RemoveProfileResult {
+    pub removed: bool,
+}
+
+impl fmt::Display for RemoveProfileResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.removed {
+            f.write_str("Profile removed.")
+        } else {
+            f.write_str("Removal skipped.")
+        }
+    }
+}
+
+fn not_found(name: &str) -> Error {
+    Error::InvalidArgument(format!(
+        "profile '{name}' not found, run `topk profile list` to see profiles"
+    ))
+}
+
+/// `topk profile add`
+pub fn add(
+    config: &mut Config,
+    args: &AddProfileArgs,
+    api_key: Option<String>,
+) -> Result<AddProfileResult, Error> {
+    let created = !config.has_profile(&args.name);
+
+    let profile = config.profile_mut(&args.name);
+    if let Some(api_key) = api_key {
+        profile.api_key = Some(api_key);
+    }
+    if let Some(host) = &args.host {
+        profile.host = Some(host.clone());
+    }
+    if let Some(https) = args.https {
+        profile.https = Some(https);
+    }
+    if let Some(dataset) = &args.dataset {
+        profile.dataset = Some(dataset.clone());
+    }
+    if let Some(region) = &args.region {
+        profile.region = Some(region.clone());
+    }
+
+    if args.use_profile {
+        config.current_profile = Some(args.name.clone());
+    }
+
+    Ok(AddProfileResult {
+        name: args.name.clone(),
+        created,
+        active: args.use_profile,
+    })
+}
+
+/// `topk profile list`
+pub fn list(config: &Config, active: &str) -> ListProfilesResult {
+    ListProfilesResult {
+        profiles: config
+            .profile_names()
+            .into_iter()
+            .map(|name| ProfileEntry::new(&name, config.profile(&name), name == active))
+            .collect(),
+    }
+}
+
+/// `topk profile use`
+pub fn use_profile(config: &mut Config, name: &str) -> Result<UseProfileResult, Error> {
+    if !config.has_profile(name) {
+        return Err(not_found(name));
+    }
+
+    config.current_profile = Some(name.to_string());
+
+    Ok(UseProfileResult {
+        name: name.to_string(),
+    })
+}
+
+/// `topk profile remove`
+pub fn remove(
+    config: &mut Config,
+    args: &RemoveProfileArgs,
+    output: &Output,
+) -> Result<RemoveProfileResult, Error> {
+    if !config.has_profile(&args.name) {
+        return Err(not_found(&args.name));
+    }
+
+    if !output.confirm_or_yes(&format!("Remove profile '{}'? ", args.name), args.yes)? {
+        return Ok(RemoveProfileResult { removed: false });
+    }
+
+    config.remove_profile(&args.name);
+
+    Ok(RemoveProfileResult { removed: true })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_PROFILE;
+
+    fn add_args(name: &str) -> AddProfileArgs {
+        AddProfileArgs {
+            name: name.to_string(),
+            host: None,
+            https: None,
+            dataset: None,
+            region: None,
+            use_profile: false,
+        }
+    }
+
+    #[test]
+    fn add_updates_only_given_settings() {
+        let mut config = Config::default();
+
+        let args = AddProfileArgs {
+            host: Some("staging.topk.io".to_string()),
+            dataset: Some("docs".to_string()),
+            ..add_args("staging")
+        };
+        assert!(
+            add(&mut config, &args, Some("key".to_string()))
+                .unwrap()
+                .created
+        );
+
+        let args = AddProfileArgs {
+            region: Some("aws-us-east-1-elastica".to_string()),
+            use_profile: true,
+            ..add_args("staging")
+        };
+        let result = add(&mut config, &args, None).unwrap();
+        assert!(!result.created);
+        assert!(result.active);
+
+        assert_eq!(
+            config.profile("staging"),
+            Profile {
+                api_key: Some("key".to_string()),
+                host: Some("staging.topk.io".to_string()),
+                https: None,
+                dataset: Some("docs".to_string()),
+                region: Some("aws-us-east-1-elastica".to_string()),
+            }
+        );
+        assert_eq!(config.profile_name(None), "staging");
+    }
+
+    #[test]
+    fn list_marks_active_profile() {
+        let mut config: Config = toml::from_str("api_key = 'legacy'").unwrap();
+        add(&mut config, &add_args("prod"), None).unwrap();
+
+        let result = list(&config, "prod");
+        let names = result
+            .profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.active, p.authenticated))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![(DEFAULT_PROFILE, false, true), ("prod", true, false)]
+        );
+    }
+
+    #[test]
+    fn use_requires_existing_profile() {
+        let mut config = Config::default();
+        assert!(use_profile(&mut config, "prod").is_err());
+
+        add(&mut config, &add_args("prod"), None).unwrap();
+        use_profile(&mut config, "prod").unwrap();
+        assert_eq!(config.current_profile.as_deref(), Some("prod"));
+    }
+
+    #[test]
+    fn profile_names_are_path_safe() {
+        assert!(parse_profile_name("prod-eu_1").is_ok());
+        assert!(parse_profile_name("").is_err());
+        assert!(parse_profile_name("../prod").is_err());
+        assert!(parse_profile_name("a/b").is_err());
+    }
+}
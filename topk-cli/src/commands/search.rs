@@ -5,9 +5,9 @@ use futures::TryStreamExt;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use topk_rs::json::Value;
-use topk_rs::{Client, Error};
+use topk_rs::{proto::v1::data::LogicalExpr, Client, Error};
 
-use crate::util::{mime::MimeType, read_query_from_stdin, Base64};
+use crate::util::{filter, mime::MimeType, read_query_from_stdin, Base64};
 
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(transparent)]
@@ -119,6 +119,9 @@ pub struct SearchArgs {
     /// Dataset to search (repeatable)
     #[arg(short = 'd', long = "dataset")]
     pub datasets: Vec<String>,
+    /// Only search documents matching a SQL condition (e.g. "year >= 2023 and team = 'ml'")
+    #[arg(long, value_name = "FILTER", value_parser = filter::parse_filter)]
+    pub filter: Option<LogicalExpr>,
     /// Number of results to return
     #[arg(short = 'k', long, default_value = "10")]
     pub top_k: u32,
@@ -143,7 +146,7 @@ pub async fn run(client: &Client, args: &SearchArgs) -> Result<SearchResults, Er
                 query,
                 args.datasets.clone(),
                 args.top_k,
-                None,
+                args.filter.clone(),
                 args.fields.clone().unwrap_or_default(),
             )
             .await?
@@ -391,12 +394,13 @@ mod tests {
                     ("title", Value::string("My Test Document")),
                     ("author", Value::string("Test Author")),
                 ],
+                None,
             )
             .await
             .unwrap();
         ctx.client
             .dataset(&dataset)
-            .wait_for_handle(&upload, None)
+            .wait_for_handle(&upload, None, None)
             .await
             .unwrap();
 
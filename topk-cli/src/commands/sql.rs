@@ -0,0 +1,514 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use comfy_table::{presets, Attribute, Cell, Color, ContentArrangement, Table};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use terminal_size::{terminal_size, Width as TermWidth};
+use topk_rs::proto::v1::data::{Document, Value};
+use topk_rs::Error;
+use topk_sql::Statement;
+
+use crate::collections::{CollectionRegionResolver, CollectionsClient};
+use crate::commands::collection::{Collection, ListCollectionsResult, SchemaTable};
+use crate::output::{is_broken_pipe, Output, OutputFormat};
+use crate::sql::{projection, sql_error, SqlOutput, SqlSession};
+use crate::util::plural;
+use crate::util::repl::{Lines, Repl, Script};
+
+#[derive(Debug, clap::Args)]
+pub struct SqlArgs {
+    /// Execute the given statements and exit
+    #[arg(short = 'c', long, value_name = "SQL", conflicts_with = "file")]
+    pub command: Option<String>,
+    /// Execute the statements of a script file and exit
+    #[arg(short = 'f', long, value_name = "FILE")]
+    pub file: Option<PathBuf>,
+    /// Region of collections created with `CREATE TABLE`
+    #[arg(short = 'r', long)]
+    pub region: Option<String>,
+    /// Print the execution time of each statement
+    #[arg(long)]
+    pub timing: bool,
+}
+
+const HELP: &str = r"\d                list collections
+\d COLLECTION     describe a collection
+\timing [on|off]  toggle statement timing
+\?                show this help
+\q                quit
+
+Statements end with `;` and may span multiple lines.";
+
+/// `topk sql`
+pub async fn run<C>(session: SqlSession<C>, args: &SqlArgs, output: &Output) -> Result<(), Error>
+where
+    C: CollectionsClient + CollectionRegionResolver,
+{
+    let mut shell = Shell {
+        session,
+        output,
+        timing: args.timing,
+        copy_from_stdin: false,
+    };
+
+    if let Some(command) = &args.command {
+        // As with `psql -c`, `COPY … FROM STDIN` reads the process input.
+        shell.copy_from_stdin = true;
+        return shell.run(&mut Script::new(command), false).await;
+    }
+
+    if let Some(path) = &args.file {
+        let script = std::fs::read_to_string(path)?;
+        return shell.run(&mut Script::new(&script), false).await;
+    }
+
+    if !std::io::stdin().is_terminal() {
+        let mut script = String::new();
+        std::io::stdin().read_to_string(&mut script)?;
+        return shell.run(&mut Script::new(&script), false).await;
+    }
+
+    let mut repl = Repl::new("sql_history")?;
+    output.meta("Type \\? for help, \\q to quit.");
+    let result = shell.run(&mut repl, true).await;
+    repl.save();
+    result
+}
+
+struct Shell<'a, C> {
+    session: SqlSession<C>,
+    output: &'a Output,
+    timing: bool,
+    /// Read `COPY … FROM STDIN` data from the process input instead of the next lines.
+    copy_from_stdin: bool,
+}
+
+impl<C> Shell<'_, C>
+where
+    C: CollectionsClient + CollectionRegionResolver,
+{
+    /// Executes statements and meta-commands read from `lines`. Errors end a script, but only
+    /// the failed statement of an interactive session.
+    async fn run(&mut self, lines: &mut dyn Lines, interactive: bool) -> Result<(), Error> {
+        let mut buf = String::new();
+
+        loop {
+            let prompt = if buf.is_empty() { "topk=> " } else { "topk-> " };
+            let Some(line) = lines.read_line(prompt)? else {
+                break;
+            };
+
+            let result = if buf.is_empty() && line.trim_start().starts_with('\\') {
+                let command = line.trim();
+                lines.add_history(command);
+                match self.meta(command).await {
+                    Ok(true) => return Ok(()),
+                    result => result.map(|_| ()),
+                }
+            } else {
+                if buf.is_empty() && line.trim().is_empty() {
+                    continue;
+                }
+                buf.push_str(&line);
+                buf.push('\n');
+                if !statement_complete(&buf) {
+                    continue;
+                }
+
+                let sql = std::mem::take(&mut buf);
+                lines.add_history(sql.trim_end());
+                self.execute(&sql, lines, interactive).await
+            };
+
+            match result {
+                Ok(()) => {}
+                Err(err) if interactive && !is_broken_pipe(&err) => self.output.error(&err),
+                Err(err) => return Err(err),
+            }
+        }
+
+        // Trailing statement without `;`, eg. `topk sql -c "SELECT …"`.
+        if !buf.trim().is_empty() {
+            self.execute(&buf, lines, interactive).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn execute(
+        &mut self,
+        sql: &str,
+        lines: &mut dyn Lines,
+        interactive: bool,
+    ) -> Result<(), Error> {
+        let stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql).map_err(sql_error)?)
+            .map_err(sql_error)?;
+
+        for (stmt, raw) in stmts {
+            let columns = projection(raw.as_ref());
+
+            let (out, elapsed) = match stmt {
+                Statement::CopyFrom {
+                    table,
+                    columns,
+                    options,
+                } => {
+                    let data = self.copy_data(lines, interactive)?;
+                    let start = Instant::now();
+                    let out = self
+                        .session
+                        .copy_from(table, columns, options, &data)
+                        .await?;
+                    (out, start.elapsed())
+                }
+                stmt => {
+                    let start = Instant::now();
+                    let out = self.session.execute(stmt, columns).await?;
+                    (out, start.elapsed())
+                }
+            };
+
+            self.print(out)?;
+            if self.timing {
+                self.output
+                    .meta(&format!("Time: {:.3} ms", elapsed.as_secs_f64() * 1000.0));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads `COPY … FROM STDIN` data, up to a `\.` line or the end of the input.
+    fn copy_data(&mut self, lines: &mut dyn Lines, interactive: bool) -> Result<Vec<u8>, Error> {
+        let mut data = Vec::new();
+        if self.copy_from_stdin {
+            std::io::stdin().read_to_end(&mut data)?;
+            return Ok(data);
+        }
+
+        if interactive {
+            self.output.meta(
+                "Enter data to be copied followed by a newline.\n\
+                 End with a backslash and a period on a line by itself.",
+            );
+        }
+        while let Some(line) = lines.read_line(">> ")? {
+            if line == "\\." {
+                break;
+            }
+            data.extend_from_slice(line.as_bytes());
+            data.push(b'\n');
+        }
+        Ok(data)
+    }
+
+    /// Runs a `\` meta-command. Returns whether the shell should quit.
+    async fn meta(&mut self, command: &str) -> Result<bool, Error> {
+        let (name, arg) = match command.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, Some(arg.trim())),
+            None => (command, None),
+        };
+
+        match (name, arg) {
+            ("\\q", None) => return Ok(true),
+            ("\\?", None) => self.output.meta(HELP),
+            ("\\d", None) => {
+                let result = ListCollectionsResult::from(self.session.list_collections().await?);
+                match self.output.format {
+                    OutputFormat::Json => {
+                        for collection in &result.collections {
+                            self.output.print_json_line(collection)?;
+                        }
+                    }
+                    OutputFormat::Text => println!("{result}"),
+                }
+            }
+            ("\\d", Some(name)) => {
+                let collection = Collection::from(self.session.get_collection(name).await?);
+                match self.output.format {
+                    OutputFormat::Json => self.output.print_json_line(&collection)?,
+                    OutputFormat::Text => println!(
+                        "Collection \"{}\" ({})\n{}",
+                        collection.name,
+                        collection.region,
+                        SchemaTable(&collection.schema)
+                    ),
+                }
+            }
+            ("\\timing", arg) => {
+                self.timing = match arg.map(str::to_ascii_lowercase).as_deref() {
+                    None => !self.timing,
+                    Some("on") => true,
+                    Some("off") => false,
+                    Some(arg) => {
+                        return Err(Error::InvalidArgument(format!(
+                            "\\timing: expected on or off, got '{arg}'"
+                        )))
+                    }
+                };
+                let state = if self.timing { "on" } else { "off" };
+                self.output.meta(&format!("Timing is {state}."));
+            }
+            _ => {
+                return Err(Error::InvalidArgument(format!(
+                    "invalid command {command}, try \\? for help"
+                )))
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn print(&self, out: SqlOutput) -> Result<(), Error> {
+        match (out, self.output.format) {
+            (SqlOutput::Rows { columns, rows }, OutputFormat::Text) => {
+                println!("{}", RowsTable::new(columns, &rows));
+            }
+            (SqlOutput::Rows { columns, rows }, OutputFormat::Json) => {
+                let columns = columns_or_fields(columns, &rows);
+                for row in &rows {
+                    self.output.print_json_line(&JsonRow(&columns, row))?;
+                }
+            }
+            (SqlOutput::Text { text, .. }, OutputFormat::Text) => println!("{text}"),
+            (SqlOutput::Text { column, text }, OutputFormat::Json) => {
+                // `EXPLAIN (FORMAT JSON)` is nested as is.
+                let value = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+                self.output
+                    .print_json_line(&BTreeMap::from([(column, value)]))?;
+            }
+            (SqlOutput::Copy(data), _) => {
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(&data)?;
+                stdout.flush()?;
+            }
+            (SqlOutput::Tag(tag), OutputFormat::Text) => println!("{tag}"),
+            (SqlOutput::Tag(_), OutputFormat::Json) => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether `sql` ends with a `;` outside of quotes and comments.
+fn statement_complete(sql: &str) -> bool {
+    let mut chars = sql.chars().peekable();
+    let mut complete = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                complete = false;
+                // A doubled quote closes and reopens the literal.
+                if !chars.by_ref().any(|q| q == c) {
+                    return false;
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                if !chars.by_ref().any(|c| {
+                    let end = prev == '*' && c == '/';
+                    prev = c;
+                    end
+                }) {
+                    return false;
+                }
+            }
+            ';' => complete = true,
+            c if c.is_whitespace() => {}
+            _ => complete = false,
+        }
+    }
+
+    complete
+}
+
+/// Output columns, or the fields of the rows (`_id` first) if the query did not name them.
+fn columns_or_fields(columns: Vec<String>, rows: &[Document]) -> Vec<String> {
+    if !columns.is_empty() {
+        return columns;
+    }
+
+    let mut fields = rows
+        .iter()
+        .flat_map(|row| row.fields.keys())
+        .filter(|field| *field != "_id")
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    if rows.iter().any(|row| row.fields.contains_key("_id")) {
+        fields.insert(0, "_id".to_string());
+    }
+    fields
+}
+
+/// Renders a value as a table cell: strings as is, `NULL` as empty, others as JSON.
+fn render_value(value: &Value) -> String {
+    match serde_json::to_value(topk_rs::json::Value(value.clone())) {
+        Ok(serde_json::Value::Null) => String::new(),
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(value) => value.to_string(),
+        Err(_) => format!("{value:?}"),
+    }
+}
+
+/// Query rows as a table, followed by the row count.
+struct RowsTable<'a> {
+    columns: Vec<String>,
+    rows: &'a [Document],
+}
+
+impl<'a> RowsTable<'a> {
+    fn new(columns: Vec<String>, rows: &'a [Document]) -> Self {
+        Self {
+            columns: columns_or_fields(columns, rows),
+            rows,
+        }
+    }
+}
+
+impl fmt::Display for RowsTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let term_width = terminal_size().map(|(TermWidth(w), _)| w).unwrap_or(80);
+
+        let mut table = Table::new();
+        table
+            .load_preset(presets::ASCII_HORIZONTAL_ONLY)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(term_width)
+            .set_header(
+                self.columns
+                    .iter()
+                    .map(|c| Cell::new(c).add_attribute(Attribute::Bold).fg(Color::Cyan)),
+            );
+
+        for row in self.rows {
+            table.add_row(self.columns.iter().map(|c| match row.fields.get(c) {
+                Some(value) => Cell::new(render_value(value)),
+                None => Cell::new(""),
+            }));
+        }
+
+        let n = self.rows.len();
+        if !self.columns.is_empty() {
+            writeln!(f, "{table}")?;
+        }
+        write!(f, "({n} {})", plural(n, "row", "rows"))
+    }
+}
+
+/// Row serialized as a JSON object with the fields in column order.
+struct JsonRow<'a>(&'a [String], &'a Document);
+
+impl Serialize for JsonRow<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for column in self.0 {
+            let value = self
+                .1
+                .fields
+                .get(column)
+                .cloned()
+                .unwrap_or_else(Value::null);
+            map.serialize_entry(column, &topk_rs::json::Value(value))?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_context::{CliTestContext, OutputJsonExt};
+    use assert_cmd::Command;
+    use test_context::test_context;
+
+    #[test]
+    fn statement_complete_ignores_quotes_and_comments() {
+        assert!(statement_complete("SELECT 1;"));
+        assert!(statement_complete("SELECT 1; -- done\n"));
+        assert!(statement_complete("SELECT 1; /* done */"));
+        assert!(statement_complete("SELECT 'a;b', \"c;\" FROM t;\n"));
+        assert!(statement_complete("SELECT 'it''s';"));
+
+        assert!(!statement_complete("SELECT 1"));
+        assert!(!statement_complete("SELECT 'a;"));
+        assert!(!statement_complete("SELECT 1 -- ;"));
+        assert!(!statement_complete("SELECT 1 /* ; */"));
+        assert!(!statement_complete("SELECT 1; SELECT 2"));
+    }
+
+    #[test]
+    fn rows_render_in_column_order() {
+        let rows = vec![
+            Document::from([
+                ("_id", Value::string("1")),
+                ("title", Value::string("Dune")),
+                ("rating", Value::f64(4.5)),
+            ]),
+            Document::from([("_id", Value::string("2")), ("rating", Value::null())]),
+        ];
+        let columns = vec!["title".to_string(), "_id".to_string()];
+
+        let json = rows
+            .iter()
+            .map(|row| serde_json::to_string(&JsonRow(&columns, row)).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            json,
+            [
+                r#"{"title":"Dune","_id":"1"}"#,
+                r#"{"title":null,"_id":"2"}"#
+            ]
+        );
+
+        assert_eq!(columns_or_fields(vec![], &rows), ["_id", "rating", "title"]);
+
+        colored::control::set_override(false);
+        let rendered = RowsTable::new(columns, &rows).to_string();
+        assert!(rendered.contains("Dune"), "{rendered}");
+        assert!(rendered.ends_with("(2 rows)"), "{rendered}");
+    }
+
+    #[test]
+    fn render_value_formats_cells() {
+        assert_eq!(render_value(&Value::string("Dune")), "Dune");
+        assert_eq!(render_value(&Value::null()), "");
+        assert_eq!(render_value(&Value::i64(42)), "42");
+        assert_eq!(render_value(&Value::bool(true)), "true");
+    }
+
+    #[test_context(CliTestContext)]
+    #[tokio::test]
+    async fn insert_and_select(ctx: &mut CliTestContext) {
+        let name = ctx.wrap("books");
+        let script = format!(
+            "CREATE TABLE \"{name}\" (title TEXT NOT NULL);
+             INSERT INTO \"{name}\" (_id, title) VALUES ('1', 'Dune'), ('2', 'Emma');
+             SELECT _id, title FROM \"{name}\" WHERE title = 'Dune';"
+        );
+
+        let out = Command::cargo_bin("topk")
+            .unwrap()
+            .args(["-o", "json", "sql", "--region", &ctx.region, "-c", &script])
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+
+        let rows: Vec<serde_json::Value> = out.json_lines().unwrap();
+        assert_eq!(rows, [serde_json::json!({"_id": "1", "title": "Dune"})]);
+    }
+}
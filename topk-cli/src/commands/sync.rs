@@ -0,0 +1,494 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use clap::Args;
+use colored::Colorize;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use topk_rs::client::DatasetClient;
+use topk_rs::{
+    proto::v1::{ctx::file::InputFile, data::Value},
+    Client, Error,
+};
+
+use crate::commands::upload::UploadError;
+use crate::config::{load_toml_or_default, save_toml};
+use crate::output::Output;
+use crate::util::{
+    files::{collect_directory_files, expand_path},
+    plural,
+};
+
+#[derive(Debug, Clone, Args)]
+pub struct SyncArgs {
+    /// Dataset to sync into
+    #[arg(short = 'd', long, value_name = "DATASET_NAME")]
+    pub dataset: String,
+    /// Number of concurrent uploads and deletes (1–64)
+    #[arg(short = 'c', long, default_value = "8", value_parser = clap::value_parser!(u64).range(1..=64))]
+    pub concurrency: u64,
+    /// Skip sync confirmation prompt
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+    /// Show the changes without syncing
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Directory to sync, including subdirectories
+    #[arg(value_name = "DIR")]
+    pub dir: String,
+}
+
+/// State of a synced file, as of its last upload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ManifestEntry {
+    path: PathBuf,
+    size: u64,
+    /// Modification time in nanoseconds since the epoch.
+    modified: u64,
+    /// SHA-256 of the file contents.
+    sha256: String,
+}
+
+/// Files of a directory synced into a dataset, by document ID.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncManifest {
+    #[serde(default)]
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+/// Manifest of `root` synced into `dataset`, under the config directory.
+fn manifest_path(dataset: &str, root: &Path) -> Option<PathBuf> {
+    let key = format!("{dataset}\0{}", root.to_string_lossy());
+    let digest = format!("{:x}", Sha256::digest(key.as_bytes()));
+    dirs::config_dir().map(|d| {
+        d.join("topk")
+            .join("sync")
+            .join(format!("{}.toml", &digest[..16]))
+    })
+}
+
+fn hash_file(path: &Path) -> Result<String, Error> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[derive(Debug, Clone)]
+struct LocalFile {
+    doc_id: String,
+    entry: ManifestEntry,
+}
+
+/// Files of `root` with their content hashes. Hashes of files whose size and modification time
+/// match the manifest are reused.
+fn scan(root: &Path, manifest: &SyncManifest) -> Result<Vec<LocalFile>, Error> {
+    collect_directory_files(root, true)?
+        .into_iter()
+        .map(|file| {
+            let metadata = file.path.metadata()?;
+            let modified = metadata
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or_default();
+
+            let sha256 = match manifest.files.get(&file.doc_id) {
+                Some(entry) if entry.size == metadata.len() && entry.modified == modified => {
+                    entry.sha256.clone()
+                }
+                _ => hash_file(&file.path)?,
+            };
+
+            Ok(LocalFile {
+                doc_id: file.doc_id,
+                entry: ManifestEntry {
+                    path: file.path,
+                    size: metadata.len(),
+                    modified,
+                    sha256,
+                },
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+enum SyncOp {
+    Add(LocalFile),
+    Change(LocalFile),
+    Delete { doc_id: String, path: PathBuf },
+}
+
+impl SyncOp {
+    fn path(&self) -> &Path {
+        match self {
+            SyncOp::Add(file) | SyncOp::Change(file) => &file.entry.path,
+            SyncOp::Delete { path, .. } => path,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SyncPlan {
+    ops: Vec<SyncOp>,
+    unchanged: Vec<LocalFile>,
+}
+
+fn make_sync_plan(local: Vec<LocalFile>, manifest: &SyncManifest) -> SyncPlan {
+    let mut plan = SyncPlan::default();
+
+    let mut removed = manifest.files.clone();
+    for file in local {
+        match removed.remove(&file.doc_id) {
+            None => plan.ops.push(SyncOp::Add(file)),
+            Some(entry) if entry.sha256 != file.entry.sha256 => plan.ops.push(SyncOp::Change(file)),
+            Some(_) => plan.unchanged.push(file),
+        }
+    }
+    plan.ops
+        .extend(removed.into_iter().map(|(doc_id, entry)| SyncOp::Delete {
+            doc_id,
+            path: entry.path,
+        }));
+
+    plan
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncResult {
+    #[serde(default)]
+    pub dry_run: bool,
+    pub added: Vec<PathBuf>,
+    pub changed: Vec<PathBuf>,
+    pub deleted: Vec<PathBuf>,
+    pub unchanged: usize,
+    pub errors: Vec<UploadError>,
+}
+
+impl SyncResult {
+    fn record(&mut self, op: &SyncOp, root: &Path) {
+        let path = op
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(op.path())
+            .to_path_buf();
+        match op {
+            SyncOp::Add(_) => self.added.push(path),
+            SyncOp::Change(_) => self.changed.push(path),
+            SyncOp::Delete { .. } => self.deleted.push(path),
+        }
+    }
+}
+
+impl fmt::Display for SyncResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let uploads = self.added.len() + self.changed.len();
+        let deletes = self.deleted.len();
+
+        if self.dry_run {
+            for path in &self.added {
+                writeln!(f, "{}", format!("+ {}", path.display()).green())?;
+            }
+            for path in &self.changed {
+                writeln!(f, "{}", format!("~ {}", path.display()).yellow())?;
+            }
+            for path in &self.deleted {
+                writeln!(f, "{}", format!("- {}", path.display()).red())?;
+            }
+            return write!(
+                f,
+                "Dry run: would upload {uploads} and delete {deletes} {} ({} unchanged).",
+                plural(deletes, "file", "files"),
+                self.unchanged
+            );
+        }
+
+        if uploads == 0 && deletes == 0 {
+            return match self.errors.len() {
+                0 => f.write_str("Already up to date."),
+                _ => f.write_str("Sync failed."),
+            };
+        }
+
+        write!(
+            f,
+            "Synced: uploaded {uploads} and deleted {deletes} {} ({} unchanged).",
+            plural(deletes, "file", "files"),
+            self.unchanged
+        )
+    }
+}
+
+async fn upload(dataset: &DatasetClient, file: &LocalFile) -> Result<(), Error> {
+    dataset
+        .upsert_file(
+            file.doc_id.clone(),
+            InputFile::from_path(&file.entry.path)?,
+            HashMap::<String, Value>::default(),
+            None,
+        )
+        .await?;
+    Ok(())
+}
+
+/// `topk sync`
+pub async fn run(client: &Client, args: &SyncArgs, output: &Output) -> Result<SyncResult, Error> {
+    let cwd = std::env::current_dir().map_err(Error::IoError)?;
+    let root = expand_path(&args.dir, &cwd)?;
+    if !root.is_dir() {
+        return Err(Error::InvalidArgument(format!(
+            "not a directory: {}",
+            root.display()
+        )));
+    }
+    let root = root.canonicalize()?;
+
+    let path = manifest_path(&args.dataset, &root);
+    let mut manifest: SyncManifest = load_toml_or_default(path.clone(), |p, err| {
+        eprintln!(
+            "warning: failed to parse file {}: {err}, syncing all files",
+            p.display()
+        );
+    });
+
+    let spinner = output.spinner("Scanning files");
+    let local = scan(&root, &manifest);
+    spinner.finish();
+    let plan = make_sync_plan(local?, &manifest);
+
+    let mut result = SyncResult {
+        dry_run: args.dry_run,
+        unchanged: plan.unchanged.len(),
+        ..Default::default()
+    };
+
+    if args.dry_run {
+        for op in &plan.ops {
+            result.record(op, &root);
+        }
+        return Ok(result);
+    }
+
+    // Refresh modification times of unchanged files so that they are not hashed again.
+    for file in plan.unchanged {
+        manifest.files.insert(file.doc_id, file.entry);
+    }
+
+    let total = plan.ops.len();
+    if total == 0 {
+        save_toml(path.clone(), &manifest)?;
+        return Ok(result);
+    }
+
+    let uploads = plan
+        .ops
+        .iter()
+        .filter(|op| !matches!(op, SyncOp::Delete { .. }))
+        .count();
+    let deletes = total - uploads;
+    if !output.confirm_or_yes(
+        &format!(
+            "Upload {} and delete {} in '{}' dataset? ",
+            format!("{uploads} {}", plural(uploads, "file", "files")).bold(),
+            format!("{deletes} {}", plural(deletes, "file", "files")).bold(),
+            args.dataset
+        ),
+        args.yes,
+    )? {
+        return Ok(result);
+    }
+
+    let spinner = output.spinner(format!("0/{total} synced"));
+    let mut ops = stream::iter(plan.ops)
+        .map(|op| {
+            let dataset = client.dataset(&args.dataset);
+
+            async move {
+                let result = match &op {
+                    SyncOp::Add(file) | SyncOp::Change(file) => upload(&dataset, file).await,
+                    SyncOp::Delete { doc_id, .. } => {
+                        dataset.delete(doc_id.clone(), None).await.map(|_| ())
+                    }
+                };
+                (op, result)
+            }
+        })
+        .buffer_unordered(args.concurrency as usize);
+
+    // The manifest is saved after every operation, so an interrupted sync resumes where it
+    // stopped.
+    let mut done = 0;
+    while let Some((op, op_result)) = ops.next().await {
+        done += 1;
+        match op_result {
+            Ok(()) => {
+                result.record(&op, &root);
+                match op {
+                    SyncOp::Add(file) | SyncOp::Change(file) => {
+                        manifest.files.insert(file.doc_id, file.entry);
+                    }
+                    SyncOp::Delete { doc_id, .. } => {
+                        manifest.files.remove(&doc_id);
+                    }
+                }
+                save_toml(path.clone(), &manifest)?;
+            }
+            Err(err) => {
+                let (doc_id, path) = match op {
+                    SyncOp::Add(file) | SyncOp::Change(file) => (file.doc_id, file.entry.path),
+                    SyncOp::Delete { doc_id, path } => (doc_id, path),
+                };
+                spinner.print(format!("  {}: {err}", path.display()));
+                result.errors.push(UploadError {
+                    doc_id,
+                    path: Some(path),
+                    error: err.to_string(),
+                });
+            }
+        }
+        spinner.set_message(format!("{done}/{total} synced"));
+    }
+    spinner.finish();
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_context::{CliTestContext, OutputJsonExt};
+    use assert_cmd::Command;
+    use std::fs;
+    use tempfile::tempdir;
+    use test_context::test_context;
+
+    fn local(doc_id: &str, sha256: &str) -> LocalFile {
+        LocalFile {
+            doc_id: doc_id.to_string(),
+            entry: ManifestEntry {
+                path: PathBuf::from(format!("/docs/{doc_id}.md")),
+                size: 1,
+                modified: 1,
+                sha256: sha256.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn plan_diffs_against_manifest() {
+        let manifest = SyncManifest {
+            files: [
+                local("same", "a"),
+                local("edited", "b"),
+                local("removed", "c"),
+            ]
+            .into_iter()
+            .map(|file| (file.doc_id, file.entry))
+            .collect(),
+        };
+
+        let plan = make_sync_plan(
+            vec![local("same", "a"), local("edited", "x"), local("new", "d")],
+            &manifest,
+        );
+
+        let ops = plan
+            .ops
+            .iter()
+            .map(|op| match op {
+                SyncOp::Add(file) => format!("+{}", file.doc_id),
+                SyncOp::Change(file) => format!("~{}", file.doc_id),
+                SyncOp::Delete { doc_id, .. } => format!("-{doc_id}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ops, ["~edited", "+new", "-removed"]);
+        assert_eq!(plan.unchanged.len(), 1);
+        assert_eq!(plan.unchanged[0].doc_id, "same");
+    }
+
+    #[test]
+    fn scan_hashes_changed_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("doc.md");
+        fs::write(&path, "# hello").unwrap();
+
+        let files = scan(dir.path(), &SyncManifest::default()).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            files[0].entry.sha256,
+            format!("{:x}", Sha256::digest(b"# hello"))
+        );
+
+        // Unchanged size and modification time reuse the manifest hash.
+        let manifest = SyncManifest {
+            files: [(
+                files[0].doc_id.clone(),
+                ManifestEntry {
+                    sha256: "cached".to_string(),
+                    ..files[0].entry.clone()
+                },
+            )]
+            .into(),
+        };
+        assert_eq!(
+            scan(dir.path(), &manifest).unwrap()[0].entry.sha256,
+            "cached"
+        );
+    }
+
+    #[test]
+    fn manifest_path_is_per_dataset_and_directory() {
+        let a = manifest_path("docs", Path::new("/a"));
+        assert_ne!(a, manifest_path("docs", Path::new("/b")));
+        assert_ne!(a, manifest_path("other", Path::new("/a")));
+        assert_eq!(a, manifest_path("docs", Path::new("/a")));
+    }
+
+    #[test_context(CliTestContext)]
+    #[tokio::test]
+    async fn sync_uploads_changes_and_deletes_removed_files(ctx: &mut CliTestContext) {
+        let dataset = ctx.wrap("sync");
+        ctx.create_dataset(&dataset);
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.md"), "# a").unwrap();
+        fs::write(dir.path().join("b.md"), "# b").unwrap();
+
+        let sync = |dry_run: bool| {
+            let mut cmd = Command::cargo_bin("topk").unwrap();
+            cmd.args(["-o", "json", "sync", "-y", "-d", &dataset]);
+            if dry_run {
+                cmd.arg("--dry-run");
+            }
+            let out = cmd.arg(dir.path()).output().unwrap();
+            assert!(
+                out.status.success(),
+                "{}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+            out.json::<SyncResult>().unwrap()
+        };
+
+        let result = sync(false);
+        assert_eq!(result.added.len(), 2);
+
+        fs::write(dir.path().join("a.md"), "# a, edited").unwrap();
+        fs::remove_file(dir.path().join("b.md")).unwrap();
+
+        let result = sync(true);
+        assert_eq!(result.changed, [PathBuf::from("a.md")]);
+        assert_eq!(result.deleted, [PathBuf::from("b.md")]);
+
+        let result = sync(false);
+        assert_eq!((result.changed.len(), result.deleted.len()), (1, 1));
+        assert!(result.errors.is_empty());
+
+        let result = sync(false);
+        assert_eq!(result.unchanged, 1);
+        assert!(result.added.is_empty() && result.changed.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -13,14 +13,12 @@ use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use topk_rs::client::WaitConfig;
-use topk_rs::{
-    proto::v1::{ctx::file::InputFile, data::Value},
-    Client, Error,
-};
+use topk_rs::{proto::v1::ctx::file::InputFile, Client, Error};
 
 use crate::output::{Output, OutputFormat};
 use crate::util::{
     files::{resolve_files, UploadFile},
+    metadata::MetadataArgs,
     plural,
 };
 
@@ -103,6 +101,8 @@ pub struct UploadArgs {
     /// Wait for all uploaded files to be fully processed, optionally up to a duration (e.g. 5m, 2h)
     #[arg(short = 'w', long, value_name = "DURATION", num_args = 0..=1, value_parser = humantime::parse_duration)]
     pub wait: Option<Option<Duration>>,
+    #[command(flatten)]
+    pub metadata: MetadataArgs,
     /// File paths, directories, or glob patterns (e.g. "./report.pdf" "./docs" "*.pdf" "docs/**/*.md")
     #[arg(value_name = "PATTERN", required = true, num_args = 1..)]
     pub patterns: Vec<String>,
@@ -226,6 +226,8 @@ struct FileUpload {
 async fn upload_all(
     client: &Client,
     dataset: &str,
+    cwd: &Path,
+    metadata: &MetadataArgs,
     files: Vec<UploadFile>,
     concurrency: usize,
     progress_bar: &UploadProgressBar,
@@ -242,7 +244,8 @@ async fn upload_all(
                         .upsert_file(
                             file.doc_id.clone(),
                             InputFile::from_path(&file.path)?,
-                            HashMap::<String, Value>::default(),
+                            metadata.file_metadata(cwd, &file.path)?,
+                            None,
                         )
                         .await?;
                     Ok::<String, Error>(handle)
@@ -300,7 +303,7 @@ async fn wait_for_all(
                     async move {
                         client
                             .dataset(&dataset)
-                            .wait_for_handle(&handle, Some(wait_config))
+                            .wait_for_handle(&handle, Some(wait_config), None)
                             .await?;
                         let n = done.fetch_add(1, Ordering::Relaxed) + 1;
                         if let Some(pb) = &progress_bar {
@@ -380,6 +383,8 @@ pub async fn run(
     let file_uploads = upload_all(
         client,
         &args.dataset,
+        &cwd,
+        &args.metadata,
         files,
         args.concurrency as usize,
         &progress_bar,
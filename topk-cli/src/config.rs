@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
@@ -5,9 +6,97 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tempfile::NamedTempFile;
 use topk_rs::Error;
 
+/// Name of the profile used when neither `--profile` nor `TOPK_PROFILE` nor `topk profile use`
+/// selects one. Its settings also live at the top level of configs written before profiles.
+pub const DEFAULT_PROFILE: &str = "default";
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
+    /// API key of the default profile, as saved by `topk login` before profiles existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+    /// Profile selected with `topk profile use`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings of one TopK organization or environment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub https: Option<bool>,
+    /// Dataset of commands run without `--dataset`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dataset: Option<String>,
+    /// Region of datasets and collections created without `--region`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+impl Config {
+    /// Name of the active profile: `selected` (from `--profile` or `TOPK_PROFILE`), else the
+    /// one chosen with `topk profile use`, else the default profile.
+    pub fn profile_name(&self, selected: Option<&str>) -> String {
+        selected
+            .or(self.current_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE)
+            .to_string()
+    }
+
+    /// Settings of profile `name`. Unknown profiles have no settings.
+    pub fn profile(&self, name: &str) -> Profile {
+        let mut profile = self.profiles.get(name).cloned().unwrap_or_default();
+        if name == DEFAULT_PROFILE && profile.api_key.is_none() {
+            profile.api_key = self.api_key.clone();
+        }
+        profile
+    }
+
+    /// Mutable settings of profile `name`, moving the top-level API key into the default profile.
+    pub fn profile_mut(&mut self, name: &str) -> &mut Profile {
+        let api_key = match name {
+            DEFAULT_PROFILE => self.api_key.take(),
+            _ => None,
+        };
+        let profile = self.profiles.entry(name.to_string()).or_default();
+        if profile.api_key.is_none() {
+            profile.api_key = api_key;
+        }
+        profile
+    }
+
+    pub fn has_profile(&self, name: &str) -> bool {
+        self.profiles.contains_key(name) || (name == DEFAULT_PROFILE && self.api_key.is_some())
+    }
+
+    /// Names of all profiles, including the default profile if it has settings.
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names = self.profiles.keys().cloned().collect::<Vec<_>>();
+        if self.api_key.is_some() && !self.profiles.contains_key(DEFAULT_PROFILE) {
+            names.push(DEFAULT_PROFILE.to_string());
+            names.sort();
+        }
+        names
+    }
+
+    pub fn remove_profile(&mut self, name: &str) -> Option<Profile> {
+        let mut removed = self.profiles.remove(name);
+        if name == DEFAULT_PROFILE {
+            if let Some(api_key) = self.api_key.take() {
+                removed.get_or_insert_with(Profile::default).api_key = Some(api_key);
+            }
+        }
+        if removed.is_some() && self.current_profile.as_deref() == Some(name) {
+            self.current_profile = None;
+        }
+        removed
+    }
 }
 
 /// Returns the path to the config file
@@ -15,6 +104,16 @@ pub fn config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|d| d.join("topk").join("config.toml"))
 }
 
+/// Directory of files cached for a profile. The default profile keeps the top-level directory
+/// it used before profiles existed.
+pub fn profile_dir(profile: &str) -> Option<PathBuf> {
+    let dir = dirs::config_dir()?.join("topk");
+    Some(match profile {
+        DEFAULT_PROFILE => dir,
+        _ => dir.join("profiles").join(profile),
+    })
+}
+
 /// Loads the config file. Returns an empty config on any read or parse error.
 pub fn load() -> Config {
     load_toml_or_default(config_path(), |_, _| {})
@@ -25,14 +124,19 @@ pub fn save(config: &Config) -> Result<(), Error> {
     save_toml_with(config_path(), config, write_config_file)
 }
 
-pub fn set_api_key(api_key: String) -> Result<(), Error> {
+pub fn set_api_key(profile: &str, api_key: String) -> Result<(), Error> {
     let mut config = load();
-    config.api_key = Some(api_key);
+    config.profile_mut(profile).api_key = Some(api_key);
     save(&config)
 }
 
-pub fn clear() -> Result<(), Error> {
-    save(&Config::default())
+/// Removes the API key of `profile`, keeping its other settings.
+pub fn clear(profile: &str) -> Result<(), Error> {
+    let mut config = load();
+    if config.has_profile(profile) {
+        config.profile_mut(profile).api_key = None;
+    }
+    save(&config)
 }
 
 #[cfg(unix)]
@@ -136,11 +240,49 @@ mod tests {
 
     #[test]
     fn config_with_none_api_key_serializes_and_roundtrips() {
-        let config = super::Config { api_key: None };
+        let config = super::Config {
+            api_key: None,
+            ..Default::default()
+        };
 
         let toml = toml::to_string(&config).expect("serialize config");
         let restored: super::Config = toml::from_str(&toml).expect("deserialize config");
 
         assert!(restored.api_key.is_none());
     }
+
+    #[test]
+    fn legacy_api_key_is_the_default_profile() {
+        let mut config: super::Config = toml::from_str("api_key = 'legacy'").unwrap();
+
+        assert_eq!(config.profile_name(None), super::DEFAULT_PROFILE);
+        assert_eq!(config.profile("default").api_key.as_deref(), Some("legacy"));
+        assert_eq!(config.profile_names(), vec!["default"]);
+        assert!(config.profile("staging").api_key.is_none());
+
+        config.profile_mut("default").host = Some("localhost".to_string());
+        let toml = toml::to_string(&config).unwrap();
+        assert!(!toml.starts_with("api_key"), "{toml}");
+
+        let restored: super::Config = toml::from_str(&toml).unwrap();
+        let profile = restored.profile("default");
+        assert_eq!(profile.api_key.as_deref(), Some("legacy"));
+        assert_eq!(profile.host.as_deref(), Some("localhost"));
+    }
+
+    #[test]
+    fn selected_profile_overrides_current_profile() {
+        let mut config = super::Config::default();
+        config.profile_mut("staging").api_key = Some("staging-key".to_string());
+        config.profile_mut("prod").api_key = Some("prod-key".to_string());
+        config.current_profile = Some("prod".to_string());
+
+        assert_eq!(config.profile_name(None), "prod");
+        assert_eq!(config.profile_name(Some("staging")), "staging");
+
+        assert!(config.remove_profile("prod").is_some());
+        assert_eq!(config.profile_name(None), super::DEFAULT_PROFILE);
+        assert_eq!(config.profile_names(), vec!["staging"]);
+        assert!(config.remove_profile("prod").is_none());
+    }
 }
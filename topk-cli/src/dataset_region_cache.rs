@@ -5,7 +5,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use topk_rs::Error;
 
-use crate::config::{load_toml_or_default, save_toml};
+use crate::config::{load_toml_or_default, profile_dir, save_toml};
 
 const DATASET_REGION_CACHE_TTL: Duration = Duration::from_mins(5);
 
@@ -123,20 +123,32 @@ impl DatasetRegionCache {
     }
 }
 
-pub fn dataset_region_cache_path() -> Option<PathBuf> {
-    dirs::config_dir().map(|d| d.join("topk").join("datasets.toml"))
+/// Regions are cached per profile, as profiles can belong to different organizations.
+pub fn dataset_region_cache_path(profile: &str) -> Option<PathBuf> {
+    profile_dir(profile).map(|d| d.join("datasets.toml"))
 }
 
-pub fn clear() -> Result<(), Error> {
-    let Some(path) = dataset_region_cache_path() else {
-        return Ok(());
-    };
+/// Collections share the cache format, keyed by collection name.
+pub fn collection_region_cache_path(profile: &str) -> Option<PathBuf> {
+    profile_dir(profile).map(|d| d.join("collections.toml"))
+}
 
-    match std::fs::remove_file(path) {
-        Ok(()) => Ok(()),
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
-        Err(err) => Err(Error::IoError(err)),
+pub fn clear(profile: &str) -> Result<(), Error> {
+    for path in [
+        dataset_region_cache_path(profile),
+        collection_region_cache_path(profile),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        match std::fs::remove_file(path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(Error::IoError(err)),
+        }
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
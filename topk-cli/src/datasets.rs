@@ -157,10 +157,13 @@ where
     }
 }
 
-pub fn make_cached_datasets_client(client: Client) -> impl DatasetsClient + DatasetRegionResolver {
+pub fn make_cached_datasets_client(
+    client: Client,
+    profile: &str,
+) -> impl DatasetsClient + DatasetRegionResolver {
     CachedDatasetsClient::new(
         RealDatasetsClient::new(client),
-        DatasetRegionCache::new(dataset_region_cache_path()),
+        DatasetRegionCache::new(dataset_region_cache_path(profile)),
     )
 }
 
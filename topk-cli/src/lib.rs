@@ -1,7 +1,10 @@
 pub mod client;
+pub mod collections;
 pub mod commands;
 pub mod config;
 pub mod dataset_region_cache;
 pub mod datasets;
+pub mod mcp;
 pub mod output;
+pub mod sql;
 pub mod util;
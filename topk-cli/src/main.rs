@@ -2,20 +2,28 @@ use std::collections::HashMap;
 use std::process::ExitCode;
 
 use anyhow::Result;
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use clap_complete::{generate, Shell};
 use colored::Colorize;
 use futures::TryStreamExt;
 use tokio_stream::StreamExt;
 
 use topk::client::{make_client, make_global_client};
-use topk::commands::{ask, dataset, delete, list, login, search, upload};
+use topk::collections::{make_cached_collections_client, CollectionsClient};
+use topk::commands::{
+    ask, collection, dataset, delete, docs, eval, list, login, mcp, profile, search, sql, sync,
+    upload,
+};
 use topk::config;
 use topk::dataset_region_cache;
 use topk::datasets::{ensure_unique_region, get_region, make_cached_datasets_client};
+use topk::mcp::McpServer;
 use topk::output::{is_broken_pipe, Output, OutputFormat};
+use topk::sql::SqlSession;
 use topk_rs::Error;
 
+const DEFAULT_HOST: &str = "topk.io";
+
 #[derive(Parser)]
 #[command(name = "topk", version)]
 struct Cli {
@@ -32,24 +40,23 @@ struct Cli {
     )]
     api_key: Option<String>,
 
-    /// Host (overrides TOPK_HOST environment variable, default: topk.io)
-    #[arg(
-        long,
-        env = "TOPK_HOST",
-        default_value = "topk.io",
-        global = true,
-        hide = true
-    )]
-    host: String,
+    /// Profile to use (overrides TOPK_PROFILE environment variable and `topk profile use`)
+    #[arg(long, env = "TOPK_PROFILE", global = true)]
+    profile: Option<String>,
+
+    /// Host (overrides TOPK_HOST environment variable and the profile host, default: topk.io)
+    #[arg(long, env = "TOPK_HOST", global = true, hide = true)]
+    host: Option<String>,
 
     #[arg(
         long,
         env = "TOPK_HTTPS",
-        default_value = "true",
+        num_args = 0..=1,
+        default_missing_value = "true",
         global = true,
         hide = true
     )]
-    https: bool,
+    https: Option<bool>,
 
     /// Output format
     #[arg(short = 'o', long, default_value = "text", global = true)]
@@ -67,9 +74,15 @@ enum Commands {
     /// Find relevant passages in documents for a query
     Search(search::SearchArgs),
 
+    /// Measure retrieval quality against relevance judgments
+    Eval(eval::EvalArgs),
+
     /// Upload files
     Upload(upload::UploadArgs),
 
+    /// Sync a directory into a dataset, uploading new and changed files and deleting removed ones
+    Sync(sync::SyncArgs),
+
     /// Delete a document
     Delete(delete::DeleteArgs),
 
@@ -82,6 +95,30 @@ enum Commands {
         action: dataset::DatasetAction,
     },
 
+    /// Manage collections (create, list, get, delete)
+    Collection {
+        #[command(subcommand)]
+        action: collection::CollectionAction,
+    },
+
+    /// Import and export collection documents
+    Docs {
+        #[command(subcommand)]
+        action: docs::DocsAction,
+    },
+
+    /// Run SQL statements against collections
+    Sql(sql::SqlArgs),
+
+    /// Serve datasets to AI agents over MCP (Model Context Protocol) on stdio
+    Mcp(mcp::McpArgs),
+
+    /// Manage profiles for multiple organizations or environments (add, list, use, remove)
+    Profile {
+        #[command(subcommand)]
+        action: profile::ProfileAction,
+    },
+
     /// Remove auth credentials
     Logout,
 
@@ -92,11 +129,12 @@ enum Commands {
 
 #[tokio::main]
 async fn main() -> ExitCode {
-    let cli = Cli::parse();
+    let config = config::load();
+    let cli = parse_cli(&config);
 
     let output = Output::new(cli.output);
 
-    match run(cli, &output).await {
+    match run(cli, config, &output).await {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
             output.error(&e);
@@ -105,19 +143,81 @@ async fn main() -> ExitCode {
     }
 }
 
-async fn run(cli: Cli, output: &Output) -> Result<(), Error> {
-    let config = config::load();
+/// Parses the command line, with the dataset and region of the selected profile as defaults.
+fn parse_cli(config: &config::Config) -> Cli {
+    // The profile is selected by the command line being parsed, so it is first parsed on its own.
+    // Help and version requests still fail that parse, leaving only TOPK_PROFILE.
+    let selected = match Cli::command().ignore_errors(true).try_get_matches() {
+        Ok(matches) => matches.get_one::<String>("profile").cloned(),
+        Err(_) => std::env::var("TOPK_PROFILE").ok(),
+    };
+    let profile = config.profile(&config.profile_name(selected.as_deref()));
+
+    let mut command = Cli::command();
+    if let Some(dataset) = profile.dataset {
+        for (subcommand, arg) in [
+            ("ask", "datasets"),
+            ("search", "datasets"),
+            ("eval", "datasets"),
+            ("upload", "dataset"),
+            ("sync", "dataset"),
+            ("delete", "dataset"),
+            ("list", "dataset"),
+        ] {
+            command = command.mut_subcommand(subcommand, |c| {
+                c.mut_arg(arg, |a| a.default_value(dataset.clone()).required(false))
+            });
+        }
+    }
+    if let Some(region) = profile.region {
+        for subcommand in ["dataset", "collection"] {
+            command = command.mut_subcommand(subcommand, |c| {
+                c.mut_subcommand("create", |c| {
+                    c.mut_arg("region", |a| {
+                        a.default_value(region.clone()).required(false)
+                    })
+                })
+            });
+        }
+        command = command.mut_subcommand("sql", |c| {
+            c.mut_arg("region", |a| a.default_value(region.clone()))
+        });
+    }
+
+    Cli::from_arg_matches(&command.get_matches()).unwrap_or_else(|err| err.exit())
+}
+
+async fn run(cli: Cli, mut config: config::Config, output: &Output) -> Result<(), Error> {
+    let profile_name = config.profile_name(cli.profile.as_deref());
+    if !config.has_profile(&profile_name)
+        && profile_name != config::DEFAULT_PROFILE
+        && !matches!(
+            cli.command,
+            Some(Commands::Login | Commands::Profile { .. })
+        )
+    {
+        return Err(Error::InvalidArgument(format!(
+            "profile '{profile_name}' not found, run `topk profile add {profile_name}` to create it"
+        )));
+    }
+    let profile = config.profile(&profile_name);
+    let host = cli
+        .host
+        .clone()
+        .or_else(|| profile.host.clone())
+        .unwrap_or_else(|| DEFAULT_HOST.to_string());
+    let https = cli.https.or(profile.https).unwrap_or(true);
 
     match cli.command {
         Some(Commands::Login) => {
             let api_key = match cli.api_key {
                 Some(key) => Some(key),
-                None => login::run(&cli.host, cli.https)?,
+                None => login::run(&host, https)?,
             };
 
             match api_key {
                 Some(api_key) => {
-                    config::set_api_key(api_key)?;
+                    config::set_api_key(&profile_name, api_key)?;
                     output.success("API key saved.");
                 }
                 None => {
@@ -129,10 +229,12 @@ async fn run(cli: Cli, output: &Output) -> Result<(), Error> {
         }
 
         Some(Commands::Dataset { action }) => {
-            let api_key = get_api_key(cli.api_key, &config)?;
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
 
-            let client =
-                make_cached_datasets_client(make_global_client(&api_key, &cli.host, cli.https));
+            let client = make_cached_datasets_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
 
             match action {
                 dataset::DatasetAction::List => {
@@ -170,28 +272,206 @@ async fn run(cli: Cli, output: &Output) -> Result<(), Error> {
             Ok(())
         }
 
+        Some(Commands::Collection { action }) => {
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
+
+            let client = make_cached_collections_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
+
+            match action {
+                collection::CollectionAction::List => {
+                    let result = collection::list(client).await?;
+                    match output.format {
+                        OutputFormat::Json => {
+                            for collection in &result.collections {
+                                if let Err(err) = output.print_json_line(collection) {
+                                    if is_broken_pipe(&err) {
+                                        break;
+                                    }
+                                    return Err(err);
+                                }
+                            }
+                        }
+                        OutputFormat::Text => {
+                            output.print(&result)?;
+                        }
+                    }
+                }
+                collection::CollectionAction::Get { collection: name } => {
+                    output.print(&collection::get(client, &name).await?)?;
+                }
+                collection::CollectionAction::Create(args) => {
+                    output.print(&collection::create(client, &args).await?)?;
+                }
+                collection::CollectionAction::Delete(args) => {
+                    output.print(&collection::delete(client, &args, output).await?)?;
+                }
+            }
+
+            Ok(())
+        }
+
+        Some(Commands::Docs { action }) => {
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
+
+            let mut collections = make_cached_collections_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
+
+            match action {
+                docs::DocsAction::Import(args) => {
+                    let collection = collections.get(&args.collection).await?;
+                    let client = make_client(&api_key, &collection.region, &host, https);
+
+                    output
+                        .print(&docs::import(&client, collection.schema, &args, output).await?)?;
+                }
+                docs::DocsAction::Export(args) => {
+                    let collection = collections.get(&args.collection).await?;
+                    let client = make_client(&api_key, &collection.region, &host, https);
+
+                    let result = docs::export(&client, collection.schema, &args, output).await?;
+                    // Documents written to stdout are the output.
+                    if args.output_file.is_some() {
+                        output.print(&result)?;
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        Some(Commands::Sql(args)) => {
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
+
+            let collections = make_cached_collections_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
+            let session = SqlSession::new(collections, &api_key, &host, https, args.region.clone());
+
+            sql::run(session, &args, output).await
+        }
+
+        Some(Commands::Mcp(args)) => {
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
+
+            let datasets_client = make_cached_datasets_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
+            let server = McpServer::new(datasets_client, &api_key, &host, https, args.read_only);
+
+            mcp::run(server).await
+        }
+
+        Some(Commands::Eval(args)) => {
+            let baseline = match args.compare.as_slice() {
+                [baseline, current] => {
+                    let comparison = eval::EvalComparison::new(
+                        &eval::EvalRun::read(baseline)?,
+                        &eval::EvalRun::read(current)?,
+                        args.tolerance,
+                    )?;
+                    output.print(&comparison)?;
+                    return eval::check_regressions(&comparison);
+                }
+                [baseline] => Some(eval::EvalRun::read(baseline)?),
+                _ => None,
+            };
+            let Some(judgments) = &args.judgments else {
+                return Err(Error::InvalidArgument(
+                    "JUDGMENTS is required unless --compare is given two runs".to_string(),
+                ));
+            };
+            let judgments = eval::read_judgments(judgments)?;
+
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
+
+            let result = match &args.query_template {
+                Some(template) => {
+                    let collections = make_cached_collections_client(
+                        make_global_client(&api_key, &host, https),
+                        &profile_name,
+                    );
+                    let session = SqlSession::new(collections, &api_key, &host, https, None);
+                    let mut retriever = eval::SqlRetriever::new(session, template)?;
+
+                    eval::run(&mut retriever, &judgments, args.top_k, output).await?
+                }
+                None => {
+                    let mut datasets_client = make_cached_datasets_client(
+                        make_global_client(&api_key, &host, https),
+                        &profile_name,
+                    );
+
+                    let region =
+                        ensure_unique_region(&mut datasets_client, args.datasets.clone()).await?;
+                    let mut retriever = eval::SearchRetriever {
+                        client: make_client(&api_key, &region, &host, https),
+                        datasets: args.datasets.clone(),
+                        filter: args.filter.clone(),
+                    };
+
+                    eval::run(&mut retriever, &judgments, args.top_k, output).await?
+                }
+            };
+
+            match baseline {
+                Some(baseline) => {
+                    let comparison = eval::EvalComparison::new(&baseline, &result, args.tolerance)?;
+                    output.print(&comparison)?;
+                    eval::check_regressions(&comparison)
+                }
+                None => output.print(&result),
+            }
+        }
+
         Some(Commands::Upload(args)) => {
-            let api_key = get_api_key(cli.api_key, &config)?;
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
 
-            let mut datasets_client =
-                make_cached_datasets_client(make_global_client(&api_key, &cli.host, cli.https));
+            let mut datasets_client = make_cached_datasets_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
 
             let region = get_region(&mut datasets_client, &args.dataset).await?;
-            let client = make_client(&api_key, &region, &cli.host, cli.https);
+            let client = make_client(&api_key, &region, &host, https);
 
             output.print(&upload::run(&client, &args, output).await?)?;
 
             Ok(())
         }
 
+        Some(Commands::Sync(args)) => {
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
+
+            let mut datasets_client = make_cached_datasets_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
+
+            let region = get_region(&mut datasets_client, &args.dataset).await?;
+            let client = make_client(&api_key, &region, &host, https);
+
+            output.print(&sync::run(&client, &args, output).await?)?;
+
+            Ok(())
+        }
+
         Some(Commands::Delete(args)) => {
-            let api_key = get_api_key(cli.api_key, &config)?;
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
 
-            let mut datasets_client =
-                make_cached_datasets_client(make_global_client(&api_key, &cli.host, cli.https));
+            let mut datasets_client = make_cached_datasets_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
 
             let region = get_region(&mut datasets_client, &args.dataset).await?;
-            let client = make_client(&api_key, &region, &cli.host, cli.https);
+            let client = make_client(&api_key, &region, &host, https);
 
             output.print(&delete::run(&client, &args, output).await?)?;
 
@@ -199,13 +479,15 @@ async fn run(cli: Cli, output: &Output) -> Result<(), Error> {
         }
 
         Some(Commands::List(args)) => {
-            let api_key = get_api_key(cli.api_key, &config)?;
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
 
-            let mut datasets_client =
-                make_cached_datasets_client(make_global_client(&api_key, &cli.host, cli.https));
+            let mut datasets_client = make_cached_datasets_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
 
             let region = get_region(&mut datasets_client, &args.dataset).await?;
-            let client = make_client(&api_key, &region, &cli.host, cli.https);
+            let client = make_client(&api_key, &region, &host, https);
 
             let stream = list::run(&client, &args).await?;
 
@@ -233,13 +515,20 @@ async fn run(cli: Cli, output: &Output) -> Result<(), Error> {
         }
 
         Some(Commands::Ask(args)) => {
-            let api_key = get_api_key(cli.api_key, &config)?;
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
 
-            let mut datasets_client =
-                make_cached_datasets_client(make_global_client(&api_key, &cli.host, cli.https));
+            let mut datasets_client = make_cached_datasets_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
+
+            if args.interactive {
+                let session = ask::AskSession::new(datasets_client, &api_key, &host, https, &args);
+                return ask::interactive(session, &args, output).await;
+            }
 
             let region = ensure_unique_region(&mut datasets_client, args.datasets.clone()).await?;
-            let client = make_client(&api_key, &region, &cli.host, cli.https);
+            let client = make_client(&api_key, &region, &host, https);
 
             let result = ask::run(&client, &args, output).await?;
             let paths = match args.output_dir.as_deref() {
@@ -278,13 +567,15 @@ async fn run(cli: Cli, output: &Output) -> Result<(), Error> {
         }
 
         Some(Commands::Search(args)) => {
-            let api_key = get_api_key(cli.api_key, &config)?;
+            let api_key = get_api_key(cli.api_key, &profile_name, &profile)?;
 
-            let mut datasets_client =
-                make_cached_datasets_client(make_global_client(&api_key, &cli.host, cli.https));
+            let mut datasets_client = make_cached_datasets_client(
+                make_global_client(&api_key, &host, https),
+                &profile_name,
+            );
 
             let region = ensure_unique_region(&mut datasets_client, args.datasets.clone()).await?;
-            let client = make_client(&api_key, &region, &cli.host, cli.https);
+            let client = make_client(&api_key, &region, &host, https);
 
             let result = search::run(&client, &args).await?;
 
@@ -320,9 +611,60 @@ async fn run(cli: Cli, output: &Output) -> Result<(), Error> {
             Ok(())
         }
 
+        Some(Commands::Profile { action }) => {
+            match action {
+                profile::ProfileAction::Add(args) => {
+                    let api_key = match cli.api_key {
+                        Some(key) => Some(key),
+                        None if config.profile(&args.name).api_key.is_none() => {
+                            let host = args.host.as_deref().unwrap_or(DEFAULT_HOST);
+                            login::run(host, args.https.unwrap_or(true))?
+                        }
+                        None => None,
+                    };
+                    let result = profile::add(&mut config, &args, api_key)?;
+                    config::save(&config)?;
+                    output.print(&result)?;
+                }
+                profile::ProfileAction::List => {
+                    let result = profile::list(&config, &profile_name);
+                    match output.format {
+                        OutputFormat::Json => {
+                            for profile in &result.profiles {
+                                if let Err(err) = output.print_json_line(profile) {
+                                    if is_broken_pipe(&err) {
+                                        break;
+                                    }
+                                    return Err(err);
+                                }
+                            }
+                        }
+                        OutputFormat::Text => {
+                            output.print(&result)?;
+                        }
+                    }
+                }
+                profile::ProfileAction::Use { name } => {
+                    let result = profile::use_profile(&mut config, &name)?;
+                    config::save(&config)?;
+                    output.print(&result)?;
+                }
+                profile::ProfileAction::Remove(args) => {
+                    let result = profile::remove(&mut config, &args, output)?;
+                    if result.removed {
+                        config::save(&config)?;
+                        dataset_region_cache::clear(&args.name)?;
+                    }
+                    output.print(&result)?;
+                }
+            }
+
+            Ok(())
+        }
+
         Some(Commands::Logout) => {
-            config::clear()?;
-            dataset_region_cache::clear()?;
+            config::clear(&profile_name)?;
+            dataset_region_cache::clear(&profile_name)?;
             output.success("Logged out.");
             Ok(())
         }
@@ -339,16 +681,26 @@ async fn run(cli: Cli, output: &Output) -> Result<(), Error> {
     }
 }
 
-/// Gets the API key from the CLI arguments or the config file.
-fn get_api_key(api_key: Option<String>, config: &config::Config) -> Result<String, Error> {
+/// Gets the API key from the CLI arguments or the profile.
+fn get_api_key(
+    api_key: Option<String>,
+    profile_name: &str,
+    profile: &config::Profile,
+) -> Result<String, Error> {
     if let Some(key) = api_key {
         return Ok(key);
     }
 
-    if let Some(key) = config.api_key.clone() {
+    if let Some(key) = profile.api_key.clone() {
         return Ok(key);
     }
 
+    if profile_name != config::DEFAULT_PROFILE {
+        return Err(Error::Unauthenticated(format!(
+            "API key not set for profile '{profile_name}'. Run `topk login --profile {profile_name}` or set TOPK_API_KEY environment variable."
+        )));
+    }
+
     Err(Error::Unauthenticated(format!(
         "API key not set. Run `topk login` or set TOPK_API_KEY environment variable."
     )))
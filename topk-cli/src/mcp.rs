@@ -0,0 +1,888 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use topk_rs::proto::v1::ctx::{ask_result, content};
+use topk_rs::proto::v1::ctx::{file::InputFile, Source};
+use topk_rs::{Client, Error};
+
+use crate::client::make_client;
+use crate::commands::ask::{AskResult, Mode};
+use crate::commands::dataset::Dataset;
+use crate::commands::list::ListEntry;
+use crate::commands::search::SearchResult;
+use crate::datasets::{ensure_unique_region, get_region, DatasetRegionResolver, DatasetsClient};
+use crate::util::{files::resolve_files, filter, metadata::json_object};
+
+/// Latest MCP revision implemented by the server.
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Earlier revisions whose messages the server also handles.
+const SUPPORTED_PROTOCOL_VERSIONS: [&str; 3] = [PROTOCOL_VERSION, "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Documents returned by `list_documents` when the call sets no limit.
+const DEFAULT_LIST_LIMIT: usize = 100;
+
+/// Serves the datasets of a project over the Model Context Protocol.
+///
+/// Messages are newline-delimited JSON-RPC, as in the MCP stdio transport, and are handled one at
+/// a time. Dataset requests go to the region of each dataset (see `datasets::get_region`).
+pub struct McpServer<D> {
+    datasets: D,
+    api_key: String,
+    host: String,
+    https: bool,
+    /// Hides the `upload` tool.
+    read_only: bool,
+    /// Directory relative paths of `upload` are resolved against.
+    cwd: PathBuf,
+    /// Regional clients, by region.
+    clients: HashMap<String, Client>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct CallToolParams {
+    name: String,
+    #[serde(default)]
+    arguments: Map<String, Value>,
+    #[serde(default, rename = "_meta")]
+    meta: Option<RequestMeta>,
+}
+
+#[derive(Deserialize)]
+struct RequestMeta {
+    #[serde(default, rename = "progressToken")]
+    progress_token: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct ListDocumentsArgs {
+    dataset: String,
+    #[serde(default)]
+    filter: Option<String>,
+    #[serde(default)]
+    fields: Vec<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct SearchArgs {
+    query: String,
+    datasets: Vec<String>,
+    #[serde(default)]
+    filter: Option<String>,
+    #[serde(default)]
+    fields: Vec<String>,
+    #[serde(default)]
+    top_k: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct AskArgs {
+    query: String,
+    datasets: Vec<String>,
+    #[serde(default)]
+    filter: Option<String>,
+    #[serde(default)]
+    fields: Vec<String>,
+    #[serde(default)]
+    mode: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GetContentArgs {
+    dataset: String,
+    content_id: String,
+}
+
+#[derive(Deserialize)]
+struct UploadArgs {
+    dataset: String,
+    paths: Vec<String>,
+    #[serde(default)]
+    recursive: bool,
+    #[serde(default)]
+    metadata: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct UploadedFile {
+    doc_id: String,
+    path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Sends `notifications/progress` for a request that asked for them with a progress token.
+struct Progress<'a, W> {
+    output: &'a mut W,
+    token: Option<Value>,
+    count: u64,
+}
+
+impl<W: AsyncWrite + Unpin> Progress<'_, W> {
+    async fn update(&mut self, message: &str) -> Result<(), Error> {
+        let Some(token) = &self.token else {
+            return Ok(());
+        };
+        self.count += 1;
+
+        write_message(
+            self.output,
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": {
+                    "progressToken": token,
+                    "progress": self.count,
+                    "message": message,
+                },
+            }),
+        )
+        .await
+    }
+}
+
+impl<D> McpServer<D>
+where
+    D: DatasetsClient + DatasetRegionResolver,
+{
+    pub fn new(datasets: D, api_key: &str, host: &str, https: bool, read_only: bool) -> Self {
+        Self {
+            datasets,
+            api_key: api_key.to_string(),
+            host: host.to_string(),
+            https,
+            read_only,
+            cwd: std::env::current_dir().unwrap_or_default(),
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Handles the messages of `input` until it is closed, writing responses and notifications
+    /// to `output`.
+    pub async fn serve<R, W>(&mut self, input: R, mut output: W) -> Result<(), Error>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = input.lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(value) => match serde_json::from_value::<Message>(value) {
+                    Ok(message) => self.handle(message, &mut output).await,
+                    Err(e) => Some(error_response(Value::Null, INVALID_REQUEST, e.to_string())),
+                },
+                Err(e) => Some(error_response(Value::Null, PARSE_ERROR, e.to_string())),
+            };
+            if let Some(response) = response {
+                write_message(&mut output, &response).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Response to `message`, or `None` for notifications and responses.
+    async fn handle<W: AsyncWrite + Unpin>(
+        &mut self,
+        message: Message,
+        output: &mut W,
+    ) -> Option<Value> {
+        let Some(method) = message.method else {
+            // A response to a server request; the server sends none.
+            return None;
+        };
+        let Some(id) = message.id else {
+            // Notifications, eg. `notifications/initialized`, need no response.
+            return None;
+        };
+        let params = message.params.unwrap_or(Value::Null);
+
+        let result = match method.as_str() {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools(self.read_only) })),
+            "tools/call" => match serde_json::from_value::<CallToolParams>(params) {
+                Ok(params) => self.call_tool(params, output).await,
+                Err(e) => Err((INVALID_PARAMS, e.to_string())),
+            },
+            _ => Err((METHOD_NOT_FOUND, format!("method not found: {method}"))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let version = requested
+            .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+            .unwrap_or(PROTOCOL_VERSION);
+
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": { "name": "topk", "version": env!("CARGO_PKG_VERSION") },
+            "instructions": "Search and ask questions about documents in TopK datasets. \
+                Use list_datasets to find dataset names, search to find relevant passages, and \
+                ask for answers with citations.",
+        })
+    }
+
+    async fn call_tool<W: AsyncWrite + Unpin>(
+        &mut self,
+        params: CallToolParams,
+        output: &mut W,
+    ) -> Result<Value, (i64, String)> {
+        let args = Value::Object(params.arguments);
+        let mut progress = Progress {
+            output,
+            token: params.meta.and_then(|meta| meta.progress_token),
+            count: 0,
+        };
+
+        let content = match params.name.as_str() {
+            "list_datasets" => self.list_datasets().await,
+            "list_documents" => match parse_args(args) {
+                Ok(args) => self.list_documents(args).await,
+                Err(e) => Err(e),
+            },
+            "search" => match parse_args(args) {
+                Ok(args) => self.search(args).await,
+                Err(e) => Err(e),
+            },
+            "ask" => match parse_args(args) {
+                Ok(args) => self.ask(args, &mut progress).await,
+                Err(e) => Err(e),
+            },
+            "get_content" => match parse_args(args) {
+                Ok(args) => self.get_content(args).await,
+                Err(e) => Err(e),
+            },
+            "upload" if !self.read_only => match parse_args(args) {
+                Ok(args) => self.upload(args).await,
+                Err(e) => Err(e),
+            },
+            name => return Err((INVALID_PARAMS, format!("unknown tool: {name}"))),
+        };
+
+        // Failed calls are results too, so that the model can see what went wrong.
+        Ok(match content {
+            Ok(content) => json!({ "content": content, "isError": false }),
+            Err(e) => json!({
+                "content": [{ "type": "text", "text": e.to_string() }],
+                "isError": true,
+            }),
+        })
+    }
+
+    /// Client of the region of `dataset`.
+    async fn dataset_client(&mut self, dataset: &str) -> Result<Client, Error> {
+        let region = get_region(&mut self.datasets, dataset).await?;
+        Ok(self.client(&region))
+    }
+
+    /// Client of the region shared by all `datasets`.
+    async fn datasets_client(&mut self, datasets: &[String]) -> Result<Client, Error> {
+        let region = ensure_unique_region(&mut self.datasets, datasets.to_vec()).await?;
+        Ok(self.client(&region))
+    }
+
+    fn client(&mut self, region: &str) -> Client {
+        self.clients
+            .entry(region.to_string())
+            .or_insert_with(|| make_client(&self.api_key, region, &self.host, self.https))
+            .clone()
+    }
+
+    async fn list_datasets(&mut self) -> Result<Vec<Value>, Error> {
+        let datasets = self
+            .datasets
+            .list()
+            .await?
+            .into_iter()
+            .map(Dataset::from)
+            .collect::<Vec<_>>();
+        json_content(&datasets)
+    }
+
+    async fn list_documents(&mut self, args: ListDocumentsArgs) -> Result<Vec<Value>, Error> {
+        let filter = args.filter.as_deref().map(parse_filter).transpose()?;
+        let client = self.dataset_client(&args.dataset).await?;
+
+        let mut stream = client
+            .dataset(&args.dataset)
+            .list(Some(args.fields), filter, None)
+            .await?;
+        let limit = args.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+        let mut entries = Vec::new();
+        while entries.len() < limit {
+            match stream.next().await {
+                Some(entry) => entries.push(ListEntry::from(entry?)),
+                None => break,
+            }
+        }
+
+        json_content(&entries)
+    }
+
+    async fn search(&mut self, args: SearchArgs) -> Result<Vec<Value>, Error> {
+        let filter = args.filter.as_deref().map(parse_filter).transpose()?;
+        let client = self.datasets_client(&args.datasets).await?;
+
+        let mut stream = client
+            .search(
+                args.query,
+                args.datasets,
+                args.top_k.unwrap_or(10),
+                filter,
+                args.fields,
+            )
+            .await?;
+        let mut results = Vec::new();
+        while let Some(result) = stream.next().await {
+            results.push(SearchResult::from(result?));
+        }
+
+        json_content(&results)
+    }
+
+    async fn ask<W: AsyncWrite + Unpin>(
+        &mut self,
+        args: AskArgs,
+        progress: &mut Progress<'_, W>,
+    ) -> Result<Vec<Value>, Error> {
+        let filter = args.filter.as_deref().map(parse_filter).transpose()?;
+        let mode = args
+            .mode
+            .as_deref()
+            .map(|mode| Mode::from_str(mode, true).map_err(Error::InvalidArgument))
+            .transpose()?;
+        let client = self.datasets_client(&args.datasets).await?;
+
+        let mut stream = client
+            .ask(
+                args.query,
+                args.datasets.iter().map(Source::new),
+                filter,
+                mode.map(Into::into),
+                Some(args.fields),
+                Some(true),
+            )
+            .await?;
+
+        while let Some(item) = stream.next().await {
+            match item?.message {
+                Some(ask_result::Message::Progress(p)) => progress.update(&p.update).await?,
+                Some(ask_result::Message::Answer(answer)) => {
+                    return json_content(&AskResult::from_answer(answer, true));
+                }
+                None => return Err(Error::InvalidProto),
+            }
+        }
+
+        Err(Error::Internal("No answer found".to_string()))
+    }
+
+    async fn get_content(&mut self, args: GetContentArgs) -> Result<Vec<Value>, Error> {
+        let client = self.dataset_client(&args.dataset).await?;
+        let response = client
+            .dataset(&args.dataset)
+            .get_content(args.content_id, None)
+            .await?;
+
+        match response.content.and_then(|content| content.data) {
+            Some(content::Data::Chunk(chunk)) => Ok(vec![text_content(chunk.text)]),
+            Some(content::Data::Image(image)) => {
+                Ok(vec![image_content(&image.data, &image.mime_type)])
+            }
+            Some(content::Data::Page(page)) => {
+                let mut content = vec![text_content(format!("Page {}", page.page_number))];
+                content.extend(
+                    page.image
+                        .map(|image| image_content(&image.data, &image.mime_type)),
+                );
+                Ok(content)
+            }
+            None => Err(Error::NotFound),
+        }
+    }
+
+    async fn upload(&mut self, args: UploadArgs) -> Result<Vec<Value>, Error> {
+        let metadata = match args.metadata {
+            Some(metadata) => json_object(metadata)?,
+            None => HashMap::new(),
+        };
+
+        let mut files = Vec::new();
+        for path in &args.paths {
+            files.extend(resolve_files(&self.cwd, path, args.recursive)?);
+        }
+        if files.is_empty() {
+            return Err(Error::InvalidArgument("no files to upload".to_string()));
+        }
+
+        let client = self.dataset_client(&args.dataset).await?;
+        let dataset = client.dataset(&args.dataset);
+
+        let mut uploaded = Vec::with_capacity(files.len());
+        for file in files {
+            let result = match InputFile::from_path(&file.path) {
+                Ok(input) => {
+                    dataset
+                        .upsert_file(file.doc_id.clone(), input, metadata.clone(), None)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            let (handle, error) = match result {
+                Ok(handle) => (Some(handle), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            uploaded.push(UploadedFile {
+                doc_id: file.doc_id,
+                path: file.path,
+                handle,
+                error,
+            });
+        }
+
+        json_content(&uploaded)
+    }
+}
+
+fn parse_args<T: for<'de> Deserialize<'de>>(args: Value) -> Result<T, Error> {
+    serde_json::from_value(args).map_err(|e| Error::InvalidArgument(e.to_string()))
+}
+
+fn parse_filter(filter: &str) -> Result<topk_rs::proto::v1::data::LogicalExpr, Error> {
+    filter::parse_filter(filter).map_err(Error::InvalidArgument)
+}
+
+fn json_content(value: &impl Serialize) -> Result<Vec<Value>, Error> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| Error::Internal(e.to_string()))?;
+    Ok(vec![text_content(text)])
+}
+
+fn text_content(text: String) -> Value {
+    json!({ "type": "text", "text": text })
+}
+
+fn image_content(data: &[u8], mime_type: &str) -> Value {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    json!({ "type": "image", "data": STANDARD.encode(data), "mimeType": mime_type })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(
+    output: &mut W,
+    message: &Value,
+) -> Result<(), Error> {
+    let mut line = message.to_string();
+    line.push('\n');
+    output.write_all(line.as_bytes()).await?;
+    output.flush().await?;
+    Ok(())
+}
+
+/// Tools of `tools/list`.
+fn tools(read_only: bool) -> Vec<Value> {
+    let filter = json!({
+        "type": "string",
+        "description": "Metadata filter written like a SQL WHERE clause, e.g. \
+            year >= 2023 and team in ('infra', 'ml')",
+    });
+    let fields = json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "description": "Metadata fields to include in results",
+    });
+    let datasets = json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "minItems": 1,
+        "description": "Datasets to search, all in the same region",
+    });
+
+    let mut tools = vec![
+        json!({
+            "name": "list_datasets",
+            "description": "List the datasets of the project",
+            "inputSchema": { "type": "object", "properties": {} },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "list_documents",
+            "description": "List documents in a dataset, with their processing status",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "dataset": { "type": "string" },
+                    "filter": filter,
+                    "fields": fields,
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": format!("Maximum number of documents (default: {DEFAULT_LIST_LIMIT})"),
+                    },
+                },
+                "required": ["dataset"],
+            },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "search",
+            "description": "Find passages relevant to a query in documents",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "datasets": datasets,
+                    "top_k": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Number of results (default: 10)",
+                    },
+                    "filter": filter,
+                    "fields": fields,
+                },
+                "required": ["query", "datasets"],
+            },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "ask",
+            "description": "Get an answer grounded in documents, with facts citing their references",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "datasets": datasets,
+                    "mode": { "type": "string", "enum": ["auto", "summarize", "research"] },
+                    "filter": filter,
+                    "fields": fields,
+                },
+                "required": ["query", "datasets"],
+            },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "get_content",
+            "description": "Get the text or image of a search result or reference by its content_id",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "dataset": { "type": "string" },
+                    "content_id": { "type": "string" },
+                },
+                "required": ["dataset", "content_id"],
+            },
+            "annotations": { "readOnlyHint": true },
+        }),
+    ];
+
+    if !read_only {
+        tools.push(json!({
+            "name": "upload",
+            "description": "Upload local files into a dataset. Documents are processed in the \
+                background; list_documents shows their status",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "dataset": { "type": "string" },
+                    "paths": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "minItems": 1,
+                        "description": "File paths, directories or glob patterns",
+                    },
+                    "recursive": {
+                        "type": "boolean",
+                        "description": "Recurse into subdirectories of directory paths",
+                    },
+                    "metadata": {
+                        "type": "object",
+                        "description": "Metadata of every uploaded document",
+                    },
+                },
+                "required": ["dataset", "paths"],
+            },
+            "annotations": { "readOnlyHint": false, "destructiveHint": false },
+        }));
+    }
+
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use topk_rs::proto::v1::control::Dataset as DatasetPb;
+
+    struct FakeDatasetsClient {
+        datasets: Vec<DatasetPb>,
+    }
+
+    #[async_trait(?Send)]
+    impl DatasetsClient for FakeDatasetsClient {
+        async fn list(&mut self) -> Result<Vec<DatasetPb>, Error> {
+            Ok(self.datasets.clone())
+        }
+
+        async fn get(&mut self, name: &str) -> Result<DatasetPb, Error> {
+            self.datasets
+                .iter()
+                .find(|d| d.name == name)
+                .cloned()
+                .ok_or(Error::DatasetNotFound)
+        }
+
+        async fn create(
+            &mut self,
+            _: &str,
+            _: &str,
+            _: Option<String>,
+        ) -> Result<DatasetPb, Error> {
+            unimplemented!()
+        }
+
+        async fn update(&mut self, _: &str, _: Option<String>) -> Result<DatasetPb, Error> {
+            unimplemented!()
+        }
+
+        async fn delete(&mut self, _: &str) -> Result<(), Error> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl DatasetRegionResolver for FakeDatasetsClient {
+        async fn get_region(&mut self, name: &str) -> Result<String, Error> {
+            Ok(self.get(name).await?.region)
+        }
+    }
+
+    fn server(read_only: bool) -> McpServer<FakeDatasetsClient> {
+        let datasets = vec![DatasetPb {
+            name: "docs".to_string(),
+            description: None,
+            region: "aws-us-east-1-elastica".to_string(),
+            org_id: "org".to_string(),
+            project_id: "project".to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        }];
+        McpServer::new(
+            FakeDatasetsClient { datasets },
+            "key",
+            "localhost",
+            false,
+            read_only,
+        )
+    }
+
+    async fn exchange(server: &mut McpServer<FakeDatasetsClient>, input: &[Value]) -> Vec<Value> {
+        let input = input
+            .iter()
+            .map(|message| format!("{message}\n"))
+            .collect::<String>();
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).await.unwrap();
+
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn initialize_and_list_tools() {
+        let responses = exchange(
+            &mut server(false),
+            &[
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "initialize",
+                    "params": { "protocolVersion": "2025-03-26", "capabilities": {} },
+                }),
+                json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+                json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+            ],
+        )
+        .await;
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(responses[0]["result"]["serverInfo"]["name"], "topk");
+
+        let tools = responses[1]["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tools,
+            vec![
+                "list_datasets",
+                "list_documents",
+                "search",
+                "ask",
+                "get_content",
+                "upload"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn read_only_hides_upload() {
+        let responses = exchange(
+            &mut server(true),
+            &[
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }),
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 2,
+                    "method": "tools/call",
+                    "params": { "name": "upload", "arguments": {} },
+                }),
+            ],
+        )
+        .await;
+
+        assert!(responses[0]["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|tool| tool["name"] != "upload"));
+        assert_eq!(responses[1]["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn call_list_datasets() {
+        let responses = exchange(
+            &mut server(false),
+            &[json!({
+                "jsonrpc": "2.0",
+                "id": "a",
+                "method": "tools/call",
+                "params": { "name": "list_datasets" },
+            })],
+        )
+        .await;
+
+        let result = &responses[0]["result"];
+        assert_eq!(result["isError"], false);
+        let datasets: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(datasets[0]["name"], "docs");
+        assert_eq!(datasets[0]["region"], "aws-us-east-1-elastica");
+    }
+
+    #[tokio::test]
+    async fn tool_errors_are_results() {
+        let responses = exchange(
+            &mut server(false),
+            &[
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": { "name": "search", "arguments": { "query": "q" } },
+                }),
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 2,
+                    "method": "tools/call",
+                    "params": {
+                        "name": "search",
+                        "arguments": { "query": "q", "datasets": ["docs"], "filter": "year >=" },
+                    },
+                }),
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 3,
+                    "method": "tools/call",
+                    "params": {
+                        "name": "list_documents",
+                        "arguments": { "dataset": "missing" },
+                    },
+                }),
+            ],
+        )
+        .await;
+
+        for response in &responses {
+            assert_eq!(response["result"]["isError"], true, "{response}");
+        }
+        assert!(responses[0]["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("datasets"));
+    }
+
+    #[tokio::test]
+    async fn protocol_errors() {
+        let mut server = server(false);
+        let mut output = Vec::new();
+        server
+            .serve(
+                "not json\n{\"jsonrpc\":\"2.0\",\"id\":6,\"method\":6}\n{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"resources/list\"}\n"
+                    .as_bytes(),
+                &mut output,
+            )
+            .await
+            .unwrap();
+
+        let responses = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(responses[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[1]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[2]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["id"], 7);
+    }
+}
@@ -74,6 +74,13 @@ impl Output {
         }
     }
 
+    pub fn progress_bytes(&self, total: u64, msg: impl Into<String>) -> Spinner {
+        match self.format {
+            OutputFormat::Text => Spinner::with_bytes(total, msg),
+            OutputFormat::Json => Spinner::disabled(),
+        }
+    }
+
     pub fn success(&self, msg: &str) {
         match self.format {
             OutputFormat::Text => {
@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use futures::future::try_join_all;
+use futures::TryStreamExt;
+use topk_rs::proto::v1::control::Collection;
+use topk_rs::proto::v1::data::{ConsistencyLevel, Document, Query, Value};
+use topk_rs::{Client, CollectionClient, Error, RequestOptions};
+use topk_sql::{
+    resolve_score_orders, Catalog, CopyDecoder, CopyEncoder, CopyOptions, ExplainAnalyze,
+    MigrationPlan, RowFilter, SelectItemExt, SqlStatementExt, Statement, Table, Variable,
+};
+
+use crate::client::make_client;
+use crate::collections::{get_region, CollectionRegionResolver, CollectionsClient};
+
+/// Result of a single SQL statement.
+#[derive(Debug)]
+pub enum SqlOutput {
+    /// Rows of a query, with the column names in output order.
+    Rows {
+        columns: Vec<String>,
+        rows: Vec<Document>,
+    },
+    /// Text of `EXPLAIN`, `SHOW CREATE TABLE` and the migration plan of `ALTER TABLE`, returned
+    /// as a single `column`.
+    Text { column: String, text: String },
+    /// Raw `COPY … TO STDOUT` data.
+    Copy(Vec<u8>),
+    /// Command tag of statements without rows, eg. `INSERT 0 3`.
+    Tag(String),
+}
+
+/// Executes `topk_sql` statements against the collections of a project.
+///
+/// Data requests go to the region of each collection (see `collections::get_region`). Writes
+/// record their LSN so that later queries in the session read them back.
+pub struct SqlSession<C> {
+    collections: C,
+    api_key: String,
+    host: String,
+    https: bool,
+    /// Region of collections created with `CREATE TABLE`.
+    region: Option<String>,
+    /// `SET consistency_level`
+    consistency: Option<ConsistencyLevel>,
+    /// Regional clients, by region.
+    clients: HashMap<String, Client>,
+    /// LSN of the last write, by collection.
+    lsns: HashMap<String, String>,
+}
+
+impl<C> SqlSession<C>
+where
+    C: CollectionsClient + CollectionRegionResolver,
+{
+    pub fn new(
+        collections: C,
+        api_key: &str,
+        host: &str,
+        https: bool,
+        region: Option<String>,
+    ) -> Self {
+        Self {
+            collections,
+            api_key: api_key.to_string(),
+            host: host.to_string(),
+            https,
+            region,
+            consistency: None,
+            clients: HashMap::new(),
+            lsns: HashMap::new(),
+        }
+    }
+
+    pub async fn list_collections(&mut self) -> Result<Vec<Collection>, Error> {
+        self.collections.list().await
+    }
+
+    pub async fn get_collection(&mut self, name: &str) -> Result<Collection, Error> {
+        self.collections.get(name).await
+    }
+
+    /// Executes `stmt`, whose output `columns` are given by `projection`.
+    ///
+    /// `COPY … FROM STDIN` needs its input, see `copy_from`.
+    pub async fn execute(
+        &mut self,
+        stmt: Statement,
+        columns: Vec<String>,
+    ) -> Result<SqlOutput, Error> {
+        match stmt {
+            Statement::Select { .. }
+            | Statement::Count { .. }
+            | Statement::Fuse { .. }
+            | Statement::Catalog { .. } => {
+                let (rows, _) = self.query(&stmt, &columns).await?;
+                let columns = match &stmt {
+                    Statement::Catalog { query } => query
+                        .projection
+                        .iter()
+                        .map(|(name, _)| name.clone())
+                        .collect(),
+                    _ => columns,
+                };
+                Ok(SqlOutput::Rows { columns, rows })
+            }
+            Statement::Insert { table, docs } => {
+                let n = docs.len();
+                let lsn = self.collection(&table).await?.upsert(docs, None).await?;
+                self.lsns.insert(table.collection().to_string(), lsn);
+                Ok(SqlOutput::Tag(format!("INSERT 0 {n}")))
+            }
+            Statement::Update {
+                table,
+                docs,
+                fail_on_missing,
+            } => {
+                let n = docs.len();
+                let lsn = self
+                    .collection(&table)
+                    .await?
+                    .update(docs, fail_on_missing, None)
+                    .await?;
+                self.lsns.insert(table.collection().to_string(), lsn);
+                Ok(SqlOutput::Tag(format!("UPDATE {n}")))
+            }
+            Statement::Delete { table, filter } => {
+                let client = self.collection(&table).await?;
+                let lsn = match filter {
+                    RowFilter::Ids(ids) => client.delete(ids, None).await?,
+                    RowFilter::Expr(expr) => client.delete(expr, None).await?,
+                };
+                self.lsns.insert(table.collection().to_string(), lsn);
+                Ok(SqlOutput::Tag("DELETE".to_string()))
+            }
+            Statement::DeletePartition { table } => {
+                let Table::Partition(collection, partition) = &table else {
+                    return Err(Error::InvalidArgument(format!(
+                        "expected a partition, got `{table}`"
+                    )));
+                };
+                self.collection(&Table::Collection(collection.clone()))
+                    .await?
+                    .delete_partition(partition, None)
+                    .await?;
+                Ok(SqlOutput::Tag("DELETE".to_string()))
+            }
+            Statement::CopyFrom { .. } => Err(Error::InvalidArgument(
+                "COPY … FROM STDIN requires input data".into(),
+            )),
+            Statement::CopyTo {
+                stmt,
+                columns,
+                options,
+            } => {
+                let (rows, _) = self.query(&stmt, &columns).await?;
+                let mut encoder = CopyEncoder::new(options, columns);
+                if let Some(table) = stmt.table() {
+                    encoder =
+                        encoder.with_schema(self.get_collection(table.collection()).await?.schema);
+                }
+                let mut data = encoder.encode(&rows).map_err(sql_error)?;
+                data.extend(encoder.finish());
+                Ok(SqlOutput::Copy(data))
+            }
+            Statement::CreateTable {
+                table,
+                schema,
+                if_not_exists,
+            } => {
+                let region = self.region.clone().ok_or_else(|| {
+                    Error::InvalidArgument("CREATE TABLE requires --region".into())
+                })?;
+                match self
+                    .collections
+                    .create(table.collection(), schema, &region)
+                    .await
+                {
+                    Ok(_) => {}
+                    Err(Error::CollectionAlreadyExists) if if_not_exists => {}
+                    Err(err) => return Err(err),
+                }
+                Ok(SqlOutput::Tag("CREATE TABLE".to_string()))
+            }
+            Statement::DropTable { table, if_exists } => {
+                match self.collections.delete(table.collection()).await {
+                    Ok(()) => {}
+                    Err(Error::CollectionNotFound) if if_exists => {}
+                    Err(err) => return Err(err),
+                }
+                self.lsns.remove(table.collection());
+                Ok(SqlOutput::Tag("DROP TABLE".to_string()))
+            }
+            Statement::AlterTable {
+                table,
+                changes,
+                if_exists,
+            } => {
+                let collection = match self.get_collection(table.collection()).await {
+                    Ok(collection) => collection,
+                    Err(Error::CollectionNotFound) if if_exists => {
+                        return Ok(SqlOutput::Tag("ALTER TABLE".to_string()))
+                    }
+                    Err(err) => return Err(err),
+                };
+                let plan = MigrationPlan::new(&collection.name, &collection.schema, &changes)
+                    .map_err(sql_error)?;
+                if plan.steps.is_empty() {
+                    return Ok(SqlOutput::Tag("ALTER TABLE".to_string()));
+                }
+                // Copying every document through a staging collection is left to the user,
+                // so the plan is returned like `EXPLAIN` output.
+                Ok(SqlOutput::Text {
+                    column: "migration_plan".to_string(),
+                    text: format!(
+                        "-- ALTER TABLE rebuilds `{table}`, which `topk sql` does not run. \
+                         Run these steps to apply it:\n{plan}"
+                    ),
+                })
+            }
+            Statement::ShowCreateTable { table } => {
+                let collection = self.get_collection(table.collection()).await?;
+                Ok(SqlOutput::Text {
+                    column: "create_table".to_string(),
+                    text: topk_sql::create_table_sql(&collection.name, &collection.schema)
+                        .map_err(sql_error)?,
+                })
+            }
+            Statement::Explain {
+                stmt,
+                verbose,
+                analyze,
+                format,
+            } => {
+                let analyze = match analyze {
+                    true => {
+                        let start = Instant::now();
+                        let (rows, matched_count) = self.query(&stmt, &columns).await?;
+                        Some(ExplainAnalyze {
+                            elapsed: start.elapsed(),
+                            matched_count,
+                            rows: rows.len() as u64,
+                        })
+                    }
+                    false => None,
+                };
+                Ok(SqlOutput::Text {
+                    column: "QUERY PLAN".to_string(),
+                    text: stmt.explain(format, verbose, analyze.as_ref()),
+                })
+            }
+            Statement::Set { variable, value } => {
+                match variable {
+                    Variable::ConsistencyLevel => {
+                        self.consistency = match value.as_string() {
+                            Some(s) if s.eq_ignore_ascii_case("indexed") => {
+                                Some(ConsistencyLevel::Indexed)
+                            }
+                            Some(s) if s.eq_ignore_ascii_case("strong") => {
+                                Some(ConsistencyLevel::Strong)
+                            }
+                            Some(s) if s.eq_ignore_ascii_case("default") => None,
+                            _ => return Err(Error::InvalidArgument(
+                                "consistency_level must be one of: 'indexed', 'strong', 'default'"
+                                    .into(),
+                            )),
+                        }
+                    }
+                }
+                Ok(SqlOutput::Tag("SET".to_string()))
+            }
+            Statement::Show { variable } => {
+                let value = match variable {
+                    Variable::ConsistencyLevel => match self.consistency {
+                        Some(ConsistencyLevel::Indexed) => "indexed",
+                        Some(ConsistencyLevel::Strong) => "strong",
+                        _ => "default",
+                    },
+                };
+                Ok(SqlOutput::Rows {
+                    columns: vec![variable.as_str().to_string()],
+                    rows: vec![Document::from([(
+                        variable.as_str().to_string(),
+                        Value::string(value),
+                    )])],
+                })
+            }
+            Statement::Begin => Ok(SqlOutput::Tag("BEGIN".to_string())),
+            Statement::Commit => Ok(SqlOutput::Tag("COMMIT".to_string())),
+            Statement::Rollback => Ok(SqlOutput::Tag("ROLLBACK".to_string())),
+            Statement::Discard => Ok(SqlOutput::Tag("DISCARD ALL".to_string())),
+        }
+    }
+
+    /// Executes `COPY … FROM STDIN` with the complete input `data`.
+    pub async fn copy_from(
+        &mut self,
+        table: Table,
+        columns: Vec<String>,
+        options: CopyOptions,
+        data: &[u8],
+    ) -> Result<SqlOutput, Error> {
+        let schema = self.get_collection(table.collection()).await?.schema;
+        let mut decoder = CopyDecoder::new(options, columns, schema).map_err(sql_error)?;
+
+        let mut batches = decoder.push(data).map_err(sql_error)?;
+        batches.extend(decoder.finish().map_err(sql_error)?);
+
+        let client = self.collection(&table).await?;
+        let mut rows = 0;
+        for batch in batches {
+            rows += batch.len();
+            let lsn = client.upsert(batch, None).await?;
+            self.lsns.insert(table.collection().to_string(), lsn);
+        }
+
+        Ok(SqlOutput::Tag(format!("COPY {rows}")))
+    }
+
+    /// Runs a query statement, returning its rows and the matched count (for `EXPLAIN ANALYZE`).
+    async fn query(
+        &mut self,
+        stmt: &Statement,
+        columns: &[String],
+    ) -> Result<(Vec<Document>, Option<u64>), Error> {
+        match stmt {
+            Statement::Select { table, query } => {
+                let options = self.options(table);
+                let mut stream = self
+                    .collection(table)
+                    .await?
+                    .query_stream(query.clone(), Some(options))
+                    .await?;
+                let matched_count = stream.matched_count();
+                let mut rows = Vec::new();
+                while let Some(doc) = stream.try_next().await? {
+                    rows.push(doc);
+                }
+                Ok((rows, matched_count))
+            }
+            Statement::Count { table, query } => {
+                let rows = self.run(table, query.clone()).await?;
+                // Report `_count` under the projected name, eg. `count`.
+                let rows = match columns.first() {
+                    Some(column) => rows
+                        .into_iter()
+                        .map(|mut doc| {
+                            if let Some(count) = doc.fields.remove("_count") {
+                                doc.fields.insert(column.clone(), count);
+                            }
+                            doc
+                        })
+                        .collect(),
+                    None => rows,
+                };
+                Ok((rows, None))
+            }
+            Statement::Fuse {
+                table,
+                queries,
+                fusion,
+                alias,
+                offset,
+                limit,
+            } => {
+                let schema = self.collections.get(table.collection()).await?.schema;
+                let mut queries = queries.clone();
+                let orders = resolve_score_orders(&mut queries, &schema);
+
+                let client = self.collection(table).await?;
+                let options = self.options(table);
+                let results = try_join_all(
+                    queries
+                        .into_iter()
+                        .map(|query| client.query(query, Some(options.clone()))),
+                )
+                .await?;
+                let rows = fusion
+                    .fuse(results, &orders, alias)
+                    .map_err(sql_error)?
+                    .into_iter()
+                    .skip(*offset as usize)
+                    .take(*limit as usize)
+                    .collect();
+                Ok((rows, None))
+            }
+            Statement::Catalog { query } => {
+                let catalog = Catalog::new(self.collections.list().await?);
+                Ok((query.execute(&catalog), None))
+            }
+            stmt => Err(Error::InvalidArgument(format!(
+                "expected a query, got `{}`",
+                stmt.as_str()
+            ))),
+        }
+    }
+
+    async fn run(&mut self, table: &Table, query: Query) -> Result<Vec<Document>, Error> {
+        let options = self.options(table);
+        self.collection(table)
+            .await?
+            .query(query, Some(options))
+            .await
+    }
+
+    /// Options of queries against `table`, reading the session's own writes.
+    fn options(&self, table: &Table) -> RequestOptions {
+        RequestOptions {
+            lsn: self.lsns.get(table.collection()).cloned(),
+            consistency: self.consistency,
+            ..Default::default()
+        }
+    }
+
+    /// Client of `table`, in the region of its collection.
+    async fn collection(&mut self, table: &Table) -> Result<CollectionClient, Error> {
+        let region = get_region(&mut self.collections, table.collection()).await?;
+        let client = self.clients.entry(region).or_insert_with_key(|region| {
+            make_client(&self.api_key, region, &self.host, self.https)
+        });
+        Ok(table.clone().configure(client.clone()))
+    }
+}
+
+/// Output column names of a parsed query, in projection order.
+pub fn projection<S: SqlStatementExt>(raw: Option<&S>) -> Vec<String> {
+    raw.and_then(|raw| raw.projection())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.projection_name().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn sql_error(err: topk_sql::Error) -> Error {
+    Error::InvalidArgument(err.to_string())
+}
@@ -0,0 +1,103 @@
+use topk_rs::proto::v1::{
+    ctx::Source,
+    data::{stage::filter_stage::filter_expr::Expr, LogicalExpr},
+};
+
+/// Parses a `--filter` expression with the grammar of SQL `WHERE`, e.g.
+/// `year >= 2023 and team in ("infra", "ml") and title starts_with "RFC"`.
+pub fn parse_filter(s: &str) -> Result<LogicalExpr, String> {
+    let exprs = topk_sql::parse_cli_filter(s).map_err(|e| e.to_string())?;
+
+    exprs
+        .into_iter()
+        .map(|expr| match expr.expr {
+            Some(Expr::LogicalExpr(expr)) => Ok(expr),
+            _ => Err("text matching is not supported in metadata filters".to_string()),
+        })
+        .reduce(|left, right| Ok(left?.and(right?)))
+        .unwrap_or_else(|| Err("empty filter".to_string()))
+}
+
+/// Parses a `--dataset-filter` flag: `DATASET=FILTER`.
+pub fn parse_dataset_filter(s: &str) -> Result<(String, LogicalExpr), String> {
+    let (dataset, filter) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected DATASET=FILTER, got '{s}'"))?;
+    let dataset = dataset.trim();
+    if dataset.is_empty() {
+        return Err(format!("missing dataset in '{s}'"));
+    }
+
+    Ok((dataset.to_string(), parse_filter(filter)?))
+}
+
+/// Sources of `datasets`, with the filters of `dataset_filters`.
+pub fn sources(
+    datasets: &[String],
+    dataset_filters: &[(String, LogicalExpr)],
+) -> Result<Vec<Source>, topk_rs::Error> {
+    if let Some((dataset, _)) = dataset_filters
+        .iter()
+        .find(|(dataset, _)| !datasets.contains(dataset))
+    {
+        return Err(topk_rs::Error::InvalidArgument(format!(
+            "filter for dataset '{dataset}', which is not one of the --dataset flags"
+        )));
+    }
+
+    Ok(datasets
+        .iter()
+        .map(|dataset| {
+            dataset_filters
+                .iter()
+                .filter(|(name, _)| name == dataset)
+                .map(|(_, filter)| filter.clone())
+                .reduce(LogicalExpr::and)
+                .into_iter()
+                .fold(Source::new(dataset), Source::with_filter)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use topk_rs::proto::v1::data::Value;
+
+    #[test]
+    fn filter_is_a_conjunction() {
+        assert_eq!(
+            parse_filter(r#"year >= 2023 and title starts_with "RFC""#).unwrap(),
+            LogicalExpr::field("year")
+                .gte(Value::i64(2023))
+                .and(LogicalExpr::field("title").starts_with(Value::string("RFC")))
+        );
+
+        assert!(parse_filter("match('rfc')").is_err());
+        assert!(parse_filter("year >=").is_err());
+    }
+
+    #[test]
+    fn dataset_filters() {
+        let datasets = vec!["docs".to_string(), "wiki".to_string()];
+        let filters = vec![
+            parse_dataset_filter("wiki=year > 2020").unwrap(),
+            parse_dataset_filter("wiki = team = 'ml'").unwrap(),
+        ];
+
+        let sources = sources(&datasets, &filters).unwrap();
+        assert_eq!(sources[0], Source::new("docs"));
+        assert_eq!(
+            sources[1],
+            Source::new("wiki").with_filter(
+                LogicalExpr::field("year")
+                    .gt(Value::i64(2020))
+                    .and(LogicalExpr::field("team").eq(Value::string("ml")))
+            )
+        );
+
+        let filters = vec![parse_dataset_filter("blog=year > 2020").unwrap()];
+        assert!(super::sources(&datasets, &filters).is_err());
+        assert!(parse_dataset_filter("year > 2020").is_err());
+    }
+}
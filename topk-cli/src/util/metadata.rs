@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use clap::Args;
+use regex::Regex;
+use topk_rs::{proto::v1::data::Value, Error};
+
+/// Metadata flags shared by commands that upload files.
+#[derive(Debug, Clone, Default, Args)]
+pub struct MetadataArgs {
+    /// Metadata for every file, as KEY=VALUE or KEY:TYPE=VALUE (TYPE: string, int, float, bool, list)
+    #[arg(short = 'm', long = "meta", value_name = "KEY=VALUE", value_parser = parse_meta)]
+    pub meta: Vec<(String, Value)>,
+    /// Metadata captured from file paths, e.g. 'docs/{team}/{year}/*'
+    #[arg(long, value_name = "TEMPLATE", value_parser = PathTemplate::parse)]
+    pub meta_from_path: Vec<PathTemplate>,
+}
+
+impl MetadataArgs {
+    /// Metadata of the file at `path`, relative to `cwd`. Later sources override earlier ones:
+    /// path templates, Markdown front matter, the `<file>.meta.json` sidecar and `--meta` flags.
+    pub fn file_metadata(&self, cwd: &Path, path: &Path) -> Result<HashMap<String, Value>, Error> {
+        let mut metadata = HashMap::new();
+
+        let relative = path.strip_prefix(cwd).unwrap_or(path);
+        for template in &self.meta_from_path {
+            metadata.extend(template.captures(relative));
+        }
+
+        let is_markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+            });
+        if is_markdown {
+            metadata.extend(front_matter(&std::fs::read_to_string(path)?).map_err(|e| {
+                Error::InvalidArgument(format!("{}: front matter: {e}", path.display()))
+            })?);
+        }
+
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(".meta.json");
+        let sidecar = Path::new(&sidecar);
+        if sidecar.is_file() {
+            let json = serde_json::from_str(&std::fs::read_to_string(sidecar)?)
+                .map_err(|e| Error::InvalidArgument(format!("{}: {e}", sidecar.display())))?;
+            metadata.extend(
+                json_object(json)
+                    .map_err(|e| Error::InvalidArgument(format!("{}: {e}", sidecar.display())))?,
+            );
+        }
+
+        metadata.extend(self.meta.iter().cloned());
+
+        Ok(metadata)
+    }
+}
+
+/// Parses a `--meta` flag: `KEY=VALUE` with an inferred type, or `KEY:TYPE=VALUE`.
+fn parse_meta(s: &str) -> Result<(String, Value), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{s}'"))?;
+
+    let (key, value) = match key.split_once(':') {
+        Some((key, ty)) => (key, typed_value(ty, value)?),
+        None => (key, infer_value(value)),
+    };
+    if key.is_empty() {
+        return Err(format!("missing key in '{s}'"));
+    }
+
+    Ok((key.to_string(), value))
+}
+
+fn typed_value(ty: &str, value: &str) -> Result<Value, String> {
+    match ty.to_ascii_lowercase().as_str() {
+        "string" | "str" => Ok(Value::string(value)),
+        "int" | "integer" => value
+            .trim()
+            .parse::<i64>()
+            .map(Value::i64)
+            .map_err(|e| format!("invalid int '{value}': {e}")),
+        "float" => value
+            .trim()
+            .parse::<f64>()
+            .map(Value::f64)
+            .map_err(|e| format!("invalid float '{value}': {e}")),
+        "bool" | "boolean" => value
+            .trim()
+            .parse::<bool>()
+            .map(Value::bool)
+            .map_err(|_| format!("invalid bool '{value}', expected true or false")),
+        "list" => Ok(list_value(value)),
+        _ => Err(format!(
+            "unknown type '{ty}', expected string, int, float, bool or list"
+        )),
+    }
+}
+
+/// Infers the type of `value`: bool, int, float, `[a, b]` list, or string.
+fn infer_value(value: &str) -> Value {
+    if let Ok(b) = value.parse::<bool>() {
+        return Value::bool(b);
+    }
+    if let Ok(i) = value.parse::<i64>() {
+        return Value::i64(i);
+    }
+    // Excludes `inf` and `NaN`, which are more likely strings.
+    if value.contains(|c: char| c.is_ascii_digit()) {
+        if let Ok(f) = value.parse::<f64>() {
+            return Value::f64(f);
+        }
+    }
+    if let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return list_value(items);
+    }
+    Value::string(value)
+}
+
+/// Comma-separated list: of ints or floats if all items are numbers, of strings otherwise.
+fn list_value(items: &str) -> Value {
+    let items = items
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect::<Vec<_>>();
+
+    if let Ok(ints) = items.iter().map(|i| i.parse::<i64>()).collect() {
+        return Value::list::<Vec<i64>>(ints);
+    }
+    if let Ok(floats) = items.iter().map(|i| i.parse::<f64>()).collect() {
+        return Value::list::<Vec<f64>>(floats);
+    }
+    Value::list(items.into_iter().map(str::to_string).collect::<Vec<_>>())
+}
+
+/// Converts the fields of a JSON object. `null` fields are skipped.
+pub(crate) fn json_object(json: serde_json::Value) -> Result<HashMap<String, Value>, Error> {
+    let serde_json::Value::Object(object) = json else {
+        return Err(Error::InvalidArgument("expected an object".into()));
+    };
+
+    object
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| Ok((key, Value::try_from(value)?)))
+        .collect()
+}
+
+/// Fields of the YAML front matter (between `---` lines) at the start of a Markdown document.
+fn front_matter(markdown: &str) -> Result<HashMap<String, Value>, Error> {
+    let markdown = markdown.strip_prefix('\u{feff}').unwrap_or(markdown);
+    let Some(rest) = markdown
+        .strip_prefix("---\n")
+        .or_else(|| markdown.strip_prefix("---\r\n"))
+    else {
+        return Ok(HashMap::new());
+    };
+
+    let mut yaml = String::new();
+    for line in rest.lines() {
+        if line.trim_end() == "---" {
+            let json: serde_json::Value =
+                serde_yaml::from_str(&yaml).map_err(|e| Error::InvalidArgument(e.to_string()))?;
+            return match json {
+                serde_json::Value::Null => Ok(HashMap::new()),
+                json => json_object(json),
+            };
+        }
+        yaml.push_str(line);
+        yaml.push('\n');
+    }
+
+    // Unterminated: a thematic break rather than front matter.
+    Ok(HashMap::new())
+}
+
+/// Path template with `{name}` captures, `*` (within a directory) and `**` (any directories).
+#[derive(Debug, Clone)]
+pub struct PathTemplate {
+    regex: Regex,
+}
+
+impl PathTemplate {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut pattern = String::from(r"(?:^|/)");
+        let mut rest = template.trim_start_matches("./");
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix("**") {
+                pattern.push_str(".*");
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('*') {
+                pattern.push_str("[^/]*");
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('{') {
+                let (name, after) = after
+                    .split_once('}')
+                    .ok_or_else(|| format!("unclosed '{{' in '{template}'"))?;
+                if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    return Err(format!("invalid capture '{{{name}}}' in '{template}'"));
+                }
+                pattern.push_str(&format!("(?P<{name}>[^/]+)"));
+                rest = after;
+            } else {
+                let end = rest.find(['*', '{']).unwrap_or(rest.len());
+                pattern.push_str(&regex::escape(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+        pattern.push('$');
+
+        let regex =
+            Regex::new(&pattern).map_err(|e| format!("invalid template '{template}': {e}"))?;
+        Ok(Self { regex })
+    }
+
+    /// Captured values of `path`, with inferred types. Empty if `path` does not match.
+    fn captures(&self, path: &Path) -> HashMap<String, Value> {
+        let path = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let Some(caps) = self.regex.captures(&path) else {
+            return HashMap::new();
+        };
+        self.regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| Some((name.to_string(), infer_value(caps.name(name)?.as_str()))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn meta_flags_are_typed() {
+        assert_eq!(
+            parse_meta("team=infra").unwrap(),
+            ("team".to_string(), Value::string("infra"))
+        );
+        assert_eq!(parse_meta("year=2023").unwrap().1, Value::i64(2023));
+        assert_eq!(parse_meta("score=0.5").unwrap().1, Value::f64(0.5));
+        assert_eq!(parse_meta("draft=true").unwrap().1, Value::bool(true));
+        assert_eq!(
+            parse_meta("tags=[a, b]").unwrap().1,
+            Value::list(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(
+            parse_meta("ids:list=1,2").unwrap().1,
+            Value::list(vec![1i64, 2])
+        );
+        assert_eq!(
+            parse_meta("year:string=2023").unwrap().1,
+            Value::string("2023")
+        );
+        assert_eq!(parse_meta("url=a=b").unwrap().1, Value::string("a=b"));
+
+        assert!(parse_meta("year").is_err());
+        assert!(parse_meta("=1").is_err());
+        assert!(parse_meta("year:int=soon").is_err());
+        assert!(parse_meta("year:date=2023").is_err());
+    }
+
+    #[test]
+    fn path_template_captures() {
+        let template = PathTemplate::parse("docs/{team}/{year}/*").unwrap();
+        assert_eq!(
+            template.captures(Path::new("docs/ml/2023/report.pdf")),
+            HashMap::from([
+                ("team".to_string(), Value::string("ml")),
+                ("year".to_string(), Value::i64(2023)),
+            ])
+        );
+        assert_eq!(
+            template
+                .captures(Path::new("/home/me/docs/ml/2023/report.pdf"))
+                .len(),
+            2
+        );
+        assert!(template
+            .captures(Path::new("docs/ml/2023/q1/report.pdf"))
+            .is_empty());
+        assert!(template
+            .captures(Path::new("mydocs/ml/2023/report.pdf"))
+            .is_empty());
+
+        let template = PathTemplate::parse("{team}/**/*.md").unwrap();
+        assert_eq!(
+            template.captures(Path::new("infra/a/b/notes.md"))["team"],
+            Value::string("infra")
+        );
+
+        assert!(PathTemplate::parse("docs/{team").is_err());
+        assert!(PathTemplate::parse("docs/{a-b}").is_err());
+    }
+
+    #[test]
+    fn front_matter_fields() {
+        let metadata =
+            front_matter("---\ntitle: RFC 12\nyear: 2023\ntags: [a, b]\n---\n# Body\n").unwrap();
+        assert_eq!(metadata["title"], Value::string("RFC 12"));
+        assert_eq!(metadata["year"], Value::i64(2023));
+        assert_eq!(
+            metadata["tags"],
+            Value::list(vec!["a".to_string(), "b".to_string()])
+        );
+
+        assert!(front_matter("# No front matter\n---\n").unwrap().is_empty());
+        assert!(front_matter("---\nnot closed\n").unwrap().is_empty());
+        assert!(front_matter("---\n- a list\n---\n").is_err());
+    }
+
+    #[test]
+    fn file_metadata_precedence() {
+        let dir = tempdir().unwrap();
+        let docs = dir.path().join("docs").join("ml");
+        fs::create_dir_all(&docs).unwrap();
+        let path = docs.join("rfc.md");
+        fs::write(&path, "---\nteam: infra\nstatus: draft\n---\n# RFC\n").unwrap();
+        fs::write(
+            docs.join("rfc.md.meta.json"),
+            r#"{"status": "final", "pages": 3}"#,
+        )
+        .unwrap();
+
+        let args = MetadataArgs {
+            meta: vec![parse_meta("pages=4").unwrap()],
+            meta_from_path: vec![PathTemplate::parse("docs/{team}/*").unwrap()],
+        };
+        let metadata = args.file_metadata(dir.path(), &path).unwrap();
+
+        assert_eq!(metadata["team"], Value::string("infra"));
+        assert_eq!(metadata["status"], Value::string("final"));
+        assert_eq!(metadata["pages"], Value::i64(4));
+    }
+}
@@ -4,8 +4,11 @@ use chrono::{DateTime, Local, Utc};
 
 pub mod bytes;
 pub mod files;
+pub mod filter;
+pub mod metadata;
 pub mod mime;
 pub mod progress;
+pub mod repl;
 
 pub use bytes::Base64;
 pub use mime::MimeType;
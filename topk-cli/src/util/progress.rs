@@ -16,6 +16,15 @@ impl Spinner {
         Self::create(msg, "{spinner:.cyan} {msg} [{elapsed}]")
     }
 
+    /// Progress bar over `total` bytes, eg. of an input file.
+    pub fn with_bytes(total: u64, msg: impl Into<String>) -> Self {
+        Self::create_bar(
+            ProgressBar::new(total),
+            msg,
+            "[{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} {msg}",
+        )
+    }
+
     pub fn disabled() -> Self {
         Self {
             progress_bar: None,
@@ -24,12 +33,20 @@ impl Spinner {
     }
 
     fn create(msg: impl Into<String>, template: &str) -> Self {
+        Self::create_bar(ProgressBar::new_spinner(), msg, template)
+    }
+
+    fn create_bar(bar: ProgressBar, msg: impl Into<String>, template: &str) -> Self {
         if !io::stderr().is_terminal() {
             return Self::disabled();
         }
         let multi = MultiProgress::new();
-        let bar = multi.add(ProgressBar::new_spinner());
-        bar.set_style(ProgressStyle::with_template(template).expect("valid spinner template"));
+        let bar = multi.add(bar);
+        bar.set_style(
+            ProgressStyle::with_template(template)
+                .expect("valid spinner template")
+                .progress_chars("=>-"),
+        );
         bar.set_message(msg.into());
         bar.enable_steady_tick(std::time::Duration::from_millis(100));
         Self {
@@ -44,6 +61,12 @@ impl Spinner {
         }
     }
 
+    pub fn set_position(&self, pos: u64) {
+        if let Some(progress_bar) = &self.progress_bar {
+            progress_bar.set_position(pos);
+        }
+    }
+
     /// Print text above the spinner without disrupting it.
     pub fn print(&self, msg: impl AsRef<str>) {
         match &self.multi {
@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use topk_rs::Error;
+
+/// Source of input lines: a script or the interactive prompt.
+pub(crate) trait Lines {
+    fn read_line(&mut self, prompt: &str) -> Result<Option<String>, Error>;
+
+    fn add_history(&mut self, _entry: &str) {}
+}
+
+pub(crate) struct Script {
+    lines: std::vec::IntoIter<String>,
+}
+
+impl Script {
+    pub(crate) fn new(script: &str) -> Self {
+        let lines = script.lines().map(str::to_string).collect::<Vec<_>>();
+        Self {
+            lines: lines.into_iter(),
+        }
+    }
+}
+
+impl Lines for Script {
+    fn read_line(&mut self, _prompt: &str) -> Result<Option<String>, Error> {
+        Ok(self.lines.next())
+    }
+}
+
+pub(crate) struct Repl {
+    editor: DefaultEditor,
+    history: Option<PathBuf>,
+}
+
+impl Repl {
+    /// Prompt with the history of the `history` file of the config directory.
+    pub(crate) fn new(history: &str) -> Result<Self, Error> {
+        let mut editor = DefaultEditor::new().map_err(readline_error)?;
+        let history = dirs::config_dir().map(|d| d.join("topk").join(history));
+        if let Some(path) = &history {
+            // Missing on first use
+            let _ = editor.load_history(path);
+        }
+        Ok(Self { editor, history })
+    }
+
+    pub(crate) fn save(&mut self) {
+        let Some(path) = &self.history else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(err) = self.editor.save_history(path) {
+            eprintln!("warning: failed to save history: {err}");
+        }
+    }
+}
+
+impl Lines for Repl {
+    fn read_line(&mut self, prompt: &str) -> Result<Option<String>, Error> {
+        match self.editor.readline(prompt) {
+            Ok(line) => Ok(Some(line)),
+            // Ctrl-C discards the current line, as in psql.
+            Err(ReadlineError::Interrupted) => Ok(Some(String::new())),
+            Err(ReadlineError::Eof) => Ok(None),
+            Err(err) => Err(readline_error(err)),
+        }
+    }
+
+    fn add_history(&mut self, entry: &str) {
+        let _ = self.editor.add_history_entry(entry);
+    }
+}
+
+fn readline_error(err: ReadlineError) -> Error {
+    match err {
+        ReadlineError::Io(err) => Error::IoError(err),
+        err => Error::IoError(std::io::Error::other(err)),
+    }
+}
@@ -5,6 +5,7 @@ use futures_util::StreamExt;
 use napi::bindgen_prelude::*;
 use napi::tokio::{self, sync::mpsc};
 use napi_derive::napi;
+use topk_rs::RequestOptions;
 
 use super::partition::{PartitionListStream, PartitionListStreamMessage};
 use super::{RUNTIME, STREAM_BUFFER_SIZE};
@@ -105,8 +106,11 @@ impl CollectionClient {
             .get(
                 ids,
                 fields,
-                options.lsn,
-                options.consistency.map(|c| c.into()),
+                Some(RequestOptions {
+                    lsn: options.lsn,
+                    consistency: options.consistency.map(|c| c.into()),
+                    ..Default::default()
+                }),
             )
             .await
             .map_err(TopkError::from)?;
@@ -124,7 +128,11 @@ impl CollectionClient {
 
         let count = self
             .collection()
-            .count(options.lsn, options.consistency.map(|c| c.into()))
+            .count(Some(RequestOptions {
+                lsn: options.lsn,
+                consistency: options.consistency.map(|c| c.into()),
+                ..Default::default()
+            }))
             .await
             .map_err(TopkError::from)?;
 
@@ -144,8 +152,11 @@ impl CollectionClient {
             .collection()
             .query(
                 query.clone().into(),
-                options.lsn,
-                options.consistency.map(|c| c.into()),
+                Some(RequestOptions {
+                    lsn: options.lsn,
+                    consistency: options.consistency.map(|c| c.into()),
+                    ..Default::default()
+                }),
             )
             .await
             .map_err(TopkError::from)?;
@@ -168,7 +179,7 @@ impl CollectionClient {
 
         let lsn = self
             .collection()
-            .upsert(documents)
+            .upsert(documents, None)
             .await
             .map_err(TopkError::from)?;
 
@@ -197,7 +208,7 @@ impl CollectionClient {
 
         let lsn = self
             .collection()
-            .update(documents, fail_on_missing.unwrap_or(false))
+            .update(documents, fail_on_missing.unwrap_or(false), None)
             .await
             .map_err(TopkError::from)?;
 
@@ -225,7 +236,7 @@ impl CollectionClient {
     ) -> Result<String> {
         let lsn = self
             .collection()
-            .delete(expr)
+            .delete(expr, None)
             .await
             .map_err(TopkError::from)?;
 
@@ -240,7 +251,11 @@ impl CollectionClient {
         let collection = self.collection.clone();
 
         RUNTIME.spawn(async move {
-            let mut stream = match client.collection(&collection).list_partitions(prefix).await {
+            let mut stream = match client
+                .collection(&collection)
+                .list_partitions(prefix, None)
+                .await
+            {
                 Ok(stream) => stream,
                 Err(error) => {
                     let _ = tx.send(Err(format!("{error}"))).await;
@@ -275,7 +290,7 @@ impl CollectionClient {
     pub async fn delete_partition(&self, name: String) -> Result<()> {
         self.client
             .collection(&self.collection)
-            .delete_partition(name)
+            .delete_partition(name, None)
             .await
             .map_err(TopkError::from)?;
         Ok(())
@@ -212,7 +212,7 @@ impl DatasetClient {
         let handle = self
             .client
             .dataset(&self.dataset)
-            .upsert_file(doc_id, input, metadata)
+            .upsert_file(doc_id, input, metadata, None)
             .await
             .map_err(TopkError::from)?;
 
@@ -229,7 +229,7 @@ impl DatasetClient {
         let response = self
             .client
             .dataset(&self.dataset)
-            .get_metadata(ids, fields)
+            .get_metadata(ids, fields, None)
             .await
             .map_err(TopkError::from)?;
 
@@ -260,7 +260,7 @@ impl DatasetClient {
         let response = self
             .client
             .dataset(&self.dataset)
-            .update_metadata(doc_id, metadata)
+            .update_metadata(doc_id, metadata, None)
             .await
             .map_err(TopkError::from)?;
 
@@ -273,7 +273,7 @@ impl DatasetClient {
         let handle = self
             .client
             .dataset(&self.dataset)
-            .delete(doc_id)
+            .delete(doc_id, None)
             .await
             .map_err(TopkError::from)?;
 
@@ -286,7 +286,7 @@ impl DatasetClient {
         Ok(self
             .client
             .dataset(&self.dataset)
-            .check_handle(&handle)
+            .check_handle(&handle, None)
             .await
             .map_err(TopkError::from)?)
     }
@@ -299,7 +299,7 @@ impl DatasetClient {
         Ok(self
             .client
             .dataset(&self.dataset)
-            .wait_for_handle(&handle, config.map(|c| c.into()))
+            .wait_for_handle(&handle, config.map(|c| c.into()), None)
             .await
             .map_err(TopkError::from)?)
     }
@@ -319,7 +319,7 @@ impl DatasetClient {
         let filter = filter.map(|f| f.clone().into());
 
         RUNTIME.spawn(async move {
-            let mut stream = match client.dataset(&dataset).list(fields, filter).await {
+            let mut stream = match client.dataset(&dataset).list(fields, filter, None).await {
                 Ok(stream) => stream,
                 Err(error) => {
                     let _ = tx.send(Err(format!("{error}"))).await;
@@ -10,6 +10,7 @@ use pyo3::{prelude::*, types::PyAny};
 use pyo3_async_runtimes::tokio::future_into_py;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{mpsc, Mutex};
+use topk_rs::RequestOptions;
 
 #[pyclass]
 pub struct AsyncCollectionClient {
@@ -56,7 +57,15 @@ impl AsyncCollectionClient {
 
         future_into_py(py, async move {
             let docs = collection
-                .get(ids, fields, lsn, consistency.map(|c| c.into()))
+                .get(
+                    ids,
+                    fields,
+                    Some(RequestOptions {
+                        lsn,
+                        consistency: consistency.map(|c| c.into()),
+                        ..Default::default()
+                    }),
+                )
                 .await
                 .map_err(RustError)?;
 
@@ -81,7 +90,11 @@ impl AsyncCollectionClient {
 
         future_into_py(py, async move {
             let count = collection
-                .count(lsn, consistency.map(|c| c.into()))
+                .count(Some(RequestOptions {
+                    lsn,
+                    consistency: consistency.map(|c| c.into()),
+                    ..Default::default()
+                }))
                 .await
                 .map_err(RustError)?;
 
@@ -104,7 +117,14 @@ impl AsyncCollectionClient {
 
         future_into_py(py, async move {
             let docs = collection
-                .query(query, lsn, consistency.map(|c| c.into()))
+                .query(
+                    query,
+                    Some(RequestOptions {
+                        lsn,
+                        consistency: consistency.map(|c| c.into()),
+                        ..Default::default()
+                    }),
+                )
                 .await
                 .map_err(RustError)?;
 
@@ -130,7 +150,10 @@ impl AsyncCollectionClient {
                 })
                 .collect();
 
-            let lsn = collection.upsert(documents).await.map_err(RustError)?;
+            let lsn = collection
+                .upsert(documents, None)
+                .await
+                .map_err(RustError)?;
 
             Ok(lsn)
         })
@@ -153,7 +176,7 @@ impl AsyncCollectionClient {
 
         future_into_py(py, async move {
             let lsn = collection
-                .update(documents, fail_on_missing.unwrap_or(false))
+                .update(documents, fail_on_missing.unwrap_or(false), None)
                 .await
                 .map_err(RustError)?;
 
@@ -168,7 +191,7 @@ impl AsyncCollectionClient {
         let collection = self.collection();
 
         future_into_py(py, async move {
-            let lsn = collection.delete(spec).await.map_err(RustError)?;
+            let lsn = collection.delete(spec, None).await.map_err(RustError)?;
 
             Ok(lsn)
         })
@@ -188,7 +211,7 @@ impl AsyncCollectionClient {
         pyo3_async_runtimes::tokio::get_runtime().spawn(async move {
             let mut stream = match client
                 .collection(collection.as_str())
-                .list_partitions(prefix)
+                .list_partitions(prefix, None)
                 .await
             {
                 Ok(stream) => stream,
@@ -226,7 +249,7 @@ impl AsyncCollectionClient {
         future_into_py(py, async move {
             client
                 .collection(collection.as_str())
-                .delete_partition(name)
+                .delete_partition(name, None)
                 .await
                 .map_err(RustError)?;
             Ok(())
@@ -45,7 +45,7 @@ impl AsyncDatasetClient {
         future_into_py(py, async move {
             client
                 .dataset(&dataset)
-                .upsert_file(doc_id, input_file, metadata)
+                .upsert_file(doc_id, input_file, metadata, None)
                 .await
                 .map_err(|e| RustError::from(e).into())
         })
@@ -65,7 +65,7 @@ impl AsyncDatasetClient {
         future_into_py(py, async move {
             let docs = client
                 .dataset(&dataset)
-                .get_metadata(ids, fields)
+                .get_metadata(ids, fields, None)
                 .await
                 .map_err(RustError)?;
             let docs: HashMap<String, HashMap<String, Value>> = docs
@@ -96,7 +96,7 @@ impl AsyncDatasetClient {
         future_into_py(py, async move {
             client
                 .dataset(&dataset)
-                .update_metadata(doc_id, metadata)
+                .update_metadata(doc_id, metadata, None)
                 .await
                 .map_err(|e| RustError::from(e).into())
         })
@@ -110,7 +110,7 @@ impl AsyncDatasetClient {
         future_into_py(py, async move {
             client
                 .dataset(&dataset)
-                .delete(doc_id)
+                .delete(doc_id, None)
                 .await
                 .map_err(|e| RustError::from(e).into())
         })
@@ -124,7 +124,7 @@ impl AsyncDatasetClient {
         future_into_py(py, async move {
             client
                 .dataset(&dataset)
-                .check_handle(&handle)
+                .check_handle(&handle, None)
                 .await
                 .map_err(|e| RustError::from(e).into())
         })
@@ -145,7 +145,7 @@ impl AsyncDatasetClient {
         future_into_py(py, async move {
             client
                 .dataset(&dataset)
-                .wait_for_handle(&handle, wait_config)
+                .wait_for_handle(&handle, wait_config, None)
                 .await
                 .map_err(RustError)?;
             Python::attach(|py| Ok(py.None().into_any()))
@@ -167,7 +167,7 @@ impl AsyncDatasetClient {
             let client = self.client.clone();
             let dataset = self.dataset.clone();
             async move {
-                let mut stream = match client.dataset(&dataset).list(fields, filter).await {
+                let mut stream = match client.dataset(&dataset).list(fields, filter, None).await {
                     Ok(stream) => stream,
                     Err(e) => {
                         let _ = tx.send(Err(RustError(e).into())).await;
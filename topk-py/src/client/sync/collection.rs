@@ -10,6 +10,7 @@ use futures_util::StreamExt;
 use pyo3::prelude::*;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::mpsc;
+use topk_rs::RequestOptions;
 
 #[pyclass]
 pub struct CollectionClient {
@@ -59,8 +60,15 @@ impl CollectionClient {
             .runtime
             .block_on(
                 py,
-                self.collection()
-                    .get(ids, fields, lsn, consistency.map(|c| c.into())),
+                self.collection().get(
+                    ids,
+                    fields,
+                    Some(RequestOptions {
+                        lsn,
+                        consistency: consistency.map(|c| c.into()),
+                        ..Default::default()
+                    }),
+                ),
             )
             .map_err(RustError)?;
 
@@ -81,7 +89,11 @@ impl CollectionClient {
             .runtime
             .block_on(
                 py,
-                self.collection().count(lsn, consistency.map(|c| c.into())),
+                self.collection().count(Some(RequestOptions {
+                    lsn,
+                    consistency: consistency.map(|c| c.into()),
+                    ..Default::default()
+                })),
             )
             .map_err(RustError)?;
 
@@ -103,8 +115,14 @@ impl CollectionClient {
             .runtime
             .block_on(
                 py,
-                self.collection()
-                    .query(query, lsn, consistency.map(|c| c.into())),
+                self.collection().query(
+                    query,
+                    Some(RequestOptions {
+                        lsn,
+                        consistency: consistency.map(|c| c.into()),
+                        ..Default::default()
+                    }),
+                ),
             )
             .map_err(RustError)?;
 
@@ -127,7 +145,7 @@ impl CollectionClient {
 
         Ok(self
             .runtime
-            .block_on(py, self.collection().upsert(documents))
+            .block_on(py, self.collection().upsert(documents, None))
             .map_err(RustError)?)
     }
 
@@ -149,7 +167,7 @@ impl CollectionClient {
             .block_on(
                 py,
                 self.collection()
-                    .update(documents, fail_on_missing.unwrap_or(false)),
+                    .update(documents, fail_on_missing.unwrap_or(false), None),
             )
             .map_err(RustError)?)
     }
@@ -160,7 +178,7 @@ impl CollectionClient {
 
         Ok(self
             .runtime
-            .block_on(py, self.collection().delete(spec))
+            .block_on(py, self.collection().delete(spec, None))
             .map_err(RustError)?)
     }
 
@@ -172,7 +190,10 @@ impl CollectionClient {
             let client = self.client.clone();
             let collection = self.collection.clone();
             async move {
-                let mut stream = match client.collection(&collection).list_partitions(prefix).await
+                let mut stream = match client
+                    .collection(&collection)
+                    .list_partitions(prefix, None)
+                    .await
                 {
                     Ok(stream) => stream,
                     Err(e) => {
@@ -211,7 +232,7 @@ impl CollectionClient {
                 py,
                 self.client
                     .collection(&self.collection)
-                    .delete_partition(name),
+                    .delete_partition(name, None),
             )
             .map_err(RustError)?;
         Ok(())
@@ -51,7 +51,7 @@ impl DatasetClient {
                 py,
                 self.client
                     .dataset(&self.dataset)
-                    .upsert_file(doc_id, input_file, metadata),
+                    .upsert_file(doc_id, input_file, metadata, None),
             )
             .map_err(RustError)?;
 
@@ -69,7 +69,9 @@ impl DatasetClient {
             .runtime
             .block_on(
                 py,
-                self.client.dataset(&self.dataset).get_metadata(ids, fields),
+                self.client
+                    .dataset(&self.dataset)
+                    .get_metadata(ids, fields, None),
             )
             .map_err(RustError)?;
 
@@ -101,7 +103,7 @@ impl DatasetClient {
                 py,
                 self.client
                     .dataset(&self.dataset)
-                    .update_metadata(doc_id, metadata),
+                    .update_metadata(doc_id, metadata, None),
             )
             .map_err(RustError)?;
 
@@ -111,7 +113,7 @@ impl DatasetClient {
     pub fn delete(&self, py: Python<'_>, doc_id: String) -> PyResult<String> {
         let handle = self
             .runtime
-            .block_on(py, self.client.dataset(&self.dataset).delete(doc_id))
+            .block_on(py, self.client.dataset(&self.dataset).delete(doc_id, None))
             .map_err(RustError)?;
 
         Ok(handle)
@@ -120,7 +122,12 @@ impl DatasetClient {
     pub fn check_handle(&self, py: Python<'_>, handle: String) -> PyResult<bool> {
         Ok(self
             .runtime
-            .block_on(py, self.client.dataset(&self.dataset).check_handle(&handle))
+            .block_on(
+                py,
+                self.client
+                    .dataset(&self.dataset)
+                    .check_handle(&handle, None),
+            )
             .map_err(RustError)?)
     }
 
@@ -137,7 +144,7 @@ impl DatasetClient {
                 py,
                 self.client
                     .dataset(&self.dataset)
-                    .wait_for_handle(&handle, wait_config),
+                    .wait_for_handle(&handle, wait_config, None),
             )
             .map_err(RustError)?;
         Ok(())
@@ -156,7 +163,7 @@ impl DatasetClient {
             let client = self.client.clone();
             let dataset = self.dataset.clone();
             async move {
-                let mut stream = match client.dataset(&dataset).list(fields, filter).await {
+                let mut stream = match client.dataset(&dataset).list(fields, filter, None).await {
                     Ok(stream) => stream,
                     Err(e) => {
                         let _ = tx.send(Err(RustError(e).into())).await;
@@ -0,0 +1,215 @@
+//! Upserts and queries of vector and matrix payloads against a local server, with and without
+//! compression.
+//!
+//! Run with `cargo bench --bench compression`. The server runs on loopback, so the results show
+//! the CPU cost of each encoding, while the throughput is that of the uncompressed payload.
+
+use std::sync::{Arc, Mutex};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures_util::stream::{self, BoxStream};
+use prost::Message;
+use rand::Rng;
+use tokio::net::TcpListener;
+use tokio::runtime::Runtime;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Response, Status};
+use topk_rs::doc;
+use topk_rs::proto::v1::data::query_service_server::{QueryService, QueryServiceServer};
+use topk_rs::proto::v1::data::write_service_server::{WriteService, WriteServiceServer};
+use topk_rs::proto::v1::data::{
+    DeleteDocumentsRequest, DeleteDocumentsResponse, Document, DocumentData, GetRequest,
+    GetResponse, QueryRequest, QueryResponse, UpdateDocumentsRequest, UpdateDocumentsResponse,
+    UpsertDocumentsRequest, UpsertDocumentsResponse, Value,
+};
+use topk_rs::query::{field, select};
+use topk_rs::transport::CompressionEncoding;
+use topk_rs::{Client, ClientConfig, CollectionClient};
+
+const NUM_DOCS: usize = 100;
+
+/// Server accepting upserts and returning the last upserted documents from queries.
+#[derive(Clone, Default)]
+struct Server {
+    docs: Arc<Mutex<Vec<Document>>>,
+}
+
+#[tonic::async_trait]
+impl WriteService for Server {
+    async fn upsert_documents(
+        &self,
+        request: tonic::Request<UpsertDocumentsRequest>,
+    ) -> Result<Response<UpsertDocumentsResponse>, Status> {
+        *self.docs.lock().unwrap() = request.into_inner().docs;
+        Ok(Response::new(UpsertDocumentsResponse { lsn: "1".into() }))
+    }
+
+    async fn update_documents(
+        &self,
+        _request: tonic::Request<UpdateDocumentsRequest>,
+    ) -> Result<Response<UpdateDocumentsResponse>, Status> {
+        Err(Status::unimplemented("update_documents"))
+    }
+
+    async fn delete_documents(
+        &self,
+        _request: tonic::Request<DeleteDocumentsRequest>,
+    ) -> Result<Response<DeleteDocumentsResponse>, Status> {
+        Err(Status::unimplemented("delete_documents"))
+    }
+}
+
+#[tonic::async_trait]
+impl QueryService for Server {
+    type QueryStreamStream = BoxStream<'static, Result<DocumentData, Status>>;
+    type GetStreamStream = BoxStream<'static, Result<DocumentData, Status>>;
+
+    async fn query(
+        &self,
+        _request: tonic::Request<QueryRequest>,
+    ) -> Result<Response<QueryResponse>, Status> {
+        Err(Status::unimplemented("query"))
+    }
+
+    async fn query_stream(
+        &self,
+        _request: tonic::Request<QueryRequest>,
+    ) -> Result<Response<Self::QueryStreamStream>, Status> {
+        let docs = self.docs.lock().unwrap().clone();
+        Ok(Response::new(Box::pin(stream::iter(docs.into_iter().map(
+            |doc| {
+                Ok(DocumentData {
+                    data: doc.encode_to_vec().into(),
+                })
+            },
+        )))))
+    }
+
+    async fn get(
+        &self,
+        _request: tonic::Request<GetRequest>,
+    ) -> Result<Response<GetResponse>, Status> {
+        Err(Status::unimplemented("get"))
+    }
+
+    async fn get_stream(
+        &self,
+        _request: tonic::Request<GetRequest>,
+    ) -> Result<Response<Self::GetStreamStream>, Status> {
+        Err(Status::unimplemented("get_stream"))
+    }
+}
+
+/// Starts a server accepting and sending all encodings and returns its address.
+async fn serve() -> String {
+    let server = Server::default();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut write = WriteServiceServer::new(server.clone());
+    let mut query = QueryServiceServer::new(server);
+    for encoding in [CompressionEncoding::Gzip, CompressionEncoding::Zstd] {
+        write = write.accept_compressed(encoding).send_compressed(encoding);
+        query = query.accept_compressed(encoding).send_compressed(encoding);
+    }
+
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(write)
+            .add_service(query)
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    format!("http://{addr}")
+}
+
+/// Documents with a dense f32 embedding, like those upserted for vector search.
+fn vector_docs(dimension: usize) -> Vec<Document> {
+    let mut rng = rand::thread_rng();
+    (0..NUM_DOCS)
+        .map(|i| {
+            let embedding: Vec<f32> = (0..dimension).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            doc!(
+                "_id" => format!("doc-{i}"),
+                "title" => format!("Document {i}"),
+                "embedding" => Value::list(embedding),
+            )
+        })
+        .collect()
+}
+
+/// Documents with a matrix of token embeddings, like those upserted for multi-vector search.
+fn matrix_docs(num_rows: usize, num_cols: usize) -> Vec<Document> {
+    let mut rng = rand::thread_rng();
+    (0..NUM_DOCS)
+        .map(|i| {
+            let values: Vec<f32> = (0..num_rows * num_cols)
+                .map(|_| rng.gen_range(-1.0..1.0))
+                .collect();
+            doc!(
+                "_id" => format!("doc-{i}"),
+                "tokens" => Value::matrix(num_cols as u32, values),
+            )
+        })
+        .collect()
+}
+
+fn collection(endpoint: &str, compression: Option<CompressionEncoding>) -> CollectionClient {
+    let mut config = ClientConfig::new("api-key", "local").with_endpoint(endpoint);
+    if let Some(encoding) = compression {
+        config = config.with_compression(encoding);
+    }
+    Client::new(config).collection("bench")
+}
+
+fn bench_compression(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let endpoint = runtime.block_on(serve());
+
+    let payloads = [
+        ("f32_vector_768", vector_docs(768)),
+        ("f32_matrix_32x128", matrix_docs(32, 128)),
+    ];
+    let encodings = [
+        ("none", None),
+        ("gzip", Some(CompressionEncoding::Gzip)),
+        ("zstd", Some(CompressionEncoding::Zstd)),
+    ];
+
+    for (payload, docs) in payloads {
+        let size = UpsertDocumentsRequest { docs: docs.clone() }.encoded_len();
+
+        let mut group = c.benchmark_group(format!("upsert/{payload}"));
+        group.sample_size(20);
+        group.throughput(Throughput::Bytes(size as u64));
+        for (name, encoding) in encodings {
+            let collection = collection(&endpoint, encoding);
+            group.bench_function(BenchmarkId::from_parameter(name), |b| {
+                b.to_async(&runtime)
+                    .iter(|| async { collection.upsert(docs.clone(), None).await.unwrap() })
+            });
+        }
+        group.finish();
+
+        // Queries return the documents of the last upsert
+        let mut group = c.benchmark_group(format!("query/{payload}"));
+        group.sample_size(20);
+        group.throughput(Throughput::Bytes(size as u64));
+        for (name, encoding) in encodings {
+            let collection = collection(&endpoint, encoding);
+            group.bench_function(BenchmarkId::from_parameter(name), |b| {
+                b.to_async(&runtime).iter(|| async {
+                    collection
+                        .query(select([("_id", field("_id"))]).limit(100), None)
+                        .await
+                        .unwrap()
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_compression);
+criterion_main!(benches);
@@ -0,0 +1,1329 @@
+//! Conversion between documents and Arrow [`RecordBatch`]es.
+//!
+//! A [`DocumentConverter`] maps the fields of a collection schema to Arrow columns:
+//!
+//! | Field type                        | Arrow type                                          |
+//! |-----------------------------------|-----------------------------------------------------|
+//! | `text`                            | `Utf8`                                              |
+//! | `integer`                         | `Int64`                                             |
+//! | `float`                           | `Float64`                                           |
+//! | `boolean`                         | `Boolean`                                           |
+//! | `bytes`                           | `Binary`                                            |
+//! | `timestamp`                       | `Timestamp(Millisecond, "UTC")`                     |
+//! | `f32_vector(n)`, `f8_vector(n)`   | `FixedSizeList<Float32, n>`                         |
+//! | `f16_vector(n)`                   | `FixedSizeList<Float16, n>`                         |
+//! | `u8_vector(n)`, `binary_vector(n)`| `FixedSizeList<UInt8, n>`                           |
+//! | `i8_vector(n)`                    | `FixedSizeList<Int8, n>`                            |
+//! | `*_sparse_vector`                 | `Struct<indices: List<UInt32>, values: List<T>>`    |
+//! | `matrix(n, T)`                    | `List<FixedSizeList<T, n>>`                         |
+//! | `list<integer\|float\|string>`    | `List<Int64\|Float64\|Utf8>`                        |
+//! | `struct`                          | `Struct`                                            |
+//!
+//! The `_id` column always comes first, followed by the schema fields in name order.
+//!
+//! When converting a [`RecordBatch`] into documents, columns are cast to the type of
+//! the schema field (e.g. `List<Float64>` into an `f32_vector`), and columns that are
+//! not part of the schema are mapped from their Arrow type.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    BinaryBuilder, BooleanBuilder, Float64Builder, Int64Builder, ListBuilder, StringBuilder,
+    TimestampMillisecondBuilder,
+};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Float16Type, Float32Type, Float64Type, Int64Type, Int8Type, TimestampMillisecondType,
+    UInt32Type, UInt8Type,
+};
+use arrow_array::{
+    Array, ArrayRef, FixedSizeListArray, Float16Array, Float32Array, Int8Array, ListArray,
+    RecordBatch, StructArray, UInt32Array, UInt8Array,
+};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use arrow_schema::{ArrowError, DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+use float8::F8E4M3;
+use futures::{Stream, StreamExt};
+use futures_util::TryStreamExt;
+
+use crate::client::DocumentStream;
+use crate::error::Error;
+use crate::proto::v1::control::field_type::DataType as FieldDataType;
+use crate::proto::v1::control::field_type_list::ListValueType;
+use crate::proto::v1::control::field_type_matrix::MatrixValueType;
+use crate::proto::v1::control::{FieldSpec, FieldType};
+use crate::proto::v1::data::{list, matrix, sparse_vector, value, vector, Document, Value};
+
+const ID: &str = "_id";
+
+/// Converts documents into [`RecordBatch`]es and back, based on a collection schema.
+#[derive(Debug, Clone)]
+pub struct DocumentConverter {
+    columns: Vec<(String, Kind)>,
+    schema: SchemaRef,
+}
+
+impl DocumentConverter {
+    /// Creates a converter for a collection schema.
+    pub fn new(fields: &HashMap<String, FieldSpec>) -> Result<Self, Error> {
+        let mut columns = vec![(ID.to_string(), Kind::Text)];
+        for (name, spec) in sorted(fields) {
+            if name != ID {
+                columns.push((name.clone(), Kind::from_spec(name, spec)?));
+            }
+        }
+
+        Ok(Self {
+            schema: schema(&columns),
+            columns,
+        })
+    }
+
+    /// Adds a column for a field that is not part of the collection schema,
+    /// e.g. a score computed by a query.
+    pub fn with_field(
+        mut self,
+        name: impl Into<String>,
+        field_type: FieldType,
+    ) -> Result<Self, Error> {
+        let name = name.into();
+        let kind = Kind::from_field_type(&name, &field_type)?;
+        match self.columns.iter_mut().find(|(n, _)| *n == name) {
+            Some(column) => column.1 = kind,
+            None => self.columns.push((name, kind)),
+        }
+        self.schema = schema(&self.columns);
+        Ok(self)
+    }
+
+    /// Names of the converted fields, excluding `_id`.
+    pub(crate) fn fields(&self) -> impl Iterator<Item = &str> {
+        self.columns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| *name != ID)
+    }
+
+    /// Arrow schema of the record batches produced by [`DocumentConverter::to_record_batch`].
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Converts documents into a record batch. Missing and null fields become nulls.
+    pub fn to_record_batch(&self, docs: &[Document]) -> Result<RecordBatch, Error> {
+        for doc in docs {
+            doc.id()
+                .map_err(|e| Error::InvalidArgument(e.to_string()))?;
+
+            if let Some(name) = doc
+                .fields
+                .keys()
+                .find(|name| !self.columns.iter().any(|(n, _)| n == *name))
+            {
+                return Err(Error::InvalidArgument(format!(
+                    "Field `{name}` is not part of the schema, add it with `DocumentConverter::with_field`"
+                )));
+            }
+        }
+
+        let columns = self
+            .columns
+            .iter()
+            .map(|(name, kind)| {
+                let values = docs
+                    .iter()
+                    .map(|doc| doc.fields.get(name).and_then(inner))
+                    .collect::<Vec<_>>();
+                kind.to_array(name, &values)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        RecordBatch::try_new(self.schema.clone(), columns).map_err(arrow_error)
+    }
+
+    /// Converts a record batch into documents ready for `upsert`. Null values are omitted.
+    pub fn to_documents(&self, batch: &RecordBatch) -> Result<Vec<Document>, Error> {
+        if batch.column_by_name(ID).is_none() {
+            return Err(Error::InvalidArgument(format!(
+                "Record batch has no `{ID}` column"
+            )));
+        }
+
+        let mut docs = vec![Document::default(); batch.num_rows()];
+        for (field, array) in batch.schema().fields().iter().zip(batch.columns()) {
+            let name = field.name();
+            let kind = match self.columns.iter().find(|(n, _)| n == name) {
+                Some((_, kind)) => kind.clone(),
+                None => Kind::infer(field.data_type()).ok_or_else(|| unsupported(name, field))?,
+            };
+
+            for (doc, value) in docs.iter_mut().zip(kind.read(name, array)?) {
+                if let Some(value) = value {
+                    doc.fields.insert(name.clone(), value);
+                }
+            }
+        }
+
+        Ok(docs)
+    }
+}
+
+impl DocumentStream {
+    /// Collects the documents into record batches of up to `batch_size` rows.
+    pub fn record_batches(
+        self,
+        converter: DocumentConverter,
+        batch_size: usize,
+    ) -> impl Stream<Item = Result<RecordBatch, Error>> + Send {
+        self.try_chunks(batch_size).map(move |chunk| match chunk {
+            Ok(docs) => converter.to_record_batch(&docs),
+            Err(e) => Err(e.1),
+        })
+    }
+}
+
+fn sorted<V>(fields: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut fields = fields.iter().collect::<Vec<_>>();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    fields
+}
+
+fn schema(columns: &[(String, Kind)]) -> SchemaRef {
+    Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|(name, kind)| Field::new(name, kind.data_type(), name != ID))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+fn inner(value: &Value) -> Option<&value::Value> {
+    match &value.value {
+        None | Some(value::Value::Null(_)) => None,
+        Some(value) => Some(value),
+    }
+}
+
+pub(crate) fn arrow_error(e: ArrowError) -> Error {
+    Error::InvalidArgument(e.to_string())
+}
+
+fn unsupported(name: &str, field: &Field) -> Error {
+    Error::InvalidArgument(format!(
+        "Column `{name}` has unsupported type {}",
+        field.data_type()
+    ))
+}
+
+/// Element type of vectors, sparse vectors and matrices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Element {
+    F32,
+    F16,
+    F8,
+    U8,
+    I8,
+}
+
+impl Element {
+    fn data_type(self) -> DataType {
+        match self {
+            Element::F32 | Element::F8 => DataType::Float32,
+            Element::F16 => DataType::Float16,
+            Element::U8 => DataType::UInt8,
+            Element::I8 => DataType::Int8,
+        }
+    }
+
+    fn infer(data_type: &DataType) -> Option<Self> {
+        match data_type {
+            DataType::Float32 | DataType::Float64 => Some(Element::F32),
+            DataType::Float16 => Some(Element::F16),
+            DataType::UInt8 => Some(Element::U8),
+            DataType::Int8 => Some(Element::I8),
+            _ => None,
+        }
+    }
+
+    /// Reads the values of an array of [`Element::data_type`].
+    fn read(self, array: &dyn Array) -> Elements {
+        match self {
+            Element::F32 => Elements::F32(array.as_primitive::<Float32Type>().values().to_vec()),
+            Element::F8 => Elements::F8(
+                array
+                    .as_primitive::<Float32Type>()
+                    .values()
+                    .iter()
+                    .map(|v| F8E4M3::from_f32(*v))
+                    .collect(),
+            ),
+            Element::F16 => Elements::F16(array.as_primitive::<Float16Type>().values().to_vec()),
+            Element::U8 => Elements::U8(array.as_primitive::<UInt8Type>().values().to_vec()),
+            Element::I8 => Elements::I8(array.as_primitive::<Int8Type>().values().to_vec()),
+        }
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Element::F32 => write!(f, "f32"),
+            Element::F16 => write!(f, "f16"),
+            Element::F8 => write!(f, "f8"),
+            Element::U8 => write!(f, "u8"),
+            Element::I8 => write!(f, "i8"),
+        }
+    }
+}
+
+/// Flat buffer of vector, sparse vector or matrix values.
+enum Elements {
+    F32(Vec<f32>),
+    F16(Vec<half::f16>),
+    F8(Vec<F8E4M3>),
+    U8(Vec<u8>),
+    I8(Vec<i8>),
+}
+
+impl Elements {
+    fn new(element: Element) -> Self {
+        match element {
+            Element::F32 => Elements::F32(vec![]),
+            Element::F16 => Elements::F16(vec![]),
+            Element::F8 => Elements::F8(vec![]),
+            Element::U8 => Elements::U8(vec![]),
+            Element::I8 => Elements::I8(vec![]),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Elements::F32(v) => v.len(),
+            Elements::F16(v) => v.len(),
+            Elements::F8(v) => v.len(),
+            Elements::U8(v) => v.len(),
+            Elements::I8(v) => v.len(),
+        }
+    }
+
+    /// Appends `n` zeros as placeholder for a null vector.
+    fn pad(&mut self, n: usize) {
+        match self {
+            Elements::F32(v) => v.resize(v.len() + n, 0.0),
+            Elements::F16(v) => v.resize(v.len() + n, half::f16::ZERO),
+            Elements::F8(v) => v.resize(v.len() + n, F8E4M3::ZERO),
+            Elements::U8(v) => v.resize(v.len() + n, 0),
+            Elements::I8(v) => v.resize(v.len() + n, 0),
+        }
+    }
+
+    /// Appends the values of a vector, returning how many were appended,
+    /// or `None` if the value type does not match.
+    fn extend(&mut self, value: &value::Value) -> Option<usize> {
+        let len = self.len();
+        match (&mut *self, value) {
+            (this, value::Value::List(list)) => match (this, list.values.as_ref()?) {
+                (Elements::F32(v), list::Values::F32(l)) => v.extend(&l.values),
+                (Elements::F32(v), list::Values::F64(l)) => {
+                    v.extend(l.values.iter().map(|x| *x as f32))
+                }
+                (Elements::F16(v), list::Values::F16(l)) => v.extend(l.as_ref()),
+                (Elements::F16(v), list::Values::F32(l)) => {
+                    v.extend(l.values.iter().map(|x| half::f16::from_f32(*x)))
+                }
+                (Elements::F8(v), list::Values::F8(l)) => v.extend(l.as_ref()),
+                (Elements::F8(v), list::Values::F32(l)) => {
+                    v.extend(l.values.iter().map(|x| F8E4M3::from_f32(*x)))
+                }
+                (Elements::U8(v), list::Values::U8(l)) => v.extend(&l.values),
+                (Elements::I8(v), list::Values::I8(l)) => v.extend(l.as_ref()),
+                _ => return None,
+            },
+            #[allow(deprecated)]
+            (this, value::Value::Vector(vec)) => match (this, vec.vector.as_ref()?) {
+                (Elements::F32(v), vector::Vector::Float(f)) => v.extend(&f.values),
+                (Elements::U8(v), vector::Vector::Byte(b)) => v.extend(&b.values),
+                _ => return None,
+            },
+            _ => return None,
+        }
+        Some(self.len() - len)
+    }
+
+    fn extend_sparse(&mut self, values: &sparse_vector::Values) -> Option<usize> {
+        let len = self.len();
+        match (&mut *self, values) {
+            (Elements::F32(v), sparse_vector::Values::F32(s)) => v.extend(&s.values),
+            (Elements::F16(v), sparse_vector::Values::F16(s)) => v.extend(s.as_ref()),
+            (Elements::F8(v), sparse_vector::Values::F8(s)) => v.extend(s.as_ref()),
+            (Elements::U8(v), sparse_vector::Values::U8(s)) => v.extend(&s.values),
+            (Elements::I8(v), sparse_vector::Values::I8(s)) => v.extend(s.as_ref()),
+            _ => return None,
+        }
+        Some(self.len() - len)
+    }
+
+    fn extend_matrix(&mut self, values: &matrix::Values) -> Option<usize> {
+        let len = self.len();
+        match (&mut *self, values) {
+            (Elements::F32(v), matrix::Values::F32(m)) => v.extend(m.as_ref()),
+            (Elements::F16(v), matrix::Values::F16(m)) => v.extend(m.as_ref()),
+            (Elements::F8(v), matrix::Values::F8(m)) => v.extend(m.as_ref()),
+            (Elements::U8(v), matrix::Values::U8(m)) => v.extend(m.as_ref()),
+            (Elements::I8(v), matrix::Values::I8(m)) => v.extend(m.as_ref()),
+            _ => return None,
+        }
+        Some(self.len() - len)
+    }
+
+    fn into_array(self) -> ArrayRef {
+        match self {
+            Elements::F32(v) => Arc::new(Float32Array::from(v)),
+            Elements::F16(v) => Arc::new(Float16Array::from(v)),
+            Elements::F8(v) => Arc::new(Float32Array::from_iter_values(
+                v.into_iter().map(|x| x.to_f32()),
+            )),
+            Elements::U8(v) => Arc::new(UInt8Array::from(v)),
+            Elements::I8(v) => Arc::new(Int8Array::from(v)),
+        }
+    }
+
+    fn into_list(self) -> Value {
+        match self {
+            Elements::F32(v) => Value::list(v),
+            Elements::F16(v) => Value::list(v),
+            Elements::F8(v) => Value::list(v),
+            Elements::U8(v) => Value::list(v),
+            Elements::I8(v) => Value::list(v),
+        }
+    }
+
+    fn into_sparse(self, indices: Vec<u32>) -> Value {
+        match self {
+            Elements::F32(v) => Value::f32_sparse_vector(indices, v),
+            Elements::F16(v) => Value::f16_sparse_vector(indices, v),
+            Elements::F8(v) => Value::f8_sparse_vector(indices, v),
+            Elements::U8(v) => Value::u8_sparse_vector(indices, v),
+            Elements::I8(v) => Value::i8_sparse_vector(indices, v),
+        }
+    }
+
+    fn into_matrix(self, num_cols: u32) -> Value {
+        match self {
+            Elements::F32(v) => Value::matrix(num_cols, v),
+            Elements::F16(v) => Value::matrix(num_cols, v),
+            Elements::F8(v) => Value::matrix(num_cols, v),
+            Elements::U8(v) => Value::matrix(num_cols, v),
+            Elements::I8(v) => Value::matrix(num_cols, v),
+        }
+    }
+}
+
+/// Column type derived from a field type.
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Text,
+    Integer,
+    Float,
+    Boolean,
+    Bytes,
+    Timestamp,
+    Vector(Element, i32),
+    SparseVector(Element),
+    Matrix(Element, i32),
+    List(ListValueType),
+    Struct(Vec<(String, Kind)>),
+}
+
+impl Kind {
+    fn from_spec(name: &str, spec: &FieldSpec) -> Result<Self, Error> {
+        match &spec.data_type {
+            Some(field_type) => Self::from_field_type(name, field_type),
+            None => Err(Error::InvalidArgument(format!(
+                "Field `{name}` has no data type"
+            ))),
+        }
+    }
+
+    fn from_field_type(name: &str, field_type: &FieldType) -> Result<Self, Error> {
+        let unsupported = || {
+            Error::InvalidArgument(format!(
+                "Field `{name}` has an unsupported data type: {field_type:?}"
+            ))
+        };
+        let dimension = |d: u32| i32::try_from(d).map_err(|_| unsupported());
+
+        Ok(
+            match field_type.data_type.as_ref().ok_or_else(unsupported)? {
+                FieldDataType::Text(_) => Kind::Text,
+                FieldDataType::Integer(_) => Kind::Integer,
+                FieldDataType::Float(_) => Kind::Float,
+                FieldDataType::Boolean(_) => Kind::Boolean,
+                FieldDataType::Bytes(_) => Kind::Bytes,
+                FieldDataType::Timestamp(_) => Kind::Timestamp,
+                FieldDataType::F32Vector(v) => Kind::Vector(Element::F32, dimension(v.dimension)?),
+                FieldDataType::F16Vector(v) => Kind::Vector(Element::F16, dimension(v.dimension)?),
+                FieldDataType::F8Vector(v) => Kind::Vector(Element::F8, dimension(v.dimension)?),
+                FieldDataType::U8Vector(v) => Kind::Vector(Element::U8, dimension(v.dimension)?),
+                FieldDataType::I8Vector(v) => Kind::Vector(Element::I8, dimension(v.dimension)?),
+                FieldDataType::BinaryVector(v) => {
+                    Kind::Vector(Element::U8, dimension(v.dimension)?)
+                }
+                FieldDataType::F32SparseVector(_) => Kind::SparseVector(Element::F32),
+                FieldDataType::F16SparseVector(_) => Kind::SparseVector(Element::F16),
+                FieldDataType::F8SparseVector(_) => Kind::SparseVector(Element::F8),
+                FieldDataType::U8SparseVector(_) => Kind::SparseVector(Element::U8),
+                FieldDataType::I8SparseVector(_) => Kind::SparseVector(Element::I8),
+                FieldDataType::Matrix(m) => {
+                    let element = match m.value_type() {
+                        MatrixValueType::F32 => Element::F32,
+                        MatrixValueType::F16 => Element::F16,
+                        MatrixValueType::F8 => Element::F8,
+                        MatrixValueType::U8 => Element::U8,
+                        MatrixValueType::I8 => Element::I8,
+                        MatrixValueType::Unspecified => return Err(unsupported()),
+                    };
+                    Kind::Matrix(element, dimension(m.dimension)?)
+                }
+                FieldDataType::List(l) => match l.value_type() {
+                    ListValueType::Unspecified => return Err(unsupported()),
+                    value_type => Kind::List(value_type),
+                },
+                FieldDataType::Struct(s) => Kind::Struct(
+                    sorted(&s.fields)
+                        .into_iter()
+                        .map(|(child, spec)| {
+                            Ok((
+                                child.clone(),
+                                Self::from_spec(&format!("{name}.{child}"), spec)?,
+                            ))
+                        })
+                        .collect::<Result<_, Error>>()?,
+                ),
+            },
+        )
+    }
+
+    /// Maps an Arrow type to a column type, for columns that are not part of the schema.
+    fn infer(data_type: &DataType) -> Option<Self> {
+        Some(match data_type {
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => Kind::Text,
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64 => Kind::Integer,
+            DataType::Float16 | DataType::Float32 | DataType::Float64 => Kind::Float,
+            DataType::Boolean => Kind::Boolean,
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView => Kind::Bytes,
+            DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => Kind::Timestamp,
+            DataType::FixedSizeList(item, n) => Kind::Vector(Element::infer(item.data_type())?, *n),
+            DataType::List(item) | DataType::LargeList(item) => match item.data_type() {
+                DataType::FixedSizeList(row, n) => {
+                    Kind::Matrix(Element::infer(row.data_type())?, *n)
+                }
+                DataType::Utf8 | DataType::LargeUtf8 => Kind::List(ListValueType::String),
+                t if t.is_integer() => Kind::List(ListValueType::Integer),
+                t if t.is_floating() => Kind::List(ListValueType::Float),
+                _ => return None,
+            },
+            DataType::Struct(fields) => match (fields.find("indices"), fields.find("values")) {
+                (Some(_), Some((_, values))) if fields.len() == 2 => match values.data_type() {
+                    DataType::List(item) => Kind::SparseVector(Element::infer(item.data_type())?),
+                    _ => return None,
+                },
+                _ => Kind::Struct(
+                    fields
+                        .iter()
+                        .map(|f| Some((f.name().clone(), Self::infer(f.data_type())?)))
+                        .collect::<Option<_>>()?,
+                ),
+            },
+            _ => return None,
+        })
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Kind::Text => DataType::Utf8,
+            Kind::Integer => DataType::Int64,
+            Kind::Float => DataType::Float64,
+            Kind::Boolean => DataType::Boolean,
+            Kind::Bytes => DataType::Binary,
+            Kind::Timestamp => DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            Kind::Vector(element, n) => DataType::FixedSizeList(element_field(*element), *n),
+            Kind::SparseVector(element) => DataType::Struct(sparse_fields(*element)),
+            Kind::Matrix(element, n) => DataType::List(Arc::new(Field::new_list_field(
+                DataType::FixedSizeList(element_field(*element), *n),
+                false,
+            ))),
+            Kind::List(value_type) => DataType::List(Arc::new(Field::new_list_field(
+                match value_type {
+                    ListValueType::Integer => DataType::Int64,
+                    ListValueType::Float => DataType::Float64,
+                    _ => DataType::Utf8,
+                },
+                false,
+            ))),
+            Kind::Struct(fields) => DataType::Struct(struct_fields(fields)),
+        }
+    }
+
+    fn to_array(&self, name: &str, values: &[Option<&value::Value>]) -> Result<ArrayRef, Error> {
+        let mismatch = |value: &value::Value| {
+            Error::InvalidArgument(format!(
+                "Invalid value for field `{name}`: expected {self}, got {}",
+                value.to_user_friendly_type_name()
+            ))
+        };
+
+        Ok(match self {
+            Kind::Text => {
+                let mut builder = StringBuilder::new();
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(value::Value::String(s)) => builder.append_value(s),
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::Integer => {
+                let mut builder = Int64Builder::new();
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(value::Value::I32(v)) => builder.append_value(*v as i64),
+                        Some(value::Value::I64(v)) => builder.append_value(*v),
+                        Some(value::Value::U32(v)) => builder.append_value(*v as i64),
+                        Some(v @ value::Value::U64(u)) => {
+                            builder.append_value(i64::try_from(*u).map_err(|_| mismatch(v))?)
+                        }
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::Float => {
+                let mut builder = Float64Builder::new();
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(value::Value::F32(v)) => builder.append_value(*v as f64),
+                        Some(value::Value::F64(v)) => builder.append_value(*v),
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::Boolean => {
+                let mut builder = BooleanBuilder::new();
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(value::Value::Bool(v)) => builder.append_value(*v),
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::Bytes => {
+                let mut builder = BinaryBuilder::new();
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(value::Value::Binary(v)) => builder.append_value(v),
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::Timestamp => {
+                let mut builder = TimestampMillisecondBuilder::new().with_timezone("UTC");
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(value::Value::I64(v)) => builder.append_value(*v),
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::Vector(element, n) => {
+                let mut elements = Elements::new(*element);
+                let mut nulls = Vec::with_capacity(values.len());
+                for value in values {
+                    match value {
+                        None => elements.pad(*n as usize),
+                        Some(v) => match elements.extend(v) {
+                            Some(len) if len == *n as usize => {}
+                            _ => return Err(mismatch(v)),
+                        },
+                    }
+                    nulls.push(value.is_some());
+                }
+                Arc::new(
+                    FixedSizeListArray::try_new(
+                        element_field(*element),
+                        *n,
+                        elements.into_array(),
+                        Some(NullBuffer::from(nulls)),
+                    )
+                    .map_err(arrow_error)?,
+                )
+            }
+            Kind::SparseVector(element) => {
+                let mut indices = Vec::<u32>::new();
+                let mut elements = Elements::new(*element);
+                let mut lengths = Vec::with_capacity(values.len());
+                let mut nulls = Vec::with_capacity(values.len());
+                for value in values {
+                    match value {
+                        None => lengths.push(0),
+                        Some(v @ value::Value::SparseVector(sparse)) => {
+                            match sparse
+                                .values
+                                .as_ref()
+                                .and_then(|s| elements.extend_sparse(s))
+                            {
+                                Some(len) if len == sparse.indices.len() => {}
+                                _ => return Err(mismatch(v)),
+                            }
+                            indices.extend(&sparse.indices);
+                            lengths.push(sparse.indices.len());
+                        }
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                    nulls.push(value.is_some());
+                }
+
+                let offsets = OffsetBuffer::<i32>::from_lengths(lengths);
+                let fields = sparse_fields(*element);
+                let children: Vec<ArrayRef> = vec![
+                    Arc::new(
+                        ListArray::try_new(
+                            Arc::new(Field::new_list_field(DataType::UInt32, false)),
+                            offsets.clone(),
+                            Arc::new(UInt32Array::from(indices)),
+                            None,
+                        )
+                        .map_err(arrow_error)?,
+                    ),
+                    Arc::new(
+                        ListArray::try_new(
+                            element_field(*element),
+                            offsets,
+                            elements.into_array(),
+                            None,
+                        )
+                        .map_err(arrow_error)?,
+                    ),
+                ];
+                Arc::new(
+                    StructArray::try_new(fields, children, Some(NullBuffer::from(nulls)))
+                        .map_err(arrow_error)?,
+                )
+            }
+            Kind::Matrix(element, n) => {
+                let mut elements = Elements::new(*element);
+                let mut lengths = Vec::with_capacity(values.len());
+                let mut nulls = Vec::with_capacity(values.len());
+                for value in values {
+                    match value {
+                        None => lengths.push(0),
+                        Some(v @ value::Value::Matrix(m)) => {
+                            if m.num_cols as i32 != *n {
+                                return Err(mismatch(v));
+                            }
+                            m.values
+                                .as_ref()
+                                .and_then(|values| elements.extend_matrix(values))
+                                .ok_or_else(|| mismatch(v))?;
+                            lengths.push(m.num_rows as usize);
+                        }
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                    nulls.push(value.is_some());
+                }
+
+                let rows = FixedSizeListArray::try_new(
+                    element_field(*element),
+                    *n,
+                    elements.into_array(),
+                    None,
+                )
+                .map_err(arrow_error)?;
+                Arc::new(
+                    ListArray::try_new(
+                        Arc::new(Field::new_list_field(rows.data_type().clone(), false)),
+                        OffsetBuffer::<i32>::from_lengths(lengths),
+                        Arc::new(rows),
+                        Some(NullBuffer::from(nulls)),
+                    )
+                    .map_err(arrow_error)?,
+                )
+            }
+            Kind::List(ListValueType::Integer) => {
+                let mut builder = ListBuilder::new(Int64Builder::new())
+                    .with_field(Field::new_list_field(DataType::Int64, false));
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(v @ value::Value::List(l)) => {
+                            let items = builder.values();
+                            match l.values.as_ref() {
+                                Some(list::Values::I32(l)) => {
+                                    items.extend(l.values.iter().map(|x| Some(*x as i64)))
+                                }
+                                Some(list::Values::I64(l)) => {
+                                    items.append_slice(&l.values);
+                                }
+                                Some(list::Values::U32(l)) => {
+                                    items.extend(l.values.iter().map(|x| Some(*x as i64)))
+                                }
+                                Some(list::Values::U64(l)) => {
+                                    for x in &l.values {
+                                        items.append_value(
+                                            i64::try_from(*x).map_err(|_| mismatch(v))?,
+                                        );
+                                    }
+                                }
+                                Some(list::Values::U8(l)) => {
+                                    items.extend(l.values.iter().map(|x| Some(*x as i64)))
+                                }
+                                Some(list::Values::I8(l)) => {
+                                    let l: &[i8] = l.as_ref();
+                                    items.extend(l.iter().map(|x| Some(*x as i64)))
+                                }
+                                _ => return Err(mismatch(v)),
+                            }
+                            builder.append(true);
+                        }
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::List(ListValueType::Float) => {
+                let mut builder = ListBuilder::new(Float64Builder::new())
+                    .with_field(Field::new_list_field(DataType::Float64, false));
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(v @ value::Value::List(l)) => {
+                            let items = builder.values();
+                            match l.values.as_ref() {
+                                Some(list::Values::F32(l)) => {
+                                    items.extend(l.values.iter().map(|x| Some(*x as f64)))
+                                }
+                                Some(list::Values::F64(l)) => items.append_slice(&l.values),
+                                Some(list::Values::F16(l)) => {
+                                    let l: &[half::f16] = l.as_ref();
+                                    items.extend(l.iter().map(|x| Some(x.to_f64())))
+                                }
+                                Some(list::Values::F8(l)) => {
+                                    let l: &[F8E4M3] = l.as_ref();
+                                    items.extend(l.iter().map(|x| Some(x.to_f64())))
+                                }
+                                _ => return Err(mismatch(v)),
+                            }
+                            builder.append(true);
+                        }
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::List(_) => {
+                let mut builder = ListBuilder::new(StringBuilder::new())
+                    .with_field(Field::new_list_field(DataType::Utf8, false));
+                for value in values {
+                    match value {
+                        None => builder.append_null(),
+                        Some(v @ value::Value::List(l)) => match l.values.as_ref() {
+                            Some(list::Values::String(l)) => {
+                                for s in &l.values {
+                                    builder.values().append_value(s);
+                                }
+                                builder.append(true);
+                            }
+                            _ => return Err(mismatch(v)),
+                        },
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Kind::Struct(fields) => {
+                let mut nulls = Vec::with_capacity(values.len());
+                for value in values {
+                    match value {
+                        None => {}
+                        Some(value::Value::Struct(s)) => {
+                            if let Some(child) = s
+                                .fields
+                                .keys()
+                                .find(|child| !fields.iter().any(|(n, _)| n == *child))
+                            {
+                                return Err(Error::InvalidArgument(format!(
+                                    "Field `{name}.{child}` is not part of the schema"
+                                )));
+                            }
+                        }
+                        Some(v) => return Err(mismatch(v)),
+                    }
+                    nulls.push(value.is_some());
+                }
+
+                let children = fields
+                    .iter()
+                    .map(|(child, kind)| {
+                        let values = values
+                            .iter()
+                            .map(|value| match value {
+                                Some(value::Value::Struct(s)) => {
+                                    s.fields.get(child).and_then(inner)
+                                }
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>();
+                        kind.to_array(&format!("{name}.{child}"), &values)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Arc::new(
+                    StructArray::try_new(
+                        struct_fields(fields),
+                        children,
+                        Some(NullBuffer::from(nulls)),
+                    )
+                    .map_err(arrow_error)?,
+                )
+            }
+        })
+    }
+
+    fn read(&self, name: &str, array: &dyn Array) -> Result<Vec<Option<Value>>, Error> {
+        let valid = |i: usize| array.is_valid(i);
+
+        match self {
+            Kind::Struct(fields) => {
+                let array = array
+                    .as_struct_opt()
+                    .ok_or_else(|| self.cast_error(name, array))?;
+                let mut rows = (0..array.len())
+                    .map(|i| valid(i).then(HashMap::new))
+                    .collect::<Vec<_>>();
+
+                for (field, column) in array.fields().iter().zip(array.columns()) {
+                    let child = field.name();
+                    let kind = match fields.iter().find(|(n, _)| n == child) {
+                        Some((_, kind)) => kind.clone(),
+                        None => Kind::infer(field.data_type())
+                            .ok_or_else(|| unsupported(name, field))?,
+                    };
+                    let values = kind.read(&format!("{name}.{child}"), column)?;
+                    for (row, value) in rows.iter_mut().zip(values) {
+                        if let (Some(row), Some(value)) = (row, value) {
+                            row.insert(child.clone(), value);
+                        }
+                    }
+                }
+
+                Ok(rows
+                    .into_iter()
+                    .map(|row| row.map(Value::r#struct))
+                    .collect())
+            }
+            Kind::SparseVector(element) => {
+                let array = array
+                    .as_struct_opt()
+                    .ok_or_else(|| self.cast_error(name, array))?;
+                let column = |child: &str, data_type: DataType| {
+                    let column = array
+                        .column_by_name(child)
+                        .ok_or_else(|| self.cast_error(name, array))?;
+                    arrow_cast::cast(column, &data_type).map_err(|_| self.cast_error(name, array))
+                };
+                let indices = column(
+                    "indices",
+                    DataType::List(Arc::new(Field::new_list_field(DataType::UInt32, false))),
+                )?;
+                let values = column("values", DataType::List(element_field(*element)))?;
+                let (indices, values) = (indices.as_list::<i32>(), values.as_list::<i32>());
+
+                Ok((0..array.len())
+                    .map(|i| {
+                        valid(i).then(|| {
+                            let indices = indices.value(i);
+                            let indices = indices.as_primitive::<UInt32Type>().values().to_vec();
+                            element.read(&values.value(i)).into_sparse(indices)
+                        })
+                    })
+                    .collect())
+            }
+            // Binary vectors are often stored as fixed size binary columns
+            Kind::Vector(Element::U8, n)
+                if matches!(
+                    array.data_type(),
+                    DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_)
+                ) =>
+            {
+                let binary = arrow_cast::cast(array, &DataType::Binary)
+                    .map_err(|_| self.cast_error(name, array))?;
+                binary
+                    .as_binary::<i32>()
+                    .iter()
+                    .map(|bytes| match bytes {
+                        Some(bytes) if bytes.len() == *n as usize => {
+                            Ok(Some(Value::list(bytes.to_vec())))
+                        }
+                        Some(_) => Err(self.cast_error(name, array)),
+                        None => Ok(None),
+                    })
+                    .collect()
+            }
+            _ => {
+                let data_type = self.data_type();
+                let array = if array.data_type() == &data_type {
+                    array.slice(0, array.len())
+                } else {
+                    arrow_cast::cast(array, &data_type).map_err(|_| self.cast_error(name, array))?
+                };
+
+                let rows: Vec<Option<Value>> = match self {
+                    Kind::Text => array
+                        .as_string::<i32>()
+                        .iter()
+                        .map(|v| v.map(Value::string))
+                        .collect(),
+                    Kind::Integer => array
+                        .as_primitive::<Int64Type>()
+                        .iter()
+                        .map(|v| v.map(Value::i64))
+                        .collect(),
+                    Kind::Float => array
+                        .as_primitive::<Float64Type>()
+                        .iter()
+                        .map(|v| v.map(Value::f64))
+                        .collect(),
+                    Kind::Boolean => array
+                        .as_boolean()
+                        .iter()
+                        .map(|v| v.map(Value::bool))
+                        .collect(),
+                    Kind::Bytes => array
+                        .as_binary::<i32>()
+                        .iter()
+                        .map(|v| v.map(|v| Value::binary(v.to_vec())))
+                        .collect(),
+                    Kind::Timestamp => array
+                        .as_primitive::<TimestampMillisecondType>()
+                        .iter()
+                        .map(|v| v.map(Value::timestamp))
+                        .collect(),
+                    Kind::Vector(element, _) => {
+                        let array = array.as_fixed_size_list();
+                        (0..array.len())
+                            .map(|i| valid(i).then(|| element.read(&array.value(i)).into_list()))
+                            .collect()
+                    }
+                    Kind::Matrix(element, n) => {
+                        let array = array.as_list::<i32>();
+                        (0..array.len())
+                            .map(|i| {
+                                valid(i).then(|| {
+                                    let rows = array.value(i);
+                                    element
+                                        .read(rows.as_fixed_size_list().values())
+                                        .into_matrix(*n as u32)
+                                })
+                            })
+                            .collect()
+                    }
+                    Kind::List(value_type) => {
+                        let array = array.as_list::<i32>();
+                        (0..array.len())
+                            .map(|i| {
+                                valid(i).then(|| {
+                                    let items = array.value(i);
+                                    match value_type {
+                                        ListValueType::Integer => Value::list(
+                                            items.as_primitive::<Int64Type>().values().to_vec(),
+                                        ),
+                                        ListValueType::Float => Value::list(
+                                            items.as_primitive::<Float64Type>().values().to_vec(),
+                                        ),
+                                        _ => Value::list(
+                                            items
+                                                .as_string::<i32>()
+                                                .iter()
+                                                .flatten()
+                                                .map(String::from)
+                                                .collect::<Vec<_>>(),
+                                        ),
+                                    }
+                                })
+                            })
+                            .collect()
+                    }
+                    Kind::Struct(_) | Kind::SparseVector(_) => unreachable!(),
+                };
+                Ok(rows)
+            }
+        }
+    }
+
+    fn cast_error(&self, name: &str, array: &dyn Array) -> Error {
+        Error::InvalidArgument(format!(
+            "Cannot convert column `{name}` of type {} to {self}",
+            array.data_type()
+        ))
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Text => write!(f, "text"),
+            Kind::Integer => write!(f, "integer"),
+            Kind::Float => write!(f, "float"),
+            Kind::Boolean => write!(f, "boolean"),
+            Kind::Bytes => write!(f, "bytes"),
+            Kind::Timestamp => write!(f, "timestamp"),
+            Kind::Vector(element, n) => write!(f, "{element}_vector({n})"),
+            Kind::SparseVector(element) => write!(f, "{element}_sparse_vector"),
+            Kind::Matrix(element, n) => write!(f, "matrix({n}, {element})"),
+            Kind::List(ListValueType::Integer) => write!(f, "list<integer>"),
+            Kind::List(ListValueType::Float) => write!(f, "list<float>"),
+            Kind::List(_) => write!(f, "list<string>"),
+            Kind::Struct(_) => write!(f, "struct"),
+        }
+    }
+}
+
+fn element_field(element: Element) -> Arc<Field> {
+    Arc::new(Field::new_list_field(element.data_type(), false))
+}
+
+fn sparse_fields(element: Element) -> Fields {
+    Fields::from(vec![
+        Field::new(
+            "indices",
+            DataType::List(Arc::new(Field::new_list_field(DataType::UInt32, false))),
+            false,
+        ),
+        Field::new("values", DataType::List(element_field(element)), false),
+    ])
+}
+
+fn struct_fields(fields: &[(String, Kind)]) -> Fields {
+    fields
+        .iter()
+        .map(|(name, kind)| Field::new(name, kind.data_type(), true))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{FixedSizeBinaryArray, Float64Array, Int32Array, StringArray};
+
+    use super::*;
+    use crate::proto::v1::data::Matrix;
+
+    fn schema() -> HashMap<String, FieldSpec> {
+        HashMap::from([
+            ("title".to_string(), FieldSpec::text(true)),
+            ("year".to_string(), FieldSpec::integer(false)),
+            ("rating".to_string(), FieldSpec::float(false)),
+            ("published".to_string(), FieldSpec::boolean(false)),
+            ("cover".to_string(), FieldSpec::bytes(false)),
+            ("updated_at".to_string(), FieldSpec::timestamp(false)),
+            ("f32".to_string(), FieldSpec::f32_vector(2, false)),
+            ("f16".to_string(), FieldSpec::f16_vector(2, false)),
+            ("f8".to_string(), FieldSpec::f8_vector(2, false)),
+            ("u8".to_string(), FieldSpec::u8_vector(2, false)),
+            ("i8".to_string(), FieldSpec::i8_vector(2, false)),
+            ("binary".to_string(), FieldSpec::binary_vector(1, false)),
+            ("sparse".to_string(), FieldSpec::f32_sparse_vector(false)),
+            ("sparse_u8".to_string(), FieldSpec::u8_sparse_vector(false)),
+            (
+                "matrix".to_string(),
+                FieldSpec::matrix(false, 2, MatrixValueType::F32),
+            ),
+            (
+                "tags".to_string(),
+                FieldSpec::list(false, ListValueType::String),
+            ),
+            (
+                "scores".to_string(),
+                FieldSpec::list(false, ListValueType::Float),
+            ),
+            (
+                "author".to_string(),
+                FieldSpec::r#struct(
+                    false,
+                    [
+                        ("name", FieldSpec::text(false)),
+                        ("born", FieldSpec::integer(false)),
+                    ],
+                ),
+            ),
+        ])
+    }
+
+    fn f16s(values: &[f32]) -> Vec<half::f16> {
+        values.iter().copied().map(half::f16::from_f32).collect()
+    }
+
+    fn f8s(values: &[f32]) -> Vec<F8E4M3> {
+        values.iter().copied().map(F8E4M3::from_f32).collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let docs = vec![
+            Document::from([
+                ("_id", Value::string("1")),
+                ("title", Value::string("Dune")),
+                ("year", Value::i64(1965)),
+                ("rating", Value::f64(4.5)),
+                ("published", Value::bool(true)),
+                ("cover", Value::binary(vec![1u8, 2, 3])),
+                ("updated_at", Value::timestamp(1_700_000_000_000i64)),
+                ("f32", Value::list(vec![1.0f32, 2.0])),
+                ("f16", Value::list(f16s(&[0.5, 1.5]))),
+                ("f8", Value::list(f8s(&[0.5, 2.0]))),
+                ("u8", Value::list(vec![1u8, 2])),
+                ("i8", Value::list(vec![-1i8, 1])),
+                ("binary", Value::list(vec![255u8])),
+                (
+                    "sparse",
+                    Value::f32_sparse_vector(vec![0, 7], vec![0.1, 0.2]),
+                ),
+                ("sparse_u8", Value::u8_sparse_vector(vec![3], vec![9])),
+                ("matrix", Matrix::new(2, vec![1.0f32, 2.0, 3.0, 4.0]).into()),
+                ("tags", Value::list(vec!["sci-fi", "classic"])),
+                ("scores", Value::list(vec![0.25f64, 0.75])),
+                (
+                    "author",
+                    Value::r#struct([
+                        ("name", Value::string("Frank Herbert")),
+                        ("born", Value::i64(1920)),
+                    ]),
+                ),
+            ]),
+            Document::from([("_id", Value::string("2")), ("title", Value::null())]),
+        ];
+
+        let converter = DocumentConverter::new(&schema()).unwrap();
+        let batch = converter.to_record_batch(&docs).unwrap();
+
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().field(0).name(), "_id");
+        assert_eq!(
+            batch.schema().field_with_name("f16").unwrap().data_type(),
+            &DataType::FixedSizeList(element_field(Element::F16), 2)
+        );
+        assert_eq!(
+            batch
+                .schema()
+                .field_with_name("updated_at")
+                .unwrap()
+                .data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+        );
+
+        let mut expected = docs;
+        expected[1].fields.remove("title");
+        assert_eq!(converter.to_documents(&batch).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let converter = DocumentConverter::new(&schema()).unwrap();
+        let docs = vec![Document::from([
+            ("_id", Value::string("1")),
+            ("_score", Value::f32(0.5)),
+        ])];
+
+        let err = converter.to_record_batch(&docs).unwrap_err();
+        assert!(err.to_string().contains("with_field"), "{err}");
+
+        let batch = converter
+            .with_field("_score", FieldType::float())
+            .unwrap()
+            .to_record_batch(&docs)
+            .unwrap();
+        assert_eq!(
+            batch.schema().field(batch.num_columns() - 1).name(),
+            "_score"
+        );
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let converter = DocumentConverter::new(&schema()).unwrap();
+        let docs = vec![Document::from([
+            ("_id", Value::string("1")),
+            ("f32", Value::list(vec![1.0f32, 2.0, 3.0])),
+        ])];
+
+        let err = converter.to_record_batch(&docs).unwrap_err();
+        assert!(err.to_string().contains("f32_vector(2)"), "{err}");
+    }
+
+    #[test]
+    fn test_coerce_record_batch() {
+        let embedding = ListArray::from_iter_primitive::<Float64Type, _, _>(vec![
+            Some(vec![Some(1.0), Some(2.0)]),
+            None,
+        ]);
+        let batch = RecordBatch::try_from_iter([
+            (
+                "_id",
+                Arc::new(StringArray::from(vec!["1", "2"])) as ArrayRef,
+            ),
+            ("year", Arc::new(Int32Array::from(vec![Some(1965), None]))),
+            ("f32", Arc::new(embedding)),
+            ("extra", Arc::new(Float64Array::from(vec![0.5, 1.5]))),
+            (
+                "binary",
+                Arc::new(
+                    FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                        vec![None, Some(vec![255u8])].into_iter(),
+                        1,
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ])
+        .unwrap();
+
+        let converter = DocumentConverter::new(&schema()).unwrap();
+        let docs = converter.to_documents(&batch).unwrap();
+
+        assert_eq!(
+            docs,
+            vec![
+                Document::from([
+                    ("_id", Value::string("1")),
+                    ("year", Value::i64(1965)),
+                    ("f32", Value::list(vec![1.0f32, 2.0])),
+                    ("extra", Value::f64(0.5)),
+                ]),
+                Document::from([
+                    ("_id", Value::string("2")),
+                    ("extra", Value::f64(1.5)),
+                    ("binary", Value::list(vec![255u8])),
+                ]),
+            ]
+        );
+    }
+}
@@ -8,7 +8,7 @@ use crate::proto::v1::ctx::AskResult;
 use crate::proto::v1::ctx::Mode;
 use crate::proto::v1::ctx::Source;
 use crate::proto::v1::data::LogicalExpr;
-use crate::retry::call_with_retry;
+use crate::retry::call_with_reauth;
 use crate::Error;
 
 impl super::Client {
@@ -39,11 +39,15 @@ impl super::Client {
             include_content: include_content.unwrap_or(false),
         };
 
-        let response = call_with_retry(self.config().retry_config(), || {
-            let request = request.clone();
-            let mut client = client.clone();
-            async move { client.ask(request).map_err(Error::from).await }
-        })
+        let response = call_with_reauth(
+            self.config().retry_config(),
+            self.config().refreshes(),
+            || {
+                let request = request.clone();
+                let mut client = client.clone();
+                async move { client.ask(request).map_err(Error::from).await }
+            },
+        )
         .await?;
 
         Ok(response.into_inner())
@@ -4,9 +4,11 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use futures::{Stream, TryStreamExt};
-use futures_util::{StreamExt, TryFutureExt};
+use futures_util::{stream, StreamExt, TryFutureExt};
 use prost::Message;
 use tokio::sync::OnceCell;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 use tonic::transport::Channel;
 use tonic::{Response, Streaming};
 
@@ -15,19 +17,20 @@ use crate::error::Error;
 use crate::proto::v1::data::partition_service_client::PartitionServiceClient;
 use crate::proto::v1::data::query_service_client::QueryServiceClient;
 use crate::proto::v1::data::write_service_client::WriteServiceClient;
+use crate::proto::v1::data::GetRequest;
 use crate::proto::v1::data::ListPartitionsRequest;
 use crate::proto::v1::data::Partition;
 use crate::proto::v1::data::Query;
 use crate::proto::v1::data::Stage;
 use crate::proto::v1::data::UpdateDocumentsRequest;
-use crate::proto::v1::data::{ConsistencyLevel, GetRequest};
 use crate::proto::v1::data::{
     DeleteDocumentsRequest, Document, QueryRequest, UpsertDocumentsRequest, Value,
 };
 use crate::proto::v1::data::{DeletePartitionRequest, DocumentData};
 
 use super::config::ClientConfig;
-use super::retry::call_with_retry;
+use super::options::RequestOptions;
+use super::retry::call_with_reauth;
 
 pub struct DocumentStream {
     // Underying stream
@@ -59,6 +62,48 @@ impl DocumentStream {
     pub fn matched_count(&self) -> Option<u64> {
         self.matched_count
     }
+
+    /// Ends the stream with [`Error::Cancelled`] once `token` is cancelled, or with
+    /// [`Error::DeadlineExceeded`] at `deadline`.
+    fn until(self, token: Option<CancellationToken>, deadline: Option<Instant>) -> Self {
+        if token.is_none() && deadline.is_none() {
+            return self;
+        }
+
+        let stream = stream::unfold(Some(self.stream), move |stream| {
+            let token = token.clone();
+            async move {
+                let mut stream = stream?;
+                tokio::select! {
+                    _ = cancelled(token.as_ref()) => Some((Err(Error::Cancelled), None)),
+                    _ = expired(deadline) => Some((
+                        Err(Error::DeadlineExceeded("request timed out".to_string())),
+                        None,
+                    )),
+                    item = stream.next() => Some((item?, Some(stream))),
+                }
+            }
+        });
+
+        Self {
+            stream: Box::pin(stream),
+            matched_count: self.matched_count,
+        }
+    }
+}
+
+async fn cancelled(token: Option<&CancellationToken>) {
+    match token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn expired(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
 }
 
 impl Stream for DocumentStream {
@@ -107,64 +152,67 @@ impl CollectionClient {
         &self,
         ids: impl IntoIterator<Item = impl Into<String>>,
         fields: Option<Vec<String>>,
-        lsn: Option<String>,
-        consistency: Option<ConsistencyLevel>,
+        options: Option<RequestOptions>,
     ) -> Result<HashMap<String, HashMap<String, Value>>, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(QueryServiceClient, self.read, self.config).await?;
+        let metadata = options.metadata()?;
         let ids: Vec<String> = ids.into_iter().map(|id| id.into()).collect();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let ids = ids.clone();
-            let fields = fields.clone();
-            let lsn = lsn.clone();
-            let consistency = consistency.clone();
-
-            async move {
-                client
-                    .get_stream(GetRequest {
-                        ids: ids,
-                        fields: fields.unwrap_or_default(),
-                        required_lsn: lsn,
-                        consistency_level: consistency.map(|c| c.into()),
-                    })
-                    .map_err(Self::map_status_to_error)
-                    .await
-            }
-        })
-        .await?;
-
-        // Collect results from stream
-        let mut stream = response.into_inner();
-        let mut docs = HashMap::new();
-        while let Some(result) = stream.next().await {
-            // Decode document
-            let doc = Document::decode(result?.data)
-                .map_err(|e| Error::MalformedResponse(e.to_string()))?;
-
-            docs.insert(
-                doc.id()
-                    .map_err(|e| Error::MalformedResponse(e.to_string()))?
-                    .to_string(),
-                doc.fields,
-            );
-        }
-        Ok(docs)
+        options
+            .run(async {
+                let retry_config = options.retry_config(self.config.retry_config());
+                let response = call_with_reauth(retry_config, self.config.refreshes(), || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &metadata,
+                        GetRequest {
+                            ids: ids.clone(),
+                            fields: fields.clone().unwrap_or_default(),
+                            required_lsn: options.lsn.clone(),
+                            consistency_level: options.consistency.map(|c| c.into()),
+                        },
+                    );
+
+                    async move {
+                        client
+                            .get_stream(request)
+                            .map_err(Self::map_status_to_error)
+                            .await
+                    }
+                })
+                .await?;
+
+                // Collect results from stream
+                let mut stream = response.into_inner();
+                let mut docs = HashMap::new();
+                while let Some(result) = stream.next().await {
+                    // Decode document
+                    let doc = Document::decode(result?.data)
+                        .map_err(|e| Error::MalformedResponse(e.to_string()))?;
+
+                    docs.insert(
+                        doc.id()
+                            .map_err(|e| Error::MalformedResponse(e.to_string()))?
+                            .to_string(),
+                        doc.fields,
+                    );
+                }
+                Ok(docs)
+            })
+            .await
     }
 
-    pub async fn count(
-        &self,
-        lsn: Option<String>,
-        consistency: Option<ConsistencyLevel>,
-    ) -> Result<u64, Error> {
+    pub async fn count(&self, options: Option<RequestOptions>) -> Result<u64, Error> {
+        let options = options.unwrap_or_default();
         let query = Query::new(vec![Stage::count()]);
 
-        let docs = call_with_retry(&self.config.retry_config(), || {
+        let retry_config = options.retry_config(self.config.retry_config());
+        let docs = call_with_reauth(retry_config, self.config.refreshes(), || {
             let query = query.clone();
-            let lsn = lsn.clone();
-            let consistency = consistency.clone();
+            let options = options.clone();
 
-            async move { self.query(query, lsn, consistency).await }
+            async move { self.query(query, Some(options)).await }
         })
         .await?;
 
@@ -194,63 +242,94 @@ impl CollectionClient {
     pub async fn query(
         &self,
         query: Query,
-        lsn: Option<String>,
-        consistency: Option<ConsistencyLevel>,
+        options: Option<RequestOptions>,
     ) -> Result<Vec<Document>, Error> {
-        let stream = self.query_stream(query, lsn, consistency).await?;
-        let results = stream.try_collect::<Vec<_>>().await?;
-        Ok(results)
+        let options = options.unwrap_or_default();
+
+        options
+            .run(async {
+                let stream = self.query_stream(query, Some(options.clone())).await?;
+                let results = stream.try_collect::<Vec<_>>().await?;
+                Ok(results)
+            })
+            .await
     }
 
     pub async fn query_stream(
         &self,
         query: Query,
-        lsn: Option<String>,
-        consistency: Option<ConsistencyLevel>,
+        options: Option<RequestOptions>,
     ) -> Result<DocumentStream, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(QueryServiceClient, self.read, self.config).await?;
+        let metadata = options.metadata()?;
+
+        let retry_config = options.retry_config(self.config.retry_config());
+        let (sent_at, response) = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let sent_at = Instant::now();
+                    let request = options.request(
+                        &metadata,
+                        QueryRequest {
+                            query: Some(query.clone()),
+                            required_lsn: options.lsn.clone(),
+                            consistency_level: options.consistency.map(|c| c.into()),
+                            // DEPRECATED: This field is no longer used, kept for backwards compatibility.
+                            collection: String::new(),
+                        },
+                    );
+
+                    async move {
+                        client
+                            .query_stream(request)
+                            .map_ok(|response| (sent_at, response))
+                            .map_err(Self::map_status_to_error)
+                            .await
+                    }
+                },
+            ))
+            .await?;
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let query = query.clone();
-            let lsn = lsn.clone();
-
-            async move {
-                client
-                    .query_stream(QueryRequest {
-                        query: Some(query.into()),
-                        required_lsn: lsn.clone(),
-                        consistency_level: consistency.map(|c| c.into()),
-                        // DEPRECATED: This field is no longer used, kept for backwards compatibility.
-                        collection: String::new(),
-                    })
-                    .map_err(Self::map_status_to_error)
-                    .await
-            }
-        })
-        .await?;
-
-        Ok(DocumentStream::new(response))
+        // The timeout and cancellation of the request also cover reading the stream
+        let deadline = options.timeout.map(|timeout| sent_at + timeout);
+        Ok(DocumentStream::new(response).until(options.cancellation_token.clone(), deadline))
     }
 
     /// Upsert documents into the collection.
     ///
     /// Existing documents will be replaced, new documents will be created.
-    pub async fn upsert(&self, docs: Vec<Document>) -> Result<String, Error> {
+    pub async fn upsert(
+        &self,
+        docs: Vec<Document>,
+        options: Option<RequestOptions>,
+    ) -> Result<String, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(WriteServiceClient, self.write, self.config).await?;
-
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let docs = docs.clone();
-
-            async move {
-                client
-                    .upsert_documents(UpsertDocumentsRequest { docs })
-                    .await
-                    .map_err(Self::map_status_to_error)
-            }
-        })
-        .await?;
+        let metadata = options.metadata()?;
+
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request =
+                        options.request(&metadata, UpsertDocumentsRequest { docs: docs.clone() });
+
+                    async move {
+                        client
+                            .upsert_documents(request)
+                            .await
+                            .map_err(Self::map_status_to_error)
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner().lsn)
     }
@@ -263,46 +342,70 @@ impl CollectionClient {
         &self,
         docs: Vec<Document>,
         fail_on_missing: bool,
+        options: Option<RequestOptions>,
     ) -> Result<String, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(WriteServiceClient, self.write, self.config).await?;
-
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let docs = docs.clone();
-
-            async move {
-                client
-                    .update_documents(UpdateDocumentsRequest {
-                        docs,
-                        fail_on_missing,
-                    })
-                    .await
-                    .map_err(Self::map_status_to_error)
-            }
-        })
-        .await?;
+        let metadata = options.metadata()?;
+
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &metadata,
+                        UpdateDocumentsRequest {
+                            docs: docs.clone(),
+                            fail_on_missing,
+                        },
+                    );
+
+                    async move {
+                        client
+                            .update_documents(request)
+                            .await
+                            .map_err(Self::map_status_to_error)
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner().lsn)
     }
 
     /// Delete documents from the collection.
-    pub async fn delete(&self, req: impl Into<DeleteDocumentsRequest>) -> Result<String, Error> {
+    pub async fn delete(
+        &self,
+        req: impl Into<DeleteDocumentsRequest>,
+        options: Option<RequestOptions>,
+    ) -> Result<String, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(WriteServiceClient, self.write, self.config).await?;
+        let metadata = options.metadata()?;
 
         let req = req.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let req = req.clone();
-
-            async move {
-                client
-                    .delete_documents(req)
-                    .await
-                    .map_err(Self::map_status_to_error)
-            }
-        })
-        .await?;
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(&metadata, req.clone());
+
+                    async move {
+                        client
+                            .delete_documents(request)
+                            .await
+                            .map_err(Self::map_status_to_error)
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner().lsn)
     }
@@ -314,22 +417,36 @@ impl CollectionClient {
     pub async fn list_partitions(
         &self,
         prefix: Option<String>,
+        options: Option<RequestOptions>,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<Partition, Error>> + Send>>, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(PartitionServiceClient, self.write, self.config).await?;
-
-        let stream = call_with_retry(&self.config.retry_config(), || {
-            let prefix = prefix.clone();
-            let mut client = client.clone();
-
-            async move {
-                client
-                    .list(ListPartitionsRequest { prefix })
-                    .map_err(Self::map_status_to_error)
-                    .await
-            }
-        })
-        .await?
-        .into_inner();
+        let metadata = options.metadata()?;
+
+        let retry_config = options.retry_config(self.config.retry_config());
+        let stream = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &metadata,
+                        ListPartitionsRequest {
+                            prefix: prefix.clone(),
+                        },
+                    );
+
+                    async move {
+                        client
+                            .list(request)
+                            .map_err(Self::map_status_to_error)
+                            .await
+                    }
+                },
+            ))
+            .await?
+            .into_inner();
 
         Ok(Box::pin(stream.map_err(|e| Error::from(e))))
     }
@@ -337,22 +454,35 @@ impl CollectionClient {
     /// Delete partition from the collection.
     ///
     /// All documents in the partition will be deleted atomically.
-    pub async fn delete_partition(&self, name: impl Into<String>) -> Result<(), Error> {
+    pub async fn delete_partition(
+        &self,
+        name: impl Into<String>,
+        options: Option<RequestOptions>,
+    ) -> Result<(), Error> {
+        let options = options.unwrap_or_default();
         let name = name.into();
         let client = create_client!(PartitionServiceClient, self.write, self.config).await?;
-
-        call_with_retry(&self.config.retry_config(), || {
-            let name = name.clone();
-            let mut client = client.clone();
-
-            async move {
-                client
-                    .delete(DeletePartitionRequest { name })
-                    .await
-                    .map_err(Self::map_status_to_error)
-            }
-        })
-        .await?;
+        let metadata = options.metadata()?;
+
+        let retry_config = options.retry_config(self.config.retry_config());
+        options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request =
+                        options.request(&metadata, DeletePartitionRequest { name: name.clone() });
+
+                    async move {
+                        client
+                            .delete(request)
+                            .await
+                            .map_err(Self::map_status_to_error)
+                    }
+                },
+            ))
+            .await?;
 
         Ok(())
     }
@@ -6,7 +6,7 @@ use tokio::sync::OnceCell;
 use tonic::transport::Channel;
 
 use super::config::ClientConfig;
-use super::retry::call_with_retry;
+use super::retry::call_with_reauth;
 use crate::create_client;
 use crate::error::Error;
 use crate::proto::v1::control::collection_service_client::CollectionServiceClient;
@@ -30,17 +30,18 @@ impl CollectionsClient {
     pub async fn list(&self) -> Result<Vec<Collection>, Error> {
         let client = create_client!(CollectionServiceClient, self.channel, self.config).await?;
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
+        let response =
+            call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
+                let mut client = client.clone();
 
-            async move {
-                client
-                    .list_collections(ListCollectionsRequest {})
-                    .map_err(Error::from)
-                    .await
-            }
-        })
-        .await?;
+                async move {
+                    client
+                        .list_collections(ListCollectionsRequest {})
+                        .map_err(Error::from)
+                        .await
+                }
+            })
+            .await?;
 
         Ok(response.into_inner().collections)
     }
@@ -49,23 +50,24 @@ impl CollectionsClient {
         let client = create_client!(CollectionServiceClient, self.channel, self.config).await?;
         let name = name.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let name = name.clone();
-
-            async move {
-                client
-                    .get_collection(GetCollectionRequest { name })
-                    .map_err(|e| match e.code() {
-                        // Collection not found
-                        tonic::Code::NotFound => Error::CollectionNotFound,
-                        // Delegate other errors
-                        _ => Error::from(e),
-                    })
-                    .await
-            }
-        })
-        .await?;
+        let response =
+            call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
+                let mut client = client.clone();
+                let name = name.clone();
+
+                async move {
+                    client
+                        .get_collection(GetCollectionRequest { name })
+                        .map_err(|e| match e.code() {
+                            // Collection not found
+                            tonic::Code::NotFound => Error::CollectionNotFound,
+                            // Delegate other errors
+                            _ => Error::from(e),
+                        })
+                        .await
+                }
+            })
+            .await?;
 
         Ok(response
             .into_inner()
@@ -83,29 +85,30 @@ impl CollectionsClient {
         let name = name.into();
         let schema = schema.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let name = name.clone();
-            let schema = schema.clone();
-            let region = region.clone();
-
-            async move {
-                client
-                    .create_collection(CreateCollectionRequest {
-                        name,
-                        schema,
-                        region,
-                    })
-                    .await
-                    .map_err(|e| match e.code() {
-                        // Collection already exists
-                        tonic::Code::AlreadyExists => Error::CollectionAlreadyExists,
-                        // Delegate other errors
-                        _ => e.into(),
-                    })
-            }
-        })
-        .await?;
+        let response =
+            call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
+                let mut client = client.clone();
+                let name = name.clone();
+                let schema = schema.clone();
+                let region = region.clone();
+
+                async move {
+                    client
+                        .create_collection(CreateCollectionRequest {
+                            name,
+                            schema,
+                            region,
+                        })
+                        .await
+                        .map_err(|e| match e.code() {
+                            // Collection already exists
+                            tonic::Code::AlreadyExists => Error::CollectionAlreadyExists,
+                            // Delegate other errors
+                            _ => e.into(),
+                        })
+                }
+            })
+            .await?;
 
         Ok(response
             .into_inner()
@@ -117,7 +120,7 @@ impl CollectionsClient {
         let client = create_client!(CollectionServiceClient, self.channel, self.config).await?;
         let name = name.into();
 
-        let _ = call_with_retry(&self.config.retry_config(), || {
+        let _ = call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
             let mut client = client.clone();
             let name = name.clone();
 
@@ -1,11 +1,20 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use tonic::transport::{ClientTlsConfig, Endpoint};
+use tonic::body::Body;
+use tonic::codec::CompressionEncoding;
+use tonic::codegen::http::{Request, Response};
+use tonic::codegen::{Service, StdError};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tower::Layer;
 
 use crate::Error;
 
+use super::credentials::{Authenticate, CredentialsProvider, Refreshes, StaticCredentials};
+use super::middleware::{channel_service, HttpService, Layers};
 use super::retry::RetryConfig;
+use super::transport::{ProxyConnector, TransportConfig};
 
 #[derive(Clone)]
 pub struct ClientConfig {
@@ -18,26 +27,57 @@ pub struct ClientConfig {
     /// Whether to use HTTPS
     https: bool,
 
+    /// Endpoint URL, overriding the one derived from region and host
+    endpoint: Option<String>,
+
+    /// Credentials
+    credentials: Arc<dyn CredentialsProvider>,
+
+    /// Credentials refreshed after the server rejected them
+    refreshes: Refreshes,
+
     /// Headers
     headers: HashMap<&'static str, String>,
 
     /// Retry config
     retry_config: RetryConfig,
+
+    /// Transport config
+    transport_config: TransportConfig,
+
+    /// Encoding of requests and accepted encoding of responses
+    compression: Option<CompressionEncoding>,
+
+    /// Middleware layers
+    layers: Layers,
 }
 
 impl ClientConfig {
     pub fn new(api_key: impl Into<String>, region: impl Into<String>) -> Self {
+        Self::from_credentials(StaticCredentials::new(api_key), region)
+    }
+
+    /// Creates a config authenticating with the API key from `credentials`, which is consulted on
+    /// every request.
+    pub fn from_credentials(
+        credentials: impl CredentialsProvider + 'static,
+        region: impl Into<String>,
+    ) -> Self {
         Self {
             region: region.into(),
             host: "topk.io".to_string(),
             https: true,
+            endpoint: None,
+            credentials: Arc::new(credentials),
+            refreshes: Refreshes::default(),
             headers: HashMap::from([
-                // Add API key
-                ("authorization", format!("Bearer {}", api_key.into())),
                 // Add SDK version
                 ("x-topk-sdk-version", env!("CARGO_PKG_VERSION").to_string()),
             ]),
             retry_config: RetryConfig::default(),
+            transport_config: TransportConfig::default(),
+            compression: None,
+            layers: Layers::default(),
         }
     }
 
@@ -55,6 +95,10 @@ impl ClientConfig {
         self.https
     }
 
+    pub fn credentials(&self) -> &Arc<dyn CredentialsProvider> {
+        &self.credentials
+    }
+
     pub fn headers(&self) -> &HashMap<&'static str, String> {
         &self.headers
     }
@@ -63,6 +107,39 @@ impl ClientConfig {
         &self.retry_config
     }
 
+    pub(crate) fn refreshes(&self) -> &Refreshes {
+        &self.refreshes
+    }
+
+    pub fn transport_config(&self) -> &TransportConfig {
+        &self.transport_config
+    }
+
+    pub fn compression(&self) -> Option<CompressionEncoding> {
+        self.compression
+    }
+
+    /// Wraps `channel` with the authentication and the layers of the config.
+    pub(crate) fn service(&self, channel: Channel) -> HttpService {
+        let service = Authenticate::new(
+            channel_service(channel),
+            self.credentials.clone(),
+            self.refreshes.clone(),
+        );
+        self.layers.apply(HttpService::new(service))
+    }
+
+    /// Endpoint URL, either set with [`ClientConfig::with_endpoint`] or derived from region
+    /// and host.
+    pub fn endpoint_url(&self) -> String {
+        let protocol = if self.https() { "https" } else { "http" };
+        match (&self.endpoint, self.region()) {
+            (Some(endpoint), _) => endpoint.clone(),
+            (None, "global") => format!("{}://api.{}", protocol, self.host()),
+            (None, region) => format!("{}://{}.api.{}", protocol, region, self.host()),
+        }
+    }
+
     // Setters
 
     pub fn with_host(mut self, host: impl Into<String>) -> Self {
@@ -75,6 +152,11 @@ impl ClientConfig {
         self
     }
 
+    pub fn with_credentials(mut self, credentials: impl CredentialsProvider + 'static) -> Self {
+        self.credentials = Arc::new(credentials);
+        self
+    }
+
     pub fn with_headers(
         mut self,
         headers: impl IntoIterator<Item = (&'static str, impl Into<String>)>,
@@ -89,17 +171,102 @@ impl ClientConfig {
         self
     }
 
+    /// Connects to `url` instead of the endpoint derived from region and host, e.g.
+    /// `https://topk.internal:8443` for a sidecar or `unix:///var/run/topk.sock` for a Unix
+    /// domain socket.
+    pub fn with_endpoint(mut self, url: impl Into<String>) -> Self {
+        self.endpoint = Some(url.into());
+        self
+    }
+
+    pub fn with_transport_config(mut self, transport_config: TransportConfig) -> Self {
+        self.transport_config = transport_config;
+        self
+    }
+
+    /// Compresses requests with `encoding` and asks the server to compress responses with it.
+    ///
+    /// Worth it for bandwidth-bound workloads, e.g. upserting embeddings or fetching large
+    /// documents, at the cost of CPU time on both ends.
+    pub fn with_compression(mut self, encoding: CompressionEncoding) -> Self {
+        self.compression = Some(encoding);
+        self
+    }
+
+    /// Adds a [`tower::Layer`] wrapping the channel of every request, e.g. for rate limiting,
+    /// metrics or request logging. Layers added first are outermost.
+    ///
+    /// The layered service must be [`Clone`], wrap it in `tower::buffer::Buffer` if it is not.
+    pub fn with_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<HttpService> + Send + Sync + 'static,
+        L::Service:
+            Service<Request<Body>, Response = Response<Body>> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request<Body>>>::Error: Into<StdError>,
+        <L::Service as Service<Request<Body>>>::Future: Send + 'static,
+    {
+        self.layers.push(layer);
+        self
+    }
+
     /// Builds [`Endpoint`] from the client config.
     pub fn endpoint(&self) -> Result<Endpoint, Error> {
-        let protocol = if self.https() { "https" } else { "http" };
-        let uri = match self.region() {
-            "global" => format!("{}://api.{}", protocol, self.host()),
-            _ => format!("{}://{}.api.{}", protocol, self.region(), self.host()),
-        };
-        let mut endpoint = Endpoint::from_str(&uri)?;
-        if self.https() {
-            endpoint = endpoint.tls_config(ClientTlsConfig::new().with_native_roots())?;
+        let transport = self.transport_config();
+        let uri = self.endpoint_url();
+
+        let mut endpoint = Endpoint::from_str(&uri)?
+            .keep_alive_while_idle(transport.keep_alive_while_idle)
+            .http2_max_header_list_size(transport.max_header_list_size)
+            .timeout(transport.timeout)
+            // Disable Nagle's algorithm
+            .tcp_nodelay(true)
+            // Disable adaptive window
+            .http2_adaptive_window(false)
+            .initial_stream_window_size(transport.initial_stream_window_size)
+            .initial_connection_window_size(transport.initial_connection_window_size);
+        if let Some(connect_timeout) = transport.connect_timeout {
+            endpoint = endpoint.connect_timeout(connect_timeout);
+        }
+        if let Some(interval) = transport.keep_alive_interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = transport.keep_alive_timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
         }
+
+        if uri.starts_with("https:") {
+            let tls = &transport.tls;
+            let mut tls_config =
+                ClientTlsConfig::new().ca_certificates(tls.ca_certificates.iter().cloned());
+            if tls.native_roots {
+                tls_config = tls_config.with_native_roots();
+            }
+            if let Some(identity) = &tls.identity {
+                tls_config = tls_config.identity(identity.clone());
+            }
+            if let Some(domain_name) = &tls.domain_name {
+                tls_config = tls_config.domain_name(domain_name.clone());
+            }
+            endpoint = endpoint.tls_config(tls_config)?;
+        }
+
         Ok(endpoint)
     }
+
+    /// Connects a [`Channel`] to the endpoint, through the configured proxy.
+    pub async fn connect(&self) -> Result<Channel, Error> {
+        let endpoint = self.endpoint()?;
+
+        // Unix domain sockets are never proxied
+        if self.endpoint_url().starts_with("unix:") {
+            return Ok(endpoint.connect().await?);
+        }
+
+        match self.transport_config().proxy.resolve(endpoint.uri())? {
+            Some(proxy) => Ok(endpoint
+                .connect_with_connector(ProxyConnector::new(proxy, true))
+                .await?),
+            None => Ok(endpoint.connect().await?),
+        }
+    }
 }
@@ -0,0 +1,492 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use tokio::time::{Duration, Instant};
+use tonic::body::Body;
+use tonic::codegen::http::{header, HeaderValue, Request, Response};
+use tonic::codegen::{BoxFuture, Service};
+use tonic::Status;
+use tower::ServiceExt;
+
+use crate::Error;
+
+use super::middleware::HttpService;
+
+/// Source of the API key sent with every request.
+///
+/// The client asks for the token on each request, so providers are expected to be cheap or to
+/// cache, see [`CachedCredentials`].
+#[async_trait]
+pub trait CredentialsProvider: Send + Sync {
+    /// Returns the API key.
+    async fn token(&self) -> Result<String, Error>;
+
+    /// Called when the server rejects `token`, so that the next call to
+    /// [`CredentialsProvider::token`] returns a fresh one.
+    ///
+    /// Returns whether the next token may differ from `token`. Only then is the rejected request
+    /// retried, and only if the token did change.
+    fn invalidate(&self, _token: &str) -> bool {
+        false
+    }
+}
+
+/// Fixed API key, as passed to [`ClientConfig::new`](super::ClientConfig::new).
+#[derive(Clone)]
+pub struct StaticCredentials {
+    token: String,
+}
+
+impl StaticCredentials {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for StaticCredentials {
+    async fn token(&self) -> Result<String, Error> {
+        Ok(self.token.clone())
+    }
+}
+
+/// API key read from an environment variable on every request.
+#[derive(Clone)]
+pub struct EnvCredentials {
+    var: String,
+}
+
+impl EnvCredentials {
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl Default for EnvCredentials {
+    /// Reads `TOPK_API_KEY`.
+    fn default() -> Self {
+        Self::new("TOPK_API_KEY")
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for EnvCredentials {
+    async fn token(&self) -> Result<String, Error> {
+        match std::env::var(&self.var) {
+            Ok(token) if !token.trim().is_empty() => Ok(token.trim().to_string()),
+            _ => Err(Error::Unauthenticated(format!("{} is not set", self.var))),
+        }
+    }
+
+    fn invalidate(&self, _token: &str) -> bool {
+        // The variable is read again on every request
+        true
+    }
+}
+
+/// API key read from a file, e.g. one mounted from a secrets manager. The file is read again
+/// whenever it is modified.
+pub struct FileCredentials {
+    file: WatchedFile,
+}
+
+impl FileCredentials {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            file: WatchedFile::new(path.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for FileCredentials {
+    async fn token(&self) -> Result<String, Error> {
+        self.file
+            .read(|contents| match contents.trim() {
+                "" => Err(Error::Unauthenticated(format!(
+                    "{} is empty",
+                    self.file.path.display()
+                ))),
+                token => Ok(token.to_string()),
+            })
+            .await
+    }
+
+    fn invalidate(&self, _token: &str) -> bool {
+        self.file.invalidate()
+    }
+}
+
+/// API key of a profile in the config file of the `topk` CLI, as saved by `topk login`. The file
+/// is read again whenever it is modified.
+#[cfg(feature = "cli-config")]
+pub struct CliConfigCredentials {
+    file: WatchedFile,
+    profile: Option<String>,
+}
+
+#[cfg(feature = "cli-config")]
+impl CliConfigCredentials {
+    /// Reads the profile selected with `TOPK_PROFILE` or `topk profile use` from the CLI's
+    /// `config.toml`.
+    pub fn new() -> Result<Self, Error> {
+        let dir = dirs::config_dir().ok_or_else(|| {
+            Error::Input(anyhow::anyhow!("could not determine the config directory"))
+        })?;
+        Ok(Self::from_path(dir.join("topk").join("config.toml")))
+    }
+
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            file: WatchedFile::new(path.into()),
+            profile: std::env::var("TOPK_PROFILE").ok(),
+        }
+    }
+
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+}
+
+#[cfg(feature = "cli-config")]
+#[async_trait]
+impl CredentialsProvider for CliConfigCredentials {
+    async fn token(&self) -> Result<String, Error> {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            api_key: Option<String>,
+            current_profile: Option<String>,
+            #[serde(default)]
+            profiles: std::collections::HashMap<String, Profile>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Profile {
+            api_key: Option<String>,
+        }
+
+        self.file
+            .read(|contents| {
+                let config: Config = toml::from_str(contents)
+                    .map_err(|e| Error::Input(anyhow::anyhow!("invalid CLI config: {e}")))?;
+
+                let name = self
+                    .profile
+                    .as_deref()
+                    .or(config.current_profile.as_deref())
+                    .unwrap_or("default");
+                let api_key = config.profiles.get(name).and_then(|p| p.api_key.clone());
+                // Configs written before profiles keep the default API key at the top level
+                let api_key = match name {
+                    "default" => api_key.or(config.api_key),
+                    _ => api_key,
+                };
+                api_key.ok_or_else(|| {
+                    Error::Unauthenticated(format!("profile `{name}` has no API key"))
+                })
+            })
+            .await
+    }
+
+    fn invalidate(&self, _token: &str) -> bool {
+        self.file.invalidate()
+    }
+}
+
+/// Caches the token of a provider, e.g. one fetching it from a secrets manager, for `ttl`.
+pub struct CachedCredentials<P> {
+    provider: P,
+    ttl: Duration,
+    cache: Mutex<Option<(String, Instant)>>,
+    /// Held while fetching, so that concurrent requests share the fetch. The cache itself is
+    /// behind a sync mutex, so that invalidations never wait for (or miss) a fetch.
+    fetch: tokio::sync::Mutex<()>,
+}
+
+impl<P: CredentialsProvider> CachedCredentials<P> {
+    pub fn new(provider: P, ttl: Duration) -> Self {
+        Self {
+            provider,
+            ttl,
+            cache: Mutex::new(None),
+            fetch: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn cached(&self) -> Option<String> {
+        match self.cache.lock().unwrap().as_ref() {
+            Some((token, fetched_at)) if fetched_at.elapsed() < self.ttl => Some(token.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl<P: CredentialsProvider> CredentialsProvider for CachedCredentials<P> {
+    async fn token(&self) -> Result<String, Error> {
+        if let Some(token) = self.cached() {
+            return Ok(token);
+        }
+
+        let _fetch = self.fetch.lock().await;
+        // Fetched by another request while waiting for the lock
+        if let Some(token) = self.cached() {
+            return Ok(token);
+        }
+
+        let token = self.provider.token().await?;
+        *self.cache.lock().unwrap() = Some((token.clone(), Instant::now()));
+        Ok(token)
+    }
+
+    fn invalidate(&self, token: &str) -> bool {
+        let refreshed = {
+            let mut cache = self.cache.lock().unwrap();
+            match cache.as_ref() {
+                Some((cached, _)) if cached == token => {
+                    *cache = None;
+                    true
+                }
+                // Already refreshed by another request
+                Some(_) => true,
+                None => false,
+            }
+        };
+        self.provider.invalidate(token) || refreshed
+    }
+}
+
+/// File read again when its modification time changes.
+struct WatchedFile {
+    path: PathBuf,
+    cache: Mutex<Option<(SystemTime, String)>>,
+}
+
+impl WatchedFile {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            cache: Mutex::new(None),
+        }
+    }
+
+    async fn read(&self, parse: impl Fn(&str) -> Result<String, Error>) -> Result<String, Error> {
+        let modified = tokio::fs::metadata(&self.path).await?.modified()?;
+        if let Some((cached_at, token)) = self.cache.lock().unwrap().as_ref() {
+            if *cached_at == modified {
+                return Ok(token.clone());
+            }
+        }
+
+        let token = parse(&tokio::fs::read_to_string(&self.path).await?)?;
+        *self.cache.lock().unwrap() = Some((modified, token.clone()));
+        Ok(token)
+    }
+
+    /// Drops the cached contents, returning whether there were any.
+    fn invalidate(&self) -> bool {
+        self.cache.lock().unwrap().take().is_some()
+    }
+}
+
+/// Number of times [`Authenticate`] got a different token after the server rejected one, i.e.
+/// when retrying the request may succeed.
+///
+/// Shared by the services and the retry loop of a client, so that refreshes are seen whichever
+/// task sends the request, e.g. behind a `tower::buffer::Buffer` layer.
+#[derive(Clone, Default)]
+pub(crate) struct Refreshes(Arc<AtomicU64>);
+
+impl Refreshes {
+    pub(crate) fn count(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn record(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Service setting the `authorization` header from a [`CredentialsProvider`].
+#[derive(Clone)]
+pub(crate) struct Authenticate {
+    inner: HttpService,
+    credentials: Arc<dyn CredentialsProvider>,
+    refreshes: Refreshes,
+}
+
+impl Authenticate {
+    pub(crate) fn new(
+        inner: HttpService,
+        credentials: Arc<dyn CredentialsProvider>,
+        refreshes: Refreshes,
+    ) -> Self {
+        Self {
+            inner,
+            credentials,
+            refreshes,
+        }
+    }
+}
+
+impl Service<Request<Body>> for Authenticate {
+    type Response = Response<Body>;
+    type Error = Status;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let inner = self.inner.clone();
+        let credentials = self.credentials.clone();
+        let refreshes = self.refreshes.clone();
+
+        Box::pin(async move {
+            let token = credentials
+                .token()
+                .await
+                .map_err(|e| Status::unauthenticated(format!("failed to get credentials: {e}")))?;
+            let value = HeaderValue::try_from(format!("Bearer {token}"))
+                .map_err(|_| Status::unauthenticated("invalid API key"))?;
+            request.headers_mut().insert(header::AUTHORIZATION, value);
+
+            let response = inner.oneshot(request).await?;
+
+            // Rejected requests fail with a trailers-only response, i.e. the status is in the
+            // headers
+            let status = response.headers().get("grpc-status");
+            let rejected = status.is_some_and(|s| s.as_bytes() == b"16");
+            // Retrying only helps with a different token
+            if rejected
+                && credentials.invalidate(&token)
+                && credentials.token().await.is_ok_and(|fresh| fresh != token)
+            {
+                refreshes.record();
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    struct Counting {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CredentialsProvider for Counting {
+        async fn token(&self) -> Result<String, Error> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("token-{n}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_credentials() {
+        let provider = CachedCredentials::new(
+            Counting {
+                calls: AtomicUsize::new(0),
+            },
+            Duration::from_secs(60),
+        );
+
+        assert_eq!(provider.token().await.unwrap(), "token-0");
+        assert_eq!(provider.token().await.unwrap(), "token-0");
+
+        // Invalidating a stale token keeps the cached one, which differs from it
+        assert!(provider.invalidate("token-old"));
+        assert_eq!(provider.token().await.unwrap(), "token-0");
+
+        assert!(provider.invalidate("token-0"));
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+
+        // Invalidations are not lost while a fetch is in progress
+        let fetch = provider.fetch.lock().await;
+        assert!(provider.invalidate("token-1"));
+        drop(fetch);
+        assert_eq!(provider.token().await.unwrap(), "token-2");
+    }
+
+    #[tokio::test]
+    async fn test_static_credentials_not_refreshed() {
+        let provider = StaticCredentials::new("key");
+        assert!(!provider.invalidate("key"));
+    }
+
+    #[tokio::test]
+    async fn test_file_credentials() {
+        let path = std::env::temp_dir().join(format!("topk-credentials-{}", std::process::id()));
+        std::fs::write(&path, "key-1\n").unwrap();
+
+        let provider = FileCredentials::new(&path);
+        assert_eq!(provider.token().await.unwrap(), "key-1");
+
+        // Rotated keys are picked up on invalidation, even if the modification time is unchanged
+        std::fs::write(&path, "key-2\n").unwrap();
+        assert!(provider.invalidate("key-1"));
+        assert_eq!(provider.token().await.unwrap(), "key-2");
+
+        std::fs::write(&path, "").unwrap();
+        provider.invalidate("key-2");
+        assert!(matches!(
+            provider.token().await,
+            Err(Error::Unauthenticated(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(provider.token().await, Err(Error::IoError(_))));
+    }
+
+    #[cfg(feature = "cli-config")]
+    #[tokio::test]
+    async fn test_cli_config_credentials() {
+        let path = std::env::temp_dir().join(format!("topk-config-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+            api_key = "legacy"
+            current_profile = "staging"
+
+            [profiles.staging]
+            api_key = "staging-key"
+
+            [profiles.empty]
+            region = "aws-us-east-1-elastica"
+            "#,
+        )
+        .unwrap();
+
+        // Profile selected with `topk profile use`
+        let provider = CliConfigCredentials {
+            file: WatchedFile::new(path.clone()),
+            profile: None,
+        };
+        assert_eq!(provider.token().await.unwrap(), "staging-key");
+
+        let provider = CliConfigCredentials::from_path(&path).with_profile("default");
+        assert_eq!(provider.token().await.unwrap(), "legacy");
+
+        let provider = CliConfigCredentials::from_path(&path).with_profile("empty");
+        assert!(matches!(
+            provider.token().await,
+            Err(Error::Unauthenticated(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
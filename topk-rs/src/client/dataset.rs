@@ -28,9 +28,9 @@ use crate::proto::v1::ctx::{
 };
 use crate::proto::v1::data::LogicalExpr;
 use crate::proto::v1::data::Value;
-use crate::retry::call_with_retry;
+use crate::retry::call_with_reauth;
 use crate::Error;
-use crate::{create_client, ClientConfig};
+use crate::{create_client, ClientConfig, RequestOptions};
 
 /// Configuration for polling when waiting for a handle to be processed.
 #[derive(Debug, Clone)]
@@ -83,25 +83,36 @@ impl DatasetClient {
         &self,
         fields: Option<Vec<String>>,
         filter: Option<LogicalExpr>,
+        options: Option<RequestOptions>,
     ) -> Result<Streaming<ListEntry>, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(DatasetReadServiceClient, self.read, self.config).await?;
+        let metadata = options.metadata()?;
         let fields = fields.unwrap_or_default();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let filter = filter.clone();
-            let fields = fields.clone();
-            async move {
-                client
-                    .list(ListRequest { fields, filter })
-                    .await
-                    .map_err(|e| match e.code() {
-                        tonic::Code::NotFound => Error::DatasetNotFound,
-                        _ => Error::from(e),
-                    })
-            }
-        })
-        .await?;
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &metadata,
+                        ListRequest {
+                            fields: fields.clone(),
+                            filter: filter.clone(),
+                        },
+                    );
+                    async move {
+                        client.list(request).await.map_err(|e| match e.code() {
+                            tonic::Code::NotFound => Error::DatasetNotFound,
+                            _ => Error::from(e),
+                        })
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner())
     }
@@ -111,8 +122,11 @@ impl DatasetClient {
         doc_id: impl Into<DocId>,
         input: impl Into<InputFile>,
         metadata: impl IntoIterator<Item = (impl Into<String>, impl Into<Value>)>,
+        options: Option<RequestOptions>,
     ) -> Result<String, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(DatasetWriteServiceClient, self.write, self.config).await?;
+        let headers = options.metadata()?;
         let file = input.into();
         let metadata: HashMap<String, Value> = metadata
             .into_iter()
@@ -121,98 +135,135 @@ impl DatasetClient {
 
         let doc_id = doc_id.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let metadata = metadata.clone();
-            let id = doc_id.clone();
-            let file = file.clone();
-
-            // Channel for the upsert stream
-            let (tx, rx) = mpsc::channel(MAX_CHUNKS_IN_FLIGHT);
-
-            // Upload task
-            let upload = tokio::spawn(async move { stream_file(id, &file, metadata, tx).await });
-
-            async move {
-                let res =
-                    client
-                        .upsert(ReceiverStream::new(rx))
-                        .await
-                        .map_err(|e| match e.code() {
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let metadata = metadata.clone();
+                    let id = doc_id.clone();
+                    let file = file.clone();
+
+                    // Channel for the upsert stream
+                    let (tx, rx) = mpsc::channel(MAX_CHUNKS_IN_FLIGHT);
+                    let request = options.request(&headers, ReceiverStream::new(rx));
+
+                    // Upload task
+                    let upload =
+                        tokio::spawn(async move { stream_file(id, &file, metadata, tx).await });
+
+                    async move {
+                        let res = client.upsert(request).await.map_err(|e| match e.code() {
                             tonic::Code::NotFound => Error::DatasetNotFound,
                             _ => Error::from(e),
                         });
 
-                // Abort the upload task if upsert failed early
-                let res = match res {
-                    Ok(res) => res,
-                    Err(e) => {
-                        upload.abort();
-                        return Err(e);
-                    }
-                };
-
-                match upload.await {
-                    Ok(Ok(())) => {}
-                    Ok(Err(e)) => return Err(e),
-                    Err(e) if e.is_cancelled() => {
-                        return Err(Error::Internal("upload task was cancelled".to_string()));
-                    }
-                    Err(e) => {
-                        return Err(Error::Internal(format!("upload task failed: {e}")));
+                        // Abort the upload task if upsert failed early
+                        let res = match res {
+                            Ok(res) => res,
+                            Err(e) => {
+                                upload.abort();
+                                return Err(e);
+                            }
+                        };
+
+                        match upload.await {
+                            Ok(Ok(())) => {}
+                            Ok(Err(e)) => return Err(e),
+                            Err(e) if e.is_cancelled() => {
+                                return Err(Error::Internal(
+                                    "upload task was cancelled".to_string(),
+                                ));
+                            }
+                            Err(e) => {
+                                return Err(Error::Internal(format!("upload task failed: {e}")));
+                            }
+                        }
+
+                        Ok(res)
                     }
-                }
-
-                Ok(res)
-            }
-        })
-        .await?;
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner().handle)
     }
 
-    pub async fn delete(&self, doc_id: impl Into<DocId>) -> Result<String, Error> {
+    pub async fn delete(
+        &self,
+        doc_id: impl Into<DocId>,
+        options: Option<RequestOptions>,
+    ) -> Result<String, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(DatasetWriteServiceClient, self.write, self.config).await?;
+        let metadata = options.metadata()?;
 
         let doc_id = doc_id.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let id = doc_id.clone().into();
-
-            async move {
-                client
-                    .delete(DeleteRequest { id })
-                    .await
-                    .map_err(|e| match e.code() {
-                        tonic::Code::NotFound => Error::DatasetNotFound,
-                        _ => Error::from(e),
-                    })
-            }
-        })
-        .await?;
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &metadata,
+                        DeleteRequest {
+                            id: doc_id.clone().into(),
+                        },
+                    );
+
+                    async move {
+                        client.delete(request).await.map_err(|e| match e.code() {
+                            tonic::Code::NotFound => Error::DatasetNotFound,
+                            _ => Error::from(e),
+                        })
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner().handle)
     }
 
     /// Checks if a handle has been processed (single shot).
-    pub async fn check_handle(&self, handle: &str) -> Result<bool, Error> {
+    pub async fn check_handle(
+        &self,
+        handle: &str,
+        options: Option<RequestOptions>,
+    ) -> Result<bool, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(DatasetWriteServiceClient, self.write, self.config).await?;
-
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let handle = handle.to_string();
-            async move {
-                client
-                    .check_handle(CheckHandleRequest { handle })
-                    .await
-                    .map_err(|e| match e.code() {
-                        tonic::Code::NotFound => Error::DatasetNotFound,
-                        _ => Error::from(e),
-                    })
-            }
-        })
-        .await?;
+        let metadata = options.metadata()?;
+
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &metadata,
+                        CheckHandleRequest {
+                            handle: handle.to_string(),
+                        },
+                    );
+                    async move {
+                        client
+                            .check_handle(request)
+                            .await
+                            .map_err(|e| match e.code() {
+                                tonic::Code::NotFound => Error::DatasetNotFound,
+                                _ => Error::from(e),
+                            })
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner().processed)
     }
@@ -226,47 +277,68 @@ impl DatasetClient {
         &self,
         handle: &str,
         config: Option<WaitConfig>,
+        options: Option<RequestOptions>,
     ) -> Result<(), Error> {
         let config = config.unwrap_or_default();
+        let options = options.unwrap_or_default();
         let start = Instant::now();
-        loop {
-            if start.elapsed() > config.timeout {
-                return Err(Error::RetryTimeout);
-            }
 
-            if self.check_handle(handle).await? {
-                return Ok(());
-            }
+        options
+            .run(async {
+                loop {
+                    if start.elapsed() > config.timeout {
+                        return Err(Error::RetryTimeout);
+                    }
 
-            tokio::time::sleep(config.frequency).await;
-        }
+                    if self.check_handle(handle, Some(options.clone())).await? {
+                        return Ok(());
+                    }
+
+                    tokio::time::sleep(config.frequency).await;
+                }
+            })
+            .await
     }
 
     pub async fn get_metadata(
         &self,
         ids: impl IntoIterator<Item = impl Into<String>>,
         fields: Option<Vec<String>>,
+        options: Option<RequestOptions>,
     ) -> Result<HashMap<String, Document>, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(DatasetReadServiceClient, self.read, self.config).await?;
+        let metadata = options.metadata()?;
         let ids = ids.into_iter().map(|id| id.into()).collect::<Vec<_>>();
         let fields = fields.unwrap_or_default();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let ids = ids.clone();
-            let fields = fields.clone();
-
-            async move {
-                client
-                    .get_metadata(GetMetadataRequest { ids, fields })
-                    .await
-                    .map_err(|e| match e.code() {
-                        tonic::Code::NotFound => Error::DatasetNotFound,
-                        _ => Error::from(e),
-                    })
-            }
-        })
-        .await?;
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &metadata,
+                        GetMetadataRequest {
+                            ids: ids.clone(),
+                            fields: fields.clone(),
+                        },
+                    );
+
+                    async move {
+                        client
+                            .get_metadata(request)
+                            .await
+                            .map_err(|e| match e.code() {
+                                tonic::Code::NotFound => Error::DatasetNotFound,
+                                _ => Error::from(e),
+                            })
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner().docs)
     }
@@ -275,26 +347,40 @@ impl DatasetClient {
     pub async fn get_content(
         &self,
         content_id: impl Into<String>,
+        options: Option<RequestOptions>,
     ) -> Result<GetContentResponse, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(DatasetReadServiceClient, self.read, self.config).await?;
+        let metadata = options.metadata()?;
         let content_id = content_id.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let content_id = content_id.clone();
-
-            async move {
-                client
-                    .get_content(GetContentRequest { content_id })
-                    .await
-                    .map_err(|e| match e.code() {
-                        // Dataset missing or content_id missing.
-                        tonic::Code::NotFound => Error::NotFound,
-                        _ => Error::from(e),
-                    })
-            }
-        })
-        .await?;
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &metadata,
+                        GetContentRequest {
+                            content_id: content_id.clone(),
+                        },
+                    );
+
+                    async move {
+                        client
+                            .get_content(request)
+                            .await
+                            .map_err(|e| match e.code() {
+                                // Dataset missing or content_id missing.
+                                tonic::Code::NotFound => Error::NotFound,
+                                _ => Error::from(e),
+                            })
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner())
     }
@@ -303,8 +389,11 @@ impl DatasetClient {
         &self,
         doc_id: impl Into<DocId>,
         metadata: impl IntoIterator<Item = (impl Into<String>, impl Into<Value>)>,
+        options: Option<RequestOptions>,
     ) -> Result<String, Error> {
+        let options = options.unwrap_or_default();
         let client = create_client!(DatasetWriteServiceClient, self.write, self.config).await?;
+        let headers = options.metadata()?;
 
         let doc_id = doc_id.into();
         let metadata: HashMap<String, Value> = metadata
@@ -312,22 +401,33 @@ impl DatasetClient {
             .map(|(k, v)| (k.into(), v.into()))
             .collect();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let id = doc_id.clone().into();
-            let metadata = metadata.clone();
-
-            async move {
-                client
-                    .update_metadata(UpdateMetadataRequest { id, metadata })
-                    .await
-                    .map_err(|e| match e.code() {
-                        tonic::Code::NotFound => Error::DatasetNotFound,
-                        _ => Error::from(e),
-                    })
-            }
-        })
-        .await?;
+        let retry_config = options.retry_config(self.config.retry_config());
+        let response = options
+            .run(call_with_reauth(
+                retry_config,
+                self.config.refreshes(),
+                || {
+                    let mut client = client.clone();
+                    let request = options.request(
+                        &headers,
+                        UpdateMetadataRequest {
+                            id: doc_id.clone().into(),
+                            metadata: metadata.clone(),
+                        },
+                    );
+
+                    async move {
+                        client
+                            .update_metadata(request)
+                            .await
+                            .map_err(|e| match e.code() {
+                                tonic::Code::NotFound => Error::DatasetNotFound,
+                                _ => Error::from(e),
+                            })
+                    }
+                },
+            ))
+            .await?;
 
         Ok(response.into_inner().handle)
     }
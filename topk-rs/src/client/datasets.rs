@@ -5,7 +5,7 @@ use tokio::sync::OnceCell;
 use tonic::transport::Channel;
 
 use super::config::ClientConfig;
-use super::retry::call_with_retry;
+use super::retry::call_with_reauth;
 use crate::create_client;
 use crate::error::Error;
 use crate::proto::v1::control::dataset_service_client::DatasetServiceClient;
@@ -29,17 +29,18 @@ impl DatasetsClient {
     pub async fn list(&self) -> Result<Vec<Dataset>, Error> {
         let client = create_client!(DatasetServiceClient, self.channel, self.config).await?;
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
+        let response =
+            call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
+                let mut client = client.clone();
 
-            async move {
-                client
-                    .list_datasets(ListDatasetsRequest {})
-                    .map_err(Error::from)
-                    .await
-            }
-        })
-        .await?;
+                async move {
+                    client
+                        .list_datasets(ListDatasetsRequest {})
+                        .map_err(Error::from)
+                        .await
+                }
+            })
+            .await?;
 
         Ok(response.into_inner().datasets)
     }
@@ -48,23 +49,24 @@ impl DatasetsClient {
         let client = create_client!(DatasetServiceClient, self.channel, self.config).await?;
         let name = name.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let name = name.clone();
-
-            async move {
-                client
-                    .get_dataset(GetDatasetRequest { name })
-                    .map_err(|e| match e.code() {
-                        // Dataset not found
-                        tonic::Code::NotFound => Error::DatasetNotFound,
-                        // Delegate other errors
-                        _ => Error::from(e),
-                    })
-                    .await
-            }
-        })
-        .await?;
+        let response =
+            call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
+                let mut client = client.clone();
+                let name = name.clone();
+
+                async move {
+                    client
+                        .get_dataset(GetDatasetRequest { name })
+                        .map_err(|e| match e.code() {
+                            // Dataset not found
+                            tonic::Code::NotFound => Error::DatasetNotFound,
+                            // Delegate other errors
+                            _ => Error::from(e),
+                        })
+                        .await
+                }
+            })
+            .await?;
 
         let dataset = response.into_inner().dataset.ok_or(Error::InvalidProto)?;
         Ok(dataset)
@@ -79,29 +81,30 @@ impl DatasetsClient {
         let client = create_client!(DatasetServiceClient, self.channel, self.config).await?;
         let name = name.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let name = name.clone();
-            let region = region.clone();
-            let description = description.clone();
-
-            async move {
-                client
-                    .create_dataset(CreateDatasetRequest {
-                        name,
-                        region,
-                        description,
-                    })
-                    .await
-                    .map_err(|e| match e.code() {
-                        // Dataset already exists
-                        tonic::Code::AlreadyExists => Error::DatasetAlreadyExists,
-                        // Delegate other errors
-                        _ => e.into(),
-                    })
-            }
-        })
-        .await?;
+        let response =
+            call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
+                let mut client = client.clone();
+                let name = name.clone();
+                let region = region.clone();
+                let description = description.clone();
+
+                async move {
+                    client
+                        .create_dataset(CreateDatasetRequest {
+                            name,
+                            region,
+                            description,
+                        })
+                        .await
+                        .map_err(|e| match e.code() {
+                            // Dataset already exists
+                            tonic::Code::AlreadyExists => Error::DatasetAlreadyExists,
+                            // Delegate other errors
+                            _ => e.into(),
+                        })
+                }
+            })
+            .await?;
 
         let dataset = response.into_inner().dataset.ok_or(Error::InvalidProto)?;
         Ok(dataset)
@@ -115,24 +118,25 @@ impl DatasetsClient {
         let client = create_client!(DatasetServiceClient, self.channel, self.config).await?;
         let name = name.into();
 
-        let response = call_with_retry(&self.config.retry_config(), || {
-            let mut client = client.clone();
-            let name = name.clone();
-            let description = description.clone();
-
-            async move {
-                client
-                    .update_dataset(UpdateDatasetRequest { name, description })
-                    .await
-                    .map_err(|e| match e.code() {
-                        // Dataset not found
-                        tonic::Code::NotFound => Error::DatasetNotFound,
-                        // Delegate other errors
-                        _ => e.into(),
-                    })
-            }
-        })
-        .await?;
+        let response =
+            call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
+                let mut client = client.clone();
+                let name = name.clone();
+                let description = description.clone();
+
+                async move {
+                    client
+                        .update_dataset(UpdateDatasetRequest { name, description })
+                        .await
+                        .map_err(|e| match e.code() {
+                            // Dataset not found
+                            tonic::Code::NotFound => Error::DatasetNotFound,
+                            // Delegate other errors
+                            _ => e.into(),
+                        })
+                }
+            })
+            .await?;
 
         let dataset = response.into_inner().dataset.ok_or(Error::InvalidProto)?;
         Ok(dataset)
@@ -142,7 +146,7 @@ impl DatasetsClient {
         let client = create_client!(DatasetServiceClient, self.channel, self.config).await?;
         let name = name.into();
 
-        call_with_retry(&self.config.retry_config(), || {
+        call_with_reauth(self.config.retry_config(), self.config.refreshes(), || {
             let mut client = client.clone();
             let name = name.clone();
 
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use tonic::body::Body;
+use tonic::codegen::http::{Request, Response};
+use tonic::codegen::{Service, StdError};
+use tonic::transport::Channel;
+use tonic::Status;
+use tower::util::BoxCloneSyncService;
+use tower::{Layer, ServiceExt};
+
+/// HTTP service that requests are sent through, i.e. the [`Channel`] wrapped by the layers of
+/// [`ClientConfig::with_layer`](super::ClientConfig::with_layer).
+///
+/// Errors are converted to [`Status`] at every layer, like the generated clients do.
+pub type HttpService = BoxCloneSyncService<Request<Body>, Response<Body>, Status>;
+
+/// Wraps `channel` as an [`HttpService`].
+pub(crate) fn channel_service(channel: Channel) -> HttpService {
+    HttpService::new(channel.map_err(|e| Status::from_error(e.into())))
+}
+
+/// Type-erased layers, applied to the channel of every request.
+#[derive(Clone, Default)]
+pub(crate) struct Layers {
+    layers: Vec<Arc<dyn Fn(HttpService) -> HttpService + Send + Sync>>,
+}
+
+impl Layers {
+    pub(crate) fn push<L>(&mut self, layer: L)
+    where
+        L: Layer<HttpService> + Send + Sync + 'static,
+        L::Service:
+            Service<Request<Body>, Response = Response<Body>> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request<Body>>>::Error: Into<StdError>,
+        <L::Service as Service<Request<Body>>>::Future: Send + 'static,
+    {
+        self.layers.push(Arc::new(move |service| {
+            HttpService::new(
+                layer
+                    .layer(service)
+                    .map_err(|e| Status::from_error(e.into())),
+            )
+        }));
+    }
+
+    /// Wraps `service` with the layers. Like with `tower::ServiceBuilder`, the first layer is the
+    /// outermost one, i.e. it sees the request first.
+    pub(crate) fn apply(&self, service: HttpService) -> HttpService {
+        self.layers
+            .iter()
+            .rev()
+            .fold(service, |service, layer| layer(service))
+    }
+}
+
+impl std::fmt::Debug for Layers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Layers")
+            .field("len", &self.layers.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tower::layer::layer_fn;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_layers_order() {
+        let calls = Arc::new(Mutex::new(vec![]));
+
+        let mut layers = Layers::default();
+        for name in ["outer", "inner"] {
+            let calls = calls.clone();
+            layers.push(layer_fn(move |service: HttpService| {
+                let calls = calls.clone();
+                tower::service_fn(move |request: Request<Body>| {
+                    calls.lock().unwrap().push(name);
+                    service.clone().oneshot(request)
+                })
+            }));
+        }
+
+        // Nothing is listening on the port, so the request fails at the channel
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let result = layers
+            .apply(channel_service(channel))
+            .oneshot(Request::new(Body::empty()));
+        assert!(result.await.is_err());
+
+        assert_eq!(*calls.lock().unwrap(), vec!["outer", "inner"]);
+    }
+}
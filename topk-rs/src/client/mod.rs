@@ -25,6 +25,16 @@ pub use config::ClientConfig;
 
 pub mod retry;
 
+pub mod transport;
+
+pub mod credentials;
+
+mod middleware;
+pub use middleware::HttpService;
+
+mod options;
+pub use options::RequestOptions;
+
 mod interceptor;
 pub use interceptor::AppendHeadersInterceptor;
 
@@ -35,9 +45,11 @@ pub use trace::TracingInterceptor;
 pub const MAX_DECODING_MESSAGE_SIZE: usize = 512 * 1024 * 1024; // 512MB
 pub const MAX_ENCODING_MESSAGE_SIZE: usize = 512 * 1024 * 1024; // 512MB
 
-// request config
+// (client) default transport config
 pub const TIMEOUT: u64 = 600_000; // 10 minutes
 pub const MAX_HEADER_LIST_SIZE: u32 = 1024 * 64; // 64KB
+pub const INITIAL_STREAM_WINDOW_SIZE: u32 = 8 * 1024 * 1024; // 8MB
+pub const INITIAL_CONNECTION_WINDOW_SIZE: u32 = 32 * 1024 * 1024; // 32MB
 
 // (client) retry config
 pub const RETRY_TIMEOUT: u64 = 180_000; // 3 minutes
@@ -111,36 +123,11 @@ macro_rules! create_client {
             use crate::client::AppendHeadersInterceptor;
             use crate::client::MAX_DECODING_MESSAGE_SIZE;
             use crate::client::MAX_ENCODING_MESSAGE_SIZE;
-            use crate::client::MAX_HEADER_LIST_SIZE;
-            use crate::client::TIMEOUT;
             use crate::Error;
 
             // Lazily connect the channel on first use
             let channel = $channel
-                .get_or_try_init(|| async {
-                    Ok::<_, Error>(
-                        $config
-                            .endpoint()?
-                            .tls_config(
-                                tonic::transport::ClientTlsConfig::new().with_native_roots(),
-                            )?
-                            // Do not close idle connections so they can be reused
-                            .keep_alive_while_idle(true)
-                            // Set max header list size to 64KB
-                            .http2_max_header_list_size(MAX_HEADER_LIST_SIZE)
-                            // Request timeout
-                            .timeout(std::time::Duration::from_millis(TIMEOUT))
-                            // Disable Nagle's algorithm
-                            .tcp_nodelay(true)
-                            // Disable adaptive window
-                            .http2_adaptive_window(false)
-                            .initial_stream_window_size(8 * 1024 * 1024) // 8MB
-                            .initial_connection_window_size(32 * 1024 * 1024) // 32MB
-                            // Connect
-                            .connect()
-                            .await?,
-                    )
-                })
+                .get_or_try_init(|| async { $config.connect().await })
                 .await?;
 
             // Build interceptor
@@ -149,9 +136,13 @@ macro_rules! create_client {
             );
 
             // Build client
-            let client = $client::with_interceptor(channel.clone(), interceptor)
+            let client = $client::with_interceptor($config.service(channel.clone()), interceptor)
                 .max_decoding_message_size(MAX_DECODING_MESSAGE_SIZE)
                 .max_encoding_message_size(MAX_ENCODING_MESSAGE_SIZE);
+            let client = match $config.compression() {
+                Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+                None => client,
+            };
 
             Result::<_, Error>::Ok(client)
         }
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::str::FromStr;
+
+use tokio::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue, MetadataMap};
+
+use crate::proto::v1::data::ConsistencyLevel;
+use crate::Error;
+
+use super::retry::RetryConfig;
+
+/// Options of a single request, overriding the client config.
+#[derive(Clone, Debug, Default)]
+pub struct RequestOptions {
+    /// Wait until the write with this LSN is visible to queries
+    pub lsn: Option<String>,
+
+    /// Consistency level of queries
+    pub consistency: Option<ConsistencyLevel>,
+
+    /// Timeout of each attempt, in addition to the timeout of the transport config
+    ///
+    /// Streams returned by [`CollectionClient::query_stream`](super::CollectionClient::query_stream)
+    /// end with [`Error::DeadlineExceeded`] once it elapses. Other streams, e.g. of
+    /// [`DatasetClient::list`](super::DatasetClient::list), are only covered while they are
+    /// opened.
+    pub timeout: Option<Duration>,
+
+    /// Retry config, overriding the one of the client config
+    pub retry_config: Option<RetryConfig>,
+
+    /// Headers added to the request
+    pub headers: HashMap<String, String>,
+
+    /// Token to cancel the request with, failing it with [`Error::Cancelled`]
+    ///
+    /// Streams returned by [`CollectionClient::query_stream`](super::CollectionClient::query_stream)
+    /// end with [`Error::Cancelled`] once it is cancelled. Other streams, e.g. of
+    /// [`DatasetClient::list`](super::DatasetClient::list), are only covered while they are
+    /// opened.
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_lsn(mut self, lsn: impl Into<String>) -> Self {
+        self.lsn = Some(lsn.into());
+        self
+    }
+
+    pub fn with_consistency(mut self, consistency: ConsistencyLevel) -> Self {
+        self.consistency = Some(consistency);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = Some(retry_config);
+        self
+    }
+
+    pub fn with_headers(
+        mut self,
+        headers: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        self.headers.extend(
+            headers
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        self
+    }
+
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Returns the retry config of the request, falling back to `default`.
+    pub(crate) fn retry_config<'a>(&'a self, default: &'a RetryConfig) -> &'a RetryConfig {
+        self.retry_config.as_ref().unwrap_or(default)
+    }
+
+    /// Returns the headers of the request as gRPC metadata.
+    pub(crate) fn metadata(&self) -> Result<MetadataMap, Error> {
+        let mut metadata = MetadataMap::new();
+        for (key, value) in &self.headers {
+            let key = AsciiMetadataKey::from_str(key)
+                .map_err(|e| Error::Input(anyhow::anyhow!("invalid header name: {e:?}")))?;
+            let value = AsciiMetadataValue::from_str(value)
+                .map_err(|e| Error::Input(anyhow::anyhow!("invalid header value: {e:?}")))?;
+            metadata.insert(key, value);
+        }
+        Ok(metadata)
+    }
+
+    /// Builds a request for a single attempt, with `metadata` from [`RequestOptions::metadata`].
+    pub(crate) fn request<T>(&self, metadata: &MetadataMap, message: T) -> tonic::Request<T> {
+        let mut request =
+            tonic::Request::from_parts(metadata.clone(), tonic::Extensions::default(), message);
+        if let Some(timeout) = self.timeout {
+            request.set_timeout(timeout);
+        }
+        request
+    }
+
+    /// Runs `future` until it completes or the request is cancelled.
+    pub(crate) async fn run<T>(
+        &self,
+        future: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        match &self.cancellation_token {
+            Some(token) => tokio::select! {
+                _ = token.cancelled() => Err(Error::Cancelled),
+                result = future => result,
+            },
+            None => future.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let token = CancellationToken::new();
+        let options = RequestOptions::new().with_cancellation_token(token.clone());
+
+        token.cancel();
+        let result = options
+            .run(async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(Error::Cancelled)));
+
+        let result = RequestOptions::new().run(async { Ok(1) }).await;
+        assert!(matches!(result, Ok(1)));
+    }
+
+    #[test]
+    fn test_request() {
+        let options = RequestOptions::new()
+            .with_timeout(Duration::from_secs(5))
+            .with_headers([("x-request-source", "batch")]);
+
+        let request = options.request(&options.metadata().unwrap(), ());
+        assert_eq!(request.metadata().get("x-request-source").unwrap(), "batch");
+        assert_eq!(request.metadata().get("grpc-timeout").unwrap(), "5000000u");
+
+        let options = RequestOptions::new().with_headers([("invalid header", "value")]);
+        assert!(matches!(options.metadata(), Err(Error::Input(_))));
+    }
+}
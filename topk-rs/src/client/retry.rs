@@ -2,6 +2,8 @@ use rand::prelude::*;
 use std::future::Future;
 use tokio::time::{Duration, Instant};
 
+use super::credentials::Refreshes;
+
 #[derive(Clone, Debug)]
 pub struct RetryConfig {
     /// Maximum number of retries
@@ -50,6 +52,30 @@ pub async fn call_with_retry<F, T>(
     retry_config: &RetryConfig,
     f: impl Fn() -> F,
 ) -> Result<T, crate::Error>
+where
+    F: Future<Output = Result<T, crate::Error>>,
+{
+    retry(retry_config, None, f).await
+}
+
+/// Like [`call_with_retry`], also retrying once a request rejected as `Unauthenticated` if the
+/// client's credentials were refreshed while it ran.
+pub(crate) async fn call_with_reauth<F, T>(
+    retry_config: &RetryConfig,
+    refreshes: &Refreshes,
+    f: impl Fn() -> F,
+) -> Result<T, crate::Error>
+where
+    F: Future<Output = Result<T, crate::Error>>,
+{
+    retry(retry_config, Some(refreshes), f).await
+}
+
+async fn retry<F, T>(
+    retry_config: &RetryConfig,
+    refreshes: Option<&Refreshes>,
+    f: impl Fn() -> F,
+) -> Result<T, crate::Error>
 where
     F: Future<Output = Result<T, crate::Error>>,
 {
@@ -60,10 +86,25 @@ where
     // Retry chain
     let retry_chain = async {
         let mut i = 0;
+        let mut reauthenticated = false;
         loop {
+            let refreshes_before = refreshes.map(Refreshes::count);
             match f().await {
                 Ok(result) => return Ok(result),
                 Err(e) => {
+                    // Retry once if the server rejected the token and the credentials provider
+                    // now returns a different one (e.g. after key rotation).
+                    if matches!(e, crate::Error::Unauthenticated(_))
+                        && refreshes.map(Refreshes::count) != refreshes_before
+                        && !reauthenticated
+                        && retry_config.max_retries > 0
+                        && i < retry_config.max_retries - 1
+                    {
+                        reauthenticated = true;
+                        i += 1;
+                        continue;
+                    }
+
                     // If error is not retryable, exit early.
                     if !e.is_retryable() {
                         return Err(e);
@@ -117,6 +158,7 @@ async fn wait_for_backoff(config: &BackoffConfig, next_backoff: Duration) -> Dur
 
 #[cfg(test)]
 mod tests {
+    use crate::client::credentials::Refreshes;
     use crate::Error;
 
     use super::*;
@@ -126,13 +168,21 @@ mod tests {
     async fn simulate(
         retry_config: &RetryConfig,
         f: impl Fn(usize) -> Result<(), crate::Error>,
+    ) -> (usize, Result<(), crate::Error>, Duration) {
+        simulate_with_refreshes(retry_config, &Refreshes::default(), f).await
+    }
+
+    async fn simulate_with_refreshes(
+        retry_config: &RetryConfig,
+        refreshes: &Refreshes,
+        f: impl Fn(usize) -> Result<(), crate::Error>,
     ) -> (usize, Result<(), crate::Error>, Duration) {
         let f = Arc::new(f);
         let counter = Arc::new(AtomicUsize::new(0));
         let count = counter.clone();
         let start_time = Instant::now();
 
-        let result = call_with_retry(retry_config, {
+        let result = call_with_reauth(retry_config, refreshes, {
             let f = f.clone();
 
             move || {
@@ -236,6 +286,74 @@ mod tests {
         assert!(start_time.elapsed() >= Duration::from_millis(100));
     }
 
+    #[tokio::test]
+    async fn unauthenticated_retried_once_with_refreshed_credentials() {
+        let retry_config = RetryConfig::default();
+        let refreshes = Refreshes::default();
+
+        let (attempts, result, _) =
+            simulate_with_refreshes(&retry_config, &refreshes, |count| match count {
+                0 => {
+                    refreshes.record();
+                    Err(Error::Unauthenticated("expired".to_string()))
+                }
+                _ => Ok(()),
+            })
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(attempts, 2);
+
+        // Rejected again with the refreshed token
+        let (attempts, result, _) = simulate_with_refreshes(&retry_config, &refreshes, |_| {
+            refreshes.record();
+            Err(Error::Unauthenticated("invalid".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(crate::Error::Unauthenticated(_))));
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_not_retried_without_refreshed_credentials() {
+        // E.g. static credentials, or a credentials provider failing to return a token
+        let (attempts, result, _) = simulate(&RetryConfig::default(), |_| {
+            Err(Error::Unauthenticated("invalid".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(crate::Error::Unauthenticated(_))));
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_not_retried_without_refreshes() {
+        // E.g. `call_with_retry` called directly, without a client's refreshes
+        let refreshes = Refreshes::default();
+        let result = call_with_retry(&RetryConfig::default(), || async {
+            refreshes.record();
+            Err::<(), _>(Error::Unauthenticated("expired".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(crate::Error::Unauthenticated(_))));
+        assert_eq!(refreshes.count(), 1);
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_zero_max_retries() {
+        let retry_config = RetryConfig {
+            max_retries: 0,
+            ..Default::default()
+        };
+        let refreshes = Refreshes::default();
+
+        let (attempts, result, _) = simulate_with_refreshes(&retry_config, &refreshes, |_| {
+            refreshes.record();
+            Err(Error::Unauthenticated("expired".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(crate::Error::Unauthenticated(_))));
+        assert_eq!(attempts, 1);
+    }
+
     #[tokio::test]
     async fn success_first_attempt() {
         let retry_config = RetryConfig::default();
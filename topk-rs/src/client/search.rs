@@ -7,7 +7,7 @@ use crate::proto::v1::ctx::SearchRequest;
 use crate::proto::v1::ctx::SearchResult;
 use crate::proto::v1::ctx::Source;
 use crate::proto::v1::data::LogicalExpr;
-use crate::retry::call_with_retry;
+use crate::retry::call_with_reauth;
 use crate::Error;
 
 impl super::Client {
@@ -36,11 +36,15 @@ impl super::Client {
             select_fields: select_fields.into_iter().map(|s| s.into()).collect(),
         };
 
-        let response = call_with_retry(&self.config().retry_config(), || {
-            let request = request.clone();
-            let mut client = client.clone();
-            async move { client.search(request).map_err(Error::from).await }
-        })
+        let response = call_with_reauth(
+            self.config().retry_config(),
+            self.config().refreshes(),
+            || {
+                let request = request.clone();
+                let mut client = client.clone();
+                async move { client.search(request).map_err(Error::from).await }
+            },
+        )
         .await?;
 
         Ok(response.into_inner())
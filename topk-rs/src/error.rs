@@ -14,6 +14,9 @@ pub enum Error {
     #[error("retry timeout")]
     RetryTimeout,
 
+    #[error("request cancelled")]
+    Cancelled,
+
     #[error("collection already exists")]
     CollectionAlreadyExists,
 
@@ -97,6 +100,7 @@ impl Error {
             Error::TransportError(_) => true,
             // Not retryable
             Error::RetryTimeout => false,
+            Error::Cancelled => false,
             Error::CollectionAlreadyExists => false,
             Error::CollectionNotFound => false,
             Error::PartitionNotFound => false,
@@ -25,6 +25,96 @@ macro_rules! array_of {
     };
 }
 
+/// Declares a (de)serializable wrapper of a proto type, converted through its JSON
+/// representation `$repr`.
+macro_rules! json_wrapper {
+    ($(#[$meta:meta])* $name:ident($proto:ty) as $repr:ty) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct $name(pub $proto);
+
+        impl $name {
+            pub fn into_inner(self) -> $proto {
+                self.0
+            }
+        }
+
+        impl From<$proto> for $name {
+            fn from(value: $proto) -> Self {
+                Self(value)
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = $proto;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                <$repr>::try_from(&self.0)
+                    .map_err(serde::ser::Error::custom)?
+                    .serialize(serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <$proto>::try_from(<$repr>::deserialize(deserializer)?)
+                    .map(Self)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+mod query;
+mod schema;
+
+pub use query::{AggregateExpr, FunctionExpr, LogicalExpr, Query, Stage, TextExpr};
+pub use schema::{FieldSpec, Schema};
+
+/// Version of the JSON format of [`Query`] and [`Schema`], written as their `version`.
+/// Documents written with a newer version are rejected.
+pub const FORMAT_VERSION: u32 = 1;
+
+fn check_version(version: u32) -> Result<(), crate::Error> {
+    match version {
+        1..=FORMAT_VERSION => Ok(()),
+        _ => Err(crate::Error::InvalidArgument(format!(
+            "unsupported format version {version}, expected at most {FORMAT_VERSION}"
+        ))),
+    }
+}
+
+/// Name of a proto enum value in the JSON format, e.g. `VECTOR_DISTANCE_METRIC_DOT_PRODUCT`
+/// is `dot_product`.
+fn enum_name(name: &str, prefix: &str) -> String {
+    name.strip_prefix(prefix)
+        .unwrap_or(name)
+        .to_ascii_lowercase()
+}
+
+fn parse_enum<T>(
+    name: &str,
+    prefix: &str,
+    from_str_name: fn(&str) -> Option<T>,
+) -> Result<T, crate::Error> {
+    from_str_name(&format!("{prefix}{}", name.to_ascii_uppercase()))
+        .ok_or_else(|| crate::Error::InvalidArgument(format!("unknown value `{name}`")))
+}
+
+fn unknown_enum(e: prost::UnknownEnumValue) -> crate::Error {
+    crate::Error::InvalidArgument(e.to_string())
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Value(pub TopkValue);
 
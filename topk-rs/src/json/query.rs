@@ -0,0 +1,1401 @@
+//! JSON format of queries and expressions.
+//!
+//! Operators are named and literals keep their exact type, so that queries round-trip
+//! without changes:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "stages": [
+//!     {"filter": {"binary": {"op": "gte", "left": {"field": "year"}, "right": {"literal": 2000}}}},
+//!     {"select": {"distance": {"vector_distance": {"field": "embedding", "query": {"list": {"f32": [0.1, 0.2]}}}}}},
+//!     {"sort": [{"expr": {"field": "distance"}, "order": "asc"}]},
+//!     {"limit": 10}
+//!   ]
+//! }
+//! ```
+//!
+//! Literals are plain JSON for `null`, booleans, strings, `i64` (integers) and `f64` (numbers
+//! with a fraction or exponent, e.g. `1.0`). Other values are tagged with their type:
+//! `{"f32": 0.5}`, `{"u32": 1}`, `{"u64": 1}`, `{"i32": 1}`, `{"binary": [1, 2]}`,
+//! `{"list": {"f32": [0.1, 0.2]}}`, `{"sparse_vector": {"indices": [0, 4], "f32": [0.1, 0.2]}}`,
+//! `{"matrix": {"num_cols": 2, "f16": [0.1, 0.2, 0.3, 0.4]}}` and `{"struct": {"a": 1}}`.
+//! Element types are `u8`, `i8`, `u32`, `u64`, `i32`, `i64`, `f8`, `f16`, `f32`, `f64` and
+//! `string`.
+
+use std::collections::BTreeMap;
+
+use serde::de::Error as DeError;
+use serde::ser::Error as SerError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{check_version, enum_name, is_false, parse_enum, unknown_enum};
+use crate::error::Error;
+use crate::proto::v1::data::{
+    aggregate_expr, function_expr, list, logical_expr, matrix, sparse_vector, stage, text_expr,
+    value, SparseVector, Value,
+};
+use crate::proto::v1::data::{
+    AggregateExpr as ProtoAggregateExpr, FunctionExpr as ProtoFunctionExpr,
+    LogicalExpr as ProtoLogicalExpr, Query as ProtoQuery, Stage as ProtoStage,
+    TextExpr as ProtoTextExpr,
+};
+
+json_wrapper!(
+    /// Query in the JSON format, with a `version`.
+    Query(ProtoQuery) as QueryRepr
+);
+
+json_wrapper!(
+    /// Query stage in the JSON format.
+    Stage(ProtoStage) as StageRepr
+);
+
+json_wrapper!(
+    /// Logical expression in the JSON format.
+    LogicalExpr(ProtoLogicalExpr) as Expr
+);
+
+json_wrapper!(
+    /// Text expression in the JSON format.
+    TextExpr(ProtoTextExpr) as Text
+);
+
+json_wrapper!(
+    /// Function expression in the JSON format.
+    FunctionExpr(ProtoFunctionExpr) as Function
+);
+
+json_wrapper!(
+    /// Aggregate expression in the JSON format.
+    AggregateExpr(ProtoAggregateExpr) as Aggregate
+);
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidArgument(message.into())
+}
+
+fn required<'a, T>(value: Option<&'a T>, what: &str) -> Result<&'a T, Error> {
+    value.ok_or_else(|| invalid(format!("{what} is missing")))
+}
+
+// Query
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QueryRepr {
+    version: u32,
+    stages: Vec<StageRepr>,
+}
+
+impl TryFrom<&ProtoQuery> for QueryRepr {
+    type Error = Error;
+
+    fn try_from(query: &ProtoQuery) -> Result<Self, Error> {
+        Ok(Self {
+            version: super::FORMAT_VERSION,
+            stages: query
+                .stages
+                .iter()
+                .map(StageRepr::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<QueryRepr> for ProtoQuery {
+    type Error = Error;
+
+    fn try_from(query: QueryRepr) -> Result<Self, Error> {
+        check_version(query.version)?;
+        Ok(ProtoQuery::new(
+            query
+                .stages
+                .into_iter()
+                .map(ProtoStage::try_from)
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
+
+// Stages
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum StageRepr {
+    Select(BTreeMap<String, Select>),
+    Filter(Filter),
+    TopK {
+        expr: Expr,
+        k: u64,
+        #[serde(default, skip_serializing_if = "is_false")]
+        asc: bool,
+    },
+    Count {},
+    Rerank {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        query: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        fields: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        topk_multiple: Option<u32>,
+    },
+    Limit(u64),
+    Offset(u64),
+    Sort(Vec<SortExpr>),
+    Fetch(Vec<String>),
+    GroupBy {
+        keys: BTreeMap<String, Expr>,
+        aggs: BTreeMap<String, Aggregate>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SortExpr {
+    expr: Expr,
+    order: String,
+}
+
+const SORT_ORDER: &str = "SORT_ORDER_";
+
+impl TryFrom<&ProtoStage> for StageRepr {
+    type Error = Error;
+
+    #[allow(deprecated)]
+    fn try_from(stage: &ProtoStage) -> Result<Self, Error> {
+        Ok(match required(stage.stage.as_ref(), "stage")? {
+            stage::Stage::Select(select) => Self::Select(
+                select
+                    .exprs
+                    .iter()
+                    .map(|(name, expr)| Ok((name.clone(), Select::try_from(expr)?)))
+                    .collect::<Result<_, Error>>()?,
+            ),
+            stage::Stage::Filter(filter) => Self::Filter(Filter::try_from(required(
+                filter.expr.as_ref(),
+                "filter expression",
+            )?)?),
+            stage::Stage::TopK(top_k) => Self::TopK {
+                expr: Expr::try_from(required(top_k.expr.as_ref(), "top_k expression")?)?,
+                k: top_k.k,
+                asc: top_k.asc,
+            },
+            stage::Stage::Count(_) => Self::Count {},
+            stage::Stage::Rerank(rerank) => Self::Rerank {
+                model: rerank.model.clone(),
+                query: rerank.query.clone(),
+                fields: rerank.fields.clone(),
+                topk_multiple: rerank.topk_multiple,
+            },
+            stage::Stage::Limit(limit) => Self::Limit(limit.k),
+            stage::Stage::Offset(offset) => Self::Offset(offset.offset),
+            stage::Stage::Sort(sort) => {
+                if sort.expr.is_some() {
+                    return Err(invalid(
+                        "sort stages with a deprecated `expr` are not supported",
+                    ));
+                }
+                Self::Sort(
+                    sort.exprs
+                        .iter()
+                        .map(|sort_expr| {
+                            Ok(SortExpr {
+                                expr: Expr::try_from(required(
+                                    sort_expr.expr.as_ref(),
+                                    "sort expression",
+                                )?)?,
+                                order: enum_name(
+                                    stage::sort_stage::SortOrder::try_from(sort_expr.order)
+                                        .map_err(unknown_enum)?
+                                        .as_str_name(),
+                                    SORT_ORDER,
+                                ),
+                            })
+                        })
+                        .collect::<Result<_, Error>>()?,
+                )
+            }
+            stage::Stage::Fetch(fetch) => Self::Fetch(fetch.fields.clone()),
+            stage::Stage::GroupBy(group_by) => Self::GroupBy {
+                keys: group_by
+                    .keys
+                    .iter()
+                    .map(|(name, expr)| Ok((name.clone(), Expr::try_from(expr)?)))
+                    .collect::<Result<_, Error>>()?,
+                aggs: group_by
+                    .aggs
+                    .iter()
+                    .map(|(name, agg)| Ok((name.clone(), Aggregate::try_from(agg)?)))
+                    .collect::<Result<_, Error>>()?,
+            },
+        })
+    }
+}
+
+impl TryFrom<StageRepr> for ProtoStage {
+    type Error = Error;
+
+    fn try_from(stage: StageRepr) -> Result<Self, Error> {
+        Ok(match stage {
+            StageRepr::Select(exprs) => ProtoStage::select(
+                exprs
+                    .into_iter()
+                    .map(|(name, expr)| Ok((name, expr.try_into()?)))
+                    .collect::<Result<Vec<(String, stage::select_stage::SelectExpr)>, Error>>()?,
+            ),
+            StageRepr::Filter(expr) => {
+                ProtoStage::filter(stage::filter_stage::FilterExpr::try_from(expr)?)
+            }
+            StageRepr::TopK { expr, k, asc } => ProtoStage::topk(expr.try_into()?, k, asc),
+            StageRepr::Count {} => ProtoStage::count(),
+            #[allow(deprecated)]
+            StageRepr::Rerank {
+                model,
+                query,
+                fields,
+                topk_multiple,
+            } => ProtoStage {
+                stage: Some(stage::Stage::Rerank(stage::RerankStage {
+                    model,
+                    query,
+                    fields,
+                    topk_multiple,
+                })),
+            },
+            StageRepr::Limit(k) => ProtoStage::limit(k),
+            StageRepr::Offset(offset) => ProtoStage::offset(offset),
+            StageRepr::Sort(exprs) => ProtoStage::sort(
+                exprs
+                    .into_iter()
+                    .map(|sort_expr| {
+                        Ok((
+                            sort_expr.expr.try_into()?,
+                            parse_enum(
+                                &sort_expr.order,
+                                SORT_ORDER,
+                                stage::sort_stage::SortOrder::from_str_name,
+                            )?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?,
+            ),
+            StageRepr::Fetch(fields) => ProtoStage::fetch(fields),
+            StageRepr::GroupBy { keys, aggs } => ProtoStage::group_by(
+                keys.into_iter()
+                    .map(|(name, expr)| Ok((name, ProtoLogicalExpr::try_from(expr)?)))
+                    .collect::<Result<Vec<_>, Error>>()?,
+                aggs.into_iter()
+                    .map(|(name, agg)| Ok((name, ProtoAggregateExpr::try_from(agg)?)))
+                    .collect::<Result<Vec<_>, Error>>()?,
+            ),
+        })
+    }
+}
+
+/// Names of the function expressions, telling them apart from logical expressions.
+const FUNCTIONS: [&str; 4] = [
+    "vector_distance",
+    "bm25_score",
+    "semantic_similarity",
+    "multi_vector_distance",
+];
+
+/// Names of the text expressions, telling them apart from logical expressions.
+const TEXT_EXPRS: [&str; 3] = ["terms", "and", "or"];
+
+/// Deserializes `F` if `value` is an object with one of the `keys`, otherwise `L`.
+fn deserialize_either<'de, D, L, F>(
+    deserializer: D,
+    keys: &[&str],
+) -> Result<Result<L, F>, D::Error>
+where
+    D: Deserializer<'de>,
+    L: serde::de::DeserializeOwned,
+    F: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    let is_other = value
+        .as_object()
+        .is_some_and(|object| object.keys().any(|key| keys.contains(&key.as_str())));
+
+    if is_other {
+        F::deserialize(value).map(Err).map_err(DeError::custom)
+    } else {
+        L::deserialize(value).map(Ok).map_err(DeError::custom)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Select {
+    Logical(Expr),
+    Function(Function),
+}
+
+impl<'de> Deserialize<'de> for Select {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match deserialize_either(deserializer, &FUNCTIONS)? {
+            Ok(expr) => Self::Logical(expr),
+            Err(function) => Self::Function(function),
+        })
+    }
+}
+
+impl TryFrom<&stage::select_stage::SelectExpr> for Select {
+    type Error = Error;
+
+    fn try_from(expr: &stage::select_stage::SelectExpr) -> Result<Self, Error> {
+        use stage::select_stage::select_expr::Expr as SelectExpr;
+
+        Ok(match required(expr.expr.as_ref(), "select expression")? {
+            SelectExpr::LogicalExpr(expr) => Self::Logical(expr.try_into()?),
+            SelectExpr::FunctionExpr(expr) => Self::Function(expr.try_into()?),
+        })
+    }
+}
+
+impl TryFrom<Select> for stage::select_stage::SelectExpr {
+    type Error = Error;
+
+    fn try_from(expr: Select) -> Result<Self, Error> {
+        Ok(match expr {
+            Select::Logical(expr) => Self::logical(ProtoLogicalExpr::try_from(expr)?),
+            Select::Function(expr) => Self::function(ProtoFunctionExpr::try_from(expr)?),
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Filter {
+    Logical(Expr),
+    Text(Text),
+}
+
+impl<'de> Deserialize<'de> for Filter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match deserialize_either(deserializer, &TEXT_EXPRS)? {
+            Ok(expr) => Self::Logical(expr),
+            Err(text) => Self::Text(text),
+        })
+    }
+}
+
+impl TryFrom<&stage::filter_stage::FilterExpr> for Filter {
+    type Error = Error;
+
+    fn try_from(expr: &stage::filter_stage::FilterExpr) -> Result<Self, Error> {
+        use stage::filter_stage::filter_expr::Expr as FilterExpr;
+
+        Ok(match required(expr.expr.as_ref(), "filter expression")? {
+            FilterExpr::LogicalExpr(expr) => Self::Logical(expr.try_into()?),
+            FilterExpr::TextExpr(expr) => Self::Text(expr.try_into()?),
+        })
+    }
+}
+
+impl TryFrom<Filter> for stage::filter_stage::FilterExpr {
+    type Error = Error;
+
+    fn try_from(expr: Filter) -> Result<Self, Error> {
+        Ok(match expr {
+            Filter::Logical(expr) => Self::logical(ProtoLogicalExpr::try_from(expr)?),
+            Filter::Text(expr) => Self::text(ProtoTextExpr::try_from(expr)?),
+        })
+    }
+}
+
+// Logical expressions
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum Expr {
+    Field(String),
+    Literal(Literal),
+    Unary {
+        op: String,
+        expr: Box<Expr>,
+    },
+    Binary {
+        op: String,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Ternary {
+        op: String,
+        x: Box<Expr>,
+        y: Box<Expr>,
+        z: Box<Expr>,
+    },
+    Nary {
+        op: String,
+        exprs: Vec<Expr>,
+    },
+}
+
+const OP: &str = "OP_";
+
+fn operand(expr: &Option<Box<ProtoLogicalExpr>>) -> Result<Box<Expr>, Error> {
+    Ok(Box::new(Expr::try_from(required(
+        expr.as_deref(),
+        "operand",
+    )?)?))
+}
+
+impl TryFrom<&ProtoLogicalExpr> for Expr {
+    type Error = Error;
+
+    fn try_from(expr: &ProtoLogicalExpr) -> Result<Self, Error> {
+        use logical_expr::{binary_op, nary_op, ternary_op, unary_op};
+
+        Ok(match required(expr.expr.as_ref(), "logical expression")? {
+            logical_expr::Expr::Field(name) => Self::Field(name.clone()),
+            logical_expr::Expr::Literal(value) => Self::Literal(Literal(value.clone())),
+            logical_expr::Expr::UnaryOp(op) => Self::Unary {
+                op: enum_name(
+                    unary_op::Op::try_from(op.op)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    OP,
+                ),
+                expr: operand(&op.expr)?,
+            },
+            logical_expr::Expr::BinaryOp(op) => Self::Binary {
+                op: enum_name(
+                    binary_op::Op::try_from(op.op)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    OP,
+                ),
+                left: operand(&op.left)?,
+                right: operand(&op.right)?,
+            },
+            logical_expr::Expr::TernaryOp(op) => Self::Ternary {
+                op: enum_name(
+                    ternary_op::Op::try_from(op.op)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    OP,
+                ),
+                x: operand(&op.x)?,
+                y: operand(&op.y)?,
+                z: operand(&op.z)?,
+            },
+            logical_expr::Expr::NaryOp(op) => Self::Nary {
+                op: enum_name(
+                    nary_op::Op::try_from(op.op)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    OP,
+                ),
+                exprs: op
+                    .exprs
+                    .iter()
+                    .map(Expr::try_from)
+                    .collect::<Result<_, _>>()?,
+            },
+        })
+    }
+}
+
+impl TryFrom<Expr> for ProtoLogicalExpr {
+    type Error = Error;
+
+    fn try_from(expr: Expr) -> Result<Self, Error> {
+        use logical_expr::{binary_op, nary_op, ternary_op, unary_op};
+
+        Ok(match expr {
+            Expr::Field(name) => ProtoLogicalExpr::field(name),
+            Expr::Literal(literal) => ProtoLogicalExpr::literal(literal.0),
+            Expr::Unary { op, expr } => ProtoLogicalExpr::unary(
+                parse_enum(&op, OP, unary_op::Op::from_str_name)?,
+                ProtoLogicalExpr::try_from(*expr)?,
+            ),
+            Expr::Binary { op, left, right } => ProtoLogicalExpr::binary(
+                parse_enum(&op, OP, binary_op::Op::from_str_name)?,
+                ProtoLogicalExpr::try_from(*left)?,
+                ProtoLogicalExpr::try_from(*right)?,
+            ),
+            Expr::Ternary { op, x, y, z } => ProtoLogicalExpr::ternary(
+                parse_enum(&op, OP, ternary_op::Op::from_str_name)?,
+                ProtoLogicalExpr::try_from(*x)?,
+                ProtoLogicalExpr::try_from(*y)?,
+                ProtoLogicalExpr::try_from(*z)?,
+            ),
+            Expr::Nary { op, exprs } => ProtoLogicalExpr::nary(
+                parse_enum(&op, OP, nary_op::Op::from_str_name)?,
+                exprs
+                    .into_iter()
+                    .map(ProtoLogicalExpr::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+        })
+    }
+}
+
+// Text expressions
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum Text {
+    Terms {
+        terms: Vec<Term>,
+        #[serde(default, skip_serializing_if = "is_false")]
+        all: bool,
+        #[serde(default, skip_serializing_if = "is_false")]
+        should: bool,
+    },
+    And {
+        left: Box<Text>,
+        right: Box<Text>,
+    },
+    Or {
+        left: Box<Text>,
+        right: Box<Text>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Term {
+    token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    field: Option<String>,
+    weight: f32,
+}
+
+fn text_operand(expr: &Option<Box<ProtoTextExpr>>) -> Result<Box<Text>, Error> {
+    Ok(Box::new(Text::try_from(required(
+        expr.as_deref(),
+        "operand",
+    )?)?))
+}
+
+impl TryFrom<&ProtoTextExpr> for Text {
+    type Error = Error;
+
+    fn try_from(expr: &ProtoTextExpr) -> Result<Self, Error> {
+        Ok(match required(expr.expr.as_ref(), "text expression")? {
+            text_expr::Expr::Terms(terms) => Self::Terms {
+                terms: terms
+                    .terms
+                    .iter()
+                    .map(|term| {
+                        finite([term.weight])?;
+                        Ok(Term {
+                            token: term.token.clone(),
+                            field: term.field.clone(),
+                            weight: term.weight,
+                        })
+                    })
+                    .collect::<Result<_, Error>>()?,
+                all: terms.all,
+                should: terms.should,
+            },
+            text_expr::Expr::And(and) => Self::And {
+                left: text_operand(&and.left)?,
+                right: text_operand(&and.right)?,
+            },
+            text_expr::Expr::Or(or) => Self::Or {
+                left: text_operand(&or.left)?,
+                right: text_operand(&or.right)?,
+            },
+        })
+    }
+}
+
+impl TryFrom<Text> for ProtoTextExpr {
+    type Error = Error;
+
+    fn try_from(expr: Text) -> Result<Self, Error> {
+        Ok(match expr {
+            Text::Terms { terms, all, should } => {
+                let terms = terms
+                    .into_iter()
+                    .map(|term| text_expr::Term {
+                        token: term.token,
+                        field: term.field,
+                        weight: term.weight,
+                    })
+                    .collect();
+                ProtoTextExpr {
+                    expr: Some(text_expr::Expr::Terms(text_expr::TextTermsExpr {
+                        all,
+                        terms,
+                        should,
+                    })),
+                }
+            }
+            Text::And { left, right } => {
+                ProtoTextExpr::try_from(*left)?.and(ProtoTextExpr::try_from(*right)?)
+            }
+            Text::Or { left, right } => {
+                ProtoTextExpr::try_from(*left)?.or(ProtoTextExpr::try_from(*right)?)
+            }
+        })
+    }
+}
+
+// Function expressions
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum Function {
+    VectorDistance {
+        field: String,
+        query: Literal,
+        #[serde(default, skip_serializing_if = "is_false")]
+        skip_refine: bool,
+    },
+    Bm25Score {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        b: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        k1: Option<f32>,
+    },
+    SemanticSimilarity {
+        field: String,
+        query: String,
+    },
+    MultiVectorDistance {
+        field: String,
+        query: Literal,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        candidates: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        smve: Option<Smve>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Smve {
+    field: String,
+    query: Literal,
+}
+
+impl TryFrom<&ProtoFunctionExpr> for Function {
+    type Error = Error;
+
+    fn try_from(expr: &ProtoFunctionExpr) -> Result<Self, Error> {
+        Ok(match required(expr.func.as_ref(), "function expression")? {
+            function_expr::Func::VectorDistance(distance) => Self::VectorDistance {
+                field: distance.field.clone(),
+                query: Literal(required(distance.query.as_ref(), "vector distance query")?.clone()),
+                skip_refine: distance.skip_refine,
+            },
+            function_expr::Func::Bm25Score(score) => {
+                finite(score.b.iter().chain(&score.k1).copied())?;
+                Self::Bm25Score {
+                    b: score.b,
+                    k1: score.k1,
+                }
+            }
+            function_expr::Func::SemanticSimilarity(similarity) => Self::SemanticSimilarity {
+                field: similarity.field.clone(),
+                query: similarity.query.clone(),
+            },
+            function_expr::Func::MultiVectorDistance(distance) => Self::MultiVectorDistance {
+                field: distance.field.clone(),
+                query: Literal(
+                    required(distance.query.as_ref(), "multi-vector distance query")?.clone(),
+                ),
+                candidates: distance.candidates,
+                smve: distance
+                    .smve
+                    .as_ref()
+                    .map(|smve| {
+                        let vector = required(smve.smve.as_ref(), "sparse query")?.clone();
+                        Ok::<_, Error>(Smve {
+                            field: smve.field.clone(),
+                            query: Literal(Value {
+                                value: Some(value::Value::SparseVector(vector)),
+                            }),
+                        })
+                    })
+                    .transpose()?,
+            },
+        })
+    }
+}
+
+impl TryFrom<Function> for ProtoFunctionExpr {
+    type Error = Error;
+
+    fn try_from(expr: Function) -> Result<Self, Error> {
+        Ok(match expr {
+            Function::VectorDistance {
+                field,
+                query,
+                skip_refine,
+            } => ProtoFunctionExpr::vector_distance(field, query.0, skip_refine),
+            Function::Bm25Score { b, k1 } => {
+                if b.is_some_and(|b| !(0.0..=1.0).contains(&b)) {
+                    return Err(invalid("b must be between 0.0 and 1.0"));
+                }
+                if k1.is_some_and(|k1| k1 < 0.0) {
+                    return Err(invalid("k1 must be >= 0.0"));
+                }
+                ProtoFunctionExpr::bm25_score(b, k1)
+            }
+            Function::SemanticSimilarity { field, query } => {
+                ProtoFunctionExpr::semantic_similarity(field, query)
+            }
+            Function::MultiVectorDistance {
+                field,
+                query,
+                candidates,
+                smve,
+            } => {
+                let expr = ProtoFunctionExpr::multi_vector_distance(field, query.0, candidates);
+                match smve {
+                    Some(smve) => match smve.query.0.value {
+                        Some(value::Value::SparseVector(vector)) => {
+                            expr.with_smve(smve.field, vector)
+                        }
+                        _ => return Err(invalid("smve query must be a sparse vector")),
+                    },
+                    None => expr,
+                }
+            }
+        })
+    }
+}
+
+// Aggregate expressions
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum Aggregate {
+    Count(Option<String>),
+    Sum(String),
+    Min(String),
+    Max(String),
+    Avg(String),
+}
+
+impl TryFrom<&ProtoAggregateExpr> for Aggregate {
+    type Error = Error;
+
+    fn try_from(expr: &ProtoAggregateExpr) -> Result<Self, Error> {
+        Ok(match required(expr.op.as_ref(), "aggregate expression")? {
+            aggregate_expr::Op::Count(count) => Self::Count(count.field.clone()),
+            aggregate_expr::Op::Sum(sum) => Self::Sum(sum.field.clone()),
+            aggregate_expr::Op::Min(min) => Self::Min(min.field.clone()),
+            aggregate_expr::Op::Max(max) => Self::Max(max.field.clone()),
+            aggregate_expr::Op::Avg(avg) => Self::Avg(avg.field.clone()),
+        })
+    }
+}
+
+impl TryFrom<Aggregate> for ProtoAggregateExpr {
+    type Error = Error;
+
+    fn try_from(expr: Aggregate) -> Result<Self, Error> {
+        Ok(match expr {
+            Aggregate::Count(field) => ProtoAggregateExpr::count(field),
+            Aggregate::Sum(field) => ProtoAggregateExpr::sum(field),
+            Aggregate::Min(field) => ProtoAggregateExpr::min(field),
+            Aggregate::Max(field) => ProtoAggregateExpr::max(field),
+            Aggregate::Avg(field) => ProtoAggregateExpr::avg(field),
+        })
+    }
+}
+
+// Literals
+
+/// Literal value, see the module documentation for the format.
+struct Literal(Value);
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Tagged {
+    U32(u32),
+    U64(u64),
+    I32(i32),
+    F32(f32),
+    Binary(Vec<u8>),
+    List(Values),
+    SparseVector {
+        indices: Vec<u32>,
+        #[serde(flatten)]
+        values: Values,
+    },
+    Matrix {
+        num_cols: u32,
+        #[serde(flatten)]
+        values: Values,
+    },
+    Struct(BTreeMap<String, Literal>),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Values {
+    U8(Vec<u8>),
+    I8(Vec<i8>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+    I32(Vec<i32>),
+    I64(Vec<i64>),
+    F8(Vec<f32>),
+    F16(Vec<f32>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+    String(Vec<String>),
+}
+
+/// JSON has no representation of NaN and infinity.
+fn finite<T: Into<f64>>(values: impl IntoIterator<Item = T>) -> Result<(), Error> {
+    match values.into_iter().all(|v| v.into().is_finite()) {
+        true => Ok(()),
+        false => Err(invalid("non-finite floating-point value")),
+    }
+}
+
+fn f32s<T: Copy + Into<f32>>(values: &[T]) -> Result<Values, Error> {
+    let values = values.iter().map(|v| (*v).into()).collect::<Vec<f32>>();
+    finite(values.iter().copied())?;
+    Ok(Values::F32(values))
+}
+
+impl TryFrom<&list::Values> for Values {
+    type Error = Error;
+
+    fn try_from(values: &list::Values) -> Result<Self, Error> {
+        Ok(match values {
+            list::Values::U8(v) => Self::U8(v.values.clone()),
+            list::Values::I8(v) => Self::I8(v.as_ref().to_vec()),
+            list::Values::U32(v) => Self::U32(v.values.clone()),
+            list::Values::U64(v) => Self::U64(v.values.clone()),
+            list::Values::I32(v) => Self::I32(v.values.clone()),
+            list::Values::I64(v) => Self::I64(v.values.clone()),
+            list::Values::F8(v) => match f32s(v.as_ref())? {
+                Self::F32(values) => Self::F8(values),
+                _ => unreachable!(),
+            },
+            list::Values::F16(v) => match f32s(v.as_ref())? {
+                Self::F32(values) => Self::F16(values),
+                _ => unreachable!(),
+            },
+            list::Values::F32(v) => f32s(&v.values)?,
+            list::Values::F64(v) => {
+                finite(v.values.iter().copied())?;
+                Self::F64(v.values.clone())
+            }
+            list::Values::String(v) => Self::String(v.values.clone()),
+        })
+    }
+}
+
+impl Values {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::U8(_) => "u8",
+            Self::I8(_) => "i8",
+            Self::U32(_) => "u32",
+            Self::U64(_) => "u64",
+            Self::I32(_) => "i32",
+            Self::I64(_) => "i64",
+            Self::F8(_) => "f8",
+            Self::F16(_) => "f16",
+            Self::F32(_) => "f32",
+            Self::F64(_) => "f64",
+            Self::String(_) => "string",
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::U8(v) => v.len(),
+            Self::I8(v) => v.len(),
+            Self::U32(v) => v.len(),
+            Self::U64(v) => v.len(),
+            Self::I32(v) => v.len(),
+            Self::I64(v) => v.len(),
+            Self::F8(v) | Self::F16(v) | Self::F32(v) => v.len(),
+            Self::F64(v) => v.len(),
+            Self::String(v) => v.len(),
+        }
+    }
+
+    fn into_list(self) -> Value {
+        match self {
+            Self::U8(v) => Value::list(v),
+            Self::I8(v) => Value::list(v),
+            Self::U32(v) => Value::list(v),
+            Self::U64(v) => Value::list(v),
+            Self::I32(v) => Value::list(v),
+            Self::I64(v) => Value::list(v),
+            Self::F8(v) => Value::list(to_f8(v)),
+            Self::F16(v) => Value::list(to_f16(v)),
+            Self::F32(v) => Value::list(v),
+            Self::F64(v) => Value::list(v),
+            Self::String(v) => Value::list(v),
+        }
+    }
+
+    fn into_sparse_vector(self, indices: Vec<u32>) -> Result<Value, Error> {
+        if indices.len() != self.len() {
+            return Err(invalid("sparse vector indices and values differ in length"));
+        }
+        Ok(match self {
+            Self::U8(v) => Value::u8_sparse_vector(indices, v),
+            Self::I8(v) => Value::i8_sparse_vector(indices, v),
+            Self::F8(v) => Value::f8_sparse_vector(indices, to_f8(v)),
+            Self::F16(v) => Value::f16_sparse_vector(indices, to_f16(v)),
+            Self::F32(v) => Value::f32_sparse_vector(indices, v),
+            values => {
+                return Err(invalid(format!(
+                    "sparse vectors of {} are not supported",
+                    values.name()
+                )))
+            }
+        })
+    }
+
+    fn into_matrix(self, num_cols: u32) -> Result<Value, Error> {
+        if num_cols == 0 || !self.len().is_multiple_of(num_cols as usize) {
+            return Err(invalid(format!(
+                "{} values are not rows of {num_cols} columns",
+                self.len()
+            )));
+        }
+        Ok(match self {
+            Self::U8(v) => Value::matrix(num_cols, v),
+            Self::I8(v) => Value::matrix(num_cols, v),
+            Self::F8(v) => Value::matrix(num_cols, to_f8(v)),
+            Self::F16(v) => Value::matrix(num_cols, to_f16(v)),
+            Self::F32(v) => Value::matrix(num_cols, v),
+            values => {
+                return Err(invalid(format!(
+                    "matrices of {} are not supported",
+                    values.name()
+                )))
+            }
+        })
+    }
+}
+
+fn to_f8(values: Vec<f32>) -> Vec<float8::F8E4M3> {
+    values.into_iter().map(float8::F8E4M3::from_f32).collect()
+}
+
+fn to_f16(values: Vec<f32>) -> Vec<half::f16> {
+    values.into_iter().map(half::f16::from_f32).collect()
+}
+
+impl TryFrom<&Value> for Tagged {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        Ok(match &value.value {
+            Some(value::Value::U32(v)) => Self::U32(*v),
+            Some(value::Value::U64(v)) => Self::U64(*v),
+            Some(value::Value::I32(v)) => Self::I32(*v),
+            Some(value::Value::F32(v)) => {
+                finite([*v])?;
+                Self::F32(*v)
+            }
+            Some(value::Value::Binary(v)) => Self::Binary(v.to_vec()),
+            Some(value::Value::List(v)) => Self::List(Values::try_from(required(
+                v.values.as_ref(),
+                "list values",
+            )?)?),
+            Some(value::Value::SparseVector(v)) => Self::SparseVector {
+                indices: v.indices.clone(),
+                values: sparse_values(v)?,
+            },
+            Some(value::Value::Matrix(v)) => Self::Matrix {
+                num_cols: v.num_cols,
+                values: match required(v.values.as_ref(), "matrix values")? {
+                    matrix::Values::F32(values) => f32s(&values.values)?,
+                    matrix::Values::F16(values) => match f32s(values.as_ref())? {
+                        Values::F32(values) => Values::F16(values),
+                        _ => unreachable!(),
+                    },
+                    matrix::Values::F8(values) => match f32s(values.as_ref())? {
+                        Values::F32(values) => Values::F8(values),
+                        _ => unreachable!(),
+                    },
+                    matrix::Values::U8(values) => Values::U8(values.values.clone()),
+                    matrix::Values::I8(values) => Values::I8(values.as_ref().to_vec()),
+                },
+            },
+            Some(value::Value::Struct(v)) => Self::Struct(
+                v.fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Literal(value.clone())))
+                    .collect(),
+            ),
+            #[allow(deprecated)]
+            Some(value::Value::Vector(_)) => {
+                return Err(invalid("deprecated vector values are not supported"))
+            }
+            None
+            | Some(
+                value::Value::Null(_)
+                | value::Value::Bool(_)
+                | value::Value::String(_)
+                | value::Value::I64(_)
+                | value::Value::F64(_),
+            ) => unreachable!("untagged literal"),
+        })
+    }
+}
+
+fn sparse_values(vector: &SparseVector) -> Result<Values, Error> {
+    Ok(
+        match required(vector.values.as_ref(), "sparse vector values")? {
+            sparse_vector::Values::F32(values) => f32s(&values.values)?,
+            sparse_vector::Values::F16(values) => match f32s(values.as_ref())? {
+                Values::F32(values) => Values::F16(values),
+                _ => unreachable!(),
+            },
+            sparse_vector::Values::F8(values) => match f32s(values.as_ref())? {
+                Values::F32(values) => Values::F8(values),
+                _ => unreachable!(),
+            },
+            sparse_vector::Values::U8(values) => Values::U8(values.values.clone()),
+            sparse_vector::Values::I8(values) => Values::I8(values.as_ref().to_vec()),
+        },
+    )
+}
+
+impl TryFrom<Tagged> for Value {
+    type Error = Error;
+
+    fn try_from(value: Tagged) -> Result<Self, Error> {
+        Ok(match value {
+            Tagged::U32(v) => Value::u32(v),
+            Tagged::U64(v) => Value::u64(v),
+            Tagged::I32(v) => Value::i32(v),
+            Tagged::F32(v) => Value::f32(v),
+            Tagged::Binary(v) => Value::binary(v),
+            Tagged::List(values) => values.into_list(),
+            Tagged::SparseVector { indices, values } => values.into_sparse_vector(indices)?,
+            Tagged::Matrix { num_cols, values } => values.into_matrix(num_cols)?,
+            Tagged::Struct(fields) => {
+                Value::r#struct(fields.into_iter().map(|(name, value)| (name, value.0)))
+            }
+        })
+    }
+}
+
+impl Serialize for Literal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0.value {
+            None | Some(value::Value::Null(_)) => serializer.serialize_unit(),
+            Some(value::Value::Bool(v)) => serializer.serialize_bool(*v),
+            Some(value::Value::String(v)) => serializer.serialize_str(v),
+            Some(value::Value::I64(v)) => serializer.serialize_i64(*v),
+            Some(value::Value::F64(v)) => {
+                finite([*v]).map_err(SerError::custom)?;
+                serializer.serialize_f64(*v)
+            }
+            _ => Tagged::try_from(&self.0)
+                .map_err(SerError::custom)?
+                .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Literal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Null => Value::null(),
+            serde_json::Value::Bool(v) => Value::bool(v),
+            serde_json::Value::String(v) => Value::string(v),
+            serde_json::Value::Number(v) => match (v.as_i64(), v.is_f64()) {
+                (Some(v), _) => Value::i64(v),
+                (None, true) => Value::f64(v.as_f64().expect("f64 number")),
+                (None, false) => {
+                    return Err(DeError::custom(format!(
+                        "integer {v} is out of range, use {{\"u64\": {v}}}"
+                    )))
+                }
+            },
+            serde_json::Value::Array(_) => {
+                return Err(DeError::custom(
+                    "list literals must be typed, e.g. {\"list\": {\"f32\": [0.1, 0.2]}}",
+                ))
+            }
+            object @ serde_json::Value::Object(_) => Tagged::deserialize(object)
+                .map_err(DeError::custom)
+                .and_then(|tagged| Value::try_from(tagged).map_err(DeError::custom))?,
+        };
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::*;
+    use crate::proto::v1::data::stage::sort_stage::SortOrder;
+    use crate::proto::v1::data::text_expr::Term as ProtoTerm;
+    use crate::query::{self, field, fns, r#match, NumField, TextField, VectorField};
+
+    fn round_trip(query: ProtoQuery) {
+        let json = serde_json::to_string(&Query(query.clone())).unwrap();
+        let parsed = serde_json::from_str::<Query>(&json).unwrap();
+        assert_eq!(parsed.into_inner(), query, "{json}");
+    }
+
+    #[rstest]
+    // stages
+    #[case::select(query::select([("title", field("title"))]))]
+    #[case::filter(query::filter(field("year").gt(2000)))]
+    #[case::count(query::count())]
+    #[case::group_by(query::group_by(
+        [("year", field("year"))],
+        [
+            ("count", ProtoAggregateExpr::count(None)),
+            ("non_null", ProtoAggregateExpr::count(Some("rating".to_string()))),
+            ("sum", ProtoAggregateExpr::sum("rating")),
+            ("min", ProtoAggregateExpr::min("rating")),
+            ("max", ProtoAggregateExpr::max("rating")),
+            ("avg", ProtoAggregateExpr::avg("rating")),
+        ],
+    ))]
+    #[case::sort_limit_offset(
+        query::filter(field("a").is_not_null()).sort([(field("a"), SortOrder::Asc), (field("b"), SortOrder::Desc)]).limit(10).offset(5)
+    )]
+    #[case::sort_by_name(query::filter(field("a").is_not_null()).sort("rating"))]
+    #[case::fetch(query::filter(field("a").is_not_null()).fetch(["title", "rating"]))]
+    #[case::top_k(ProtoQuery::new(vec![ProtoStage::topk(field("rating"), 10, true)]))]
+    // logical expressions
+    #[case::not(query::filter(query::not(field("a"))))]
+    #[case::all(query::filter(query::all([field("a"), field("b")])))]
+    #[case::any(query::filter(query::any([field("a"), field("b")])))]
+    #[case::operators(query::filter(
+        (field("a") + 1 - field("b") * 2.5 / field("c")).gt(0) & !field("d").is_null() | -field("e")
+    ))]
+    #[case::unary(query::select([
+        ("is_not_null", field("a").is_not_null()),
+        ("abs", field("a").abs()),
+        ("ln", field("a").ln()),
+        ("exp", field("a").exp()),
+        ("sqrt", field("a").sqrt()),
+        ("square", field("a").square()),
+    ]))]
+    #[case::binary(query::select([
+        ("and", field("a").and(field("b"))),
+        ("or", field("a").or(field("b"))),
+        ("eq", field("a").eq("x")),
+        ("neq", field("a").neq(1_i32)),
+        ("lt", field("a").lt(1_u32)),
+        ("lte", field("a").lte(1_u64)),
+        ("gte", field("a").gte(1_i64)),
+        ("starts_with", field("a").starts_with("x")),
+        ("contains", field("a").contains("x")),
+        ("in", field("a").in_(vec!["x", "y"])),
+        ("match_all", field("a").match_all("x y")),
+        ("match_any", field("a").match_any(vec!["x".to_string()])),
+        ("coalesce", field("a").coalesce(0.5_f32)),
+        ("min", field("a").min(1.5_f64)),
+        ("max", field("a").max(field("b"))),
+        ("date_part", field("a").date_part("year")),
+    ]))]
+    #[case::ternary(query::select([
+        ("choose", field("a").choose(1, 2)),
+        ("regexp_match", field("a").regexp_match("^x", Some("i"))),
+        ("regexp_match_no_flags", field("a").regexp_match("^x", None::<String>)),
+        ("boost", field("a").boost(field("b").eq(1), 2.0_f32)),
+        ("elapsed", field("a").elapsed(field("b"), "day")),
+        ("saturate", field("a").saturate(1.0, 2.0)),
+        ("decay", field("a").decay(1.0, 2.0)),
+    ]))]
+    #[case::typed_fields(query::filter(
+        TextField::new("title").starts_with("The")
+            & NumField::new("year").gte(2000)
+            & VectorField::new("embedding").is_not_null()
+    ))]
+    // text expressions
+    #[case::match_text(query::filter(r#match("quick fox", Some("title"), Some(2.0), true)))]
+    #[case::should(query::filter(query::should("fox", None, None)))]
+    #[case::match_tokens(query::filter(
+        query::match_tokens(["quick", "fox"], Some("title"), false)
+            .or(query::match_tokens([("dog", 0.5)], None, true))
+            .and(r#match("brown", None, None, false))
+            .boost(1.5)
+    ))]
+    // functions
+    #[case::vector_distance(query::select([
+        ("f32", fns::vector_distance("embedding", vec![0.5_f32, -1.0])),
+        ("f16", fns::vector_distance("embedding", Value::list(to_f16(vec![0.5, 1.0])))),
+        ("f8", fns::vector_distance("embedding", Value::list(to_f8(vec![0.5, 1.0])))),
+        ("u8", fns::vector_distance("embedding", Value::list(vec![1_u8, 255]))),
+        ("i8", fns::vector_distance("embedding", Value::list(vec![-1_i8, 127]))),
+        ("sparse", fns::vector_distance("sparse", Value::f32_sparse_vector(vec![1, 7], vec![0.5, 1.0]))),
+        ("sparse_u8", fns::vector_distance("sparse", Value::u8_sparse_vector(vec![1, 7], vec![5, 10]))),
+        ("skip_refine", fns::vector_distance("embedding", vec![0.5_f32]).skip_refine(true)),
+    ]))]
+    #[case::multi_vector_distance(query::select([
+        ("f32", fns::multi_vector_distance("tokens", Value::matrix(2, vec![0.5_f32, 1.0, 1.5, 2.0]), Some(100))),
+        ("u8", fns::multi_vector_distance("tokens", Value::matrix(1, vec![1_u8, 2]), None)),
+        ("smve", fns::multi_vector_distance("tokens", Value::matrix(1, vec![0.5_f32]), None)
+            .with_smve("tokens_smve", SparseVector::f32(vec![0, 3], vec![0.5, 1.0]))),
+    ]))]
+    #[case::semantic_similarity(query::select([
+        ("similarity", fns::semantic_similarity("summary", "space travel")),
+        ("typed", TextField::new("summary").semantic_similarity("space travel")),
+    ]))]
+    #[case::bm25_score(query::select([
+        ("default", fns::bm25_score(None, None)),
+        ("params", fns::bm25_score(Some(0.75), Some(1.2))),
+    ]))]
+    // literals
+    #[case::literals(query::select([
+        ("null", ProtoLogicalExpr::literal(Value::null())),
+        ("bool", ProtoLogicalExpr::literal(true)),
+        ("i64", ProtoLogicalExpr::literal(-7_i64)),
+        ("f64", ProtoLogicalExpr::literal(2.0_f64)),
+        ("f32", ProtoLogicalExpr::literal(0.1_f32)),
+        ("u64", ProtoLogicalExpr::literal(u64::MAX)),
+        ("binary", ProtoLogicalExpr::literal(Value::binary(vec![0_u8, 255]))),
+        ("u32_list", ProtoLogicalExpr::literal(Value::list(vec![1_u32]))),
+        ("u64_list", ProtoLogicalExpr::literal(Value::list(vec![1_u64]))),
+        ("i32_list", ProtoLogicalExpr::literal(Value::list(vec![1_i32]))),
+        ("i64_list", ProtoLogicalExpr::literal(Value::list(vec![1_i64]))),
+        ("f64_list", ProtoLogicalExpr::literal(Value::list(vec![0.1_f64]))),
+        ("empty_list", ProtoLogicalExpr::literal(Value::list(Vec::<f32>::new()))),
+        ("f16_sparse", ProtoLogicalExpr::literal(Value::f16_sparse_vector(vec![1], to_f16(vec![0.5])))),
+        ("f8_sparse", ProtoLogicalExpr::literal(Value::f8_sparse_vector(vec![1], to_f8(vec![0.5])))),
+        ("i8_sparse", ProtoLogicalExpr::literal(Value::i8_sparse_vector(vec![1], vec![-1]))),
+        ("f16_matrix", ProtoLogicalExpr::literal(Value::matrix(1, to_f16(vec![0.5])))),
+        ("f8_matrix", ProtoLogicalExpr::literal(Value::matrix(1, to_f8(vec![0.5])))),
+        ("i8_matrix", ProtoLogicalExpr::literal(Value::matrix(1, vec![-1_i8]))),
+        ("struct", ProtoLogicalExpr::literal(Value::r#struct([
+            ("a", Value::u32(1)),
+            ("b", Value::r#struct([("c", Value::list(vec!["x"]))])),
+        ]))),
+    ]))]
+    fn test_round_trip(#[case] query: ProtoQuery) {
+        round_trip(query);
+    }
+
+    #[test]
+    fn test_format() {
+        let query = query::filter(field("year").gte(2000_i64) & field("title").match_all("fox"))
+            .select([(
+                "distance",
+                fns::vector_distance("embedding", vec![0.5_f32, 1.0]),
+            )])
+            .sort([(field("distance"), SortOrder::Asc)])
+            .limit(10);
+
+        assert_eq!(
+            serde_json::to_value(Query(query)).unwrap(),
+            json!({
+                "version": 1,
+                "stages": [
+                    {"filter": {"nary": {"op": "all", "exprs": [
+                        {"binary": {"op": "gte", "left": {"field": "year"}, "right": {"literal": 2000}}},
+                        {"binary": {"op": "match_all", "left": {"field": "title"}, "right": {"literal": "fox"}}},
+                    ]}}},
+                    {"select": {"distance": {"vector_distance": {
+                        "field": "embedding",
+                        "query": {"list": {"f32": [0.5, 1.0]}},
+                    }}}},
+                    {"sort": [{"expr": {"field": "distance"}, "order": "asc"}]},
+                    {"limit": 10},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_text_filter() {
+        let filter = ProtoQuery::new(vec![ProtoStage::filter(ProtoTextExpr::terms(
+            true,
+            vec![ProtoTerm {
+                token: "fox".to_string(),
+                field: None,
+                weight: 1.0,
+            }],
+        ))]);
+
+        assert_eq!(
+            serde_json::to_value(Query(filter)).unwrap(),
+            json!({
+                "version": 1,
+                "stages": [{"filter": {"terms": {"terms": [{"token": "fox", "weight": 1.0}], "all": true}}}],
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::missing_version(json!({"stages": []}))]
+    #[case::newer_version(json!({"version": 2, "stages": []}))]
+    #[case::unknown_stage(json!({"version": 1, "stages": [{"skip": 1}]}))]
+    #[case::unknown_op(json!({"version": 1, "stages": [
+        {"filter": {"binary": {"op": "xor", "left": {"field": "a"}, "right": {"field": "b"}}}}
+    ]}))]
+    #[case::untyped_list(json!({"version": 1, "stages": [
+        {"filter": {"binary": {"op": "in", "left": {"field": "a"}, "right": {"literal": [1, 2]}}}}
+    ]}))]
+    #[case::ragged_matrix(json!({"version": 1, "stages": [
+        {"select": {"d": {"multi_vector_distance": {"field": "a", "query": {"matrix": {"num_cols": 2, "f32": [1.0]}}}}}}
+    ]}))]
+    #[case::string_sparse_vector(json!({"version": 1, "stages": [
+        {"select": {"d": {"vector_distance": {"field": "a", "query": {"sparse_vector": {"indices": [1], "string": ["x"]}}}}}}
+    ]}))]
+    #[case::invalid_bm25(json!({"version": 1, "stages": [
+        {"select": {"s": {"bm25_score": {"b": 2.0}}}}
+    ]}))]
+    fn test_invalid(#[case] json: serde_json::Value) {
+        assert!(serde_json::from_value::<Query>(json).is_err());
+    }
+
+    #[test]
+    fn test_non_finite() {
+        let query = query::filter(field("a").gt(f32::NAN));
+        assert!(serde_json::to_value(Query(query)).is_err());
+    }
+
+    #[test]
+    fn test_expressions() {
+        let expr = field("a").gt(1) & field("b").lt(2);
+        let json = serde_json::to_string(&LogicalExpr(expr.clone())).unwrap();
+        assert_eq!(
+            serde_json::from_str::<LogicalExpr>(&json)
+                .unwrap()
+                .into_inner(),
+            expr
+        );
+
+        let agg = ProtoAggregateExpr::sum("rating");
+        assert_eq!(
+            serde_json::to_value(AggregateExpr(agg.clone())).unwrap(),
+            json!({"sum": "rating"})
+        );
+        assert_eq!(
+            serde_json::from_value::<AggregateExpr>(json!({"sum": "rating"}))
+                .unwrap()
+                .into_inner(),
+            agg
+        );
+
+        let text = r#match("fox", None, None, false);
+        let json = serde_json::to_value(TextExpr(text.clone())).unwrap();
+        assert_eq!(serde_json::from_value::<TextExpr>(json).unwrap().0, text);
+
+        let function = fns::semantic_similarity("summary", "space");
+        let json = serde_json::to_value(FunctionExpr(function.clone())).unwrap();
+        assert_eq!(
+            serde_json::from_value::<FunctionExpr>(json).unwrap().0,
+            function
+        );
+
+        let stage = ProtoStage::limit(10);
+        let json = serde_json::to_value(Stage(stage.clone())).unwrap();
+        assert_eq!(json, json!({"limit": 10}));
+        assert_eq!(serde_json::from_value::<Stage>(json).unwrap().0, stage);
+    }
+}
@@ -0,0 +1,579 @@
+//! JSON format of collection schemas.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "fields": {
+//!     "title": {"type": "text", "required": true, "index": {"keyword": {"index_type": "text"}}},
+//!     "embedding": {"type": {"f32_vector": {"dimension": 768}}, "index": {"vector": {"metric": "cosine"}}},
+//!     "tags": {"type": {"list": {"value_type": "string"}}}
+//!   }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::{check_version, enum_name, is_false, parse_enum, unknown_enum};
+use crate::error::Error;
+use crate::proto::v1::control::{
+    field_index, field_type, field_type_list::ListValueType, field_type_matrix::MatrixValueType,
+    EmbeddingDataType, FieldIndex, FieldSpec as ProtoFieldSpec, FieldType, KeywordIndex,
+    KeywordIndexType, MultiVectorDistanceMetric, MultiVectorIndex, MultiVectorQuantization,
+    NGramIndex, SemanticIndex, VectorDistanceMetric, VectorIndex,
+};
+
+json_wrapper!(
+    /// Field specification in the JSON format.
+    FieldSpec(ProtoFieldSpec) as FieldSpecRepr
+);
+
+json_wrapper!(
+    /// Collection schema in the JSON format, with a `version`.
+    Schema(HashMap<String, ProtoFieldSpec>) as SchemaRepr
+);
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaRepr {
+    version: u32,
+    fields: BTreeMap<String, FieldSpecRepr>,
+}
+
+impl TryFrom<&HashMap<String, ProtoFieldSpec>> for SchemaRepr {
+    type Error = Error;
+
+    fn try_from(schema: &HashMap<String, ProtoFieldSpec>) -> Result<Self, Error> {
+        Ok(Self {
+            version: super::FORMAT_VERSION,
+            fields: fields_repr(schema)?,
+        })
+    }
+}
+
+impl TryFrom<SchemaRepr> for HashMap<String, ProtoFieldSpec> {
+    type Error = Error;
+
+    fn try_from(schema: SchemaRepr) -> Result<Self, Error> {
+        check_version(schema.version)?;
+        fields_proto(schema.fields)
+    }
+}
+
+fn fields_repr(
+    fields: &HashMap<String, ProtoFieldSpec>,
+) -> Result<BTreeMap<String, FieldSpecRepr>, Error> {
+    fields
+        .iter()
+        .map(|(name, spec)| Ok((name.clone(), FieldSpecRepr::try_from(spec)?)))
+        .collect()
+}
+
+fn fields_proto(
+    fields: BTreeMap<String, FieldSpecRepr>,
+) -> Result<HashMap<String, ProtoFieldSpec>, Error> {
+    fields
+        .into_iter()
+        .map(|(name, spec)| Ok((name, ProtoFieldSpec::try_from(spec)?)))
+        .collect()
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldSpecRepr {
+    #[serde(rename = "type")]
+    data_type: FieldTypeRepr,
+    #[serde(default, skip_serializing_if = "is_false")]
+    required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    index: Option<IndexRepr>,
+}
+
+impl TryFrom<&ProtoFieldSpec> for FieldSpecRepr {
+    type Error = Error;
+
+    fn try_from(spec: &ProtoFieldSpec) -> Result<Self, Error> {
+        let data_type = spec
+            .data_type
+            .as_ref()
+            .and_then(|data_type| data_type.data_type.as_ref())
+            .ok_or_else(|| Error::InvalidArgument("field type is missing".into()))?;
+
+        Ok(Self {
+            data_type: FieldTypeRepr::try_from(data_type)?,
+            required: spec.required,
+            index: spec
+                .index
+                .as_ref()
+                .and_then(|index| index.index.as_ref())
+                .map(IndexRepr::try_from)
+                .transpose()?,
+        })
+    }
+}
+
+impl TryFrom<FieldSpecRepr> for ProtoFieldSpec {
+    type Error = Error;
+
+    fn try_from(spec: FieldSpecRepr) -> Result<Self, Error> {
+        Ok(ProtoFieldSpec {
+            data_type: Some(FieldType {
+                data_type: Some(spec.data_type.try_into()?),
+            }),
+            required: spec.required,
+            index: spec
+                .index
+                .map(|index| {
+                    Ok::<_, Error>(FieldIndex {
+                        index: Some(index.try_into()?),
+                    })
+                })
+                .transpose()?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum FieldTypeRepr {
+    Text,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    Bytes,
+    F32Vector { dimension: u32 },
+    F16Vector { dimension: u32 },
+    F8Vector { dimension: u32 },
+    U8Vector { dimension: u32 },
+    I8Vector { dimension: u32 },
+    BinaryVector { dimension: u32 },
+    F32SparseVector,
+    F16SparseVector,
+    F8SparseVector,
+    U8SparseVector,
+    I8SparseVector,
+    List { value_type: String },
+    Matrix { dimension: u32, value_type: String },
+    Struct(BTreeMap<String, FieldSpecRepr>),
+}
+
+const LIST_VALUE_TYPE: &str = "LIST_VALUE_TYPE_";
+const MATRIX_VALUE_TYPE: &str = "MATRIX_VALUE_TYPE_";
+
+impl TryFrom<&field_type::DataType> for FieldTypeRepr {
+    type Error = Error;
+
+    fn try_from(data_type: &field_type::DataType) -> Result<Self, Error> {
+        use field_type::DataType;
+
+        Ok(match data_type {
+            DataType::Text(_) => Self::Text,
+            DataType::Integer(_) => Self::Integer,
+            DataType::Float(_) => Self::Float,
+            DataType::Boolean(_) => Self::Boolean,
+            DataType::Timestamp(_) => Self::Timestamp,
+            DataType::Bytes(_) => Self::Bytes,
+            DataType::F32Vector(v) => Self::F32Vector {
+                dimension: v.dimension,
+            },
+            DataType::F16Vector(v) => Self::F16Vector {
+                dimension: v.dimension,
+            },
+            DataType::F8Vector(v) => Self::F8Vector {
+                dimension: v.dimension,
+            },
+            DataType::U8Vector(v) => Self::U8Vector {
+                dimension: v.dimension,
+            },
+            DataType::I8Vector(v) => Self::I8Vector {
+                dimension: v.dimension,
+            },
+            DataType::BinaryVector(v) => Self::BinaryVector {
+                dimension: v.dimension,
+            },
+            DataType::F32SparseVector(_) => Self::F32SparseVector,
+            DataType::F16SparseVector(_) => Self::F16SparseVector,
+            DataType::F8SparseVector(_) => Self::F8SparseVector,
+            DataType::U8SparseVector(_) => Self::U8SparseVector,
+            DataType::I8SparseVector(_) => Self::I8SparseVector,
+            DataType::List(list) => Self::List {
+                value_type: enum_name(
+                    ListValueType::try_from(list.value_type)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    LIST_VALUE_TYPE,
+                ),
+            },
+            DataType::Matrix(matrix) => Self::Matrix {
+                dimension: matrix.dimension,
+                value_type: enum_name(
+                    MatrixValueType::try_from(matrix.value_type)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    MATRIX_VALUE_TYPE,
+                ),
+            },
+            DataType::Struct(r#struct) => Self::Struct(fields_repr(&r#struct.fields)?),
+        })
+    }
+}
+
+impl TryFrom<FieldTypeRepr> for field_type::DataType {
+    type Error = Error;
+
+    fn try_from(data_type: FieldTypeRepr) -> Result<Self, Error> {
+        Ok(match data_type {
+            FieldTypeRepr::Text => Self::text(),
+            FieldTypeRepr::Integer => Self::integer(),
+            FieldTypeRepr::Float => Self::float(),
+            FieldTypeRepr::Boolean => Self::bool(),
+            FieldTypeRepr::Timestamp => Self::timestamp(),
+            FieldTypeRepr::Bytes => Self::bytes(),
+            FieldTypeRepr::F32Vector { dimension } => Self::f32_vector(dimension),
+            FieldTypeRepr::F16Vector { dimension } => Self::f16_vector(dimension),
+            FieldTypeRepr::F8Vector { dimension } => Self::f8_vector(dimension),
+            FieldTypeRepr::U8Vector { dimension } => Self::u8_vector(dimension),
+            FieldTypeRepr::I8Vector { dimension } => Self::i8_vector(dimension),
+            FieldTypeRepr::BinaryVector { dimension } => Self::binary_vector(dimension),
+            FieldTypeRepr::F32SparseVector => Self::f32_sparse_vector(),
+            FieldTypeRepr::F16SparseVector => Self::f16_sparse_vector(),
+            FieldTypeRepr::F8SparseVector => Self::f8_sparse_vector(),
+            FieldTypeRepr::U8SparseVector => Self::u8_sparse_vector(),
+            FieldTypeRepr::I8SparseVector => Self::i8_sparse_vector(),
+            FieldTypeRepr::List { value_type } => FieldType::list(parse_enum(
+                &value_type,
+                LIST_VALUE_TYPE,
+                ListValueType::from_str_name,
+            )?)
+            .data_type
+            .expect("list type"),
+            FieldTypeRepr::Matrix {
+                dimension,
+                value_type,
+            } => Self::matrix(
+                dimension,
+                parse_enum(
+                    &value_type,
+                    MATRIX_VALUE_TYPE,
+                    MatrixValueType::from_str_name,
+                )?,
+            ),
+            FieldTypeRepr::Struct(fields) => Self::r#struct(fields_proto(fields)?),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum IndexRepr {
+    Keyword {
+        index_type: String,
+    },
+    Vector {
+        metric: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        exact: Option<bool>,
+    },
+    Semantic {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        embedding_type: Option<String>,
+    },
+    MultiVector {
+        metric: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sketch_bits: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quantization: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        width: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        top_k: Option<u32>,
+        #[serde(default, skip_serializing_if = "is_false")]
+        skip_smve: bool,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        encoding_version: u32,
+    },
+    Ngram,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+const KEYWORD_INDEX_TYPE: &str = "KEYWORD_INDEX_TYPE_";
+const VECTOR_DISTANCE_METRIC: &str = "VECTOR_DISTANCE_METRIC_";
+const EMBEDDING_DATA_TYPE: &str = "EMBEDDING_DATA_TYPE_";
+const MULTI_VECTOR_DISTANCE_METRIC: &str = "MULTI_VECTOR_DISTANCE_METRIC_";
+const MULTI_VECTOR_QUANTIZATION: &str = "MULTI_VECTOR_QUANTIZATION_";
+
+impl TryFrom<&field_index::Index> for IndexRepr {
+    type Error = Error;
+
+    #[allow(deprecated)]
+    fn try_from(index: &field_index::Index) -> Result<Self, Error> {
+        Ok(match index {
+            field_index::Index::KeywordIndex(index) => Self::Keyword {
+                index_type: enum_name(
+                    KeywordIndexType::try_from(index.index_type)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    KEYWORD_INDEX_TYPE,
+                ),
+            },
+            field_index::Index::VectorIndex(index) => Self::Vector {
+                metric: enum_name(
+                    VectorDistanceMetric::try_from(index.metric)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    VECTOR_DISTANCE_METRIC,
+                ),
+                exact: index.exact,
+            },
+            field_index::Index::SemanticIndex(index) => Self::Semantic {
+                model: index.model.clone(),
+                embedding_type: index
+                    .embedding_type
+                    .map(|embedding_type| {
+                        Ok::<_, Error>(enum_name(
+                            EmbeddingDataType::try_from(embedding_type)
+                                .map_err(unknown_enum)?
+                                .as_str_name(),
+                            EMBEDDING_DATA_TYPE,
+                        ))
+                    })
+                    .transpose()?,
+            },
+            field_index::Index::MultiVectorIndex(index) => Self::MultiVector {
+                metric: enum_name(
+                    MultiVectorDistanceMetric::try_from(index.metric)
+                        .map_err(unknown_enum)?
+                        .as_str_name(),
+                    MULTI_VECTOR_DISTANCE_METRIC,
+                ),
+                sketch_bits: index.sketch_bits,
+                quantization: index
+                    .quantization
+                    .map(|quantization| {
+                        Ok::<_, Error>(enum_name(
+                            MultiVectorQuantization::try_from(quantization)
+                                .map_err(unknown_enum)?
+                                .as_str_name(),
+                            MULTI_VECTOR_QUANTIZATION,
+                        ))
+                    })
+                    .transpose()?,
+                width: index.width,
+                top_k: index.top_k,
+                skip_smve: index.skip_smve,
+                encoding_version: index.encoding_version,
+            },
+            field_index::Index::NgramIndex(_) => Self::Ngram,
+        })
+    }
+}
+
+impl TryFrom<IndexRepr> for field_index::Index {
+    type Error = Error;
+
+    #[allow(deprecated)]
+    fn try_from(index: IndexRepr) -> Result<Self, Error> {
+        Ok(match index {
+            IndexRepr::Keyword { index_type } => Self::KeywordIndex(KeywordIndex {
+                index_type: parse_enum(
+                    &index_type,
+                    KEYWORD_INDEX_TYPE,
+                    KeywordIndexType::from_str_name,
+                )?
+                .into(),
+            }),
+            IndexRepr::Vector { metric, exact } => Self::VectorIndex(VectorIndex {
+                metric: parse_enum(
+                    &metric,
+                    VECTOR_DISTANCE_METRIC,
+                    VectorDistanceMetric::from_str_name,
+                )?
+                .into(),
+                exact,
+            }),
+            IndexRepr::Semantic {
+                model,
+                embedding_type,
+            } => Self::SemanticIndex(SemanticIndex {
+                model,
+                embedding_type: embedding_type
+                    .map(|embedding_type| {
+                        parse_enum(
+                            &embedding_type,
+                            EMBEDDING_DATA_TYPE,
+                            EmbeddingDataType::from_str_name,
+                        )
+                        .map(i32::from)
+                    })
+                    .transpose()?,
+            }),
+            IndexRepr::MultiVector {
+                metric,
+                sketch_bits,
+                quantization,
+                width,
+                top_k,
+                skip_smve,
+                encoding_version,
+            } => Self::MultiVectorIndex(MultiVectorIndex {
+                metric: parse_enum(
+                    &metric,
+                    MULTI_VECTOR_DISTANCE_METRIC,
+                    MultiVectorDistanceMetric::from_str_name,
+                )?
+                .into(),
+                sketch_bits,
+                quantization: quantization
+                    .map(|quantization| {
+                        parse_enum(
+                            &quantization,
+                            MULTI_VECTOR_QUANTIZATION,
+                            MultiVectorQuantization::from_str_name,
+                        )
+                        .map(i32::from)
+                    })
+                    .transpose()?,
+                width,
+                top_k,
+                skip_smve,
+                encoding_version,
+            }),
+            IndexRepr::Ngram => Self::NgramIndex(NGramIndex {}),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> HashMap<String, ProtoFieldSpec> {
+        HashMap::from([
+            (
+                "title".to_string(),
+                ProtoFieldSpec::text(true).with_index(FieldIndex::keyword(KeywordIndexType::Text)),
+            ),
+            (
+                "slug".to_string(),
+                ProtoFieldSpec::text(false).with_index(FieldIndex::ngram()),
+            ),
+            (
+                "summary".to_string(),
+                ProtoFieldSpec::text(false).with_index(FieldIndex::semantic()),
+            ),
+            ("year".to_string(), ProtoFieldSpec::integer(false)),
+            ("rating".to_string(), ProtoFieldSpec::float(false)),
+            ("published".to_string(), ProtoFieldSpec::boolean(false)),
+            ("created_at".to_string(), ProtoFieldSpec::timestamp(false)),
+            ("raw".to_string(), ProtoFieldSpec::bytes(false)),
+            (
+                "tags".to_string(),
+                ProtoFieldSpec::list(false, ListValueType::String),
+            ),
+            (
+                "f32".to_string(),
+                ProtoFieldSpec::f32_vector(4, true)
+                    .with_index(FieldIndex::vector(VectorDistanceMetric::Cosine)),
+            ),
+            ("f16".to_string(), ProtoFieldSpec::f16_vector(4, false)),
+            ("f8".to_string(), ProtoFieldSpec::f8_vector(4, false)),
+            ("u8".to_string(), ProtoFieldSpec::u8_vector(4, false)),
+            ("i8".to_string(), ProtoFieldSpec::i8_vector(4, false)),
+            (
+                "binary".to_string(),
+                ProtoFieldSpec::binary_vector(4, false)
+                    .with_index(FieldIndex::vector(VectorDistanceMetric::Hamming)),
+            ),
+            (
+                "f32_sparse".to_string(),
+                ProtoFieldSpec::f32_sparse_vector(false)
+                    .with_index(FieldIndex::vector(VectorDistanceMetric::DotProduct)),
+            ),
+            (
+                "f16_sparse".to_string(),
+                ProtoFieldSpec::f16_sparse_vector(false),
+            ),
+            (
+                "f8_sparse".to_string(),
+                ProtoFieldSpec::f8_sparse_vector(false),
+            ),
+            (
+                "u8_sparse".to_string(),
+                ProtoFieldSpec::u8_sparse_vector(false),
+            ),
+            (
+                "i8_sparse".to_string(),
+                ProtoFieldSpec::i8_sparse_vector(false),
+            ),
+            (
+                "tokens".to_string(),
+                ProtoFieldSpec::matrix(false, 128, MatrixValueType::F16).with_index(
+                    FieldIndex::multi_vector(
+                        MultiVectorDistanceMetric::Maxsim,
+                        Some(MultiVectorQuantization::Binary2bit),
+                        Some(16),
+                        Some(8),
+                    )
+                    .skip_smve(),
+                ),
+            ),
+            (
+                "author".to_string(),
+                ProtoFieldSpec::r#struct(
+                    false,
+                    [
+                        ("name", ProtoFieldSpec::text(true)),
+                        ("born", ProtoFieldSpec::integer(false)),
+                    ],
+                ),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let schema = schema();
+        let json = serde_json::to_string(&Schema(schema.clone())).unwrap();
+        assert_eq!(serde_json::from_str::<Schema>(&json).unwrap().0, schema);
+    }
+
+    #[test]
+    fn test_format() {
+        let spec = ProtoFieldSpec::f32_vector(768, true)
+            .with_index(FieldIndex::vector(VectorDistanceMetric::DotProduct));
+        let json = json!({
+            "type": {"f32_vector": {"dimension": 768}},
+            "required": true,
+            "index": {"vector": {"metric": "dot_product"}},
+        });
+
+        assert_eq!(serde_json::to_value(FieldSpec(spec.clone())).unwrap(), json);
+        assert_eq!(serde_json::from_value::<FieldSpec>(json).unwrap().0, spec);
+        assert_eq!(
+            serde_json::to_value(FieldSpec(ProtoFieldSpec::text(false))).unwrap(),
+            json!({"type": "text"})
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        for json in [
+            json!({"fields": {}}),
+            json!({"version": 2, "fields": {}}),
+            json!({"version": 1, "fields": {"a": {"type": "string"}}}),
+            json!({"version": 1, "fields": {"a": {"type": "text", "index": {"keyword": {"index_type": "fuzzy"}}}}}),
+        ] {
+            assert!(serde_json::from_value::<Schema>(json).is_err());
+        }
+    }
+}
@@ -6,21 +6,35 @@ pub use error::Error;
 #[cfg(feature = "json")]
 pub mod json;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+#[cfg(feature = "parquet")]
+pub mod parquet;
+
+pub mod quantize;
+
 pub mod client;
 pub use client::Client;
 pub use client::ClientConfig;
 pub use client::CollectionClient;
 pub use client::CollectionsClient;
+pub use client::RequestOptions;
 
 pub mod defaults {
+    pub use crate::client::INITIAL_CONNECTION_WINDOW_SIZE;
+    pub use crate::client::INITIAL_STREAM_WINDOW_SIZE;
     pub use crate::client::RETRY_BACKOFF_BASE;
     pub use crate::client::RETRY_BACKOFF_INIT;
     pub use crate::client::RETRY_BACKOFF_MAX;
     pub use crate::client::RETRY_MAX_RETRIES;
     pub use crate::client::RETRY_TIMEOUT;
+    pub use crate::client::TIMEOUT;
 }
 
+pub use client::credentials;
 pub use client::retry;
+pub use client::transport;
 
 // Public API
 pub mod data {
@@ -51,6 +65,9 @@ pub mod query {
     };
 
     pub use crate::proto::v1::data::stage::sort_stage::SortOrder;
+    pub use crate::proto::v1::data::{
+        NumField, QueryIssue, QueryIssueKind, TextField, VectorField,
+    };
 
     pub mod fns {
         use crate::proto::v1::data::{FunctionExpr, Value};
@@ -83,6 +100,18 @@ pub mod query {
         LogicalExpr::field(name)
     }
 
+    pub fn text_field(name: impl Into<String>) -> TextField {
+        TextField::new(name)
+    }
+
+    pub fn num_field(name: impl Into<String>) -> NumField {
+        NumField::new(name)
+    }
+
+    pub fn vector_field(name: impl Into<String>) -> VectorField {
+        VectorField::new(name)
+    }
+
     pub fn select(
         exprs: impl IntoIterator<Item = (impl Into<String>, impl Into<SelectExpr>)>,
     ) -> Query {
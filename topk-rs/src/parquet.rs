@@ -0,0 +1,352 @@
+//! Parquet export and import for collections.
+//!
+//! Documents are converted with a [`DocumentConverter`], see [`crate::arrow`] for the
+//! mapping between field types and Parquet (Arrow) columns.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::sync::Arc;
+
+use ::parquet::arrow::arrow_reader::{RowSelection, RowSelector};
+use ::parquet::arrow::{AsyncArrowWriter, ParquetRecordBatchStreamBuilder};
+use ::parquet::basic::{Compression, ZstdLevel};
+use ::parquet::errors::ParquetError;
+use ::parquet::file::properties::WriterProperties;
+use arrow_array::RecordBatch;
+use arrow_schema::{Schema, SchemaRef};
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::arrow::{arrow_error, DocumentConverter};
+use crate::client::CollectionClient;
+use crate::error::Error;
+use crate::proto::v1::data::{LogicalExpr, Query, Stage};
+
+/// Options for [`CollectionClient::export_parquet`].
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// Query returning the exported documents. Defaults to all documents with the
+    /// fields of the converter.
+    pub query: Option<Query>,
+    /// Maximum number of rows per row group.
+    pub row_group_size: usize,
+    /// Number of documents converted at a time.
+    pub batch_size: usize,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            query: None,
+            row_group_size: 100_000,
+            batch_size: 1000,
+        }
+    }
+}
+
+/// Options for [`CollectionClient::import_parquet`].
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// Column holding the document `_id`. Defaults to `_id`.
+    pub id_column: Option<String>,
+    /// Maps columns to fields. Columns that are not mapped are imported under their own name.
+    pub columns: HashMap<String, String>,
+    /// Number of documents per upsert.
+    pub batch_size: usize,
+    /// Number of concurrent upserts.
+    pub concurrency: usize,
+    /// File recording the imported rows. An interrupted import with the same checkpoint
+    /// resumes after the last upserted batch. The file is removed once the import completes.
+    pub checkpoint: Option<PathBuf>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            id_column: None,
+            columns: HashMap::new(),
+            batch_size: 1000,
+            concurrency: 4,
+            checkpoint: None,
+        }
+    }
+}
+
+/// Rows exported or imported so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub rows: u64,
+    /// Number of rows in the file, known when importing.
+    pub total_rows: Option<u64>,
+}
+
+impl CollectionClient {
+    /// Exports documents to a Parquet file, returning the number of exported documents.
+    pub async fn export_parquet(
+        &self,
+        converter: DocumentConverter,
+        path: impl AsRef<Path>,
+        options: ExportOptions,
+        mut progress: impl FnMut(Progress),
+    ) -> Result<u64, Error> {
+        let query = match options.query {
+            Some(query) => query,
+            None => select_fields(&converter),
+        };
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(options.row_group_size)
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+
+        let file = tokio::fs::File::create(path).await?;
+        let mut writer = AsyncArrowWriter::try_new(file, converter.schema(), Some(properties))
+            .map_err(parquet_error)?;
+
+        let mut batches = pin!(self
+            .query_stream(query, None)
+            .await?
+            .record_batches(converter, options.batch_size));
+
+        let mut rows = 0;
+        while let Some(batch) = batches.try_next().await? {
+            writer.write(&batch).await.map_err(parquet_error)?;
+            rows += batch.num_rows() as u64;
+            progress(Progress {
+                rows,
+                total_rows: None,
+            });
+        }
+        writer.close().await.map_err(parquet_error)?;
+
+        Ok(rows)
+    }
+
+    /// Imports a Parquet file, returning the number of imported rows, including the rows
+    /// imported before resuming from a checkpoint.
+    pub async fn import_parquet(
+        &self,
+        converter: &DocumentConverter,
+        path: impl AsRef<Path>,
+        options: ImportOptions,
+        mut progress: impl FnMut(Progress),
+    ) -> Result<u64, Error> {
+        let path = path.as_ref();
+        let builder = ParquetRecordBatchStreamBuilder::new(tokio::fs::File::open(path).await?)
+            .await
+            .map_err(parquet_error)?;
+        let total_rows = builder.metadata().file_metadata().num_rows() as u64;
+        let schema = rename_columns(builder.schema(), &options)?;
+
+        let mut checkpoint = Checkpoint {
+            file: path.to_path_buf(),
+            total_rows,
+            rows: 0,
+        };
+        if let Some(checkpoint_path) = &options.checkpoint {
+            if let Some(saved) = Checkpoint::load(checkpoint_path).await? {
+                if saved.file != checkpoint.file || saved.total_rows != total_rows {
+                    return Err(Error::InvalidArgument(format!(
+                        "checkpoint {} belongs to a different file ({})",
+                        checkpoint_path.display(),
+                        saved.file.display()
+                    )));
+                }
+                checkpoint.rows = saved.rows;
+            }
+        }
+
+        let mut builder = builder.with_batch_size(options.batch_size);
+        if checkpoint.rows > 0 {
+            builder = builder.with_row_selection(RowSelection::from(vec![
+                RowSelector::skip(checkpoint.rows as usize),
+                RowSelector::select((total_rows - checkpoint.rows) as usize),
+            ]));
+        }
+        progress(Progress {
+            rows: checkpoint.rows,
+            total_rows: Some(total_rows),
+        });
+
+        // Upserts complete in order, so the checkpoint never skips a failed batch.
+        let mut upserts = pin!(builder
+            .build()
+            .map_err(parquet_error)?
+            .map(|batch| {
+                let schema = schema.clone();
+                async move {
+                    let batch = batch.map_err(parquet_error)?;
+                    let batch = RecordBatch::try_new(schema, batch.columns().to_vec())
+                        .map_err(arrow_error)?;
+                    let docs = converter.to_documents(&batch)?;
+                    let rows = docs.len() as u64;
+                    self.upsert(docs, None).await?;
+                    Ok::<_, Error>(rows)
+                }
+            })
+            .buffered(options.concurrency.max(1)));
+
+        while let Some(rows) = upserts.try_next().await? {
+            checkpoint.rows += rows;
+            if let Some(checkpoint_path) = &options.checkpoint {
+                checkpoint.save(checkpoint_path).await?;
+            }
+            progress(Progress {
+                rows: checkpoint.rows,
+                total_rows: Some(total_rows),
+            });
+        }
+
+        if let Some(checkpoint_path) = &options.checkpoint {
+            match tokio::fs::remove_file(checkpoint_path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+
+        Ok(checkpoint.rows)
+    }
+}
+
+/// Query returning all documents with the fields of `converter`.
+fn select_fields(converter: &DocumentConverter) -> Query {
+    let fields = converter.fields().collect::<Vec<_>>();
+    if fields.is_empty() {
+        return Query::new(vec![]);
+    }
+
+    Query::new(vec![Stage::select(
+        fields
+            .into_iter()
+            .map(|name| (name, LogicalExpr::field(name))),
+    )])
+}
+
+/// Renames the columns of a file according to `options`.
+fn rename_columns(schema: &Schema, options: &ImportOptions) -> Result<SchemaRef, Error> {
+    let id_column = options.id_column.as_deref().unwrap_or("_id");
+    if schema.field_with_name(id_column).is_err() {
+        return Err(Error::InvalidArgument(format!(
+            "file has no `{id_column}` column"
+        )));
+    }
+
+    let mut names = HashSet::new();
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = field.name();
+            let name = if column == id_column {
+                "_id"
+            } else {
+                options.columns.get(column).unwrap_or(column)
+            };
+            if !names.insert(name) {
+                return Err(Error::InvalidArgument(format!(
+                    "more than one column is imported as `{name}`"
+                )));
+            }
+            Ok(field.as_ref().clone().with_name(name))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Arc::new(Schema::new_with_metadata(
+        fields,
+        schema.metadata().clone(),
+    )))
+}
+
+fn parquet_error(e: ParquetError) -> Error {
+    Error::Input(e.into())
+}
+
+/// Progress of an import, saved after each upserted batch.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Checkpoint {
+    file: PathBuf,
+    total_rows: u64,
+    rows: u64,
+}
+
+impl Checkpoint {
+    async fn load(path: &Path) -> Result<Option<Self>, Error> {
+        match tokio::fs::read(path).await {
+            Ok(data) => serde_json::from_slice(&data).map(Some).map_err(|e| {
+                Error::InvalidArgument(format!("invalid checkpoint {}: {e}", path.display()))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes a temporary file and renames it, so that the checkpoint is never partially written.
+    async fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut tmp = OsString::from(path);
+        tmp.push(".tmp");
+
+        let data = serde_json::to_vec(self).expect("checkpoint is serializable");
+        tokio::fs::write(&tmp, data).await?;
+        tokio::fs::rename(&tmp, path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_schema::{DataType, Field};
+
+    use super::*;
+
+    #[test]
+    fn test_rename_columns() {
+        let schema = Schema::new(vec![
+            Field::new("doc_id", DataType::Utf8, false),
+            Field::new("emb", DataType::Utf8, true),
+            Field::new("title", DataType::Utf8, true),
+        ]);
+        let options = ImportOptions {
+            id_column: Some("doc_id".to_string()),
+            columns: HashMap::from([("emb".to_string(), "embedding".to_string())]),
+            ..Default::default()
+        };
+
+        let renamed = rename_columns(&schema, &options).unwrap();
+        let names = renamed
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["_id", "embedding", "title"]);
+
+        // `_id` is missing
+        assert!(rename_columns(&schema, &ImportOptions::default()).is_err());
+
+        // Two columns mapped to `title`
+        let options = ImportOptions {
+            id_column: Some("doc_id".to_string()),
+            columns: HashMap::from([("emb".to_string(), "title".to_string())]),
+            ..Default::default()
+        };
+        assert!(rename_columns(&schema, &options).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint() {
+        let path = std::env::temp_dir().join(format!("topk-{}.checkpoint", uuid::Uuid::new_v4()));
+        assert_eq!(Checkpoint::load(&path).await.unwrap(), None);
+
+        let checkpoint = Checkpoint {
+            file: PathBuf::from("books.parquet"),
+            total_rows: 10,
+            rows: 4,
+        };
+        checkpoint.save(&path).await.unwrap();
+        assert_eq!(Checkpoint::load(&path).await.unwrap(), Some(checkpoint));
+
+        std::fs::write(&path, "{").unwrap();
+        assert!(Checkpoint::load(&path).await.is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -3,6 +3,8 @@ include!(concat!(env!("OUT_DIR"), "/topk.data.v1.rs"));
 mod data_ext;
 mod query_ext;
 pub use data_ext::{IntoListValues, IntoMatrixValues};
+pub use query_ext::expr_ext::field::{NumField, TextField, VectorField};
+pub use query_ext::validate::{QueryIssue, QueryIssueKind};
 
 impl DeleteDocumentsRequest {
     pub fn ids(ids: impl Into<Vec<String>>) -> Self {
@@ -0,0 +1,307 @@
+//! Typed field handles, exposing only the operations that are valid for the type of a field.
+//!
+//! Numeric handles convert into a [`LogicalExpr`], so they can be operands of arithmetic and
+//! comparisons. Text and vector handles only convert explicitly, with `into_expr`, so that they
+//! can't be passed where any expression is accepted, e.g. `field("rating") * text_field("title")`.
+
+use crate::proto::v1::data::{FunctionExpr, LogicalExpr, Value};
+
+/// Text field, e.g. `text_field("title").starts_with("The")`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextField(String);
+
+impl TextField {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Untyped field expression, e.g. to `select` or `sort` by the field.
+    pub fn into_expr(self) -> LogicalExpr {
+        LogicalExpr::field(self.0)
+    }
+
+    fn expr(&self) -> LogicalExpr {
+        LogicalExpr::field(self.0.clone())
+    }
+
+    pub fn eq(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().eq(right)
+    }
+
+    pub fn neq(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().neq(right)
+    }
+
+    pub fn starts_with(&self, prefix: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().starts_with(prefix)
+    }
+
+    pub fn contains(&self, substring: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().contains(substring)
+    }
+
+    /// Evaluates to true if the field is one of `values`, or a substring of a string `values`.
+    pub fn in_(&self, values: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().in_(values)
+    }
+
+    pub fn regexp_match<S: Into<String>>(
+        &self,
+        pattern: impl Into<String>,
+        flags: Option<S>,
+    ) -> LogicalExpr {
+        self.expr().regexp_match(pattern, flags)
+    }
+
+    /// Matches all terms against the field with keyword index.
+    pub fn match_all(&self, terms: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().match_all(terms)
+    }
+
+    /// Matches any term against the field with keyword index.
+    pub fn match_any(&self, terms: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().match_any(terms)
+    }
+
+    /// Similarity of the field with semantic index to `query`.
+    pub fn semantic_similarity(&self, query: impl Into<String>) -> FunctionExpr {
+        FunctionExpr::semantic_similarity(self.0.clone(), query)
+    }
+
+    pub fn is_null(&self) -> LogicalExpr {
+        self.expr().is_null()
+    }
+
+    pub fn is_not_null(&self) -> LogicalExpr {
+        self.expr().is_not_null()
+    }
+}
+
+/// Numeric field, e.g. `num_field("year").gte(2000)` or `num_field("rating") * 2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumField(String);
+
+impl NumField {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Untyped field expression, e.g. to `select` or `sort` by the field.
+    pub fn into_expr(self) -> LogicalExpr {
+        LogicalExpr::field(self.0)
+    }
+
+    fn expr(&self) -> LogicalExpr {
+        LogicalExpr::field(self.0.clone())
+    }
+
+    pub fn eq(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().eq(right)
+    }
+
+    pub fn neq(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().neq(right)
+    }
+
+    pub fn lt(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().lt(right)
+    }
+
+    pub fn lte(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().lte(right)
+    }
+
+    pub fn gt(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().gt(right)
+    }
+
+    pub fn gte(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().gte(right)
+    }
+
+    /// Evaluates to true if the field is one of `values`.
+    pub fn in_(&self, values: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().in_(values)
+    }
+
+    pub fn min(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().min(right)
+    }
+
+    pub fn max(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().max(right)
+    }
+
+    /// Coalesce nulls with the provided value.
+    pub fn coalesce(&self, right: impl Into<LogicalExpr>) -> LogicalExpr {
+        self.expr().coalesce(right)
+    }
+
+    pub fn abs(&self) -> LogicalExpr {
+        self.expr().abs()
+    }
+
+    pub fn ln(&self) -> LogicalExpr {
+        self.expr().ln()
+    }
+
+    pub fn exp(&self) -> LogicalExpr {
+        self.expr().exp()
+    }
+
+    pub fn sqrt(&self) -> LogicalExpr {
+        self.expr().sqrt()
+    }
+
+    pub fn square(&self) -> LogicalExpr {
+        self.expr().square()
+    }
+
+    pub fn saturate(&self, mid: f32, exp: f32) -> LogicalExpr {
+        self.expr().saturate(mid, exp)
+    }
+
+    pub fn decay(&self, mid: f32, exp: f32) -> LogicalExpr {
+        self.expr().decay(mid, exp)
+    }
+
+    pub fn is_null(&self) -> LogicalExpr {
+        self.expr().is_null()
+    }
+
+    pub fn is_not_null(&self) -> LogicalExpr {
+        self.expr().is_not_null()
+    }
+}
+
+impl<T: Into<LogicalExpr>> std::ops::Add<T> for NumField {
+    type Output = LogicalExpr;
+
+    fn add(self, rhs: T) -> Self::Output {
+        LogicalExpr::from(self) + rhs
+    }
+}
+
+impl<T: Into<LogicalExpr>> std::ops::Sub<T> for NumField {
+    type Output = LogicalExpr;
+
+    fn sub(self, rhs: T) -> Self::Output {
+        LogicalExpr::from(self) - rhs
+    }
+}
+
+impl<T: Into<LogicalExpr>> std::ops::Mul<T> for NumField {
+    type Output = LogicalExpr;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        LogicalExpr::from(self) * rhs
+    }
+}
+
+impl<T: Into<LogicalExpr>> std::ops::Div<T> for NumField {
+    type Output = LogicalExpr;
+
+    fn div(self, rhs: T) -> Self::Output {
+        LogicalExpr::from(self) / rhs
+    }
+}
+
+/// Arithmetic negation, unlike `-` on a [`LogicalExpr`] which is `not`.
+impl std::ops::Neg for NumField {
+    type Output = LogicalExpr;
+
+    fn neg(self) -> Self::Output {
+        LogicalExpr::from(self).negate()
+    }
+}
+
+/// Vector or matrix field, e.g. `vector_field("embedding").vector_distance(vec![0.1, 0.2])`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorField(String);
+
+impl VectorField {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Untyped field expression, e.g. to `select` or `sort` by the field.
+    pub fn into_expr(self) -> LogicalExpr {
+        LogicalExpr::field(self.0)
+    }
+
+    pub fn vector_distance(&self, query: impl Into<Value>) -> FunctionExpr {
+        FunctionExpr::vector_distance(self.0.clone(), query, false)
+    }
+
+    pub fn multi_vector_distance(
+        &self,
+        query: impl Into<Value>,
+        candidates: Option<u32>,
+    ) -> FunctionExpr {
+        FunctionExpr::multi_vector_distance(self.0.clone(), query, candidates)
+    }
+
+    pub fn is_null(&self) -> LogicalExpr {
+        LogicalExpr::field(self.0.clone()).is_null()
+    }
+
+    pub fn is_not_null(&self) -> LogicalExpr {
+        LogicalExpr::field(self.0.clone()).is_not_null()
+    }
+}
+
+impl From<NumField> for LogicalExpr {
+    fn from(field: NumField) -> Self {
+        LogicalExpr::field(field.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_fields() {
+        let title = TextField::new("title");
+        let year = NumField::new("year");
+        let rating = NumField::new("rating");
+
+        assert_eq!(
+            title.starts_with("The") & year.gte(2000) & rating.is_not_null(),
+            LogicalExpr::all([
+                LogicalExpr::field("title").starts_with("The"),
+                LogicalExpr::field("year").gte(2000),
+                LogicalExpr::field("rating").is_not_null(),
+            ])
+        );
+        assert_eq!(
+            rating.clone() * 2 + year.clone(),
+            LogicalExpr::field("rating")
+                .mul(2)
+                .add(LogicalExpr::field("year"))
+        );
+        assert_eq!(-rating.clone(), LogicalExpr::field("rating").mul(-1));
+        assert_eq!(
+            year.lt(rating),
+            LogicalExpr::field("year").lt(LogicalExpr::field("rating"))
+        );
+        assert_eq!(
+            VectorField::new("embedding").vector_distance(vec![0.1f32, 0.2]),
+            FunctionExpr::vector_distance("embedding", vec![0.1f32, 0.2], false)
+        );
+        assert_eq!(title.into_expr(), LogicalExpr::field("title"));
+        assert_eq!(year.into_expr(), LogicalExpr::field("year"));
+    }
+}
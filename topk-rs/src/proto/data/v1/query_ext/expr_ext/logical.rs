@@ -155,6 +155,11 @@ impl LogicalExpr {
         Self::binary(binary_op::Op::Sub, self, right)
     }
 
+    /// Arithmetic negation, i.e. `self * -1`.
+    pub fn negate(self) -> Self {
+        Self::mul(self, -1)
+    }
+
     pub fn min(self, right: impl Into<LogicalExpr>) -> Self {
         Self::binary(binary_op::Op::Min, self, right)
     }
@@ -432,13 +437,33 @@ impl<T: Into<LogicalExpr>> std::ops::Div<T> for LogicalExpr {
     }
 }
 
+/// Logical negation, `-expr` is `not(expr)`.
+///
+/// **Deprecated:** use `!expr` instead, `-expr` is kept as `not` until the next major release.
+/// Use [`LogicalExpr::negate`] for arithmetic negation. Trait impls can't be `#[deprecated]`,
+/// so uses of `-` don't warn.
+impl std::ops::Neg for LogicalExpr {
+    type Output = LogicalExpr;
+
+    fn neg(self) -> Self::Output {
+        LogicalExpr::not(self)
+    }
+}
+
 // Logical operator overloads
+//
+// Unlike `and` and `or`, chained `&` and `|` build a single `all` or `any` expression,
+// e.g. `a & b & c` is `all([a, b, c])`.
+//
+// This changes the wire shape of queries built with `&` and `|`: they used to send nested
+// `BinaryOp::And` / `BinaryOp::Or` expressions and now send a `NaryOp::All` / `NaryOp::Any`.
+// Both evaluate the same, but code inspecting the built expressions must match the n-ary form.
 
 impl<T: Into<LogicalExpr>> std::ops::BitAnd<T> for LogicalExpr {
     type Output = LogicalExpr;
 
     fn bitand(self, rhs: T) -> Self::Output {
-        LogicalExpr::and(self, rhs)
+        LogicalExpr::flatten(nary_op::Op::All, binary_op::Op::And, self, rhs.into())
     }
 }
 
@@ -446,18 +471,46 @@ impl<T: Into<LogicalExpr>> std::ops::BitOr<T> for LogicalExpr {
     type Output = LogicalExpr;
 
     fn bitor(self, rhs: T) -> Self::Output {
-        LogicalExpr::or(self, rhs)
+        LogicalExpr::flatten(nary_op::Op::Any, binary_op::Op::Or, self, rhs.into())
     }
 }
 
-impl std::ops::Neg for LogicalExpr {
+impl std::ops::Not for LogicalExpr {
     type Output = LogicalExpr;
 
-    fn neg(self) -> Self::Output {
+    fn not(self) -> Self::Output {
         LogicalExpr::not(self)
     }
 }
 
+impl LogicalExpr {
+    /// Combines `left` and `right` into a single `op` expression, inlining the operands of
+    /// nested `op` and `binary` expressions.
+    fn flatten(
+        op: nary_op::Op,
+        binary: binary_op::Op,
+        left: LogicalExpr,
+        right: LogicalExpr,
+    ) -> Self {
+        let mut exprs = Vec::new();
+        let mut stack = vec![right, left];
+        while let Some(expr) = stack.pop() {
+            match expr.expr {
+                Some(logical_expr::Expr::NaryOp(nary)) if nary.op == op as i32 => {
+                    stack.extend(nary.exprs.into_iter().rev());
+                }
+                Some(logical_expr::Expr::BinaryOp(bin)) if bin.op == binary as i32 => {
+                    let bin = *bin;
+                    stack.extend(bin.right.map(|e| *e));
+                    stack.extend(bin.left.map(|e| *e));
+                }
+                expr => exprs.push(LogicalExpr { expr }),
+            }
+        }
+        Self::nary(op, exprs)
+    }
+}
+
 impl BinaryOp {
     pub fn and(left: LogicalExpr, right: LogicalExpr) -> Self {
         BinaryOp {
@@ -539,3 +592,72 @@ impl BinaryOp {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str) -> LogicalExpr {
+        LogicalExpr::field(name)
+    }
+
+    #[test]
+    fn test_and_or_operators_flatten() {
+        let (a, b, c, d) = (field("a"), field("b"), field("c"), field("d"));
+
+        assert_eq!(
+            a.clone() & b.clone() & c.clone() & d.clone(),
+            LogicalExpr::all([a.clone(), b.clone(), c.clone(), d.clone()])
+        );
+        assert_eq!(
+            a.clone() & (b.clone() & c.clone()),
+            LogicalExpr::all([a.clone(), b.clone(), c.clone()])
+        );
+        assert_eq!(
+            a.clone().and(b.clone()) & c.clone(),
+            LogicalExpr::all([a.clone(), b.clone(), c.clone()])
+        );
+        assert_eq!(
+            (a.clone() | b.clone()) & (c.clone() | d.clone()),
+            LogicalExpr::all([
+                LogicalExpr::any([a.clone(), b.clone()]),
+                LogicalExpr::any([c.clone(), d.clone()])
+            ])
+        );
+        assert_eq!(
+            a.clone() | b.clone() | true,
+            LogicalExpr::any([a, b, LogicalExpr::literal(true)])
+        );
+    }
+
+    #[test]
+    fn test_and_or_operators_send_nary_op() {
+        let expr = field("a") & field("b");
+        assert!(
+            matches!(&expr.expr, Some(logical_expr::Expr::NaryOp(nary)) if nary.op == nary_op::Op::All as i32),
+            "{expr:?}"
+        );
+        let expr = field("a") | field("b");
+        assert!(
+            matches!(&expr.expr, Some(logical_expr::Expr::NaryOp(nary)) if nary.op == nary_op::Op::Any as i32),
+            "{expr:?}"
+        );
+    }
+
+    /// `-` still builds `not`, so existing filters are unchanged.
+    #[test]
+    fn test_neg_is_not() {
+        assert_eq!(-field("x").eq(1), LogicalExpr::not(field("x").eq(1)));
+        assert_eq!(-field("x").eq(1), !field("x").eq(1));
+    }
+
+    #[test]
+    fn test_unary_operators() {
+        assert_eq!(!field("a"), LogicalExpr::not(field("a")));
+        assert_eq!(field("a").negate(), field("a").mul(-1));
+        assert_eq!(
+            field("a").negate() + field("b") * 2,
+            field("a").mul(-1).add(field("b").mul(2))
+        );
+    }
+}
@@ -1,4 +1,5 @@
 pub mod aggregate;
+pub mod field;
 pub mod filter;
 pub mod function;
 pub mod logical;
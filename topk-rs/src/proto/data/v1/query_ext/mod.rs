@@ -6,6 +6,7 @@ use crate::proto::data::v1::{
 
 pub mod expr_ext;
 pub mod stage_ext;
+pub mod validate;
 
 impl Query {
     pub fn new(stages: Vec<Stage>) -> Self {
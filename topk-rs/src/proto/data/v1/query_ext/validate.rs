@@ -0,0 +1,1255 @@
+//! Static validation of queries against a collection schema.
+//!
+//! Fields that are not in the schema are allowed, since collections are schemaless for fields
+//! without an index, but their type is unknown and they are not type-checked.
+
+use std::collections::HashMap;
+
+use crate::proto::control::v1::{
+    field_index, field_type, field_type_list::ListValueType, field_type_matrix::MatrixValueType,
+    FieldSpec,
+};
+use crate::proto::data::v1::{
+    aggregate_expr, function_expr, list, logical_expr, matrix, sparse_vector, stage, text_expr,
+    value, FunctionExpr, LogicalExpr, Query, TextExpr, Value,
+};
+
+/// Problem found by [`Query::validate`], at `path` of the offending (sub-)expression, e.g.
+/// `stages[1].filter.left`.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{path}: {kind}")]
+pub struct QueryIssue {
+    pub path: String,
+    pub kind: QueryIssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum QueryIssueKind {
+    #[error("query has no stages")]
+    EmptyQuery,
+
+    #[error("stage is empty")]
+    EmptyStage,
+
+    #[error("expression is empty")]
+    EmptyExpression,
+
+    #[error("unknown operator `{0}`")]
+    UnknownOperator(i32),
+
+    #[error("expected {expected}, got `{got}`")]
+    InvalidType { expected: String, got: String },
+
+    #[error("`{op}` cannot be applied to `{left}` and `{right}`")]
+    IncompatibleTypes {
+        op: String,
+        left: String,
+        right: String,
+    },
+
+    #[error("field `{field}` is not in the schema")]
+    UnknownField { field: String },
+
+    #[error("field `{field}` of type `{data_type}` is not a {expected} field")]
+    InvalidFieldType {
+        field: String,
+        data_type: String,
+        expected: String,
+    },
+
+    #[error("field `{field}` has no {index} index")]
+    MissingIndex { field: String, index: String },
+
+    #[error("no field has a keyword index")]
+    NoKeywordIndex,
+
+    #[error("query `{got}` does not match field `{field}` of type `{data_type}`")]
+    InvalidQueryType {
+        field: String,
+        data_type: String,
+        got: String,
+    },
+
+    #[error("query has dimension {got}, field `{field}` has dimension {expected}")]
+    DimensionMismatch {
+        field: String,
+        expected: u32,
+        got: usize,
+    },
+
+    #[error("`sort` must be followed by `limit`")]
+    SortWithoutLimit,
+
+    #[error("`offset` must follow `limit`")]
+    OffsetWithoutLimit,
+
+    #[error("`limit` must be greater than 0")]
+    ZeroLimit,
+}
+
+impl Query {
+    /// Type-checks the expressions of the query against `schema` and checks the order of its
+    /// stages, returning every issue found.
+    pub fn validate(&self, schema: &HashMap<String, FieldSpec>) -> Result<(), Vec<QueryIssue>> {
+        let mut validator = Validator {
+            schema,
+            computed: HashMap::new(),
+            grouped: false,
+            issues: vec![],
+        };
+
+        if self.stages.is_empty() {
+            validator.issue("stages", QueryIssueKind::EmptyQuery);
+        }
+
+        for (i, stage) in self.stages.iter().enumerate() {
+            let path = format!("stages[{i}]");
+            let Some(stage) = &stage.stage else {
+                validator.issue(&path, QueryIssueKind::EmptyStage);
+                continue;
+            };
+
+            match stage {
+                stage::Stage::Sort(_)
+                    if !matches!(
+                        self.stages.get(i + 1).and_then(|s| s.stage.as_ref()),
+                        Some(stage::Stage::Limit(_))
+                    ) =>
+                {
+                    validator.issue(&path, QueryIssueKind::SortWithoutLimit);
+                }
+                stage::Stage::Offset(_)
+                    if !self.stages[..i]
+                        .iter()
+                        .any(|s| matches!(s.stage, Some(stage::Stage::Limit(_)))) =>
+                {
+                    validator.issue(&path, QueryIssueKind::OffsetWithoutLimit);
+                }
+                stage::Stage::Limit(limit) if limit.k == 0 => {
+                    validator.issue(&path, QueryIssueKind::ZeroLimit);
+                }
+                _ => {}
+            }
+
+            validator.stage(&path, stage);
+        }
+
+        match validator.issues.is_empty() {
+            true => Ok(()),
+            false => Err(validator.issues),
+        }
+    }
+}
+
+/// Inferred type of an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ty {
+    /// Type of fields outside of the schema, compatible with every type.
+    Unknown,
+    Null,
+    Bool,
+    Int,
+    Float,
+    Text,
+    Bytes,
+    Timestamp,
+    List(ListTy),
+    Vector,
+    Struct,
+}
+
+/// Element type of a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListTy {
+    Unknown,
+    Int,
+    Float,
+    Text,
+}
+
+impl Ty {
+    fn name(self) -> &'static str {
+        match self {
+            Ty::Unknown => "unknown",
+            Ty::Null => "null",
+            Ty::Bool => "boolean",
+            Ty::Int => "integer",
+            Ty::Float => "float",
+            Ty::Text => "text",
+            Ty::Bytes => "bytes",
+            Ty::Timestamp => "timestamp",
+            Ty::List(ListTy::Unknown) => "list<_>",
+            Ty::List(ListTy::Int) => "list<integer>",
+            Ty::List(ListTy::Float) => "list<float>",
+            Ty::List(ListTy::Text) => "list<string>",
+            Ty::Vector => "vector",
+            Ty::Struct => "struct",
+        }
+    }
+
+    /// Unknown types and nulls pass every check.
+    fn is(self, f: fn(Ty) -> bool) -> bool {
+        matches!(self, Ty::Unknown | Ty::Null) || f(self)
+    }
+
+    fn is_bool(self) -> bool {
+        self.is(|ty| ty == Ty::Bool)
+    }
+
+    fn is_numeric(self) -> bool {
+        self.is(|ty| matches!(ty, Ty::Int | Ty::Float))
+    }
+
+    fn is_text(self) -> bool {
+        self.is(|ty| ty == Ty::Text)
+    }
+
+    fn is_timestamp(self) -> bool {
+        self.is(|ty| ty == Ty::Timestamp)
+    }
+
+    fn is_sortable(self) -> bool {
+        self.is(|ty| {
+            matches!(
+                ty,
+                Ty::Bool | Ty::Int | Ty::Float | Ty::Text | Ty::Timestamp
+            )
+        })
+    }
+
+    fn element(self) -> Ty {
+        match self {
+            Ty::List(ListTy::Int) => Ty::Int,
+            Ty::List(ListTy::Float) => Ty::Float,
+            Ty::List(ListTy::Text) => Ty::Text,
+            _ => Ty::Unknown,
+        }
+    }
+
+    /// Whether values of the types can be compared with each other. Timestamps compare with
+    /// integers, since timestamp literals are integers.
+    fn is_comparable(self, other: Ty) -> bool {
+        match (self, other) {
+            (Ty::Unknown | Ty::Null, _) | (_, Ty::Unknown | Ty::Null) => true,
+            (Ty::Int | Ty::Float, Ty::Int | Ty::Float) => true,
+            (Ty::Timestamp, Ty::Int | Ty::Timestamp) | (Ty::Int, Ty::Timestamp) => true,
+            (Ty::List(a), Ty::List(b)) => a == b || a == ListTy::Unknown || b == ListTy::Unknown,
+            (a, b) => a == b,
+        }
+    }
+
+    /// Type of arithmetic on numbers.
+    fn numeric(self, other: Ty) -> Ty {
+        match (self, other) {
+            (Ty::Int, Ty::Int) => Ty::Int,
+            (Ty::Float, Ty::Int | Ty::Float) | (Ty::Int, Ty::Float) => Ty::Float,
+            _ => Ty::Unknown,
+        }
+    }
+
+    fn of_field(data_type: &field_type::DataType) -> Ty {
+        use field_type::DataType;
+
+        match data_type {
+            DataType::Text(_) => Ty::Text,
+            DataType::Integer(_) => Ty::Int,
+            DataType::Float(_) => Ty::Float,
+            DataType::Boolean(_) => Ty::Bool,
+            DataType::Timestamp(_) => Ty::Timestamp,
+            DataType::Bytes(_) => Ty::Bytes,
+            DataType::List(list) => Ty::List(match list.value_type() {
+                ListValueType::Integer => ListTy::Int,
+                ListValueType::Float => ListTy::Float,
+                ListValueType::String => ListTy::Text,
+                ListValueType::Unspecified => ListTy::Unknown,
+            }),
+            DataType::Struct(_) => Ty::Struct,
+            DataType::F32Vector(_)
+            | DataType::F16Vector(_)
+            | DataType::F8Vector(_)
+            | DataType::U8Vector(_)
+            | DataType::I8Vector(_)
+            | DataType::BinaryVector(_)
+            | DataType::F32SparseVector(_)
+            | DataType::F16SparseVector(_)
+            | DataType::F8SparseVector(_)
+            | DataType::U8SparseVector(_)
+            | DataType::I8SparseVector(_)
+            | DataType::Matrix(_) => Ty::Vector,
+        }
+    }
+
+    fn of_value(value: &Value) -> Ty {
+        match &value.value {
+            None | Some(value::Value::Null(_)) => Ty::Null,
+            Some(value::Value::Bool(_)) => Ty::Bool,
+            Some(
+                value::Value::U32(_)
+                | value::Value::U64(_)
+                | value::Value::I32(_)
+                | value::Value::I64(_),
+            ) => Ty::Int,
+            Some(value::Value::F32(_) | value::Value::F64(_)) => Ty::Float,
+            Some(value::Value::String(_)) => Ty::Text,
+            Some(value::Value::Binary(_)) => Ty::Bytes,
+            Some(value::Value::List(list)) => Ty::List(match &list.values {
+                Some(
+                    list::Values::U8(_)
+                    | list::Values::I8(_)
+                    | list::Values::U32(_)
+                    | list::Values::U64(_)
+                    | list::Values::I32(_)
+                    | list::Values::I64(_),
+                ) => ListTy::Int,
+                Some(
+                    list::Values::F8(_)
+                    | list::Values::F16(_)
+                    | list::Values::F32(_)
+                    | list::Values::F64(_),
+                ) => ListTy::Float,
+                Some(list::Values::String(_)) => ListTy::Text,
+                None => ListTy::Unknown,
+            }),
+            #[allow(deprecated)]
+            Some(
+                value::Value::Vector(_) | value::Value::SparseVector(_) | value::Value::Matrix(_),
+            ) => Ty::Vector,
+            Some(value::Value::Struct(_)) => Ty::Struct,
+        }
+    }
+}
+
+/// Element kind of vector fields and queries, grouping the float types since float queries
+/// are converted to the type of the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Elem {
+    Float,
+    U8,
+    I8,
+}
+
+fn op_name(name: &str) -> String {
+    name.trim_start_matches("OP_").to_ascii_lowercase()
+}
+
+struct Validator<'a> {
+    schema: &'a HashMap<String, FieldSpec>,
+    /// Fields computed by `select` and `group_by` stages.
+    computed: HashMap<String, Ty>,
+    /// Whether a `group_by` stage replaced the fields of the documents.
+    grouped: bool,
+    issues: Vec<QueryIssue>,
+}
+
+impl<'a> Validator<'a> {
+    fn issue(&mut self, path: &str, kind: QueryIssueKind) {
+        self.issues.push(QueryIssue {
+            path: path.to_string(),
+            kind,
+        });
+    }
+
+    fn expect(&mut self, path: &str, ty: Ty, expected: &str, ok: fn(Ty) -> bool) {
+        if !ok(ty) {
+            self.issue(
+                path,
+                QueryIssueKind::InvalidType {
+                    expected: expected.to_string(),
+                    got: ty.name().to_string(),
+                },
+            );
+        }
+    }
+
+    /// Looks up a field of the schema, including fields of structs, e.g. `author.name`.
+    fn spec(&self, name: &str) -> Option<&'a FieldSpec> {
+        if let Some(spec) = self.schema.get(name) {
+            return Some(spec);
+        }
+
+        let mut parts = name.split('.');
+        let mut spec = self.schema.get(parts.next()?)?;
+        for part in parts {
+            spec = match spec.data_type.as_ref()?.data_type.as_ref()? {
+                field_type::DataType::Struct(r#struct) => r#struct.fields.get(part)?,
+                _ => return None,
+            };
+        }
+        Some(spec)
+    }
+
+    fn field_ty(&mut self, path: &str, name: &str) -> Ty {
+        if let Some(ty) = self.computed.get(name) {
+            return *ty;
+        }
+        if self.grouped {
+            self.issue(
+                path,
+                QueryIssueKind::UnknownField {
+                    field: name.to_string(),
+                },
+            );
+            return Ty::Unknown;
+        }
+        if name == "_id" {
+            return Ty::Text;
+        }
+
+        self.spec(name)
+            .and_then(|spec| spec.data_type.as_ref()?.data_type.as_ref())
+            .map(Ty::of_field)
+            .unwrap_or(Ty::Unknown)
+    }
+
+    /// Looks up an indexed field, which must be in the schema.
+    fn indexed_field(
+        &mut self,
+        path: &str,
+        name: &str,
+    ) -> Option<(&'a field_type::DataType, Option<&'a field_index::Index>)> {
+        let spec = self.spec(name);
+        let Some(data_type) = spec.and_then(|spec| spec.data_type.as_ref()?.data_type.as_ref())
+        else {
+            self.issue(
+                path,
+                QueryIssueKind::UnknownField {
+                    field: name.to_string(),
+                },
+            );
+            return None;
+        };
+        Some((
+            data_type,
+            spec.and_then(|spec| spec.index.as_ref()?.index.as_ref()),
+        ))
+    }
+
+    fn missing_index(&mut self, path: &str, field: &str, index: &str) {
+        self.issue(
+            path,
+            QueryIssueKind::MissingIndex {
+                field: field.to_string(),
+                index: index.to_string(),
+            },
+        );
+    }
+
+    fn has_keyword_index(&self) -> bool {
+        self.schema.values().any(|spec| {
+            matches!(
+                spec.index.as_ref().and_then(|index| index.index.as_ref()),
+                Some(field_index::Index::KeywordIndex(_))
+            )
+        })
+    }
+
+    fn keyword_field(&mut self, path: &str, name: &str) {
+        if let Some((_, index)) = self.indexed_field(path, name) {
+            if !matches!(index, Some(field_index::Index::KeywordIndex(_))) {
+                self.missing_index(path, name, "keyword");
+            }
+        }
+    }
+
+    #[allow(deprecated)]
+    fn stage(&mut self, path: &str, stage: &stage::Stage) {
+        match stage {
+            stage::Stage::Select(select) => {
+                use stage::select_stage::select_expr::Expr;
+
+                let mut computed = vec![];
+                for (name, expr) in &select.exprs {
+                    let path = format!("{path}.select.{name}");
+                    let ty = match &expr.expr {
+                        Some(Expr::LogicalExpr(expr)) => self.logical(&path, expr),
+                        Some(Expr::FunctionExpr(expr)) => {
+                            self.function(&path, expr);
+                            Ty::Float
+                        }
+                        None => {
+                            self.issue(&path, QueryIssueKind::EmptyExpression);
+                            Ty::Unknown
+                        }
+                    };
+                    computed.push((name.clone(), ty));
+                }
+                self.computed.extend(computed);
+            }
+            stage::Stage::Filter(filter) => {
+                use stage::filter_stage::filter_expr::Expr;
+
+                let path = format!("{path}.filter");
+                match filter.expr.as_ref().and_then(|expr| expr.expr.as_ref()) {
+                    Some(Expr::LogicalExpr(expr)) => {
+                        let ty = self.logical(&path, expr);
+                        self.expect(&path, ty, "a boolean", Ty::is_bool);
+                    }
+                    Some(Expr::TextExpr(expr)) => self.text(&path, expr),
+                    None => self.issue(&path, QueryIssueKind::EmptyExpression),
+                }
+            }
+            stage::Stage::TopK(top_k) => {
+                let path = format!("{path}.top_k");
+                self.sort_expr(&path, top_k.expr.as_ref());
+            }
+            stage::Stage::Sort(sort) => {
+                for (j, sort_expr) in sort.exprs.iter().enumerate() {
+                    self.sort_expr(&format!("{path}.sort[{j}]"), sort_expr.expr.as_ref());
+                }
+            }
+            stage::Stage::GroupBy(group_by) => {
+                let mut computed = HashMap::new();
+                for (name, expr) in &group_by.keys {
+                    let ty = self.logical(&format!("{path}.group_by.keys.{name}"), expr);
+                    computed.insert(name.clone(), ty);
+                }
+                for (name, agg) in &group_by.aggs {
+                    let ty = self.aggregate(&format!("{path}.group_by.aggs.{name}"), agg);
+                    computed.insert(name.clone(), ty);
+                }
+                self.computed = computed;
+                self.grouped = true;
+            }
+            stage::Stage::Count(_)
+            | stage::Stage::Limit(_)
+            | stage::Stage::Offset(_)
+            | stage::Stage::Fetch(_)
+            | stage::Stage::Rerank(_) => {}
+        }
+    }
+
+    fn sort_expr(&mut self, path: &str, expr: Option<&LogicalExpr>) {
+        match expr {
+            Some(expr) => {
+                let ty = self.logical(path, expr);
+                self.expect(path, ty, "a sortable value", Ty::is_sortable);
+            }
+            None => self.issue(path, QueryIssueKind::EmptyExpression),
+        }
+    }
+
+    fn aggregate(&mut self, path: &str, agg: &crate::proto::data::v1::AggregateExpr) -> Ty {
+        let numeric = |validator: &mut Self, field: &str| {
+            let ty = validator.field_ty(path, field);
+            validator.expect(path, ty, "a number", Ty::is_numeric);
+            ty
+        };
+
+        match &agg.op {
+            Some(aggregate_expr::Op::Count(_)) => Ty::Int,
+            Some(aggregate_expr::Op::Sum(sum)) => numeric(self, &sum.field),
+            Some(aggregate_expr::Op::Avg(avg)) => {
+                numeric(self, &avg.field);
+                Ty::Float
+            }
+            Some(aggregate_expr::Op::Min(min)) => self.field_ty(path, &min.field),
+            Some(aggregate_expr::Op::Max(max)) => self.field_ty(path, &max.field),
+            None => {
+                self.issue(path, QueryIssueKind::EmptyExpression);
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn operand(&mut self, path: &str, name: &str, expr: &Option<Box<LogicalExpr>>) -> Ty {
+        let path = format!("{path}.{name}");
+        match expr {
+            Some(expr) => self.logical(&path, expr),
+            None => {
+                self.issue(&path, QueryIssueKind::EmptyExpression);
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn logical(&mut self, path: &str, expr: &LogicalExpr) -> Ty {
+        match &expr.expr {
+            Some(logical_expr::Expr::Field(name)) => self.field_ty(path, name),
+            Some(logical_expr::Expr::Literal(value)) => Ty::of_value(value),
+            Some(logical_expr::Expr::UnaryOp(op)) => self.unary(path, op),
+            Some(logical_expr::Expr::BinaryOp(op)) => self.binary(path, op),
+            Some(logical_expr::Expr::TernaryOp(op)) => self.ternary(path, op),
+            Some(logical_expr::Expr::NaryOp(op)) => {
+                if !matches!(
+                    logical_expr::nary_op::Op::try_from(op.op),
+                    Ok(logical_expr::nary_op::Op::All | logical_expr::nary_op::Op::Any)
+                ) {
+                    self.issue(path, QueryIssueKind::UnknownOperator(op.op));
+                }
+                for (i, expr) in op.exprs.iter().enumerate() {
+                    let path = format!("{path}.exprs[{i}]");
+                    let ty = self.logical(&path, expr);
+                    self.expect(&path, ty, "a boolean", Ty::is_bool);
+                }
+                Ty::Bool
+            }
+            None => {
+                self.issue(path, QueryIssueKind::EmptyExpression);
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn unary(&mut self, path: &str, op: &logical_expr::UnaryOp) -> Ty {
+        use logical_expr::unary_op::Op;
+
+        let ty = self.operand(path, "expr", &op.expr);
+        let expr = format!("{path}.expr");
+        match Op::try_from(op.op) {
+            Ok(Op::Not) => {
+                self.expect(&expr, ty, "a boolean", Ty::is_bool);
+                Ty::Bool
+            }
+            Ok(Op::IsNull | Op::IsNotNull) => Ty::Bool,
+            Ok(Op::Abs | Op::Square) => {
+                self.expect(&expr, ty, "a number", Ty::is_numeric);
+                ty.numeric(ty)
+            }
+            Ok(Op::Ln | Op::Exp | Op::Sqrt) => {
+                self.expect(&expr, ty, "a number", Ty::is_numeric);
+                Ty::Float
+            }
+            Ok(Op::Unspecified) | Err(_) => {
+                self.issue(path, QueryIssueKind::UnknownOperator(op.op));
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn binary(&mut self, path: &str, op: &logical_expr::BinaryOp) -> Ty {
+        use logical_expr::binary_op::Op;
+
+        let left = self.operand(path, "left", &op.left);
+        let right = self.operand(path, "right", &op.right);
+        let (left_path, right_path) = (format!("{path}.left"), format!("{path}.right"));
+
+        let Ok(binary_op) = Op::try_from(op.op) else {
+            self.issue(path, QueryIssueKind::UnknownOperator(op.op));
+            return Ty::Unknown;
+        };
+
+        match binary_op {
+            Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Min | Op::Max => {
+                self.expect(&left_path, left, "a number", Ty::is_numeric);
+                self.expect(&right_path, right, "a number", Ty::is_numeric);
+                left.numeric(right)
+            }
+            Op::Eq | Op::Neq | Op::Lt | Op::Lte | Op::Gt | Op::Gte => {
+                if !left.is_comparable(right) {
+                    self.issue(
+                        path,
+                        QueryIssueKind::IncompatibleTypes {
+                            op: op_name(binary_op.as_str_name()),
+                            left: left.name().to_string(),
+                            right: right.name().to_string(),
+                        },
+                    );
+                }
+                Ty::Bool
+            }
+            Op::And | Op::Or => {
+                self.expect(&left_path, left, "a boolean", Ty::is_bool);
+                self.expect(&right_path, right, "a boolean", Ty::is_bool);
+                Ty::Bool
+            }
+            Op::StartsWith => {
+                self.expect(&left_path, left, "text", Ty::is_text);
+                self.expect(&right_path, right, "text", Ty::is_text);
+                Ty::Bool
+            }
+            Op::Contains => {
+                self.membership(path, binary_op, right, left);
+                Ty::Bool
+            }
+            Op::In => {
+                self.membership(path, binary_op, left, right);
+                Ty::Bool
+            }
+            Op::MatchAll | Op::MatchAny => {
+                if let Some(logical_expr::Expr::Field(name)) =
+                    op.left.as_ref().and_then(|left| left.expr.as_ref())
+                {
+                    self.keyword_field(&left_path, name);
+                }
+                self.expect(&right_path, right, "text or a list of strings", |ty| {
+                    ty.is(|ty| matches!(ty, Ty::Text | Ty::List(ListTy::Text | ListTy::Unknown)))
+                });
+                Ty::Bool
+            }
+            Op::Coalesce => match left {
+                Ty::Unknown | Ty::Null => right,
+                _ => left,
+            },
+            Op::DatePart => {
+                self.expect(&left_path, left, "a timestamp", Ty::is_timestamp);
+                self.expect(&right_path, right, "text", Ty::is_text);
+                Ty::Int
+            }
+            Op::Unspecified => {
+                self.issue(path, QueryIssueKind::UnknownOperator(op.op));
+                Ty::Unknown
+            }
+        }
+    }
+
+    /// Checks that `item` can be an element of `collection`, i.e. an element of a list or a
+    /// substring of text.
+    fn membership(
+        &mut self,
+        path: &str,
+        op: logical_expr::binary_op::Op,
+        item: Ty,
+        collection: Ty,
+    ) {
+        let ok = match collection {
+            Ty::Unknown | Ty::Null => true,
+            Ty::Text => item.is_text(),
+            Ty::List(_) => item.is_comparable(collection.element()),
+            _ => false,
+        };
+        if !ok {
+            let (left, right) = match op {
+                logical_expr::binary_op::Op::Contains => (collection, item),
+                _ => (item, collection),
+            };
+            self.issue(
+                path,
+                QueryIssueKind::IncompatibleTypes {
+                    op: op_name(op.as_str_name()),
+                    left: left.name().to_string(),
+                    right: right.name().to_string(),
+                },
+            );
+        }
+    }
+
+    fn ternary(&mut self, path: &str, op: &logical_expr::TernaryOp) -> Ty {
+        use logical_expr::ternary_op::Op;
+
+        let x = self.operand(path, "x", &op.x);
+        let y = self.operand(path, "y", &op.y);
+        let z = self.operand(path, "z", &op.z);
+        let (x_path, y_path, z_path) = (
+            format!("{path}.x"),
+            format!("{path}.y"),
+            format!("{path}.z"),
+        );
+
+        match Op::try_from(op.op) {
+            Ok(Op::Choose) => {
+                self.expect(&x_path, x, "a boolean", Ty::is_bool);
+                match y {
+                    Ty::Unknown | Ty::Null => z,
+                    _ => y,
+                }
+            }
+            Ok(Op::RegexpMatch) => {
+                self.expect(&x_path, x, "text", Ty::is_text);
+                self.expect(&y_path, y, "text", Ty::is_text);
+                self.expect(&z_path, z, "text", Ty::is_text);
+                Ty::Bool
+            }
+            Ok(Op::Elapsed) => {
+                self.expect(&x_path, x, "a timestamp", Ty::is_timestamp);
+                self.expect(&y_path, y, "a timestamp", Ty::is_timestamp);
+                self.expect(&z_path, z, "text", Ty::is_text);
+                Ty::Unknown
+            }
+            Ok(Op::Saturate | Op::Decay) => {
+                self.expect(&x_path, x, "a number", Ty::is_numeric);
+                self.expect(&y_path, y, "a number", Ty::is_numeric);
+                self.expect(&z_path, z, "a number", Ty::is_numeric);
+                Ty::Float
+            }
+            Ok(Op::Unspecified) | Err(_) => {
+                self.issue(path, QueryIssueKind::UnknownOperator(op.op));
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn text(&mut self, path: &str, expr: &TextExpr) {
+        match &expr.expr {
+            Some(text_expr::Expr::Terms(terms)) => {
+                for (i, term) in terms.terms.iter().enumerate() {
+                    let path = format!("{path}.terms[{i}]");
+                    match &term.field {
+                        Some(field) => self.keyword_field(&path, field),
+                        None if !self.has_keyword_index() => {
+                            self.issue(&path, QueryIssueKind::NoKeywordIndex)
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Some(text_expr::Expr::And(and)) => {
+                self.text_operand(path, "left", &and.left);
+                self.text_operand(path, "right", &and.right);
+            }
+            Some(text_expr::Expr::Or(or)) => {
+                self.text_operand(path, "left", &or.left);
+                self.text_operand(path, "right", &or.right);
+            }
+            None => self.issue(path, QueryIssueKind::EmptyExpression),
+        }
+    }
+
+    fn text_operand(&mut self, path: &str, name: &str, expr: &Option<Box<TextExpr>>) {
+        let path = format!("{path}.{name}");
+        match expr {
+            Some(expr) => self.text(&path, expr),
+            None => self.issue(&path, QueryIssueKind::EmptyExpression),
+        }
+    }
+
+    fn function(&mut self, path: &str, expr: &FunctionExpr) {
+        match &expr.func {
+            Some(function_expr::Func::VectorDistance(distance)) => {
+                let path = format!("{path}.vector_distance");
+                let Some((data_type, index)) = self.indexed_field(&path, &distance.field) else {
+                    return;
+                };
+                if vector_field(data_type).is_none() {
+                    return self.invalid_field_type(&path, &distance.field, data_type, "vector");
+                }
+                if !matches!(index, Some(field_index::Index::VectorIndex(_))) {
+                    self.missing_index(&path, &distance.field, "vector");
+                }
+                #[allow(deprecated)]
+                match &distance.query {
+                    Some(query) => self.vector_query(
+                        &format!("{path}.query"),
+                        &distance.field,
+                        data_type,
+                        query,
+                    ),
+                    None if distance.dense_query.is_none() && distance.sparse_query.is_none() => {
+                        self.issue(&format!("{path}.query"), QueryIssueKind::EmptyExpression)
+                    }
+                    None => {}
+                }
+            }
+            Some(function_expr::Func::MultiVectorDistance(distance)) => {
+                let path = format!("{path}.multi_vector_distance");
+                let Some((data_type, index)) = self.indexed_field(&path, &distance.field) else {
+                    return;
+                };
+                let field_type::DataType::Matrix(field_matrix) = data_type else {
+                    return self.invalid_field_type(&path, &distance.field, data_type, "matrix");
+                };
+                if !matches!(index, Some(field_index::Index::MultiVectorIndex(_))) {
+                    self.missing_index(&path, &distance.field, "multi-vector");
+                }
+
+                let query_path = format!("{path}.query");
+                let query = distance
+                    .query
+                    .as_ref()
+                    .and_then(|query| query.value.as_ref());
+                match query {
+                    Some(value::Value::Matrix(query_matrix)) => {
+                        let elem = query_matrix.values.as_ref().map(matrix_elem);
+                        if elem != matrix_field_elem(field_matrix.value_type()) {
+                            self.invalid_query_type(
+                                &query_path,
+                                &distance.field,
+                                data_type,
+                                distance.query.as_ref(),
+                            );
+                        } else if query_matrix.num_cols != field_matrix.dimension {
+                            self.dimension_mismatch(
+                                &query_path,
+                                &distance.field,
+                                field_matrix.dimension,
+                                query_matrix.num_cols as usize,
+                            );
+                        }
+                    }
+                    Some(_) => self.invalid_query_type(
+                        &query_path,
+                        &distance.field,
+                        data_type,
+                        distance.query.as_ref(),
+                    ),
+                    None => self.issue(&query_path, QueryIssueKind::EmptyExpression),
+                }
+            }
+            Some(function_expr::Func::SemanticSimilarity(similarity)) => {
+                let path = format!("{path}.semantic_similarity");
+                if let Some((_, index)) = self.indexed_field(&path, &similarity.field) {
+                    if !matches!(index, Some(field_index::Index::SemanticIndex(_))) {
+                        self.missing_index(&path, &similarity.field, "semantic");
+                    }
+                }
+            }
+            Some(function_expr::Func::Bm25Score(_)) => {
+                if !self.has_keyword_index() {
+                    self.issue(
+                        &format!("{path}.bm25_score"),
+                        QueryIssueKind::NoKeywordIndex,
+                    );
+                }
+            }
+            None => self.issue(path, QueryIssueKind::EmptyExpression),
+        }
+    }
+
+    fn vector_query(
+        &mut self,
+        path: &str,
+        field: &str,
+        data_type: &field_type::DataType,
+        query: &Value,
+    ) {
+        use field_type::DataType;
+
+        let (elem, dimension) = vector_field(data_type).expect("vector field");
+        let matches = match (&query.value, dimension) {
+            (Some(value::Value::List(list)), Some(dimension)) => {
+                match list.values.as_ref().and_then(list_elem) {
+                    Some((query_elem, len)) if query_elem == elem => {
+                        // Binary vectors are packed, with a dimension in bits.
+                        if len != dimension as usize
+                            && !matches!(data_type, DataType::BinaryVector(_))
+                        {
+                            self.dimension_mismatch(path, field, dimension, len);
+                        }
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            (Some(value::Value::SparseVector(vector)), None) => {
+                vector.values.as_ref().map(sparse_elem) == Some(elem)
+            }
+            _ => false,
+        };
+
+        if !matches {
+            self.invalid_query_type(path, field, data_type, Some(query));
+        }
+    }
+
+    fn invalid_field_type(
+        &mut self,
+        path: &str,
+        field: &str,
+        data_type: &field_type::DataType,
+        expected: &str,
+    ) {
+        self.issue(
+            path,
+            QueryIssueKind::InvalidFieldType {
+                field: field.to_string(),
+                data_type: data_type.to_user_friendly_type_name(),
+                expected: expected.to_string(),
+            },
+        );
+    }
+
+    fn invalid_query_type(
+        &mut self,
+        path: &str,
+        field: &str,
+        data_type: &field_type::DataType,
+        query: Option<&Value>,
+    ) {
+        self.issue(
+            path,
+            QueryIssueKind::InvalidQueryType {
+                field: field.to_string(),
+                data_type: data_type.to_user_friendly_type_name(),
+                got: query
+                    .and_then(|query| query.value.as_ref())
+                    .map(|value| value.to_user_friendly_type_name())
+                    .unwrap_or_else(|| "null".to_string()),
+            },
+        );
+    }
+
+    fn dimension_mismatch(&mut self, path: &str, field: &str, expected: u32, got: usize) {
+        self.issue(
+            path,
+            QueryIssueKind::DimensionMismatch {
+                field: field.to_string(),
+                expected,
+                got,
+            },
+        );
+    }
+}
+
+/// Element kind and dimension of dense vector fields, or element kind of sparse vector fields.
+fn vector_field(data_type: &field_type::DataType) -> Option<(Elem, Option<u32>)> {
+    use field_type::DataType;
+
+    Some(match data_type {
+        DataType::F32Vector(v) => (Elem::Float, Some(v.dimension)),
+        DataType::F16Vector(v) => (Elem::Float, Some(v.dimension)),
+        DataType::F8Vector(v) => (Elem::Float, Some(v.dimension)),
+        DataType::U8Vector(v) => (Elem::U8, Some(v.dimension)),
+        DataType::I8Vector(v) => (Elem::I8, Some(v.dimension)),
+        DataType::BinaryVector(v) => (Elem::U8, Some(v.dimension)),
+        DataType::F32SparseVector(_)
+        | DataType::F16SparseVector(_)
+        | DataType::F8SparseVector(_) => (Elem::Float, None),
+        DataType::U8SparseVector(_) => (Elem::U8, None),
+        DataType::I8SparseVector(_) => (Elem::I8, None),
+        _ => return None,
+    })
+}
+
+fn list_elem(values: &list::Values) -> Option<(Elem, usize)> {
+    Some(match values {
+        list::Values::F32(v) => (Elem::Float, v.len()),
+        list::Values::F16(v) => (Elem::Float, v.len()),
+        list::Values::F8(v) => (Elem::Float, v.len()),
+        list::Values::U8(v) => (Elem::U8, v.len()),
+        list::Values::I8(v) => (Elem::I8, v.len()),
+        _ => return None,
+    })
+}
+
+fn sparse_elem(values: &sparse_vector::Values) -> Elem {
+    match values {
+        sparse_vector::Values::F32(_)
+        | sparse_vector::Values::F16(_)
+        | sparse_vector::Values::F8(_) => Elem::Float,
+        sparse_vector::Values::U8(_) => Elem::U8,
+        sparse_vector::Values::I8(_) => Elem::I8,
+    }
+}
+
+fn matrix_elem(values: &matrix::Values) -> Elem {
+    match values {
+        matrix::Values::F32(_) | matrix::Values::F16(_) | matrix::Values::F8(_) => Elem::Float,
+        matrix::Values::U8(_) => Elem::U8,
+        matrix::Values::I8(_) => Elem::I8,
+    }
+}
+
+fn matrix_field_elem(value_type: MatrixValueType) -> Option<Elem> {
+    match value_type {
+        MatrixValueType::F32 | MatrixValueType::F16 | MatrixValueType::F8 => Some(Elem::Float),
+        MatrixValueType::U8 => Some(Elem::U8),
+        MatrixValueType::I8 => Some(Elem::I8),
+        MatrixValueType::Unspecified => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::proto::control::v1::{
+        FieldIndex, KeywordIndexType, MultiVectorDistanceMetric, VectorDistanceMetric,
+    };
+    use crate::proto::data::v1::{stage::sort_stage::SortOrder, AggregateExpr, Stage};
+    use crate::query::{field, filter, fns, r#match, select};
+
+    fn schema() -> HashMap<String, FieldSpec> {
+        HashMap::from([
+            (
+                "title".to_string(),
+                FieldSpec::text(true).with_index(FieldIndex::keyword(KeywordIndexType::Text)),
+            ),
+            (
+                "summary".to_string(),
+                FieldSpec::text(false).with_index(FieldIndex::semantic()),
+            ),
+            ("year".to_string(), FieldSpec::integer(false)),
+            ("published".to_string(), FieldSpec::timestamp(false)),
+            (
+                "tags".to_string(),
+                FieldSpec::list(false, ListValueType::String),
+            ),
+            (
+                "embedding".to_string(),
+                FieldSpec::f32_vector(3, false)
+                    .with_index(FieldIndex::vector(VectorDistanceMetric::Cosine)),
+            ),
+            ("raw_embedding".to_string(), FieldSpec::f32_vector(3, false)),
+            (
+                "sparse".to_string(),
+                FieldSpec::f32_sparse_vector(false)
+                    .with_index(FieldIndex::vector(VectorDistanceMetric::DotProduct)),
+            ),
+            (
+                "tokens".to_string(),
+                FieldSpec::matrix(false, 2, MatrixValueType::F32).with_index(
+                    FieldIndex::multi_vector(MultiVectorDistanceMetric::Maxsim, None, None, None),
+                ),
+            ),
+            (
+                "author".to_string(),
+                FieldSpec::r#struct(false, [("born", FieldSpec::integer(false))]),
+            ),
+        ])
+    }
+
+    fn issues(query: Query) -> Vec<(String, QueryIssueKind)> {
+        match query.validate(&schema()) {
+            Ok(()) => vec![],
+            Err(issues) => issues.into_iter().map(|i| (i.path, i.kind)).collect(),
+        }
+    }
+
+    #[rstest]
+    #[case::filter(filter(field("year").gte(2000) & field("title").starts_with("The")))]
+    #[case::unknown_field(filter(field("rating").gt(4.5)))]
+    #[case::struct_field(filter(field("author.born").lt(1900)))]
+    #[case::timestamp(filter(field("published").gt(Value::timestamp(0_i64))))]
+    #[case::tags(filter(field("tags").contains("sci-fi") & field("title").in_(Value::list(vec!["a", "b"]))))]
+    #[case::text(filter(r#match("fox", Some("title"), None, false)).select([("score", fns::bm25_score(None, None))]))]
+    #[case::vector_distance(
+        select([("d", fns::vector_distance("embedding", vec![0.1f32, 0.2, 0.3]))])
+            .sort([(field("d"), SortOrder::Asc)])
+            .limit(10)
+            .offset(10)
+    )]
+    #[case::sparse(select([("d", fns::vector_distance("sparse", Value::f32_sparse_vector(vec![1], vec![0.5])))]))]
+    #[case::multi_vector(select([(
+        "d",
+        fns::multi_vector_distance("tokens", Value::matrix(2, vec![0.1f32, 0.2]), None),
+    )]))]
+    #[case::semantic(select([("s", fns::semantic_similarity("summary", "space"))]))]
+    #[case::computed(select([("double", field("year") * 2)]).filter(field("double").gt(10)))]
+    #[case::group_by(Query::new(vec![Stage::group_by(
+        [("year", field("year"))],
+        [("n", AggregateExpr::count(None))],
+    )])
+    .sort([(field("n"), SortOrder::Desc)])
+    .limit(5))]
+    fn test_valid(#[case] query: Query) {
+        assert_eq!(issues(query), vec![]);
+    }
+
+    #[rstest]
+    #[case::starts_with_integer(
+        filter(field("year").starts_with("19")),
+        "stages[0].filter.left",
+        QueryIssueKind::InvalidType { expected: "text".into(), got: "integer".into() },
+    )]
+    #[case::nested_path(
+        filter(field("title").eq("x") & (field("year") + "1").gt(0)),
+        "stages[0].filter.exprs[1].left.right",
+        QueryIssueKind::InvalidType { expected: "a number".into(), got: "text".into() },
+    )]
+    #[case::incompatible(
+        filter(field("year").eq("2000")),
+        "stages[0].filter",
+        QueryIssueKind::IncompatibleTypes { op: "eq".into(), left: "integer".into(), right: "text".into() },
+    )]
+    #[case::not_boolean(
+        filter(field("year") + 1),
+        "stages[0].filter",
+        QueryIssueKind::InvalidType { expected: "a boolean".into(), got: "integer".into() },
+    )]
+    #[case::no_vector_index(
+        select([("d", fns::vector_distance("raw_embedding", vec![0.1f32, 0.2, 0.3]))]),
+        "stages[0].select.d.vector_distance",
+        QueryIssueKind::MissingIndex { field: "raw_embedding".into(), index: "vector".into() },
+    )]
+    #[case::not_a_vector(
+        select([("d", fns::vector_distance("title", vec![0.1f32]))]),
+        "stages[0].select.d.vector_distance",
+        QueryIssueKind::InvalidFieldType {
+            field: "title".into(),
+            data_type: "text".into(),
+            expected: "vector".into(),
+        },
+    )]
+    #[case::unknown_vector_field(
+        select([("d", fns::vector_distance("missing", vec![0.1f32]))]),
+        "stages[0].select.d.vector_distance",
+        QueryIssueKind::UnknownField { field: "missing".into() },
+    )]
+    #[case::dimension_mismatch(
+        select([("d", fns::vector_distance("embedding", vec![0.1f32, 0.2]))]),
+        "stages[0].select.d.vector_distance.query",
+        QueryIssueKind::DimensionMismatch { field: "embedding".into(), expected: 3, got: 2 },
+    )]
+    #[case::dense_query_on_sparse(
+        select([("d", fns::vector_distance("sparse", vec![0.1f32]))]),
+        "stages[0].select.d.vector_distance.query",
+        QueryIssueKind::InvalidQueryType {
+            field: "sparse".into(),
+            data_type: "sparse_vector<f32>".into(),
+            got: "list<f32>".into(),
+        },
+    )]
+    #[case::matrix_dimension(
+        select([("d", fns::multi_vector_distance("tokens", Value::matrix(1, vec![0.1f32]), None))]),
+        "stages[0].select.d.multi_vector_distance.query",
+        QueryIssueKind::DimensionMismatch { field: "tokens".into(), expected: 2, got: 1 },
+    )]
+    #[case::no_semantic_index(
+        select([("s", fns::semantic_similarity("title", "space"))]),
+        "stages[0].select.s.semantic_similarity",
+        QueryIssueKind::MissingIndex { field: "title".into(), index: "semantic".into() },
+    )]
+    #[case::match_without_keyword_index(
+        filter(r#match("fox", Some("summary"), None, false)),
+        "stages[0].filter.terms[0]",
+        QueryIssueKind::MissingIndex { field: "summary".into(), index: "keyword".into() },
+    )]
+    #[case::match_all_without_keyword_index(
+        filter(field("summary").match_all("fox")),
+        "stages[0].filter.left",
+        QueryIssueKind::MissingIndex { field: "summary".into(), index: "keyword".into() },
+    )]
+    #[case::sort_without_limit(
+        filter(field("year").gt(0)).sort("year"),
+        "stages[1]",
+        QueryIssueKind::SortWithoutLimit,
+    )]
+    #[case::offset_without_limit(
+        filter(field("year").gt(0)).offset(10),
+        "stages[1]",
+        QueryIssueKind::OffsetWithoutLimit,
+    )]
+    #[case::zero_limit(filter(field("year").gt(0)).limit(0), "stages[1]", QueryIssueKind::ZeroLimit)]
+    #[case::after_group_by(
+        Query::new(vec![Stage::group_by(
+            [("year", field("year"))],
+            [("n", AggregateExpr::count(None))],
+        )])
+        .filter(field("title").eq("x")),
+        "stages[1].filter.left",
+        QueryIssueKind::UnknownField { field: "title".into() },
+    )]
+    #[case::empty(Query::new(vec![]), "stages", QueryIssueKind::EmptyQuery)]
+    fn test_invalid(#[case] query: Query, #[case] path: &str, #[case] kind: QueryIssueKind) {
+        assert_eq!(issues(query), vec![(path.to_string(), kind)]);
+    }
+
+    #[test]
+    fn test_bm25_without_keyword_index() {
+        let schema = HashMap::from([("title".to_string(), FieldSpec::text(false))]);
+        let issues = select([("score", fns::bm25_score(None, None))])
+            .validate(&schema)
+            .unwrap_err();
+
+        assert_eq!(
+            issues,
+            vec![QueryIssue {
+                path: "stages[0].select.score.bm25_score".to_string(),
+                kind: QueryIssueKind::NoKeywordIndex,
+            }]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "stages[0].select.score.bm25_score: no field has a keyword index"
+        );
+    }
+
+    #[test]
+    fn test_reports_every_issue() {
+        let query = filter(field("year").starts_with("19"))
+            .select([("d", fns::vector_distance("embedding", vec![0.1f32]))])
+            .sort("d");
+
+        assert_eq!(query.validate(&schema()).unwrap_err().len(), 3);
+    }
+}
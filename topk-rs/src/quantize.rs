@@ -0,0 +1,365 @@
+//! Client-side quantization of `f32` vectors into the encodings of TopK vector fields.
+//!
+//! A [`Quantization`] is derived from the type of a vector or matrix field, and encodes both
+//! the stored documents and the `vector_distance` queries, so that both sides of the distance
+//! use the same encoding:
+//!
+//! ```
+//! use topk_rs::proto::v1::control::FieldType;
+//! use topk_rs::quantize::{Quantization, ScalarQuantizer};
+//!
+//! let sample = [vec![-0.5, 0.25], vec![0.75, -1.0]];
+//! let quantizer = ScalarQuantizer::fit_min_max(sample.iter().map(Vec::as_slice)).unwrap();
+//! let quantization = Quantization::for_field(&FieldType::u8_vector(2), Some(quantizer)).unwrap();
+//!
+//! let embedding = quantization.vector(&[0.1, 0.2]);
+//! let distance = quantization.vector_distance("embedding", &[0.3, -0.4]);
+//! ```
+
+use float8::F8E4M3;
+
+use crate::error::Error;
+use crate::proto::v1::control::field_type::DataType;
+use crate::proto::v1::control::field_type_matrix::MatrixValueType;
+use crate::proto::v1::control::FieldType;
+use crate::proto::v1::data::{FunctionExpr, Value};
+
+/// Converts `f32` values to `f16`, rounding to the nearest representable value.
+pub fn to_f16(values: &[f32]) -> Vec<half::f16> {
+    values.iter().map(|v| half::f16::from_f32(*v)).collect()
+}
+
+/// Converts `f32` values to `F8E4M3`, saturating values outside of its range (±448).
+pub fn to_f8(values: &[f32]) -> Vec<F8E4M3> {
+    values
+        .iter()
+        .map(|v| F8E4M3::from_f32(v.clamp(-F8_MAX, F8_MAX)))
+        .collect()
+}
+
+const F8_MAX: f32 = 448.0;
+
+/// Packs the signs of `values` into bits, most significant bit first, for `binary_vector` fields.
+///
+/// Positive values are encoded as `1`, the last byte is padded with zeros.
+pub fn pack_bits(values: &[f32]) -> Vec<u8> {
+    values
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| **v > 0.0)
+                .fold(0u8, |byte, (i, _)| byte | (0x80 >> i))
+        })
+        .collect()
+}
+
+/// Scalar quantization of `f32` values into `u8` and `i8`, calibrated on a range of values.
+///
+/// `u8` values map `[min, max]` linearly onto `[0, 255]`. `i8` values are symmetric around zero,
+/// mapping `[-m, m]` onto `[-127, 127]` with `m = max(|min|, |max|)`, which preserves the sign
+/// of dot products. Values outside of the range are clipped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScalarQuantizer {
+    min: f32,
+    max: f32,
+}
+
+impl ScalarQuantizer {
+    pub fn new(min: f32, max: f32) -> Result<Self, Error> {
+        if !(min.is_finite() && max.is_finite() && min < max) {
+            return Err(Error::InvalidArgument(format!(
+                "invalid quantization range: [{min}, {max}]"
+            )));
+        }
+        Ok(Self { min, max })
+    }
+
+    /// Calibrates on the minimum and maximum of a sample of vectors.
+    pub fn fit_min_max<'a>(sample: impl IntoIterator<Item = &'a [f32]>) -> Result<Self, Error> {
+        Self::fit_percentile(sample, 0.0, 100.0)
+    }
+
+    /// Calibrates on the `lower` and `upper` percentiles (`0.0..=100.0`) of a sample of vectors,
+    /// clipping outliers. E.g. `1.0` and `99.0` ignore the 1% most extreme values on each side.
+    pub fn fit_percentile<'a>(
+        sample: impl IntoIterator<Item = &'a [f32]>,
+        lower: f32,
+        upper: f32,
+    ) -> Result<Self, Error> {
+        if !(0.0..=100.0).contains(&lower) || !(0.0..=100.0).contains(&upper) || lower >= upper {
+            return Err(Error::InvalidArgument(format!(
+                "invalid percentiles: {lower}, {upper}"
+            )));
+        }
+
+        let mut values = sample
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|v| v.is_finite())
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            return Err(Error::InvalidArgument(
+                "cannot calibrate quantization on an empty sample".to_string(),
+            ));
+        }
+        values.sort_by(f32::total_cmp);
+
+        let percentile = |p: f32| {
+            let rank = (p / 100.0 * (values.len() - 1) as f32).round() as usize;
+            values[rank]
+        };
+        Self::new(percentile(lower), percentile(upper))
+    }
+
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    pub fn quantize_u8(&self, values: &[f32]) -> Vec<u8> {
+        let scale = 255.0 / (self.max - self.min);
+        values
+            .iter()
+            .map(|v| ((v - self.min) * scale).round().clamp(0.0, 255.0) as u8)
+            .collect()
+    }
+
+    pub fn dequantize_u8(&self, values: &[u8]) -> Vec<f32> {
+        let scale = (self.max - self.min) / 255.0;
+        values
+            .iter()
+            .map(|v| self.min + *v as f32 * scale)
+            .collect()
+    }
+
+    pub fn quantize_i8(&self, values: &[f32]) -> Vec<i8> {
+        let scale = 127.0 / self.abs_max();
+        values
+            .iter()
+            .map(|v| (v * scale).round().clamp(-127.0, 127.0) as i8)
+            .collect()
+    }
+
+    pub fn dequantize_i8(&self, values: &[i8]) -> Vec<f32> {
+        let scale = self.abs_max() / 127.0;
+        values.iter().map(|v| *v as f32 * scale).collect()
+    }
+
+    fn abs_max(&self) -> f32 {
+        self.min.abs().max(self.max.abs())
+    }
+}
+
+/// Encoding of a vector or matrix field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantization {
+    F32,
+    F16,
+    F8,
+    U8(ScalarQuantizer),
+    I8(ScalarQuantizer),
+    Binary,
+}
+
+impl Quantization {
+    /// Encoding of a vector or matrix field. `u8` and `i8` fields need a calibrated `quantizer`.
+    pub fn for_field(
+        field_type: &FieldType,
+        quantizer: Option<ScalarQuantizer>,
+    ) -> Result<Self, Error> {
+        let scalar = |wrap: fn(ScalarQuantizer) -> Self| {
+            quantizer.map(wrap).ok_or_else(|| {
+                Error::InvalidArgument("u8 and i8 fields need a scalar quantizer".to_string())
+            })
+        };
+
+        match &field_type.data_type {
+            Some(DataType::F32Vector(_)) => Ok(Self::F32),
+            Some(DataType::F16Vector(_)) => Ok(Self::F16),
+            Some(DataType::F8Vector(_)) => Ok(Self::F8),
+            Some(DataType::U8Vector(_)) => scalar(Self::U8),
+            Some(DataType::I8Vector(_)) => scalar(Self::I8),
+            Some(DataType::BinaryVector(_)) => Ok(Self::Binary),
+            Some(DataType::Matrix(matrix)) => match matrix.value_type() {
+                MatrixValueType::F32 => Ok(Self::F32),
+                MatrixValueType::F16 => Ok(Self::F16),
+                MatrixValueType::F8 => Ok(Self::F8),
+                MatrixValueType::U8 => scalar(Self::U8),
+                MatrixValueType::I8 => scalar(Self::I8),
+                MatrixValueType::Unspecified => Err(Error::InvalidArgument(
+                    "matrix field has no value type".to_string(),
+                )),
+            },
+            data_type => Err(Error::InvalidArgument(format!(
+                "not a vector or matrix field: {data_type:?}"
+            ))),
+        }
+    }
+
+    /// Encodes a vector, for documents and queries.
+    pub fn vector(&self, values: &[f32]) -> Value {
+        match self {
+            Self::F32 => Value::list(values.to_vec()),
+            Self::F16 => Value::list(to_f16(values)),
+            Self::F8 => Value::list(to_f8(values)),
+            Self::U8(quantizer) => Value::list(quantizer.quantize_u8(values)),
+            Self::I8(quantizer) => Value::list(quantizer.quantize_i8(values)),
+            Self::Binary => Value::list(pack_bits(values)),
+        }
+    }
+
+    /// Encodes a multi-vector given as rows of `num_cols` values, for documents and queries.
+    pub fn matrix(&self, num_cols: u32, values: &[f32]) -> Result<Value, Error> {
+        if num_cols == 0 || !values.len().is_multiple_of(num_cols as usize) {
+            return Err(Error::InvalidArgument(format!(
+                "{} values are not rows of {num_cols} columns",
+                values.len()
+            )));
+        }
+
+        Ok(match self {
+            Self::F32 => Value::matrix(num_cols, values.to_vec()),
+            Self::F16 => Value::matrix(num_cols, to_f16(values)),
+            Self::F8 => Value::matrix(num_cols, to_f8(values)),
+            Self::U8(quantizer) => Value::matrix(num_cols, quantizer.quantize_u8(values)),
+            Self::I8(quantizer) => Value::matrix(num_cols, quantizer.quantize_i8(values)),
+            Self::Binary => {
+                return Err(Error::InvalidArgument(
+                    "binary matrices are not supported".to_string(),
+                ))
+            }
+        })
+    }
+
+    /// `vector_distance` of `field` to the encoded `query`.
+    pub fn vector_distance(&self, field: impl Into<String>, query: &[f32]) -> FunctionExpr {
+        FunctionExpr::vector_distance(field, self.vector(query), false)
+    }
+
+    /// `multi_vector_distance` of `field` to the encoded `query` rows of `num_cols` values.
+    pub fn multi_vector_distance(
+        &self,
+        field: impl Into<String>,
+        num_cols: u32,
+        query: &[f32],
+        candidates: Option<u32>,
+    ) -> Result<FunctionExpr, Error> {
+        Ok(FunctionExpr::multi_vector_distance(
+            field,
+            self.matrix(num_cols, query)?,
+            candidates,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_float_conversions() {
+        assert_eq!(
+            to_f16(&[0.5, -2.0]),
+            [half::f16::from_f32(0.5), half::f16::from_f32(-2.0)]
+        );
+        assert_eq!(
+            to_f8(&[1.0, 1000.0, -1000.0]),
+            [
+                F8E4M3::from_f32(1.0),
+                F8E4M3::from_f32(448.0),
+                F8E4M3::from_f32(-448.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pack_bits() {
+        assert_eq!(pack_bits(&[]), Vec::<u8>::new());
+        assert_eq!(
+            pack_bits(&[1.0, -1.0, 0.0, 2.0, 0.1, -0.1, -3.0, 4.0, 5.0]),
+            [0b1001_1001, 0b1000_0000]
+        );
+    }
+
+    #[test]
+    fn test_scalar_quantizer() {
+        let quantizer = ScalarQuantizer::new(-1.0, 3.0).unwrap();
+        assert_eq!(
+            quantizer.quantize_u8(&[-1.0, 1.0, 3.0, 10.0]),
+            [0, 128, 255, 255]
+        );
+        assert_eq!(quantizer.quantize_i8(&[-3.0, 1.5, -10.0]), [-127, 64, -127]);
+
+        let values = quantizer.dequantize_u8(&quantizer.quantize_u8(&[0.3]));
+        assert!((values[0] - 0.3).abs() < 4.0 / 255.0);
+        let values = quantizer.dequantize_i8(&quantizer.quantize_i8(&[-0.7]));
+        assert!((values[0] + 0.7).abs() < 3.0 / 127.0);
+
+        assert!(ScalarQuantizer::new(1.0, 1.0).is_err());
+        assert!(ScalarQuantizer::new(f32::NAN, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_calibration() {
+        let sample = (0..=100).map(|i| vec![i as f32]).collect::<Vec<_>>();
+        let sample = || sample.iter().map(Vec::as_slice);
+
+        let quantizer = ScalarQuantizer::fit_min_max(sample()).unwrap();
+        assert_eq!((quantizer.min(), quantizer.max()), (0.0, 100.0));
+
+        let quantizer = ScalarQuantizer::fit_percentile(sample(), 5.0, 95.0).unwrap();
+        assert_eq!((quantizer.min(), quantizer.max()), (5.0, 95.0));
+
+        assert!(ScalarQuantizer::fit_percentile(sample(), 50.0, 50.0).is_err());
+        assert!(ScalarQuantizer::fit_min_max(std::iter::empty()).is_err());
+    }
+
+    #[test]
+    fn test_quantization_for_field() {
+        let quantizer = ScalarQuantizer::new(-1.0, 1.0).unwrap();
+
+        assert_eq!(
+            Quantization::for_field(&FieldType::f16_vector(2), None).unwrap(),
+            Quantization::F16
+        );
+        assert_eq!(
+            Quantization::for_field(&FieldType::i8_vector(2), Some(quantizer)).unwrap(),
+            Quantization::I8(quantizer)
+        );
+        assert_eq!(
+            Quantization::for_field(&FieldType::matrix(2, MatrixValueType::U8), Some(quantizer))
+                .unwrap(),
+            Quantization::U8(quantizer)
+        );
+        assert!(Quantization::for_field(&FieldType::u8_vector(2), None).is_err());
+        assert!(Quantization::for_field(&FieldType::text(), None).is_err());
+    }
+
+    #[test]
+    fn test_documents_and_queries_share_encoding() {
+        let quantization = Quantization::for_field(&FieldType::binary_vector(1), None).unwrap();
+        assert_eq!(
+            quantization.vector(&[1.0, -1.0]),
+            Value::list(vec![0b1000_0000u8])
+        );
+
+        let quantization = Quantization::F8;
+        assert_eq!(
+            quantization.vector_distance("embedding", &[0.5, 1.0]),
+            FunctionExpr::vector_distance("embedding", Value::list(to_f8(&[0.5, 1.0])), false)
+        );
+
+        assert_eq!(
+            Quantization::F16.matrix(2, &[1.0, 2.0, 3.0, 4.0]).unwrap(),
+            Value::matrix(2, to_f16(&[1.0, 2.0, 3.0, 4.0]))
+        );
+        assert!(Quantization::F16.matrix(3, &[1.0, 2.0]).is_err());
+        assert!(Quantization::Binary.matrix(1, &[1.0]).is_err());
+    }
+}
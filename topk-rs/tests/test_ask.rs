@@ -25,14 +25,14 @@ async fn test_ask(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new())
+        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new(), None)
         .await
         .expect("could not upsert file");
 
     // Wait for file to be processed
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait handle");
 
@@ -85,13 +85,13 @@ async fn test_ask_include_content_true_returns_chunks(ctx: &mut ProjectTestConte
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new())
+        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new(), None)
         .await
         .expect("could not upsert file");
 
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait handle");
 
@@ -130,13 +130,13 @@ async fn test_ask_include_content_false_strips_chunks(ctx: &mut ProjectTestConte
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new())
+        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new(), None)
         .await
         .expect("could not upsert file");
 
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait handle");
 
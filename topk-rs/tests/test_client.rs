@@ -44,7 +44,6 @@ async fn test_protobuf_recursion_limit_returns_invalid_argument(ctx: &mut Projec
                 .sort([(field("id"), SortOrder::Asc)])
                 .limit(10),
             None,
-            None,
         )
         .await
         .expect_err("Query should fail due to protobuf recursion limit");
@@ -6,6 +6,7 @@ use topk_rs::proto::v1::control::FieldSpec;
 use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
 use topk_rs::query::{field, select};
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::ProjectTestContext;
@@ -16,7 +17,7 @@ async fn test_delete_from_non_existent_collection(ctx: &mut ProjectTestContext)
     let err = ctx
         .client
         .collection("missing")
-        .delete(vec!["one".to_string()])
+        .delete(vec!["one".to_string()], None)
         .await
         .expect_err("should not be able to delete document from non-existent collection");
 
@@ -36,10 +37,13 @@ async fn test_delete_document(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "one", "rank" => 1),
-            doc!("_id" => "two", "rank" => 2),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "one", "rank" => 1),
+                doc!("_id" => "two", "rank" => 2),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert document");
     assert_eq!(&lsn, "1");
@@ -47,14 +51,14 @@ async fn test_delete_document(ctx: &mut ProjectTestContext) {
     // wait for write to be flushed
     ctx.client
         .collection(&collection.name)
-        .count(None, None)
+        .count(None)
         .await
         .expect("could not query documents");
 
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .delete(vec!["one".to_string()])
+        .delete(vec!["one".to_string()], None)
         .await
         .expect("could not delete document");
     assert_eq!(&lsn, "2");
@@ -66,8 +70,7 @@ async fn test_delete_document(ctx: &mut ProjectTestContext) {
             select([("title", field("title"))])
                 .sort([(field("rank"), SortOrder::Asc)])
                 .limit(100),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query documents");
@@ -89,7 +92,7 @@ async fn test_delete_non_existent_document(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .delete(vec!["one".to_string()])
+        .delete(vec!["one".to_string()], None)
         .await
         .expect("could not delete document");
     assert_eq!(&lsn, "1");
@@ -117,6 +120,7 @@ async fn test_delete_with_filter(ctx: &mut ProjectTestContext) {
                         doc!("_id" => format!("{}", idx), "batch_idx" => batch_idx)
                     })
                     .collect(),
+                None,
             )
             .await
             .expect("could not upsert document");
@@ -125,7 +129,7 @@ async fn test_delete_with_filter(ctx: &mut ProjectTestContext) {
     }
     assert_eq!(
         collection
-            .count(Some(lsn.clone()), None)
+            .count(Some(RequestOptions::new().with_lsn(lsn.clone())))
             .await
             .expect("could not count documents"),
         15
@@ -133,14 +137,14 @@ async fn test_delete_with_filter(ctx: &mut ProjectTestContext) {
 
     // Delete using filter
     let lsn = collection
-        .delete(field("batch_idx").gte(literal(1)))
+        .delete(field("batch_idx").gte(literal(1)), None)
         .await
         .expect("could not delete document");
     assert_eq!(lsn, "4");
 
     assert_eq!(
         collection
-            .count(Some(lsn.clone()), None)
+            .count(Some(RequestOptions::new().with_lsn(lsn.clone())))
             .await
             .expect("could not count documents"),
         5
@@ -153,6 +157,7 @@ async fn test_delete_with_filter(ctx: &mut ProjectTestContext) {
             (0..5)
                 .map(|i| doc!("_id" => format!("{}", 15 + i), "batch_idx" => 3, "updated" => true))
                 .collect(),
+            None,
         )
         .await
         .expect("could not upsert document");
@@ -165,8 +170,7 @@ async fn test_delete_with_filter(ctx: &mut ProjectTestContext) {
             select([("_id", field("_id"))])
                 .sort([(field("batch_idx"), SortOrder::Asc)])
                 .limit(100),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query documents");
@@ -179,7 +183,7 @@ async fn test_delete_with_filter(ctx: &mut ProjectTestContext) {
 
     // Delete updated documents
     let lsn = collection
-        .delete(field("updated").eq(literal(true)))
+        .delete(field("updated").eq(literal(true)), None)
         .await
         .expect("could not delete document");
     assert_eq!(lsn, "6");
@@ -190,8 +194,7 @@ async fn test_delete_with_filter(ctx: &mut ProjectTestContext) {
             select([("_id", field("_id"))])
                 .sort([(field("batch_idx"), SortOrder::Asc)])
                 .limit(100),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query documents");
@@ -214,11 +217,9 @@ async fn test_delete_by_id_prefix(ctx: &mut ProjectTestContext) {
     let mut lsn = String::new();
     for batch_idx in 0..3 {
         lsn = collection
-            .upsert(
-                (0..5)
+            .upsert((0..5)
                     .map(|i| doc!("_id" => format!("batch-{batch_idx}/id-{i}"), "batch_idx" => batch_idx))
-                    .collect(),
-            )
+                    .collect(), None)
             .await
             .expect("could not upsert document");
 
@@ -226,7 +227,7 @@ async fn test_delete_by_id_prefix(ctx: &mut ProjectTestContext) {
     }
     assert_eq!(
         collection
-            .count(Some(lsn.clone()), None)
+            .count(Some(RequestOptions::new().with_lsn(lsn.clone())))
             .await
             .expect("could not count documents"),
         15
@@ -234,14 +235,14 @@ async fn test_delete_by_id_prefix(ctx: &mut ProjectTestContext) {
 
     // Delete using filter
     let lsn = collection
-        .delete(field("_id").starts_with(literal("batch-1/")))
+        .delete(field("_id").starts_with(literal("batch-1/")), None)
         .await
         .expect("could not delete document");
     assert_eq!(lsn, "4");
 
     assert_eq!(
         collection
-            .count(Some(lsn.clone()), None)
+            .count(Some(RequestOptions::new().with_lsn(lsn.clone())))
             .await
             .expect("could not count documents"),
         10
@@ -249,7 +250,10 @@ async fn test_delete_by_id_prefix(ctx: &mut ProjectTestContext) {
 
     // Verify expected documents
     let doc_ids = collection
-        .query(select([("_id", field("_id"))]).limit(100), Some(lsn), None)
+        .query(
+            select([("_id", field("_id"))]).limit(100),
+            Some(RequestOptions::new().with_lsn(lsn)),
+        )
         .await
         .expect("could not query documents");
 
@@ -283,6 +287,7 @@ async fn test_delete_with_invalid_filter(ctx: &mut ProjectTestContext) {
                         doc!("_id" => format!("{}", idx), "batch_idx" => batch_idx)
                     })
                     .collect(),
+                None,
             )
             .await
             .expect("could not upsert document");
@@ -292,7 +297,10 @@ async fn test_delete_with_invalid_filter(ctx: &mut ProjectTestContext) {
 
     // A non-boolean operand to `or` is rejected while compiling the expr.
     collection
-        .delete(field("batch_idx").gte(literal(1)).or(field("batch_idx")))
+        .delete(
+            field("batch_idx").gte(literal(1)).or(field("batch_idx")),
+            None,
+        )
         .await
         .expect_err("delete should fail to compile filter");
 
@@ -300,14 +308,14 @@ async fn test_delete_with_invalid_filter(ctx: &mut ProjectTestContext) {
     // unknown bare field (which compiles to null).
     for expr in [field("batch_idx"), field("unknown_field")] {
         collection
-            .delete(expr)
+            .delete(expr, None)
             .await
             .expect_err("delete should fail with non-boolean filter");
     }
 
     assert_eq!(
         collection
-            .count(Some(lsn), None)
+            .count(Some(RequestOptions::new().with_lsn(lsn)))
             .await
             .expect("could not count documents"),
         15
@@ -332,13 +340,13 @@ async fn test_delete_with_filter_on_absent_optional_field(ctx: &mut ProjectTestC
 
     // Document omits the optional `active` field.
     let lsn = collection
-        .upsert(vec![doc!("_id" => "1")])
+        .upsert(vec![doc!("_id" => "1")], None)
         .await
         .expect("could not upsert document");
 
     assert_eq!(
         collection
-            .count(Some(lsn.clone()), None)
+            .count(Some(RequestOptions::new().with_lsn(lsn.clone())))
             .await
             .expect("could not count documents"),
         1
@@ -346,13 +354,13 @@ async fn test_delete_with_filter_on_absent_optional_field(ctx: &mut ProjectTestC
 
     // A bare field is not a boolean predicate.
     collection
-        .delete(field("active"))
+        .delete(field("active"), None)
         .await
         .expect_err("delete should fail with non-boolean filter");
 
     assert_eq!(
         collection
-            .count(Some(lsn), None)
+            .count(Some(RequestOptions::new().with_lsn(lsn)))
             .await
             .expect("could not count documents"),
         1
@@ -360,20 +368,20 @@ async fn test_delete_with_filter_on_absent_optional_field(ctx: &mut ProjectTestC
 
     // Second segment: doc that has the optional field.
     collection
-        .upsert(vec![doc!("_id" => "2", "active" => true)])
+        .upsert(vec![doc!("_id" => "2", "active" => true)], None)
         .await
         .expect("could not upsert document");
 
     // A boolean filter on the optional field validates against both segments
     // (one lacking the column) and deletes only the matching doc.
     let lsn = collection
-        .delete(field("active").eq(literal(true)))
+        .delete(field("active").eq(literal(true)), None)
         .await
         .expect("delete should succeed with boolean filter");
 
     assert_eq!(
         collection
-            .count(Some(lsn), None)
+            .count(Some(RequestOptions::new().with_lsn(lsn)))
             .await
             .expect("could not count documents"),
         1
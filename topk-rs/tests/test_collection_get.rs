@@ -7,6 +7,7 @@ use topk_rs::proto::v1::data::ConsistencyLevel;
 use topk_rs::proto::v1::data::Document;
 use topk_rs::query::field;
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::dataset;
@@ -18,7 +19,7 @@ async fn test_get_from_non_existent_collection(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection("missing")
-        .get(["doc1"], None, None, None)
+        .get(["doc1"], None, None)
         .await
         .expect_err("should not be able to get document from non-existent collection");
 
@@ -33,7 +34,7 @@ async fn test_get_non_existent_document(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["missing"], None, None, None)
+        .get(["missing"], None, None)
         .await
         .expect("get failed");
 
@@ -51,7 +52,7 @@ async fn test_get_document(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["lotr"], None, None, None)
+        .get(["lotr"], None, None)
         .await
         .expect("could not get document");
 
@@ -66,7 +67,7 @@ async fn test_get_multiple_documents(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["lotr", "moby"], None, None, None)
+        .get(["lotr", "moby"], None, None)
         .await
         .expect("could not get documents");
 
@@ -95,7 +96,6 @@ async fn test_get_document_fields(ctx: &mut ProjectTestContext) {
             ["lotr"],
             Some(vec!["title".to_string(), "published_year".to_string()]),
             None,
-            None,
         )
         .await
         .expect("could not get document");
@@ -127,14 +127,18 @@ async fn test_get_updated_document(ctx: &mut ProjectTestContext) {
 
     ctx.client
         .collection(&collection.name)
-        .upsert(vec![lotr.clone()])
+        .upsert(vec![lotr.clone()], None)
         .await
         .expect("could not upsert document");
 
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["lotr"], None, None, Some(ConsistencyLevel::Strong))
+        .get(
+            ["lotr"],
+            None,
+            Some(RequestOptions::new().with_consistency(ConsistencyLevel::Strong)),
+        )
         .await
         .expect("could not get document");
 
@@ -148,14 +152,18 @@ async fn test_get_deleted_document(ctx: &mut ProjectTestContext) {
 
     ctx.client
         .collection(&collection.name)
-        .delete(vec!["lotr".to_string()])
+        .delete(vec!["lotr".to_string()], None)
         .await
         .expect("could not upsert document");
 
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["lotr"], None, None, Some(ConsistencyLevel::Strong))
+        .get(
+            ["lotr"],
+            None,
+            Some(RequestOptions::new().with_consistency(ConsistencyLevel::Strong)),
+        )
         .await
         .expect("could not get document");
 
@@ -183,6 +191,7 @@ async fn test_get_with_delete_filter(ctx: &mut ProjectTestContext) {
                         doc!("_id" => format!("{}", idx), "batch_idx" => batch_idx)
                     })
                     .collect(),
+                None,
             )
             .await
             .expect("could not upsert document");
@@ -192,14 +201,18 @@ async fn test_get_with_delete_filter(ctx: &mut ProjectTestContext) {
 
     // Delete using filter
     let lsn = collection
-        .delete(field("batch_idx").gte(literal(1)))
+        .delete(field("batch_idx").gte(literal(1)), None)
         .await
         .expect("could not delete document");
     assert_eq!(lsn, "4");
 
     // Get documents
     let docs = collection
-        .get(["2", "8", "13"], None, Some(lsn.clone()), None)
+        .get(
+            ["2", "8", "13"],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn.clone())),
+        )
         .await
         .expect("could not get documents");
 
@@ -216,6 +229,7 @@ async fn test_get_with_delete_filter(ctx: &mut ProjectTestContext) {
             (10..15)
                 .map(|i| doc!("_id" => format!("{}", i), "batch_idx" => 2, "updated" => true))
                 .collect(),
+            None,
         )
         .await
         .expect("could not upsert document");
@@ -223,7 +237,11 @@ async fn test_get_with_delete_filter(ctx: &mut ProjectTestContext) {
 
     // Get documents
     let docs = collection
-        .get(["2", "8", "13"], None, Some(lsn.clone()), None)
+        .get(
+            ["2", "8", "13"],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn.clone())),
+        )
         .await
         .expect("could not get documents");
 
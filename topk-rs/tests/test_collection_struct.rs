@@ -7,6 +7,7 @@ use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
 use topk_rs::proto::v1::data::Value;
 use topk_rs::query::{field, fns, select};
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::ProjectTestContext;
@@ -44,20 +45,23 @@ async fn test_struct_round_trip(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "outer" => Value::r#struct([(
-                "inner",
-                Value::r#struct([("leaf", "v".into()), ("sibling", "s".into())]),
-            )]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "outer" => Value::r#struct([(
+                    "inner",
+                    Value::r#struct([("leaf", "v".into()), ("sibling", "s".into())]),
+                )]),
+            )],
+            None,
+        )
         .await
         .expect("could not upsert");
 
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["one"], None, Some(lsn), None)
+        .get(["one"], None, Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not get document");
 
@@ -99,24 +103,27 @@ async fn test_struct_query(ctx: &mut ProjectTestContext) {
 
     ctx.client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!(
-                "_id" => "old",
-                "meta" => Value::r#struct([
-                    ("author", "alice".into()),
-                    ("year", 1999i64.into()),
-                    ("tag", "classic".into()),
-                ]),
-            ),
-            doc!(
-                "_id" => "new",
-                "meta" => Value::r#struct([
-                    ("author", "bob".into()),
-                    ("year", 2024i64.into()),
-                    ("tag", "fresh".into()),
-                ]),
-            ),
-        ])
+        .upsert(
+            vec![
+                doc!(
+                    "_id" => "old",
+                    "meta" => Value::r#struct([
+                        ("author", "alice".into()),
+                        ("year", 1999i64.into()),
+                        ("tag", "classic".into()),
+                    ]),
+                ),
+                doc!(
+                    "_id" => "new",
+                    "meta" => Value::r#struct([
+                        ("author", "bob".into()),
+                        ("year", 2024i64.into()),
+                        ("tag", "fresh".into()),
+                    ]),
+                ),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert");
 
@@ -130,7 +137,6 @@ async fn test_struct_query(ctx: &mut ProjectTestContext) {
                 .limit(10)
                 .fetch(["meta.tag"]),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -185,7 +191,7 @@ async fn test_struct_semantic_index_on_sub_field(ctx: &mut ProjectTestContext) {
                 "_id" => "python",
                 "meta" => Value::r#struct([("description", "a snake".into())]),
             ),
-        ])
+        ], None)
         .await
         .expect("could not upsert");
 
@@ -199,8 +205,7 @@ async fn test_struct_semantic_index_on_sub_field(ctx: &mut ProjectTestContext) {
             )])
             .sort((field("sim"), SortOrder::Asc))
             .limit(2),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query");
@@ -240,10 +245,13 @@ async fn test_struct_update(ctx: &mut ProjectTestContext) {
 
     ctx.client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "meta" => Value::r#struct([("author", "alice".into()), ("title", "v1".into())]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "meta" => Value::r#struct([("author", "alice".into()), ("title", "v1".into())]),
+            )],
+            None,
+        )
         .await
         .expect("could not upsert");
 
@@ -256,6 +264,7 @@ async fn test_struct_update(ctx: &mut ProjectTestContext) {
                 "meta" => Value::r#struct([("title", "v2".into())]),
             )],
             true,
+            None,
         )
         .await
         .expect("could not update");
@@ -263,7 +272,7 @@ async fn test_struct_update(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["one"], None, Some(lsn), None)
+        .get(["one"], None, Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not get document");
 
@@ -293,17 +302,24 @@ async fn test_literal_dotted_field_roundtrip(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "meta.foo" => 3i64,
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "meta.foo" => 3i64,
+            )],
+            None,
+        )
         .await
         .expect("could not upsert");
 
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["one"], None, Some(lsn.clone()), None)
+        .get(
+            ["one"],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn.clone())),
+        )
         .await
         .expect("could not get document");
     let one = docs.get("one").expect("missing doc");
@@ -317,8 +333,7 @@ async fn test_literal_dotted_field_roundtrip(ctx: &mut ProjectTestContext) {
             select([("meta.foo", field("meta.foo"))])
                 .sort((field("meta.foo"), SortOrder::Asc))
                 .limit(10),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query");
@@ -342,11 +357,14 @@ async fn test_upsert_mixed_dotted_and_struct_rejected(ctx: &mut ProjectTestConte
     let err = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "meta.foo" => 3i64,
-            "meta" => Value::r#struct([("bar", 4i64.into())]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "meta.foo" => 3i64,
+                "meta" => Value::r#struct([("bar", 4i64.into())]),
+            )],
+            None,
+        )
         .await
         .expect_err("mixing literal dotted key with a struct sibling should fail");
 
@@ -406,10 +424,13 @@ async fn test_upsert_struct_dotted_sub_field_rejected(ctx: &mut ProjectTestConte
     let err = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "meta" => Value::r#struct([("a.b", "v".into())]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "meta" => Value::r#struct([("a.b", "v".into())]),
+            )],
+            None,
+        )
         .await
         .expect_err("dotted sub-field name should fail");
 
@@ -453,20 +474,23 @@ async fn test_struct_get_all_fields(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "meta" => Value::r#struct([
-                ("author", "alice".into()),
-                ("year", 2024i64.into()),
-            ]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "meta" => Value::r#struct([
+                    ("author", "alice".into()),
+                    ("year", 2024i64.into()),
+                ]),
+            )],
+            None,
+        )
         .await
         .expect("could not upsert");
 
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["one"], None, Some(lsn), None)
+        .get(["one"], None, Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not get document");
     let one = docs.get("one").expect("missing doc");
@@ -507,20 +531,27 @@ async fn test_struct_get_returns_whole_struct(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "meta" => Value::r#struct([
-                ("author", "alice".into()),
-                ("year", 2024i64.into()),
-            ]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "meta" => Value::r#struct([
+                    ("author", "alice".into()),
+                    ("year", 2024i64.into()),
+                ]),
+            )],
+            None,
+        )
         .await
         .expect("could not upsert");
 
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["one"], Some(vec!["meta".to_string()]), Some(lsn), None)
+        .get(
+            ["one"],
+            Some(vec!["meta".to_string()]),
+            Some(RequestOptions::new().with_lsn(lsn)),
+        )
         .await
         .expect("could not get document");
     let one = docs.get("one").expect("missing doc");
@@ -559,13 +590,16 @@ async fn test_struct_get_returns_flat_leaf(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "meta" => Value::r#struct([
-                ("author", "alice".into()),
-                ("year", 2024i64.into()),
-            ]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "meta" => Value::r#struct([
+                    ("author", "alice".into()),
+                    ("year", 2024i64.into()),
+                ]),
+            )],
+            None,
+        )
         .await
         .expect("could not upsert");
 
@@ -575,8 +609,7 @@ async fn test_struct_get_returns_flat_leaf(ctx: &mut ProjectTestContext) {
         .get(
             ["one"],
             Some(vec!["meta.author".to_string()]),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not get document");
@@ -611,10 +644,13 @@ async fn test_struct_underscore_sub_field(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "one",
-            "meta" => Value::r#struct([("_bar", "v".into())]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "one",
+                "meta" => Value::r#struct([("_bar", "v".into())]),
+            )],
+            None,
+        )
         .await
         .expect("could not upsert");
 
@@ -625,8 +661,7 @@ async fn test_struct_underscore_sub_field(ctx: &mut ProjectTestContext) {
         .get(
             ["one"],
             Some(vec!["meta".to_string()]),
-            Some(lsn.clone()),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn.clone())),
         )
         .await
         .expect("could not get document");
@@ -644,8 +679,7 @@ async fn test_struct_underscore_sub_field(ctx: &mut ProjectTestContext) {
         .get(
             ["one"],
             Some(vec!["meta._bar".to_string()]),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not get document");
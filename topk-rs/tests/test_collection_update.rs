@@ -7,6 +7,7 @@ use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
 use topk_rs::proto::v1::data::Value;
 use topk_rs::query::{field, fns, select};
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::ProjectTestContext;
@@ -19,7 +20,7 @@ async fn test_update_non_existent_collection(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection("missing")
-        .update(vec![doc!("_id" => "one")], false)
+        .update(vec![doc!("_id" => "one")], false, None)
         .await
         .expect_err("should not be able to upsert document to non-existent collection");
 
@@ -39,12 +40,15 @@ async fn test_update_batch(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1", "foo" => "bar1"),
-            doc!("_id" => "2", "foo" => "bar2"),
-            doc!("_id" => "3", "foo" => "bar3"),
-            doc!("_id" => "4", "foo" => "bar4"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "1", "foo" => "bar1"),
+                doc!("_id" => "2", "foo" => "bar2"),
+                doc!("_id" => "3", "foo" => "bar3"),
+                doc!("_id" => "4", "foo" => "bar4"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert document");
 
@@ -61,6 +65,7 @@ async fn test_update_batch(ctx: &mut ProjectTestContext) {
                 doc!("_id" => "5", "foo" => "bar5"), // missing id
             ],
             false,
+            None,
         )
         .await
         .expect("could not update document");
@@ -70,7 +75,11 @@ async fn test_update_batch(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["1", "2", "3", "4", "5"], None, Some(lsn), None)
+        .get(
+            ["1", "2", "3", "4", "5"],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
+        )
         .await
         .expect("could not get documents");
 
@@ -98,10 +107,13 @@ async fn test_update_missing_id(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1", "foo" => "bar1"),
-            doc!("_id" => "2", "foo" => "bar2"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "1", "foo" => "bar1"),
+                doc!("_id" => "2", "foo" => "bar2"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert document");
 
@@ -111,7 +123,7 @@ async fn test_update_missing_id(ctx: &mut ProjectTestContext) {
     let new_lsn = ctx
         .client
         .collection(&collection.name)
-        .update(vec![doc!("_id" => "3", "foo" => "bar3")], false)
+        .update(vec![doc!("_id" => "3", "foo" => "bar3")], false, None)
         .await
         .expect("could not update document");
 
@@ -121,7 +133,11 @@ async fn test_update_missing_id(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .collection(&collection.name)
-        .get(["1", "2", "3"], None, Some(lsn), None)
+        .get(
+            ["1", "2", "3"],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
+        )
         .await
         .expect("could not get documents");
 
@@ -144,10 +160,13 @@ async fn test_update_missing_id_with_fail_on_missing(ctx: &mut ProjectTestContex
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1", "foo" => "bar1"),
-            doc!("_id" => "2", "foo" => "bar2"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "1", "foo" => "bar1"),
+                doc!("_id" => "2", "foo" => "bar2"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert document");
 
@@ -157,7 +176,7 @@ async fn test_update_missing_id_with_fail_on_missing(ctx: &mut ProjectTestContex
     let err = ctx
         .client
         .collection(&collection.name)
-        .update(vec![doc!("_id" => "3", "foo" => "bar3")], true)
+        .update(vec![doc!("_id" => "3", "foo" => "bar3")], true, None)
         .await
         .expect_err("should fail to update document with missing id");
 
@@ -182,7 +201,6 @@ async fn test_update_vector_index_field(ctx: &mut ProjectTestContext) {
             .filter(field("_id").eq("1984"))
             .limit(1),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -196,6 +214,7 @@ async fn test_update_vector_index_field(ctx: &mut ProjectTestContext) {
         .update(
             vec![doc!("_id" => "1984", "summary_embedding" => vec![8.0; 16])],
             true,
+            None,
         )
         .await
         .expect("could not update document");
@@ -210,8 +229,7 @@ async fn test_update_vector_index_field(ctx: &mut ProjectTestContext) {
             )])
             .filter(field("_id").eq("1984"))
             .limit(1),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query");
@@ -232,7 +250,6 @@ async fn test_update_semantic_index_field(ctx: &mut ProjectTestContext) {
                 .sort((field("sim"), SortOrder::Asc))
                 .limit(1),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -243,7 +260,7 @@ async fn test_update_semantic_index_field(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .update(vec![doc!("_id" => id, "title" => "foobarbaz")], true)
+        .update(vec![doc!("_id" => id, "title" => "foobarbaz")], true, None)
         .await
         .expect("could not update document");
 
@@ -255,8 +272,7 @@ async fn test_update_semantic_index_field(ctx: &mut ProjectTestContext) {
                 .select([("sim", fns::semantic_similarity("title", "dummy"))])
                 .filter(field("_id").eq(id))
                 .limit(1),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query");
@@ -275,7 +291,7 @@ async fn test_update_invalid_data_type(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection(&collection.name)
-        .update(vec![doc!("_id" => "1984", "title" => 1984u32)], true)
+        .update(vec![doc!("_id" => "1984", "title" => 1984u32)], true, None)
         .await
         .expect_err("should fail to update with invalid data type");
 
@@ -290,7 +306,11 @@ async fn test_update_missing_required_field(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection(&collection.name)
-        .update(vec![doc!("_id" => "1984", "title" => Value::null())], true)
+        .update(
+            vec![doc!("_id" => "1984", "title" => Value::null())],
+            true,
+            None,
+        )
         .await
         .expect_err("should fail to update with missing required field");
 
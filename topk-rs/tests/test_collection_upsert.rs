@@ -9,6 +9,7 @@ use topk_rs::proto::v1::{
     data::Document,
 };
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::ProjectTestContext;
@@ -19,7 +20,7 @@ async fn test_upsert_to_non_existent_collection(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection("missing")
-        .upsert(vec![doc!("_id" => "one")])
+        .upsert(vec![doc!("_id" => "one")], None)
         .await
         .expect_err("should not be able to upsert document to non-existent collection");
 
@@ -39,7 +40,7 @@ async fn test_upsert_basic(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!("_id" => "one")])
+        .upsert(vec![doc!("_id" => "one")], None)
         .await
         .expect("could not upsert document");
 
@@ -59,7 +60,7 @@ async fn test_upsert_batch(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(collection.name)
-        .upsert(vec![doc!("_id" => "one"), doc!("_id" => "two")])
+        .upsert(vec![doc!("_id" => "one"), doc!("_id" => "two")], None)
         .await
         .expect("could not upsert document");
 
@@ -79,7 +80,7 @@ async fn test_upsert_sequential(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!("_id" => "one")])
+        .upsert(vec![doc!("_id" => "one")], None)
         .await
         .expect("could not upsert document");
     assert_eq!(&lsn, "1");
@@ -87,7 +88,7 @@ async fn test_upsert_sequential(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!("_id" => "two")])
+        .upsert(vec![doc!("_id" => "two")], None)
         .await
         .expect("could not upsert document");
     assert_eq!(&lsn, "2");
@@ -95,7 +96,7 @@ async fn test_upsert_sequential(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!("_id" => "three")])
+        .upsert(vec![doc!("_id" => "three")], None)
         .await
         .expect("could not upsert document");
     assert_eq!(&lsn, "3");
@@ -114,7 +115,7 @@ async fn test_upsert_no_documents(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection(collection.name)
-        .upsert(vec![])
+        .upsert(vec![], None)
         .await
         .expect_err("should not be able to upsert invalid document");
 
@@ -138,7 +139,7 @@ async fn test_upsert_invalid_document(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection(collection.name)
-        .upsert(vec![Document::default()])
+        .upsert(vec![Document::default()], None)
         .await
         .expect_err("should not be able to upsert invalid document");
 
@@ -178,7 +179,7 @@ async fn test_upsert_schema_validation(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection(collection.name)
-        .upsert(vec![doc!("_id" => "one")])
+        .upsert(vec![doc!("_id" => "one")], None)
         .await
         .expect_err("should not be able to upsert invalid document");
 
@@ -208,12 +209,15 @@ async fn test_upsert_max_doc_size(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection(collection.name)
-        .upsert(vec![
-            doc!("_id" => "one", "payload" => "x".repeat(500 * 1024)), // 500KB, too large
-            doc!("_id" => "two", "payload" => "xxx"),                  // ok
-            doc!("_id" => "three", "payload" => "x".repeat(230 * 1024)), // 230KB, too large
-            doc!("_id" => "four", "payload" => "x".repeat(126 * 1024)), // 126KB (plus overhead), ok
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "one", "payload" => "x".repeat(500 * 1024)), // 500KB, too large
+                doc!("_id" => "two", "payload" => "xxx"),                  // ok
+                doc!("_id" => "three", "payload" => "x".repeat(230 * 1024)), // 230KB, too large
+                doc!("_id" => "four", "payload" => "x".repeat(126 * 1024)), // 126KB (plus overhead), ok
+            ],
+            None,
+        )
         .await
         .expect_err("should not be able to upsert a batch containing oversized documents");
 
@@ -302,19 +306,26 @@ async fn test_upsert_vectors(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "x",
-            "f32_vector" => vec![1.0, 2.0, 3.0, 4.0],
-            "u8_vector" => Value::list(vec![4u8, 5u8, 6u8]),
-            "binary_vector" => Value::list(vec![7u8, 8u8]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "x",
+                "f32_vector" => vec![1.0, 2.0, 3.0, 4.0],
+                "u8_vector" => Value::list(vec![4u8, 5u8, 6u8]),
+                "binary_vector" => Value::list(vec![7u8, 8u8]),
+            )],
+            None,
+        )
         .await
         .expect("could not upsert document");
 
     let obj = ctx
         .client
         .collection(&collection.name)
-        .get(vec!["x".to_string()], None, Some(lsn), None)
+        .get(
+            vec!["x".to_string()],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
+        )
         .await
         .expect("could not get document");
 
@@ -354,18 +365,25 @@ async fn test_upsert_sparse_vectors(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![doc!(
-            "_id" => "x",
-            "f32_sparse_vector" => Value::f32_sparse_vector(vec![1, 2, 3], vec![1.2, 2.3, 3.4]),
-            "u8_sparse_vector" => Value::u8_sparse_vector(vec![1, 2, 3], vec![4u8, 5u8, 6u8]),
-        )])
+        .upsert(
+            vec![doc!(
+                "_id" => "x",
+                "f32_sparse_vector" => Value::f32_sparse_vector(vec![1, 2, 3], vec![1.2, 2.3, 3.4]),
+                "u8_sparse_vector" => Value::u8_sparse_vector(vec![1, 2, 3], vec![4u8, 5u8, 6u8]),
+            )],
+            None,
+        )
         .await
         .expect("could not upsert document");
 
     let obj = ctx
         .client
         .collection(&collection.name)
-        .get(vec!["x".to_string()], None, Some(lsn), None)
+        .get(
+            vec!["x".to_string()],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
+        )
         .await
         .expect("could not get document");
 
@@ -0,0 +1,221 @@
+use std::sync::{Arc, Mutex};
+
+use futures_util::stream::{self, BoxStream};
+use prost::Message;
+use rstest::rstest;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::body::Body;
+use tonic::codegen::http::Request;
+use tonic::{Response, Status};
+use topk_rs::client::HttpService;
+use topk_rs::doc;
+use topk_rs::proto::v1::data::query_service_server::{QueryService, QueryServiceServer};
+use topk_rs::proto::v1::data::write_service_server::{WriteService, WriteServiceServer};
+use topk_rs::proto::v1::data::{
+    DeleteDocumentsRequest, DeleteDocumentsResponse, Document, DocumentData, GetRequest,
+    GetResponse, QueryRequest, QueryResponse, UpdateDocumentsRequest, UpdateDocumentsResponse,
+    UpsertDocumentsRequest, UpsertDocumentsResponse, Value,
+};
+use topk_rs::query::{field, select};
+use topk_rs::transport::CompressionEncoding;
+use topk_rs::{Client, ClientConfig};
+use tower::layer::layer_fn;
+use tower::ServiceExt;
+
+/// Server storing upserted documents and returning them from queries, failing requests that are
+/// not compressed with `encoding`.
+#[derive(Clone)]
+struct Server {
+    encoding: &'static str,
+    docs: Arc<Mutex<Vec<Document>>>,
+}
+
+impl Server {
+    fn check_encoding<T>(&self, request: &tonic::Request<T>) -> Result<(), Status> {
+        match request.metadata().get("grpc-encoding") {
+            Some(encoding) if encoding == self.encoding => Ok(()),
+            encoding => Err(Status::invalid_argument(format!(
+                "expected {} request, got {encoding:?}",
+                self.encoding
+            ))),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl WriteService for Server {
+    async fn upsert_documents(
+        &self,
+        request: tonic::Request<UpsertDocumentsRequest>,
+    ) -> Result<Response<UpsertDocumentsResponse>, Status> {
+        self.check_encoding(&request)?;
+
+        let mut docs = self.docs.lock().unwrap();
+        docs.extend(request.into_inner().docs);
+        Ok(Response::new(UpsertDocumentsResponse {
+            lsn: docs.len().to_string(),
+        }))
+    }
+
+    async fn update_documents(
+        &self,
+        _request: tonic::Request<UpdateDocumentsRequest>,
+    ) -> Result<Response<UpdateDocumentsResponse>, Status> {
+        Err(Status::unimplemented("update_documents"))
+    }
+
+    async fn delete_documents(
+        &self,
+        _request: tonic::Request<DeleteDocumentsRequest>,
+    ) -> Result<Response<DeleteDocumentsResponse>, Status> {
+        Err(Status::unimplemented("delete_documents"))
+    }
+}
+
+#[tonic::async_trait]
+impl QueryService for Server {
+    type QueryStreamStream = BoxStream<'static, Result<DocumentData, Status>>;
+    type GetStreamStream = BoxStream<'static, Result<DocumentData, Status>>;
+
+    async fn query(
+        &self,
+        _request: tonic::Request<QueryRequest>,
+    ) -> Result<Response<QueryResponse>, Status> {
+        Err(Status::unimplemented("query"))
+    }
+
+    async fn query_stream(
+        &self,
+        request: tonic::Request<QueryRequest>,
+    ) -> Result<Response<Self::QueryStreamStream>, Status> {
+        self.check_encoding(&request)?;
+
+        let docs = self.docs.lock().unwrap().clone();
+        Ok(Response::new(Box::pin(stream::iter(docs.into_iter().map(
+            |doc| {
+                Ok(DocumentData {
+                    data: doc.encode_to_vec().into(),
+                })
+            },
+        )))))
+    }
+
+    async fn get(
+        &self,
+        _request: tonic::Request<GetRequest>,
+    ) -> Result<Response<GetResponse>, Status> {
+        Err(Status::unimplemented("get"))
+    }
+
+    async fn get_stream(
+        &self,
+        _request: tonic::Request<GetRequest>,
+    ) -> Result<Response<Self::GetStreamStream>, Status> {
+        Err(Status::unimplemented("get_stream"))
+    }
+}
+
+/// Starts a server compressing with `encoding` and returns its address.
+async fn serve(encoding: CompressionEncoding, name: &'static str) -> String {
+    let server = Server {
+        encoding: name,
+        docs: Arc::default(),
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(
+                WriteServiceServer::new(server.clone())
+                    .accept_compressed(encoding)
+                    .send_compressed(encoding),
+            )
+            .add_service(
+                QueryServiceServer::new(server)
+                    .accept_compressed(encoding)
+                    .send_compressed(encoding),
+            )
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    format!("http://{addr}")
+}
+
+#[rstest]
+#[case::gzip(CompressionEncoding::Gzip, "gzip")]
+#[case::zstd(CompressionEncoding::Zstd, "zstd")]
+#[tokio::test]
+async fn test_compression_round_trip(
+    #[case] encoding: CompressionEncoding,
+    #[case] name: &'static str,
+) {
+    let endpoint = serve(encoding, name).await;
+
+    // Record the encoding of responses
+    let response_encodings = Arc::new(Mutex::new(vec![]));
+    let layer = {
+        let response_encodings = response_encodings.clone();
+        layer_fn(move |service: HttpService| {
+            let response_encodings = response_encodings.clone();
+            tower::service_fn(move |request: Request<Body>| {
+                let response_encodings = response_encodings.clone();
+                let service = service.clone();
+                async move {
+                    let response = service.oneshot(request).await?;
+                    response_encodings
+                        .lock()
+                        .unwrap()
+                        .push(response.headers().get("grpc-encoding").cloned());
+                    Ok::<_, Status>(response)
+                }
+            })
+        })
+    };
+
+    let config = ClientConfig::new("api-key", "local")
+        .with_endpoint(endpoint)
+        .with_compression(encoding)
+        .with_layer(layer);
+    let collection = Client::new(config).collection("books");
+
+    let docs: Vec<Document> = (0..100)
+        .map(|i| {
+            doc!(
+                "_id" => format!("doc-{i}"),
+                "embedding" => Value::list(vec![i as f32 / 100.0; 768]),
+                "tokens" => Value::matrix(128, vec![0.5f32; 128 * 32]),
+            )
+        })
+        .collect();
+
+    let lsn = collection.upsert(docs.clone(), None).await.unwrap();
+    assert_eq!(lsn, "100");
+
+    let results = collection
+        .query(select([("_id", field("_id"))]).limit(100), None)
+        .await
+        .unwrap();
+    assert_eq!(results, docs);
+
+    let response_encodings = response_encodings.lock().unwrap();
+    assert_eq!(response_encodings.len(), 2);
+    for response_encoding in response_encodings.iter() {
+        assert_eq!(response_encoding.as_ref().unwrap(), name);
+    }
+}
+
+#[tokio::test]
+async fn test_compression_disabled() {
+    let endpoint = serve(CompressionEncoding::Gzip, "gzip").await;
+
+    let config = ClientConfig::new("api-key", "local").with_endpoint(endpoint);
+    let err = Client::new(config)
+        .collection("books")
+        .upsert(vec![doc!("_id" => "one")], None)
+        .await
+        .expect_err("uncompressed request should be rejected");
+
+    assert!(err.to_string().contains("expected gzip request"), "{err}");
+}
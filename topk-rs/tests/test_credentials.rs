@@ -0,0 +1,208 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::body::Body;
+use tonic::codegen::http::Request;
+use tonic::{Response, Status};
+use topk_rs::client::HttpService;
+use topk_rs::credentials::{CredentialsProvider, StaticCredentials};
+use topk_rs::doc;
+use topk_rs::proto::v1::data::write_service_server::{WriteService, WriteServiceServer};
+use topk_rs::proto::v1::data::{
+    DeleteDocumentsRequest, DeleteDocumentsResponse, UpdateDocumentsRequest,
+    UpdateDocumentsResponse, UpsertDocumentsRequest, UpsertDocumentsResponse,
+};
+use topk_rs::retry::RetryConfig;
+use topk_rs::{Client, ClientConfig, Error};
+use tower::buffer::BufferLayer;
+use tower::layer::layer_fn;
+use tower::ServiceExt;
+
+const API_KEY: &str = "valid-key";
+
+/// Server accepting upserts authenticated with `API_KEY`, counting all requests.
+#[derive(Clone, Default)]
+struct Server {
+    requests: Arc<AtomicUsize>,
+}
+
+#[tonic::async_trait]
+impl WriteService for Server {
+    async fn upsert_documents(
+        &self,
+        request: tonic::Request<UpsertDocumentsRequest>,
+    ) -> Result<Response<UpsertDocumentsResponse>, Status> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+
+        match request.metadata().get("authorization") {
+            Some(value) if value == format!("Bearer {API_KEY}").as_str() => {
+                Ok(Response::new(UpsertDocumentsResponse { lsn: "1".into() }))
+            }
+            _ => Err(Status::unauthenticated("invalid API key")),
+        }
+    }
+
+    async fn update_documents(
+        &self,
+        _request: tonic::Request<UpdateDocumentsRequest>,
+    ) -> Result<Response<UpdateDocumentsResponse>, Status> {
+        Err(Status::unimplemented("update_documents"))
+    }
+
+    async fn delete_documents(
+        &self,
+        _request: tonic::Request<DeleteDocumentsRequest>,
+    ) -> Result<Response<DeleteDocumentsResponse>, Status> {
+        Err(Status::unimplemented("delete_documents"))
+    }
+}
+
+/// Starts a server and returns it along with its address.
+async fn serve() -> (Server, String) {
+    let server = Server::default();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(WriteServiceServer::new(server.clone()))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    (server, format!("http://{addr}"))
+}
+
+/// Key rotated to `API_KEY` when the server rejects the current one.
+struct Rotating {
+    key: Mutex<String>,
+}
+
+#[async_trait]
+impl CredentialsProvider for Rotating {
+    async fn token(&self) -> Result<String, Error> {
+        Ok(self.key.lock().unwrap().clone())
+    }
+
+    fn invalidate(&self, _token: &str) -> bool {
+        *self.key.lock().unwrap() = API_KEY.to_string();
+        true
+    }
+}
+
+impl Default for Rotating {
+    fn default() -> Self {
+        Self {
+            key: Mutex::new("expired-key".to_string()),
+        }
+    }
+}
+
+/// Provider failing to return a key, counting its calls.
+#[derive(Clone, Default)]
+struct Failing {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl CredentialsProvider for Failing {
+    async fn token(&self) -> Result<String, Error> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Err(Error::Unauthenticated("secret not found".to_string()))
+    }
+
+    fn invalidate(&self, _token: &str) -> bool {
+        true
+    }
+}
+
+async fn upsert(config: ClientConfig) -> Result<String, Error> {
+    Client::new(config)
+        .collection("books")
+        .upsert(vec![doc!("_id" => "one")], None)
+        .await
+}
+
+#[tokio::test]
+async fn test_rotated_credentials_retried() {
+    let (server, endpoint) = serve().await;
+
+    let config =
+        ClientConfig::from_credentials(Rotating::default(), "local").with_endpoint(endpoint);
+    assert_eq!(upsert(config).await.unwrap(), "1");
+    assert_eq!(server.requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_rotated_credentials_retried_behind_buffer() {
+    let (server, endpoint) = serve().await;
+
+    let config = ClientConfig::from_credentials(Rotating::default(), "local")
+        .with_endpoint(endpoint)
+        .with_layer(BufferLayer::new(16));
+    assert_eq!(upsert(config).await.unwrap(), "1");
+    assert_eq!(server.requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_rotated_credentials_retried_from_spawned_task() {
+    let (server, endpoint) = serve().await;
+
+    // Requests are sent from another task than the one retrying them
+    let layer = layer_fn(|service: HttpService| {
+        tower::service_fn(move |request: Request<Body>| {
+            let service = service.clone();
+            async move {
+                tokio::spawn(service.oneshot(request))
+                    .await
+                    .map_err(|e| Status::internal(e.to_string()))?
+            }
+        })
+    });
+
+    let config = ClientConfig::from_credentials(Rotating::default(), "local")
+        .with_endpoint(endpoint)
+        .with_layer(layer);
+    assert_eq!(upsert(config).await.unwrap(), "1");
+    assert_eq!(server.requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_rotated_credentials_zero_max_retries() {
+    let (server, endpoint) = serve().await;
+
+    let config = ClientConfig::from_credentials(Rotating::default(), "local")
+        .with_endpoint(endpoint)
+        .with_retry_config(RetryConfig {
+            max_retries: 0,
+            ..Default::default()
+        });
+    let err = upsert(config).await.expect_err("retries are disabled");
+    assert!(matches!(err, Error::Unauthenticated(_)), "{err}");
+    assert_eq!(server.requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_static_credentials_not_retried() {
+    let (server, endpoint) = serve().await;
+
+    let config = ClientConfig::from_credentials(StaticCredentials::new("invalid-key"), "local")
+        .with_endpoint(endpoint);
+    let err = upsert(config).await.expect_err("key is invalid");
+    assert!(matches!(err, Error::Unauthenticated(_)), "{err}");
+    assert_eq!(server.requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_provider_error_not_retried() {
+    let (server, endpoint) = serve().await;
+
+    let provider = Failing::default();
+    let config = ClientConfig::from_credentials(provider.clone(), "local").with_endpoint(endpoint);
+    let err = upsert(config).await.expect_err("provider fails");
+    assert!(err.to_string().contains("secret not found"), "{err}");
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    assert_eq!(server.requests.load(Ordering::SeqCst), 0);
+}
@@ -19,13 +19,18 @@ async fn test_wait_for_handle(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc1", test_pdf(), HashMap::<String, Value>::default())
+        .upsert_file(
+            "doc1",
+            test_pdf(),
+            HashMap::<String, Value>::default(),
+            None,
+        )
         .await
         .expect("could not upsert file");
 
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("handle was not processed within timeout");
 }
@@ -43,7 +48,7 @@ async fn test_check_handle_invalid_handle(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .dataset(&dataset.name)
-        .check_handle("invalid-handle-format-12345")
+        .check_handle("invalid-handle-format-12345", None)
         .await
         .expect_err("should not be able to check handle with invalid handle");
 
@@ -56,7 +61,7 @@ async fn test_check_handle_from_non_existent_dataset(ctx: &mut ProjectTestContex
     let err = ctx
         .client
         .dataset(ctx.wrap("nonexistent"))
-        .check_handle("some-handle")
+        .check_handle("some-handle", None)
         .await
         .expect_err("should not be able to check handle for non-existent dataset");
 
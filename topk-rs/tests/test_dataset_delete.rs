@@ -22,7 +22,7 @@ async fn test_delete_document(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .dataset(&dataset.name)
-        .get_metadata(vec!["doc1"], None)
+        .get_metadata(vec!["doc1"], None, None)
         .await
         .expect("could not get metadata");
     assert!(docs.is_empty());
@@ -30,12 +30,12 @@ async fn test_delete_document(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new())
+        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new(), None)
         .await
         .expect("could not upsert file");
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait handle");
 
@@ -43,7 +43,7 @@ async fn test_delete_document(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .dataset(&dataset.name)
-        .get_metadata(vec!["doc1"], None)
+        .get_metadata(vec!["doc1"], None, None)
         .await
         .expect("could not get metadata");
     assert_eq!(docs.keys().collect::<Vec<_>>(), vec!["doc1"]);
@@ -52,12 +52,12 @@ async fn test_delete_document(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .delete("doc1")
+        .delete("doc1", None)
         .await
         .expect("could not delete");
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait handle");
 
@@ -65,7 +65,7 @@ async fn test_delete_document(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .dataset(&dataset.name)
-        .get_metadata(vec!["doc1"], None)
+        .get_metadata(vec!["doc1"], None, None)
         .await
         .unwrap();
     assert!(docs.is_empty());
@@ -84,7 +84,7 @@ async fn test_delete_non_existent_document_returns_handle(ctx: &mut ProjectTestC
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .delete("nonexistent")
+        .delete("nonexistent", None)
         .await
         .expect("could not delete");
 
@@ -92,7 +92,7 @@ async fn test_delete_non_existent_document_returns_handle(ctx: &mut ProjectTestC
     let result = ctx
         .client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await;
     assert!(matches!(result, Ok(_)));
 }
@@ -103,7 +103,7 @@ async fn test_delete_from_non_existent_dataset(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .dataset(ctx.wrap("nonexistent"))
-        .delete("doc1")
+        .delete("doc1", None)
         .await
         .expect_err("should not be able to delete from non-existent dataset");
 
@@ -129,12 +129,12 @@ async fn test_delete_id_prefix_sibling(ctx: &mut ProjectTestContext) {
         let handle = ctx
             .client
             .dataset(&dataset.name)
-            .upsert_file(id.to_string(), file, Vec::<(String, Value)>::new())
+            .upsert_file(id.to_string(), file, Vec::<(String, Value)>::new(), None)
             .await
             .expect("could not upsert file");
         ctx.client
             .dataset(&dataset.name)
-            .wait_for_handle(&handle, None)
+            .wait_for_handle(&handle, None, None)
             .await
             .expect("could not wait handle");
     }
@@ -143,19 +143,19 @@ async fn test_delete_id_prefix_sibling(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .delete("doc-01")
+        .delete("doc-01", None)
         .await
         .expect("could not delete");
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait handle");
 
     let docs = ctx
         .client
         .dataset(&dataset.name)
-        .get_metadata(vec!["doc-010", "doc-01"], None)
+        .get_metadata(vec!["doc-010", "doc-01"], None, None)
         .await
         .expect("could not get metadata");
     assert!(docs.contains_key("doc-010"));
@@ -18,20 +18,20 @@ async fn test_dataset_list(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new())
+        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new(), None)
         .await
         .expect("could not upsert PDF file");
 
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait for handle");
 
     let entries: Vec<_> = ctx
         .client
         .dataset(&dataset.name)
-        .list(None, None)
+        .list(None, None, None)
         .await
         .expect("could not list dataset entries")
         .try_collect()
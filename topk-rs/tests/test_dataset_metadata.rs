@@ -24,6 +24,7 @@ async fn test_get_metadata(ctx: &mut ProjectTestContext) {
             "doc1".to_string(),
             test_pdf(),
             vec![("title", Value::string("test"))],
+            None,
         )
         .await
         .expect("could not upsert file");
@@ -31,7 +32,7 @@ async fn test_get_metadata(ctx: &mut ProjectTestContext) {
     // Wait for file to be processed
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait for handle");
 
@@ -39,7 +40,7 @@ async fn test_get_metadata(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .dataset(&dataset.name)
-        .get_metadata(vec!["doc1"], None)
+        .get_metadata(vec!["doc1"], None, None)
         .await
         .expect("could not get metadata");
 
@@ -19,7 +19,7 @@ async fn test_upsert_file_to_non_existent_dataset(ctx: &mut ProjectTestContext)
     let err = ctx
         .client
         .dataset(ctx.wrap("nonexistent"))
-        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new())
+        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new(), None)
         .await
         .expect_err("should not be able to upsert file to non-existent dataset");
 
@@ -44,7 +44,7 @@ async fn test_upsert_file_pdf(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc1", test_pdf(), metadata)
+        .upsert_file("doc1", test_pdf(), metadata, None)
         .await
         .expect("could not upsert PDF file");
 
@@ -70,7 +70,7 @@ async fn test_upsert_file_markdown(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc2".to_string(), input_file, metadata)
+        .upsert_file("doc2".to_string(), input_file, metadata, None)
         .await;
 
     assert!(matches!(handle, Ok(_)));
@@ -96,7 +96,7 @@ async fn test_upsert_file_with_invalid_metadata(ctx: &mut ProjectTestContext) {
         let handle = ctx
             .client
             .dataset(&dataset.name)
-            .upsert_file("doc2".to_string(), input_file.clone(), metadata)
+            .upsert_file("doc2".to_string(), input_file.clone(), metadata, None)
             .await;
 
         assert!(matches!(handle, Err(Error::DocumentValidationError(_))));
@@ -128,14 +128,14 @@ async fn test_upsert_file(#[case] file: &str) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file(file, test_file(file), HashMap::<String, Value>::new())
+        .upsert_file(file, test_file(file), HashMap::<String, Value>::new(), None)
         .await
         .expect(&format!("could not upsert file: {file}"));
 
     // Wait for handle to be processed
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("handle was not processed within timeout");
 
@@ -175,12 +175,12 @@ async fn test_upsert_id_prefix_sibling(ctx: &mut ProjectTestContext) {
         let handle = ctx
             .client
             .dataset(&dataset.name)
-            .upsert_file(id.to_string(), file, Vec::<(String, Value)>::new())
+            .upsert_file(id.to_string(), file, Vec::<(String, Value)>::new(), None)
             .await
             .expect("could not upsert file");
         ctx.client
             .dataset(&dataset.name)
-            .wait_for_handle(&handle, None)
+            .wait_for_handle(&handle, None, None)
             .await
             .expect("could not wait handle");
     }
@@ -188,7 +188,7 @@ async fn test_upsert_id_prefix_sibling(ctx: &mut ProjectTestContext) {
     let docs = ctx
         .client
         .dataset(&dataset.name)
-        .get_metadata(vec!["doc-010", "doc-01"], None)
+        .get_metadata(vec!["doc-010", "doc-01"], None, None)
         .await
         .expect("could not get metadata");
     assert!(docs.contains_key("doc-010"));
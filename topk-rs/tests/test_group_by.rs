@@ -31,7 +31,6 @@ async fn test_group_by_bool_key_expr(ctx: &mut ProjectTestContext) {
                 [("count", AggregateExpr::count(None))],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -61,7 +60,6 @@ async fn test_group_by_count(ctx: &mut ProjectTestContext) {
                 [("count", AggregateExpr::count(None))],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -101,7 +99,6 @@ async fn test_group_by_count_field(ctx: &mut ProjectTestContext) {
                 ],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -136,7 +133,6 @@ async fn test_group_by_sum(ctx: &mut ProjectTestContext) {
                 [("total_year", AggregateExpr::sum("published_year"))],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -173,7 +169,6 @@ async fn test_group_by_min_max(ctx: &mut ProjectTestContext) {
                 ],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -213,7 +208,6 @@ async fn test_group_by_avg(ctx: &mut ProjectTestContext) {
                 [("avg_year", AggregateExpr::avg("published_year"))],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -253,7 +247,6 @@ async fn test_group_by_multiple_aggregations(ctx: &mut ProjectTestContext) {
                 ],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -303,7 +296,6 @@ async fn test_group_by_multiple_keys(ctx: &mut ProjectTestContext) {
                 [("count", AggregateExpr::count(None))],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -345,7 +337,6 @@ async fn test_group_by_with_filter(ctx: &mut ProjectTestContext) {
                 [("count", AggregateExpr::count(None))],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -386,7 +377,6 @@ async fn test_group_by_with_projected_columns(ctx: &mut ProjectTestContext) {
                 ],
             ),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -426,7 +416,6 @@ async fn test_group_by_then_filter(ctx: &mut ProjectTestContext) {
             )
             .filter(field("count").gt(4 as u64)),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -453,7 +442,6 @@ async fn test_group_by_then_sort_limit(ctx: &mut ProjectTestContext) {
             .sort("count")
             .limit(1),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -479,7 +467,6 @@ async fn test_group_by_then_select(ctx: &mut ProjectTestContext) {
             )
             .select([("n", field("count"))]),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -511,7 +498,6 @@ async fn test_group_by_empty_keys(ctx: &mut ProjectTestContext) {
                 [("count", AggregateExpr::count(None))],
             ),
             None,
-            None,
         )
         .await
         .expect_err("should have failed");
@@ -536,7 +522,6 @@ async fn test_group_by_empty_aggregations(ctx: &mut ProjectTestContext) {
                 Vec::<(String, AggregateExpr)>::new(),
             ),
             None,
-            None,
         )
         .await
         .expect_err("should have failed");
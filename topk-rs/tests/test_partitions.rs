@@ -8,6 +8,7 @@ use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
 use topk_rs::proto::v1::data::Value;
 use topk_rs::query::{field, select};
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::ProjectTestContext;
@@ -39,10 +40,13 @@ async fn test_partition_upsert_isolation(ctx: &mut ProjectTestContext) {
     let default_lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "shared", "partition" => "default"),
-            doc!("_id" => "only-default", "partition" => "default"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "shared", "partition" => "default"),
+                doc!("_id" => "only-default", "partition" => "default"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to default partition");
     assert_eq!(&default_lsn, "1");
@@ -51,10 +55,13 @@ async fn test_partition_upsert_isolation(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p1")
-        .upsert(vec![
-            doc!("_id" => "shared", "partition" => "p1"),
-            doc!("_id" => "only-p1", "partition" => "p1"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "shared", "partition" => "p1"),
+                doc!("_id" => "only-p1", "partition" => "p1"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to partition p1");
     assert_eq!(&p1_lsn, "1");
@@ -63,7 +70,7 @@ async fn test_partition_upsert_isolation(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p2")
-        .upsert(vec![doc!("_id" => "shared", "partition" => "p2")])
+        .upsert(vec![doc!("_id" => "shared", "partition" => "p2")], None)
         .await
         .expect("could not upsert to partition p2");
     assert_eq!(&p2_lsn, "1");
@@ -74,8 +81,7 @@ async fn test_partition_upsert_isolation(ctx: &mut ProjectTestContext) {
         .get(
             ["shared", "only-default", "only-p1"],
             None,
-            Some(default_lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(default_lsn)),
         )
         .await
         .expect("could not get from default partition");
@@ -101,8 +107,7 @@ async fn test_partition_upsert_isolation(ctx: &mut ProjectTestContext) {
         .get(
             ["shared", "only-default", "only-p1"],
             None,
-            Some(p1_lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(p1_lsn)),
         )
         .await
         .expect("could not get from partition p1");
@@ -125,7 +130,11 @@ async fn test_partition_upsert_isolation(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p2")
-        .get(["shared"], None, Some(p2_lsn), None)
+        .get(
+            ["shared"],
+            None,
+            Some(RequestOptions::new().with_lsn(p2_lsn)),
+        )
         .await
         .expect("could not get from partition p2");
 
@@ -151,7 +160,7 @@ async fn test_partition_update(ctx: &mut ProjectTestContext) {
     ctx.client
         .collection(&collection.name)
         .partition("p1")
-        .upsert(vec![doc!("_id" => "doc", "value" => "p1-v1")])
+        .upsert(vec![doc!("_id" => "doc", "value" => "p1-v1")], None)
         .await
         .expect("could not upsert to partition p1");
 
@@ -159,7 +168,7 @@ async fn test_partition_update(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p2")
-        .upsert(vec![doc!("_id" => "doc", "value" => "p2-v1")])
+        .upsert(vec![doc!("_id" => "doc", "value" => "p2-v1")], None)
         .await
         .expect("could not upsert to partition p2");
 
@@ -170,6 +179,7 @@ async fn test_partition_update(ctx: &mut ProjectTestContext) {
         .update(
             vec![doc!("_id" => "doc", "value" => "p1-v2", "extra" => "p1")],
             false,
+            None,
         )
         .await
         .expect("could not update partition p1");
@@ -179,7 +189,7 @@ async fn test_partition_update(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p1")
-        .get(["doc"], None, Some(p1_lsn), None)
+        .get(["doc"], None, Some(RequestOptions::new().with_lsn(p1_lsn)))
         .await
         .expect("could not get from partition p1");
 
@@ -192,7 +202,7 @@ async fn test_partition_update(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p2")
-        .get(["doc"], None, Some(p2_lsn), None)
+        .get(["doc"], None, Some(RequestOptions::new().with_lsn(p2_lsn)))
         .await
         .expect("could not get from partition p2");
 
@@ -216,30 +226,34 @@ async fn test_partition_delete(ctx: &mut ProjectTestContext) {
     let p2 = ctx.client.collection(&collection.name).partition("p2");
 
     let p1_lsn = p1
-        .upsert(vec![
-            doc!("_id" => "doc1", "partition" => "p1", "rank" => 1),
-            doc!("_id" => "doc2", "partition" => "p1", "rank" => 2),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "doc1", "partition" => "p1", "rank" => 1),
+                doc!("_id" => "doc2", "partition" => "p1", "rank" => 2),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to partition p1");
     assert_eq!(p1_lsn, "1");
 
     let p2_lsn = p2
-        .upsert(vec![
-            doc!("_id" => "doc1", "partition" => "p2", "rank" => 3),
-        ])
+        .upsert(
+            vec![doc!("_id" => "doc1", "partition" => "p2", "rank" => 3)],
+            None,
+        )
         .await
         .expect("could not upsert to partition p2");
     assert_eq!(p2_lsn, "1");
 
     let count = p1
-        .count(Some(p1_lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(p1_lsn)))
         .await
         .expect("could not count partition p1");
     assert_eq!(count, 2);
 
     let p1_lsn = p1
-        .delete(vec!["doc1".to_string()])
+        .delete(vec!["doc1".to_string()], None)
         .await
         .expect("could not delete from partition p1");
     assert_eq!(&p1_lsn, "2");
@@ -249,8 +263,7 @@ async fn test_partition_delete(ctx: &mut ProjectTestContext) {
             select([("_id", field("_id")), ("partition", field("partition"))])
                 .sort((field("rank"), SortOrder::Asc))
                 .limit(100),
-            Some(p1_lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(p1_lsn)),
         )
         .await
         .expect("could not query partition p1");
@@ -259,7 +272,7 @@ async fn test_partition_delete(ctx: &mut ProjectTestContext) {
     assert_doc_ids!(p1_docs, ["doc2"]);
 
     let p2_docs = p2
-        .get(["doc1"], None, Some(p2_lsn), None)
+        .get(["doc1"], None, Some(RequestOptions::new().with_lsn(p2_lsn)))
         .await
         .expect("could not get from partition p2");
 
@@ -285,10 +298,13 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
     let default_lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "doc1", "partition" => "default"),
-            doc!("_id" => "doc2", "partition" => "default"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "doc1", "partition" => "default"),
+                doc!("_id" => "doc2", "partition" => "default"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to default partition");
     assert_eq!(default_lsn, "1");
@@ -297,11 +313,14 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p1")
-        .upsert(vec![
-            doc!("_id" => "doc1", "partition" => "p1"),
-            doc!("_id" => "doc2", "partition" => "p1"),
-            doc!("_id" => "doc3", "partition" => "p1"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "doc1", "partition" => "p1"),
+                doc!("_id" => "doc2", "partition" => "p1"),
+                doc!("_id" => "doc3", "partition" => "p1"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to partition p1");
     assert_eq!(p1_lsn, "1");
@@ -310,7 +329,7 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p2")
-        .upsert(vec![doc!("_id" => "doc1", "partition" => "p2")])
+        .upsert(vec![doc!("_id" => "doc1", "partition" => "p2")], None)
         .await
         .expect("could not upsert to partition p2");
     assert_eq!(p2_lsn, "1");
@@ -318,7 +337,7 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
     let default_count = ctx
         .client
         .collection(&collection.name)
-        .count(Some(default_lsn.clone()), None)
+        .count(Some(RequestOptions::new().with_lsn(default_lsn.clone())))
         .await
         .expect("could not count default partition");
     assert_eq!(default_count, 2);
@@ -327,7 +346,7 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p1")
-        .count(Some(p1_lsn.clone()), None)
+        .count(Some(RequestOptions::new().with_lsn(p1_lsn.clone())))
         .await
         .expect("could not count partition p1");
     assert_eq!(p1_count, 3);
@@ -336,7 +355,7 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p2")
-        .count(Some(p2_lsn.clone()), None)
+        .count(Some(RequestOptions::new().with_lsn(p2_lsn.clone())))
         .await
         .expect("could not count partition p2");
     assert_eq!(p2_count, 1);
@@ -346,8 +365,7 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
         .collection(&collection.name)
         .query(
             select([("_id", field("_id")), ("partition", field("partition"))]).limit(10),
-            Some(default_lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(default_lsn)),
         )
         .await
         .expect("could not query default partition");
@@ -361,8 +379,7 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
         .partition("p1")
         .query(
             select([("_id", field("_id")), ("partition", field("partition"))]).limit(10),
-            Some(p1_lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(p1_lsn)),
         )
         .await
         .expect("could not query partition p1");
@@ -376,8 +393,7 @@ async fn test_partition_query_count(ctx: &mut ProjectTestContext) {
         .partition("p2")
         .query(
             select([("_id", field("_id")), ("partition", field("partition"))]).limit(10),
-            Some(p2_lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(p2_lsn)),
         )
         .await
         .expect("could not query partition p2");
@@ -400,11 +416,14 @@ async fn test_partition_query_filter(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p1")
-        .upsert(vec![
-            doc!("_id" => "doc1", "partition" => "p1", "region" => "us"),
-            doc!("_id" => "doc2", "partition" => "p1", "region" => "eu"),
-            doc!("_id" => "doc3", "partition" => "p1", "region" => "us"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "doc1", "partition" => "p1", "region" => "us"),
+                doc!("_id" => "doc2", "partition" => "p1", "region" => "eu"),
+                doc!("_id" => "doc3", "partition" => "p1", "region" => "us"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to partition p1");
 
@@ -414,10 +433,13 @@ async fn test_partition_query_filter(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p2")
-        .upsert(vec![
-            doc!("_id" => "doc1", "partition" => "p2", "region" => "us"),
-            doc!("_id" => "doc2", "partition" => "p2", "region" => "us"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "doc1", "partition" => "p2", "region" => "us"),
+                doc!("_id" => "doc2", "partition" => "p2", "region" => "us"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to partition p2");
 
@@ -431,8 +453,7 @@ async fn test_partition_query_filter(ctx: &mut ProjectTestContext) {
             select([("_id", field("_id")), ("partition", field("partition"))])
                 .filter(field("region").eq("us"))
                 .limit(10),
-            Some(p1_lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(p1_lsn)),
         )
         .await
         .expect("could not query partition p1");
@@ -448,8 +469,7 @@ async fn test_partition_query_filter(ctx: &mut ProjectTestContext) {
             select([("_id", field("_id")), ("partition", field("partition"))])
                 .filter(field("region").eq("us"))
                 .limit(10),
-            Some(p2_lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(p2_lsn)),
         )
         .await
         .expect("could not query partition p2");
@@ -466,7 +486,6 @@ async fn test_partition_query_filter(ctx: &mut ProjectTestContext) {
                 .filter(field("region").eq("us"))
                 .limit(10),
             None,
-            None,
         )
         .await
         .expect("could not query partition p2");
@@ -489,10 +508,13 @@ async fn test_partition_get(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p1")
-        .upsert(vec![
-            doc!("_id" => "one", "title" => "first"),
-            doc!("_id" => "two", "title" => "second"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "one", "title" => "first"),
+                doc!("_id" => "two", "title" => "second"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to partition p1");
 
@@ -501,7 +523,11 @@ async fn test_partition_get(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("p1")
-        .get(["one", "two", "missing"], None, Some(lsn.clone()), None)
+        .get(
+            ["one", "two", "missing"],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn.clone())),
+        )
         .await
         .expect("could not get from partition p1");
 
@@ -523,7 +549,7 @@ async fn test_partition_get(ctx: &mut ProjectTestContext) {
     let default_docs = ctx
         .client
         .collection(&collection.name)
-        .get(["one", "two"], None, None, None)
+        .get(["one", "two"], None, None)
         .await
         .expect("could not get from default partition");
 
@@ -544,7 +570,7 @@ async fn test_query_non_existent_partition(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("missing-partition")
-        .count(None, None)
+        .count(None)
         .await
         .expect_err("should not be able to query a partition that was never created");
 
@@ -554,7 +580,7 @@ async fn test_query_non_existent_partition(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("missing-partition")
-        .get(["doc"], None, None, None)
+        .get(["doc"], None, None)
         .await
         .expect_err("should not be able to get from a partition that was never created");
 
@@ -575,7 +601,7 @@ async fn test_upsert_creates_partition(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("new-partition")
-        .upsert(vec![doc!("_id" => "one", "value" => "created")])
+        .upsert(vec![doc!("_id" => "one", "value" => "created")], None)
         .await
         .expect("could not upsert to new partition");
     assert_eq!(&lsn, "1");
@@ -584,7 +610,11 @@ async fn test_upsert_creates_partition(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("new-partition")
-        .get(["one"], None, Some(lsn.clone()), None)
+        .get(
+            ["one"],
+            None,
+            Some(RequestOptions::new().with_lsn(lsn.clone())),
+        )
         .await
         .expect("could not get from newly created partition");
 
@@ -600,7 +630,7 @@ async fn test_upsert_creates_partition(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("new-partition")
-        .count(Some(lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not count newly created partition");
 
@@ -621,7 +651,7 @@ async fn test_partition_with_invalid_name(ctx: &mut ProjectTestContext) {
         .client
         .collection(&collection.name)
         .partition("$foo&bar")
-        .upsert(vec![doc!("_id" => "one", "value" => "created")])
+        .upsert(vec![doc!("_id" => "one", "value" => "created")], None)
         .await;
 
     assert!(res.is_err());
@@ -649,7 +679,7 @@ async fn test_partition_valid_names(ctx: &mut ProjectTestContext) {
             .client
             .collection(&collection.name)
             .partition(name)
-            .upsert(vec![doc!("_id" => "one", "value" => "created")])
+            .upsert(vec![doc!("_id" => "one", "value" => "created")], None)
             .await
             .expect("could not upsert to partition");
 
@@ -670,7 +700,7 @@ async fn test_list_partitions_empty(ctx: &mut ProjectTestContext) {
     let partitions: Vec<_> = ctx
         .client
         .collection(&collection.name)
-        .list_partitions(None)
+        .list_partitions(None, None)
         .await
         .expect("could not list partitions")
         .try_collect()
@@ -693,21 +723,21 @@ async fn test_list_partitions(ctx: &mut ProjectTestContext) {
     ctx.client
         .collection(&collection.name)
         .partition("partition-a")
-        .upsert(vec![doc!("_id" => "doc-a")])
+        .upsert(vec![doc!("_id" => "doc-a")], None)
         .await
         .expect("could not upsert to partition-a");
 
     ctx.client
         .collection(&collection.name)
         .partition("partition-b")
-        .upsert(vec![doc!("_id" => "doc-b")])
+        .upsert(vec![doc!("_id" => "doc-b")], None)
         .await
         .expect("could not upsert to partition-b");
 
     let mut partitions: Vec<_> = ctx
         .client
         .collection(&collection.name)
-        .list_partitions(None)
+        .list_partitions(None, None)
         .await
         .expect("could not list partitions")
         .try_collect()
@@ -744,7 +774,7 @@ async fn test_list_partitions_with_prefix(ctx: &mut ProjectTestContext) {
         ctx.client
             .collection(collection_name)
             .partition(name)
-            .upsert(vec![doc!("_id" => "doc")])
+            .upsert(vec![doc!("_id" => "doc")], None)
             .await
             .expect("could not upsert to partition");
     }
@@ -752,7 +782,7 @@ async fn test_list_partitions_with_prefix(ctx: &mut ProjectTestContext) {
     let partitions: Vec<_> = ctx
         .client
         .collection(collection_name)
-        .list_partitions(Some("foo".to_string()))
+        .list_partitions(Some("foo".to_string()), None)
         .await
         .expect("could not list partitions")
         .try_collect()
@@ -780,21 +810,21 @@ async fn test_list_partitions_excludes_default(ctx: &mut ProjectTestContext) {
 
     ctx.client
         .collection(&collection.name)
-        .upsert(vec![doc!("_id" => "doc", "partition" => "default")])
+        .upsert(vec![doc!("_id" => "doc", "partition" => "default")], None)
         .await
         .expect("could not upsert to default partition");
 
     ctx.client
         .collection(&collection.name)
         .partition("named-partition")
-        .upsert(vec![doc!("_id" => "doc")])
+        .upsert(vec![doc!("_id" => "doc")], None)
         .await
         .expect("could not upsert to named partition");
 
     let partitions: Vec<_> = ctx
         .client
         .collection(&collection.name)
-        .list_partitions(None)
+        .list_partitions(None, None)
         .await
         .expect("could not list partitions")
         .try_collect()
@@ -820,17 +850,20 @@ async fn test_delete_partition(ctx: &mut ProjectTestContext) {
     ctx.client
         .collection(collection_name)
         .partition("test-partition")
-        .upsert(vec![
-            doc!("_id" => "doc1", "value" => "one"),
-            doc!("_id" => "doc2", "value" => "two"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "doc1", "value" => "one"),
+                doc!("_id" => "doc2", "value" => "two"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert to partition");
 
     let partitions: Vec<_> = ctx
         .client
         .collection(collection_name)
-        .list_partitions(None)
+        .list_partitions(None, None)
         .await
         .expect("could not list partitions")
         .try_collect()
@@ -842,14 +875,14 @@ async fn test_delete_partition(ctx: &mut ProjectTestContext) {
 
     ctx.client
         .collection(collection_name)
-        .delete_partition("test-partition")
+        .delete_partition("test-partition", None)
         .await
         .expect("could not delete partition");
 
     let partitions: Vec<_> = ctx
         .client
         .collection(collection_name)
-        .list_partitions(None)
+        .list_partitions(None, None)
         .await
         .expect("could not list partitions")
         .try_collect()
@@ -862,7 +895,7 @@ async fn test_delete_partition(ctx: &mut ProjectTestContext) {
         .client
         .collection(collection_name)
         .partition("test-partition")
-        .count(None, None)
+        .count(None)
         .await
         .expect_err("should not be able to query deleted partition");
 
@@ -889,27 +922,33 @@ async fn test_delete_partition_does_not_affect_other_partitions(ctx: &mut Projec
         .partition("partition-b");
 
     let p1_lsn = p1
-        .upsert(vec![doc!("_id" => "doc-a", "partition" => "partition-a")])
+        .upsert(
+            vec![doc!("_id" => "doc-a", "partition" => "partition-a")],
+            None,
+        )
         .await
         .expect("could not upsert to partition-a");
     assert_eq!(p1_lsn, "1");
 
     let p2_lsn = p2
-        .upsert(vec![doc!("_id" => "doc-a", "partition" => "partition-b")])
+        .upsert(
+            vec![doc!("_id" => "doc-a", "partition" => "partition-b")],
+            None,
+        )
         .await
         .expect("could not upsert to partition-b");
     assert_eq!(p2_lsn, "1");
 
     ctx.client
         .collection(&collection.name)
-        .delete_partition("partition-a")
+        .delete_partition("partition-a", None)
         .await
         .expect("could not delete partition-a");
 
     let partitions: Vec<_> = ctx
         .client
         .collection(&collection.name)
-        .list_partitions(None)
+        .list_partitions(None, None)
         .await
         .expect("could not list partitions")
         .try_collect()
@@ -920,7 +959,11 @@ async fn test_delete_partition_does_not_affect_other_partitions(ctx: &mut Projec
     assert_eq!(partitions[0].name, "partition-b");
 
     let p2_docs = p2
-        .get(["doc-a"], None, Some(p2_lsn), None)
+        .get(
+            ["doc-a"],
+            None,
+            Some(RequestOptions::new().with_lsn(p2_lsn)),
+        )
         .await
         .expect("could not get from partition-b");
 
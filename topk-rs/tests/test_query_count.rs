@@ -3,6 +3,7 @@ use topk_rs::doc;
 use topk_rs::query::{field, filter};
 use topk_rs::schema;
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::dataset;
@@ -14,7 +15,7 @@ async fn test_query_non_existent_collection(ctx: &mut ProjectTestContext) {
     let err = ctx
         .client
         .collection("missing")
-        .count(None, None)
+        .count(None)
         .await
         .expect_err("should not be able to query non-existent collection");
 
@@ -34,10 +35,9 @@ async fn test_query_count_empty_collection(ctx: &mut ProjectTestContext) {
     let count = ctx
         .client
         .collection(collection.name)
-        .count(
-            None,
-            Some(topk_rs::proto::v1::data::ConsistencyLevel::Strong),
-        )
+        .count(Some(RequestOptions::new().with_consistency(
+            topk_rs::proto::v1::data::ConsistencyLevel::Strong,
+        )))
         .await
         .expect("could not query");
 
@@ -52,7 +52,7 @@ async fn test_query_count(ctx: &mut ProjectTestContext) {
     let result = ctx
         .client
         .collection(&collection.name)
-        .count(None, None)
+        .count(None)
         .await
         .expect("could not query");
 
@@ -70,7 +70,6 @@ async fn test_query_count_with_filter(ctx: &mut ProjectTestContext) {
         .query(
             filter(field("published_year").lte(1950 as u32)).count(),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -86,7 +85,7 @@ async fn test_query_count_with_delete(ctx: &mut ProjectTestContext) {
     let result = ctx
         .client
         .collection(&collection.name)
-        .count(None, None)
+        .count(None)
         .await
         .expect("could not query");
 
@@ -95,14 +94,14 @@ async fn test_query_count_with_delete(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .delete(vec!["lotr".to_string()])
+        .delete(vec!["lotr".to_string()], None)
         .await
         .expect("could not delete document");
 
     let result = ctx
         .client
         .collection(&collection.name)
-        .count(Some(lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not query");
 
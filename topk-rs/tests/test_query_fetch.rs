@@ -23,7 +23,6 @@ async fn test_query_fetch(ctx: &mut ProjectTestContext) {
                 .limit(100)
                 .fetch(["summary"]),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -55,7 +54,6 @@ async fn test_query_fetch_streaming(ctx: &mut ProjectTestContext) {
                 .limit(100)
                 .fetch(["summary"]),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -87,7 +85,6 @@ async fn test_query_fetch_rejects_select_overlap(ctx: &mut ProjectTestContext) {
                 .limit(10)
                 .fetch(["title"]),
             None,
-            None,
         )
         .await
         .expect_err("should fail with overlapping select/fetch fields");
@@ -25,7 +25,6 @@ async fn test_string_contains_literal(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -47,7 +46,6 @@ async fn test_string_contains_literal_no_match(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -69,7 +67,6 @@ async fn test_string_contains_literal_empty(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -105,7 +102,6 @@ async fn test_string_contains_literal_with_keyword_index(ctx: &mut ProjectTestCo
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -128,7 +124,6 @@ async fn test_string_contains_field(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -150,7 +145,6 @@ async fn test_string_in_field(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -172,7 +166,6 @@ async fn test_string_contains_field_self(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -199,7 +192,6 @@ async fn test_list_match_any_with_keyword_index(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -236,7 +228,6 @@ async fn test_list_match_any_all_without_keyword_index(ctx: &mut ProjectTestCont
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
                 None,
-                None,
             )
             .await
             .expect_err("should have failed");
@@ -264,7 +255,6 @@ async fn test_list_contains_with_keyword_index(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -297,7 +287,6 @@ async fn test_list_contains_literal(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -329,7 +318,6 @@ async fn test_list_contains_int_literal(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -356,7 +344,6 @@ async fn test_list_contains_int_literal_different_type(ctx: &mut ProjectTestCont
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -383,7 +370,6 @@ async fn test_list_contains_int_field(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -410,7 +396,6 @@ async fn test_list_in_int_field(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -437,7 +422,6 @@ async fn test_list_contains_string_field_with_keyword_index(ctx: &mut ProjectTes
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -464,7 +448,6 @@ async fn test_list_in_string_field_with_keyword_index(ctx: &mut ProjectTestConte
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -491,7 +474,6 @@ async fn test_list_contains_string_field_without_keyword_index(ctx: &mut Project
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -530,7 +512,6 @@ async fn test_list_contains_invalid_types(#[case] expr: LogicalExpr) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("query failed");
@@ -553,7 +534,6 @@ async fn test_string_in(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -575,7 +555,6 @@ async fn test_in_list_literal_int(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -603,7 +582,6 @@ async fn test_in_list_literal_string(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -17,7 +17,6 @@ async fn test_query_regexp_match(ctx: &mut ProjectTestContext) {
         .query(
             filter(field("_id").regexp_match("^cat", Option::<&str>::None)).limit(10),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -36,7 +35,6 @@ async fn test_query_regexp_match_with_flags(ctx: &mut ProjectTestContext) {
         .query(
             filter(field("title").regexp_match("\\salchem", Some("i"))).limit(10),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -14,11 +14,7 @@ async fn test_query_starts_with(ctx: &mut ProjectTestContext) {
     let result = ctx
         .client
         .collection(&collection.name)
-        .query(
-            filter(field("_id").starts_with("cat")).limit(100),
-            None,
-            None,
-        )
+        .query(filter(field("_id").starts_with("cat")).limit(100), None)
         .await
         .expect("could not query");
 
@@ -33,7 +29,7 @@ async fn test_query_starts_with_empty(ctx: &mut ProjectTestContext) {
     let result = ctx
         .client
         .collection(&collection.name)
-        .query(filter(field("_id").starts_with("")).limit(100), None, None)
+        .query(filter(field("_id").starts_with("")).limit(100), None)
         .await
         .expect("could not query");
 
@@ -65,7 +61,6 @@ async fn test_query_starts_with_non_existent_prefix(ctx: &mut ProjectTestContext
         .query(
             filter(field("_id").starts_with("foobarbaz")).limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -81,11 +76,7 @@ async fn test_query_starts_with_list_string_scalar(ctx: &mut ProjectTestContext)
     let result = ctx
         .client
         .collection(&collection.name)
-        .query(
-            filter(field("tags").starts_with("lov")).limit(100),
-            None,
-            None,
-        )
+        .query(filter(field("tags").starts_with("lov")).limit(100), None)
         .await
         .expect("could not query");
 
@@ -103,7 +94,6 @@ async fn test_query_starts_with_list_string_field(ctx: &mut ProjectTestContext)
         .query(
             filter(field("tags").starts_with(field("_id"))).limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
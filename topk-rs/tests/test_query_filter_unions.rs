@@ -20,7 +20,6 @@ async fn test_query_union_eq(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -45,7 +44,6 @@ async fn test_query_union_starts_with(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -72,7 +70,6 @@ async fn test_query_union_contains(ctx: &mut ProjectTestContext) {
                     .sort((field("published_year"), SortOrder::Asc))
                     .limit(100),
                 None,
-                None,
             )
             .await
             .expect("could not query");
@@ -98,7 +95,6 @@ async fn test_query_union_contains_both_string_and_list(ctx: &mut ProjectTestCon
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -38,7 +38,6 @@ async fn test_query_hybrid_vector_bm25(ctx: &mut ProjectTestContext) {
             ))
             .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -72,7 +71,6 @@ async fn test_query_hybrid_keyword_boost(ctx: &mut ProjectTestContext) {
                 .sort((score_expr, SortOrder::Asc))
                 .limit(3),
                 None,
-                None,
             )
             .await
             .expect("could not query");
@@ -111,7 +109,6 @@ async fn test_query_hybrid_coalesce_score(ctx: &mut ProjectTestContext) {
             ))
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -148,7 +145,6 @@ async fn test_query_hybrid_recency_boost(ctx: &mut ProjectTestContext) {
             ))
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
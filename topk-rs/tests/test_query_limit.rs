@@ -25,7 +25,7 @@ async fn test_query_bare_limit(ctx: &mut ProjectTestContext) {
     let result = ctx
         .client
         .collection(&collection.name)
-        .query(select([("_id", field("_id"))]).limit(100), None, None)
+        .query(select([("_id", field("_id"))]).limit(100), None)
         .await
         .expect("could not query");
 
@@ -56,7 +56,6 @@ async fn test_query_limit_select_filter(ctx: &mut ProjectTestContext) {
             .filter(field("_id").lte(literal("hobbit")))
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -84,7 +83,6 @@ async fn test_query_limit_with_bm25(ctx: &mut ProjectTestContext) {
                 .filter(r#match("quest", None, None, true))
                 .limit(10),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -111,7 +109,6 @@ async fn test_query_limit_vector_distance(ctx: &mut ProjectTestContext) {
                 )])
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -129,7 +126,6 @@ async fn test_query_limit_vector_distance(ctx: &mut ProjectTestContext) {
                 .sort((field("summary_distance"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -162,11 +158,7 @@ async fn test_query_limit_offset(ctx: &mut ProjectTestContext) {
     let result = ctx
         .client
         .collection(&collection.name)
-        .query(
-            select([("_id", field("_id"))]).limit(4).offset(3),
-            None,
-            None,
-        )
+        .query(select([("_id", field("_id"))]).limit(4).offset(3), None)
         .await
         .expect("could not query");
 
@@ -195,7 +187,6 @@ async fn test_query_sort_limit_offset(ctx: &mut ProjectTestContext) {
             .limit(4)
             .offset(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -235,7 +226,7 @@ async fn test_query_invalid_collectors(#[case] query: Query) {
     let err = ctx
         .client
         .collection(&collection.name)
-        .query(query, None, None)
+        .query(query, None)
         .await
         .expect_err("should have failed");
 
@@ -20,7 +20,6 @@ async fn test_query_lte(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -45,7 +44,6 @@ async fn test_query_and(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -66,7 +64,6 @@ async fn test_query_is_null(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -90,7 +87,6 @@ async fn test_query_is_not_null(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -111,7 +107,6 @@ async fn test_query_not(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -149,7 +144,6 @@ async fn test_query_choose_literal(ctx: &mut ProjectTestContext) {
             .sort("love_score")
             .limit(10),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -175,7 +169,6 @@ async fn test_query_choose_literal_and_field(ctx: &mut ProjectTestContext) {
             .sort("love_score")
             .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -207,7 +200,6 @@ async fn test_query_choose_field(ctx: &mut ProjectTestContext) {
             .sort("love_score")
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -236,7 +228,6 @@ async fn test_query_coalesce_nullable(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -264,7 +255,6 @@ async fn test_query_coalesce_missing(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -292,7 +282,6 @@ async fn test_query_coalesce_non_nullable(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -319,7 +308,6 @@ async fn test_query_abs(ctx: &mut ProjectTestContext) {
                 .sort((field("abs_year"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -357,7 +345,6 @@ async fn test_query_topk_min_max(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -396,7 +383,6 @@ async fn test_query_logical_deep_recursion_limit(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect_err("should have failed due to recursion limit");
@@ -417,7 +403,6 @@ async fn test_query_gt_and_lte_string(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -441,7 +426,6 @@ async fn test_query_min_string(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
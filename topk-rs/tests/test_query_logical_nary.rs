@@ -25,7 +25,6 @@ async fn test_any_codes_vec(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -50,7 +49,6 @@ async fn test_all_codes_vec(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -82,7 +80,6 @@ async fn test_select_any_flag(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -119,7 +116,6 @@ async fn test_select_all_flag(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -159,7 +155,6 @@ async fn test_nested_any_all(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -190,7 +185,6 @@ async fn test_non_nested_any_and_all(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -215,7 +209,6 @@ async fn test_any_mixed_exprs(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -240,7 +233,6 @@ async fn test_all_mixed_exprs(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -263,7 +255,6 @@ async fn test_all_large_arity(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -286,7 +277,6 @@ async fn test_all_max_arity(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect_err("should have failed due to max arity");
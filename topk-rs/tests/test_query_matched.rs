@@ -22,7 +22,6 @@ async fn test_query_matched_count_reported_for_sorted(ctx: &mut ProjectTestConte
                 .sort("published_year")
                 .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -54,7 +53,6 @@ async fn test_query_matched_count_reported_for_vector(ctx: &mut ProjectTestConte
             .sort((field("summary_distance"), SortOrder::Asc))
             .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -80,7 +78,6 @@ async fn test_query_matched_count_absent_for_unsorted(ctx: &mut ProjectTestConte
         .query_stream(
             filter(field("published_year").gte(literal(1950_u32))).limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -48,7 +48,6 @@ macro_rules! test_query_multi_vector_float {
                             .sort("dist")
                             .limit(3),
                         None,
-                        None,
                     )
                     .await
                     .expect("could not query");
@@ -135,7 +134,6 @@ macro_rules! test_query_multi_vector_float_binary {
                             .sort("dist")
                             .limit($k),
                         None,
-                        None,
                     )
                     .await
                     .expect("could not query");
@@ -203,7 +201,6 @@ async fn test_query_multi_vector_int(ctx: &mut ProjectTestContext) {
                         .sort("dist")
                         .limit(3),
                     None,
-                    None,
                 )
                 .await
                 .expect("could not query");
@@ -236,7 +233,6 @@ async fn test_query_multi_vector_with_filter(ctx: &mut ProjectTestContext) {
                     .sort("dist")
                     .limit(3),
                 None,
-                None,
             )
             .await
             .expect("could not query");
@@ -268,7 +264,6 @@ async fn test_query_multi_vector_with_smve(ctx: &mut ProjectTestContext) {
                 .sort("dist")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -303,7 +298,6 @@ async fn test_query_multi_vector_with_invalid_smve(ctx: &mut ProjectTestContext)
                 .sort("dist")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect_err("Query should fail");
@@ -327,7 +321,6 @@ async fn test_query_multi_vector_with_invalid_smve(ctx: &mut ProjectTestContext)
                 .sort("dist")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect_err("Query should fail");
@@ -355,7 +348,6 @@ async fn test_query_multi_vector_with_invalid_dim(ctx: &mut ProjectTestContext)
                 .sort("dist")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect_err("Query should fail");
@@ -384,7 +376,6 @@ async fn test_query_multi_vector_with_invalid_data_type(ctx: &mut ProjectTestCon
                 .sort("dist")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect_err("Query should fail");
@@ -413,7 +404,6 @@ async fn test_query_multi_vector_with_empty_query(ctx: &mut ProjectTestContext)
                 .sort("dist")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect_err("Query should fail");
@@ -442,7 +432,6 @@ async fn test_query_multi_vector_with_missing_index(ctx: &mut ProjectTestContext
                 .sort("dist")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect_err("Query should fail");
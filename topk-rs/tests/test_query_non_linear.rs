@@ -32,7 +32,6 @@ async fn test_query_exp_ln(ctx: &mut ProjectTestContext) {
                 .sort("bm25_score_scale")
                 .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -71,7 +70,6 @@ async fn test_query_float_inf(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -100,7 +98,6 @@ async fn test_query_sqrt_square(ctx: &mut ProjectTestContext) {
             .sort((field("published_year_2"), SortOrder::Asc))
             .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -133,7 +130,6 @@ async fn test_query_sqrt_filter(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
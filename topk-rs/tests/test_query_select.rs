@@ -5,6 +5,7 @@ use topk_rs::data::literal;
 use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
 use topk_rs::proto::v1::data::Value;
 use topk_rs::query::{field, fns, r#match, select};
+use topk_rs::RequestOptions;
 use topk_rs::{doc, Error};
 
 mod utils;
@@ -25,7 +26,6 @@ async fn test_query_select_literal(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -47,7 +47,6 @@ async fn test_query_select_non_existing_field(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -68,7 +67,6 @@ async fn test_query_topk_limit(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -82,7 +80,6 @@ async fn test_query_topk_limit(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -96,7 +93,6 @@ async fn test_query_topk_limit(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(1),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -116,7 +112,6 @@ async fn test_query_topk_asc(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -144,7 +139,6 @@ async fn test_query_topk_desc(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -173,7 +167,6 @@ async fn test_query_select_bm25_score(ctx: &mut ProjectTestContext) {
                 .sort((field("bm25_score"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -201,7 +194,6 @@ async fn test_query_select_vector_distance(ctx: &mut ProjectTestContext) {
             .sort((field("summary_distance"), SortOrder::Asc))
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -220,7 +212,6 @@ async fn test_query_select_indexed_vector(ctx: &mut ProjectTestContext) {
         .query(
             select([("vec", field("summary_embedding"))]).limit(10),
             None,
-            None,
         )
         .await
         .expect_err("expected query to fail");
@@ -243,7 +234,6 @@ async fn test_query_select_u8_vector(ctx: &mut ProjectTestContext) {
         .query(
             select([("scalar_embedding", field("scalar_embedding"))]).limit(10),
             None,
-            None,
         )
         .await
         .expect_err("expected query to fail");
@@ -265,10 +255,13 @@ async fn test_query_select_null_field(ctx: &mut ProjectTestContext) {
 
     ctx.client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1984", "a" => Value::null()),
-            doc!("_id" => "pride"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "1984", "a" => Value::null()),
+                doc!("_id" => "pride"),
+            ],
+            None,
+        )
         .await
         .expect("could not upsert documents");
 
@@ -280,7 +273,6 @@ async fn test_query_select_null_field(ctx: &mut ProjectTestContext) {
                 .sort((field("b"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -318,7 +310,6 @@ async fn test_query_select_text_match(ctx: &mut ProjectTestContext) {
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -364,7 +355,7 @@ async fn test_query_select_union(ctx: &mut ProjectTestContext) {
             doc!("_id" => "11", "rank" => 11, "mixed" => Value::bytes(vec![1, 2, 3])),
             doc!("_id" => "12", "rank" => 12, "mixed" => Value::list(vec![17u32, 6, 1997])),
             doc!("_id" => "13", "rank" => 13, "mixed" => Value::list(vec!["foo".to_string(), "bar".to_string()])),
-        ])
+        ], None)
         .await
         .expect("upsert failed");
 
@@ -372,7 +363,7 @@ async fn test_query_select_union(ctx: &mut ProjectTestContext) {
     let _ = ctx
         .client
         .collection(&collection.name)
-        .count(Some(lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not query");
 
@@ -384,7 +375,6 @@ async fn test_query_select_union(ctx: &mut ProjectTestContext) {
                 .sort((field("rank"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -431,7 +421,7 @@ async fn test_query_select_list(ctx: &mut ProjectTestContext) {
             doc!("_id" => "2", "rank" => 2),
             doc!("_id" => "3", "rank" => 3, "list" => Value::list(Vec::<String>::new())),
             doc!("_id" => "4", "rank" => 4, "list" => Value::list(vec!["baz".to_string()])),
-        ])
+        ], None)
         .await
         .expect("upsert failed");
 
@@ -439,7 +429,7 @@ async fn test_query_select_list(ctx: &mut ProjectTestContext) {
     let _ = ctx
         .client
         .collection(&collection.name)
-        .count(Some(lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not query");
 
@@ -451,7 +441,6 @@ async fn test_query_select_list(ctx: &mut ProjectTestContext) {
                 .sort((field("rank"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -475,7 +464,7 @@ async fn test_query_without_collector(ctx: &mut ProjectTestContext) {
     let result = ctx
         .client
         .collection(&collection.name)
-        .query(select([("_id", field("_id"))]), None, None)
+        .query(select([("_id", field("_id"))]), None)
         .await
         .expect("could not query");
 
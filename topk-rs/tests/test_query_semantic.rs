@@ -31,7 +31,7 @@ async fn test_semantic_index_write_docs(ctx: &mut ProjectTestContext) {
     let result = ctx
         .client
         .collection(&collection.name)
-        .count(None, None)
+        .count(None)
         .await
         .expect("could not query");
 
@@ -51,7 +51,6 @@ async fn test_semantic_index_query(ctx: &mut ProjectTestContext) {
                 .sort((field("sim"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -73,7 +72,6 @@ async fn test_semantic_index_query_with_text_filter(ctx: &mut ProjectTestContext
                 .sort((field("sim"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -94,7 +92,6 @@ async fn test_semantic_index_query_with_missing_index(ctx: &mut ProjectTestConte
                 .sort((field("sim"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect_err("should not query");
@@ -118,7 +115,6 @@ async fn test_semantic_index_query_multiple_fields(ctx: &mut ProjectTestContext)
             .sort((field("title_sim").add(field("summary_sim")), SortOrder::Asc))
             .limit(5),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -22,7 +22,6 @@ async fn test_query_sort_by_scalar(ctx: &mut ProjectTestContext) {
                 .sort("published_year")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -48,7 +47,6 @@ async fn test_query_sort_by_multiple_fields(ctx: &mut ProjectTestContext) {
                 ])
                 .limit(4),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -72,7 +70,6 @@ async fn test_query_sort_by_multiple_with_literal(ctx: &mut ProjectTestContext)
                 ])
                 .limit(4),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -97,7 +94,6 @@ async fn test_query_sort_by_too_many_fields(ctx: &mut ProjectTestContext) {
                 )
                 .limit(4),
             None,
-            None,
         )
         .await
         .expect_err("Query should have failed validation");
@@ -2,6 +2,7 @@ use float8::F8E4M3;
 use test_context::test_context;
 use topk_rs::proto::v1::data::{SparseVector, Value};
 use topk_rs::query::{field, fns, select};
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::dataset;
@@ -30,7 +31,6 @@ async fn test_query_sparse_vector_distance_f32(ctx: &mut ProjectTestContext) {
                 .sort("sparse_f32_distance")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -68,7 +68,6 @@ async fn test_query_sparse_vector_distance_f8(ctx: &mut ProjectTestContext) {
                 .sort("sparse_distance")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -96,7 +95,6 @@ async fn test_query_sparse_vector_distance_u8(ctx: &mut ProjectTestContext) {
                 .sort("sparse_u8_distance")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -124,7 +122,6 @@ async fn test_query_sparse_vector_distance_nullable(ctx: &mut ProjectTestContext
                 .sort("sparse_u8_distance")
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -133,7 +130,7 @@ async fn test_query_sparse_vector_distance_nullable(ctx: &mut ProjectTestContext
     let mut mockingbird = ctx
         .client
         .collection(&collection.name)
-        .get(["mockingbird"], None, None, None)
+        .get(["mockingbird"], None, None)
         .await
         .expect("could not get mockingbird")
         .get("mockingbird")
@@ -145,7 +142,10 @@ async fn test_query_sparse_vector_distance_nullable(ctx: &mut ProjectTestContext
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![topk_rs::proto::v1::data::Document::from(mockingbird)])
+        .upsert(
+            vec![topk_rs::proto::v1::data::Document::from(mockingbird)],
+            None,
+        )
         .await
         .expect("could not upsert mockingbird");
 
@@ -163,8 +163,7 @@ async fn test_query_sparse_vector_distance_nullable(ctx: &mut ProjectTestContext
                 )])
                 .sort("sparse_u8_distance")
                 .limit(3),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query");
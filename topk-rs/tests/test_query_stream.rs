@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
+use prost::Message;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_util::sync::CancellationToken;
+use tonic::{Response, Status};
+use topk_rs::client::DocumentStream;
+use topk_rs::doc;
+use topk_rs::proto::v1::data::query_service_server::{QueryService, QueryServiceServer};
+use topk_rs::proto::v1::data::{
+    DocumentData, GetRequest, GetResponse, QueryRequest, QueryResponse,
+};
+use topk_rs::query::{field, select};
+use topk_rs::{Client, ClientConfig, Error, RequestOptions};
+
+/// Server streaming a single document, then stalling without ending the stream.
+#[derive(Clone, Default)]
+struct Server;
+
+#[tonic::async_trait]
+impl QueryService for Server {
+    type QueryStreamStream = BoxStream<'static, Result<DocumentData, Status>>;
+    type GetStreamStream = BoxStream<'static, Result<DocumentData, Status>>;
+
+    async fn query(
+        &self,
+        _request: tonic::Request<QueryRequest>,
+    ) -> Result<Response<QueryResponse>, Status> {
+        Err(Status::unimplemented("query"))
+    }
+
+    async fn query_stream(
+        &self,
+        _request: tonic::Request<QueryRequest>,
+    ) -> Result<Response<Self::QueryStreamStream>, Status> {
+        let doc = DocumentData {
+            data: doc!("_id" => "one").encode_to_vec().into(),
+        };
+        Ok(Response::new(Box::pin(
+            stream::iter([Ok(doc)]).chain(stream::pending()),
+        )))
+    }
+
+    async fn get(
+        &self,
+        _request: tonic::Request<GetRequest>,
+    ) -> Result<Response<GetResponse>, Status> {
+        Err(Status::unimplemented("get"))
+    }
+
+    async fn get_stream(
+        &self,
+        _request: tonic::Request<GetRequest>,
+    ) -> Result<Response<Self::GetStreamStream>, Status> {
+        Err(Status::unimplemented("get_stream"))
+    }
+}
+
+/// Starts a server and returns its address.
+async fn serve() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(QueryServiceServer::new(Server))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    format!("http://{addr}")
+}
+
+async fn query_stream(options: RequestOptions) -> DocumentStream {
+    let config = ClientConfig::new("api-key", "local").with_endpoint(serve().await);
+    Client::new(config)
+        .collection("books")
+        .query_stream(select([("_id", field("_id"))]).limit(10), Some(options))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_query_stream_cancelled() {
+    let token = CancellationToken::new();
+    let mut stream =
+        query_stream(RequestOptions::new().with_cancellation_token(token.clone())).await;
+    assert_eq!(stream.next().await.unwrap().unwrap(), doc!("_id" => "one"));
+
+    token.cancel();
+    let err = stream
+        .next()
+        .await
+        .unwrap()
+        .expect_err("stream is cancelled");
+    assert!(matches!(err, Error::Cancelled), "{err}");
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_query_stream_deadline_exceeded() {
+    let mut stream =
+        query_stream(RequestOptions::new().with_timeout(Duration::from_millis(200))).await;
+    assert_eq!(stream.next().await.unwrap().unwrap(), doc!("_id" => "one"));
+
+    let err = stream.next().await.unwrap().expect_err("stream times out");
+    assert!(matches!(err, Error::DeadlineExceeded(_)), "{err}");
+    assert!(stream.next().await.is_none());
+}
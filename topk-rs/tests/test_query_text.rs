@@ -1,5 +1,6 @@
 use test_context::test_context;
 mod utils;
+use topk_rs::RequestOptions;
 use topk_rs::{
     data::literal,
     doc,
@@ -26,7 +27,6 @@ async fn test_query_text_filter_single_term_disjunctive(ctx: &mut ProjectTestCon
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -47,7 +47,6 @@ async fn test_query_text_filter_single_term_conjunctive(ctx: &mut ProjectTestCon
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -73,7 +72,6 @@ async fn test_query_text_filter_two_terms_disjunctive(ctx: &mut ProjectTestConte
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -99,7 +97,6 @@ async fn test_query_text_filter_two_terms_conjunctive(ctx: &mut ProjectTestConte
             .sort((field("published_year"), SortOrder::Asc))
             .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -120,7 +117,6 @@ async fn test_query_text_filter_stop_word(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -142,7 +138,6 @@ async fn test_query_text_should_does_not_filter(ctx: &mut ProjectTestContext) {
                 .sort("bm25")
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -174,7 +169,6 @@ async fn test_query_text_should_boosts_bm25_score(ctx: &mut ProjectTestContext)
                     .sort("bm25")
                     .limit(100),
                 None,
-                None,
             )
             .await
             .expect("could not query");
@@ -197,7 +191,6 @@ async fn test_query_select_bm25_without_text_queries(ctx: &mut ProjectTestContex
                 .sort((field("bm25_score"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect_err("should have failed");
@@ -222,7 +215,6 @@ async fn test_query_text_matches_single_term(ctx: &mut ProjectTestContext) {
                     .sort((field("published_year"), SortOrder::Asc))
                     .limit(100),
                 None,
-                None,
             )
             .await
             .expect("could not query");
@@ -244,7 +236,6 @@ async fn test_query_text_match_all_two_terms(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -265,7 +256,6 @@ async fn test_query_text_match_all_two_terms_tokenized(ctx: &mut ProjectTestCont
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -286,7 +276,6 @@ async fn test_query_text_match_any_two_terms(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -307,7 +296,6 @@ async fn test_query_text_match_any_two_terms_tokenized(ctx: &mut ProjectTestCont
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -328,7 +316,6 @@ async fn test_query_text_matches_with_logical_expr(ctx: &mut ProjectTestContext)
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(10),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -347,7 +334,6 @@ async fn test_query_text_matches_on_invalid_field(ctx: &mut ProjectTestContext)
         .query(
             filter(field("published_year").match_all("love class")).count(),
             None,
-            None,
         )
         .await
         .expect_err("should have failed");
@@ -374,10 +360,13 @@ async fn test_query_text_with_updates(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1", "text" => "totalitarian regime uses surveillance"),
-            doc!("_id" => "2", "text" => "millionaire navigates love and wealth"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "1", "text" => "totalitarian regime uses surveillance"),
+                doc!("_id" => "2", "text" => "millionaire navigates love and wealth"),
+            ],
+            None,
+        )
         .await
         .expect("upsert failed");
 
@@ -389,8 +378,7 @@ async fn test_query_text_with_updates(ctx: &mut ProjectTestContext) {
                 .filter(r#match("surveillance", None, None, true))
                 .sort((literal(1u32).into(), SortOrder::Asc))
                 .limit(10),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("query returned error");
@@ -400,9 +388,10 @@ async fn test_query_text_with_updates(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1", "text" => "totalitarian regime uses love"),
-        ])
+        .upsert(
+            vec![doc!("_id" => "1", "text" => "totalitarian regime uses love")],
+            None,
+        )
         .await
         .expect("upsert failed");
 
@@ -414,8 +403,7 @@ async fn test_query_text_with_updates(ctx: &mut ProjectTestContext) {
                 .filter(r#match("love", None, None, true))
                 .sort((literal(1u32).into(), SortOrder::Asc))
                 .limit(10),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("query returned error");
@@ -442,10 +430,13 @@ async fn test_query_text_exact_keyword(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "nyc", "tag" => "New York City"),
-            doc!("_id" => "camel", "tag" => "CamelCase"),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "nyc", "tag" => "New York City"),
+                doc!("_id" => "camel", "tag" => "CamelCase"),
+            ],
+            None,
+        )
         .await
         .expect("upsert failed");
 
@@ -463,8 +454,7 @@ async fn test_query_text_exact_keyword(ctx: &mut ProjectTestContext) {
             .collection(&collection.name)
             .query(
                 filter(r#match(token, Some("tag"), None, false)).limit(10),
-                Some(lsn.clone()),
-                None,
+                Some(RequestOptions::new().with_lsn(lsn.clone())),
             )
             .await
             .expect("query returned error");
@@ -495,7 +485,6 @@ async fn test_query_text_deep_recursion_limit(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect_err("should have failed due to recursion limit");
@@ -26,7 +26,6 @@ async fn test_query_filter_timestamp(ctx: &mut ProjectTestContext) {
             )
             .limit(20),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -50,7 +49,6 @@ async fn test_query_date_part_eq_field(ctx: &mut ProjectTestContext) {
             )
             .count(),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -70,7 +68,6 @@ async fn test_query_date_part_lt_literal(ctx: &mut ProjectTestContext) {
         .query(
             filter(field("published_ts").date_part("month").lt(literal(6))).limit(10),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -95,7 +92,6 @@ async fn test_query_date_part_group_by(ctx: &mut ProjectTestContext) {
                 .sort([(field("published_month"), SortOrder::Asc)])
                 .limit(20),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use test_context::test_context;
+use topk_rs::RequestOptions;
 use topk_rs::{
     proto::v1::data::stage::sort_stage::SortOrder,
     query::{field, select},
@@ -22,7 +23,7 @@ async fn test_query_tombstone_only(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .delete(vec!["ghost_1".into(), "ghost_2".into()])
+        .delete(vec!["ghost_1".into(), "ghost_2".into()], None)
         .await
         .expect("could not delete");
 
@@ -34,8 +35,7 @@ async fn test_query_tombstone_only(ctx: &mut ProjectTestContext) {
             select([("x", field("x"))])
                 .sort((field("x"), SortOrder::Asc))
                 .limit(10),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("query on tombstone-only WAL should not fail");
@@ -3,6 +3,7 @@ use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
 use topk_rs::proto::v1::data::Value;
 use topk_rs::query::{field, select};
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 use topk_rs::{doc, schema};
 
 mod utils;
@@ -22,7 +23,6 @@ async fn test_query_topk_by_non_primitive(ctx: &mut ProjectTestContext) {
                 .sort((field("title"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("query failed");
@@ -43,7 +43,6 @@ async fn test_query_topk_by_non_existing(ctx: &mut ProjectTestContext) {
                 .sort((field("non_existing_field"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect_err("should have failed");
@@ -67,7 +66,6 @@ async fn test_query_topk_limit_zero(ctx: &mut ProjectTestContext) {
                 .sort((field("published_year"), SortOrder::Asc))
                 .limit(0),
             None,
-            None,
         )
         .await
         .expect_err("should have failed");
@@ -93,10 +91,13 @@ async fn test_union_u32_and_binary(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1", "num" => (1 as u32)),
-            doc!("_id" => "11", "num" => Value::binary(vec![1, 2, 3])),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "1", "num" => (1 as u32)),
+                doc!("_id" => "11", "num" => Value::binary(vec![1, 2, 3])),
+            ],
+            None,
+        )
         .await
         .expect("upsert failed");
 
@@ -104,7 +105,7 @@ async fn test_union_u32_and_binary(ctx: &mut ProjectTestContext) {
     let _ = ctx
         .client
         .collection(&collection.name)
-        .count(Some(lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not query");
 
@@ -116,7 +117,6 @@ async fn test_union_u32_and_binary(ctx: &mut ProjectTestContext) {
                 .sort((field("num"), SortOrder::Asc))
                 .limit(100),
             None,
-            None,
         )
         .await
         .expect_err("should have failed");
@@ -3,6 +3,7 @@ use test_context::test_context;
 use topk_rs::proto::v1::control::{FieldIndex, FieldSpec, VectorDistanceMetric};
 use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
 use topk_rs::query::{field, fns, select};
+use topk_rs::RequestOptions;
 
 mod utils;
 use topk_rs::{doc, schema};
@@ -28,7 +29,6 @@ async fn test_query_vector_distance(ctx: &mut ProjectTestContext) {
                 .sort((field("summary_distance"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -55,7 +55,6 @@ async fn test_query_vector_distance_without_refine(ctx: &mut ProjectTestContext)
                 .sort((field("summary_distance"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -75,7 +74,6 @@ async fn test_query_vector_distance_without_refine(ctx: &mut ProjectTestContext)
                 .sort((field("summary_distance"), SortOrder::Asc))
                 .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -103,7 +101,6 @@ async fn test_query_vector_distance_nullable(ctx: &mut ProjectTestContext) {
             .sort((field("summary_distance"), SortOrder::Asc))
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -128,7 +125,6 @@ async fn test_query_vector_distance_u8_vector(ctx: &mut ProjectTestContext) {
             .sort((field("summary_distance"), SortOrder::Asc))
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -153,7 +149,6 @@ async fn test_query_vector_distance_i8_vector(ctx: &mut ProjectTestContext) {
             .sort((field("summary_distance"), SortOrder::Asc))
             .limit(3),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -178,7 +173,6 @@ async fn test_query_vector_distance_binary_vector(ctx: &mut ProjectTestContext)
             .sort((field("summary_distance"), SortOrder::Asc))
             .limit(2),
             None,
-            None,
         )
         .await
         .expect("could not query");
@@ -206,13 +200,16 @@ async fn test_query_vector_distance_f16_vector(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1", "embedding" => vec![half::f16::from_f32(1.0); 16]),
-            doc!("_id" => "2", "embedding" => vec![half::f16::from_f32(2.0); 16]),
-            doc!("_id" => "3", "embedding" => vec![half::f16::from_f32(3.0); 16]),
-            doc!("_id" => "4", "embedding" => vec![half::f16::from_f32(4.0); 16]),
-            doc!("_id" => "5", "embedding" => vec![half::f16::from_f32(5.0); 16]),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "1", "embedding" => vec![half::f16::from_f32(1.0); 16]),
+                doc!("_id" => "2", "embedding" => vec![half::f16::from_f32(2.0); 16]),
+                doc!("_id" => "3", "embedding" => vec![half::f16::from_f32(3.0); 16]),
+                doc!("_id" => "4", "embedding" => vec![half::f16::from_f32(4.0); 16]),
+                doc!("_id" => "5", "embedding" => vec![half::f16::from_f32(5.0); 16]),
+            ],
+            None,
+        )
         .await
         .expect("could not insert");
 
@@ -226,8 +223,7 @@ async fn test_query_vector_distance_f16_vector(ctx: &mut ProjectTestContext) {
             )])
             .sort((field("dist"), SortOrder::Asc))
             .limit(3),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query");
@@ -255,13 +251,16 @@ async fn test_query_vector_distance_f8_vector(ctx: &mut ProjectTestContext) {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(vec![
-            doc!("_id" => "1", "embedding" => vec![F8E4M3::from_f32(1.0); 16]),
-            doc!("_id" => "2", "embedding" => vec![F8E4M3::from_f32(2.0); 16]),
-            doc!("_id" => "3", "embedding" => vec![F8E4M3::from_f32(3.0); 16]),
-            doc!("_id" => "4", "embedding" => vec![F8E4M3::from_f32(4.0); 16]),
-            doc!("_id" => "5", "embedding" => vec![F8E4M3::from_f32(5.0); 16]),
-        ])
+        .upsert(
+            vec![
+                doc!("_id" => "1", "embedding" => vec![F8E4M3::from_f32(1.0); 16]),
+                doc!("_id" => "2", "embedding" => vec![F8E4M3::from_f32(2.0); 16]),
+                doc!("_id" => "3", "embedding" => vec![F8E4M3::from_f32(3.0); 16]),
+                doc!("_id" => "4", "embedding" => vec![F8E4M3::from_f32(4.0); 16]),
+                doc!("_id" => "5", "embedding" => vec![F8E4M3::from_f32(5.0); 16]),
+            ],
+            None,
+        )
         .await
         .expect("could not insert");
 
@@ -275,8 +274,7 @@ async fn test_query_vector_distance_f8_vector(ctx: &mut ProjectTestContext) {
             )])
             .sort((field("dist"), SortOrder::Asc))
             .limit(3),
-            Some(lsn),
-            None,
+            Some(RequestOptions::new().with_lsn(lsn)),
         )
         .await
         .expect("could not query");
@@ -1,6 +1,7 @@
 use test_context::test_context;
 use topk_rs::query::{field, select};
 use topk_rs::Error;
+use topk_rs::RequestOptions;
 
 mod utils;
 use utils::dataset;
@@ -13,7 +14,11 @@ async fn test_invalid_required_lsn(ctx: &mut ProjectTestContext) {
     let collection = ctx.client.collection(&collection.name);
 
     let err = collection
-        .get(["1984"], None, Some("0".to_string()), None)
+        .get(
+            ["1984"],
+            None,
+            Some(RequestOptions::new().with_lsn("0".to_string())),
+        )
         .await
         .expect_err("get with zero lsn must be rejected");
     assert!(
@@ -24,8 +29,7 @@ async fn test_invalid_required_lsn(ctx: &mut ProjectTestContext) {
     let err = collection
         .query(
             select([("_id", field("_id"))]).limit(1),
-            Some("0".to_string()),
-            None,
+            Some(RequestOptions::new().with_lsn("0".to_string())),
         )
         .await
         .expect_err("query with zero lsn must be rejected");
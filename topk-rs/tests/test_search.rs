@@ -22,13 +22,13 @@ async fn test_search(ctx: &mut ProjectTestContext) {
     let handle = ctx
         .client
         .dataset(&dataset.name)
-        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new())
+        .upsert_file("doc1", test_pdf(), Vec::<(String, Value)>::new(), None)
         .await
         .expect("could not upsert file");
 
     ctx.client
         .dataset(&dataset.name)
-        .wait_for_handle(&handle, None)
+        .wait_for_handle(&handle, None, None)
         .await
         .expect("could not wait for handle");
 
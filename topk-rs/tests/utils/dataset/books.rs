@@ -12,6 +12,7 @@ use topk_rs::proto::v1::{
     control::{Collection, FieldSpec},
     data::Document,
 };
+use topk_rs::RequestOptions;
 use topk_rs::{doc, schema};
 
 #[allow(dead_code)]
@@ -29,7 +30,7 @@ pub async fn setup(ctx: &mut ProjectTestContext) -> Collection {
         lsn = ctx
             .client
             .collection(&collection.name)
-            .upsert(chunk.to_vec())
+            .upsert(chunk.to_vec(), None)
             .await
             .expect("upsert failed");
     }
@@ -37,7 +38,7 @@ pub async fn setup(ctx: &mut ProjectTestContext) -> Collection {
     let count = ctx
         .client
         .collection(&collection.name)
-        .count(Some(lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not query");
 
@@ -10,6 +10,7 @@ use topk_rs::proto::v1::{
     control::{Collection, FieldSpec},
     data::Document,
 };
+use topk_rs::RequestOptions;
 use topk_rs::{doc, schema};
 
 use crate::utils::ProjectTestContext;
@@ -77,7 +78,7 @@ pub async fn setup(
         lsn = ctx
             .client
             .collection(&collection.name)
-            .upsert(chunk.to_vec())
+            .upsert(chunk.to_vec(), None)
             .await
             .expect("upsert failed");
     }
@@ -85,7 +86,7 @@ pub async fn setup(
     let count = ctx
         .client
         .collection(&collection.name)
-        .count(Some(lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not query");
 
@@ -4,6 +4,7 @@ use topk_rs::proto::v1::{
     control::{Collection, FieldIndex, FieldSpec},
     data::Document,
 };
+use topk_rs::RequestOptions;
 use topk_rs::{doc, schema};
 
 #[allow(dead_code)]
@@ -18,14 +19,14 @@ pub async fn setup(ctx: &mut ProjectTestContext) -> Collection {
     let lsn = ctx
         .client
         .collection(&collection.name)
-        .upsert(docs())
+        .upsert(docs(), None)
         .await
         .expect("upsert failed");
 
     let _ = ctx
         .client
         .collection(&collection.name)
-        .count(Some(lsn), None)
+        .count(Some(RequestOptions::new().with_lsn(lsn)))
         .await
         .expect("could not query");
 
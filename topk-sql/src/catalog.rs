@@ -0,0 +1,885 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use regex::Regex;
+use sqlparser::ast::ObjectName;
+use strum_macros::IntoStaticStr;
+use topk_rs::proto::v1::control::{Collection, FieldSpec};
+use topk_rs::proto::v1::data::{Document, Value};
+
+use crate::schema::{
+    self, PG_BOOL, PG_BYTEA, PG_FLOAT4_ARRAY, PG_FLOAT8, PG_INT8, PG_JSONB, PG_TEXT,
+};
+use crate::{Error, ObjectNameExt};
+
+/// `pg_namespace.oid` of `pg_catalog`.
+const PG_CATALOG_OID: i64 = 11;
+/// `pg_namespace.oid` of `public`.
+const PUBLIC_OID: i64 = 2200;
+/// `pg_namespace.oid` of `information_schema`.
+const INFORMATION_SCHEMA_OID: i64 = 13000;
+/// `pg_class.oid` of `pg_class` itself (`pg_description.classoid` of column comments).
+const PG_CLASS_OID: i64 = 1259;
+/// First `pg_class.oid` handed out to collections (first non-system OID in PostgreSQL).
+const FIRST_TABLE_OID: i64 = 16384;
+/// `pg_authid.oid` of the bootstrap superuser.
+const OWNER_OID: i64 = 10;
+
+/// Synthetic `information_schema` / `pg_catalog` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum CatalogTable {
+    /// `information_schema.tables`
+    Tables,
+    /// `information_schema.columns`
+    Columns,
+    /// `pg_catalog.pg_namespace`
+    PgNamespace,
+    /// `pg_catalog.pg_class`
+    PgClass,
+    /// `pg_catalog.pg_attribute`
+    PgAttribute,
+    /// `pg_catalog.pg_type`
+    PgType,
+    /// `pg_catalog.pg_description`
+    PgDescription,
+    /// `pg_catalog.pg_tables`
+    PgTables,
+}
+
+impl CatalogTable {
+    /// Resolves `information_schema.<table>` and `[pg_catalog.]pg_<table>`.
+    pub fn from_name(name: &ObjectName) -> Option<CatalogTable> {
+        let table = name.0.last()?.as_ident()?.value.to_ascii_lowercase();
+        let schema = name.schema().map(|s| s.to_ascii_lowercase());
+
+        match (schema.as_deref(), table.as_str()) {
+            (Some("information_schema"), "tables") => Some(CatalogTable::Tables),
+            (Some("information_schema"), "columns") => Some(CatalogTable::Columns),
+            (Some("pg_catalog") | None, "pg_namespace") => Some(CatalogTable::PgNamespace),
+            (Some("pg_catalog") | None, "pg_class") => Some(CatalogTable::PgClass),
+            (Some("pg_catalog") | None, "pg_attribute") => Some(CatalogTable::PgAttribute),
+            (Some("pg_catalog") | None, "pg_type") => Some(CatalogTable::PgType),
+            (Some("pg_catalog") | None, "pg_description") => Some(CatalogTable::PgDescription),
+            (Some("pg_catalog") | None, "pg_tables") => Some(CatalogTable::PgTables),
+            _ => None,
+        }
+    }
+
+    /// Qualified name, eg. `information_schema.tables`.
+    pub fn name(&self) -> String {
+        let schema = match self {
+            CatalogTable::Tables | CatalogTable::Columns => "information_schema",
+            _ => "pg_catalog",
+        };
+        format!("{schema}.{}", <&'static str>::from(self))
+    }
+
+    /// Columns of the table, in `SELECT *` order.
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            CatalogTable::Tables => &[
+                "table_catalog",
+                "table_schema",
+                "table_name",
+                "table_type",
+                "table_owner",
+            ],
+            CatalogTable::Columns => &[
+                "table_catalog",
+                "table_schema",
+                "table_name",
+                "column_name",
+                "ordinal_position",
+                "column_default",
+                "is_nullable",
+                "data_type",
+                "udt_name",
+                "column_comment",
+            ],
+            CatalogTable::PgNamespace => &["oid", "nspname", "nspowner"],
+            CatalogTable::PgClass => &[
+                "oid",
+                "relname",
+                "relnamespace",
+                "relkind",
+                "relowner",
+                "relnatts",
+                "relhasindex",
+            ],
+            CatalogTable::PgAttribute => &[
+                "attrelid",
+                "attname",
+                "atttypid",
+                "attnum",
+                "attnotnull",
+                "atthasdef",
+                "attisdropped",
+                "atttypmod",
+            ],
+            CatalogTable::PgType => &[
+                "oid",
+                "typname",
+                "typnamespace",
+                "typlen",
+                "typtype",
+                "typcategory",
+                "typelem",
+            ],
+            CatalogTable::PgDescription => &["objoid", "classoid", "objsubid", "description"],
+            CatalogTable::PgTables => &[
+                "schemaname",
+                "tablename",
+                "tableowner",
+                "tablespace",
+                "hasindexes",
+                "hasrules",
+                "hastriggers",
+                "rowsecurity",
+            ],
+        }
+    }
+}
+
+/// Catalog of the collections in a project.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    /// `(name, columns)` of each collection, sorted by name. `_id` is always the first column.
+    tables: Vec<(String, Vec<(String, FieldSpec)>)>,
+}
+
+impl Catalog {
+    pub fn new(collections: impl IntoIterator<Item = Collection>) -> Self {
+        let mut tables = collections
+            .into_iter()
+            .map(|collection| {
+                let mut columns = collection.schema.into_iter().collect::<Vec<_>>();
+                columns.sort_by(|a, b| a.0.cmp(&b.0));
+                columns.insert(0, ("_id".to_string(), FieldSpec::text(true)));
+                (collection.name, columns)
+            })
+            .collect::<Vec<_>>();
+        tables.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Self { tables }
+    }
+
+    /// Builds the catalog from `CollectionsClient::list`.
+    pub async fn load(client: &topk_rs::CollectionsClient) -> Result<Self, Error> {
+        Ok(Self::new(client.list().await?))
+    }
+
+    /// Rows of a catalog table.
+    pub fn rows(&self, table: CatalogTable) -> Vec<Document> {
+        match table {
+            CatalogTable::Tables => self
+                .tables
+                .iter()
+                .map(|(name, _)| {
+                    row([
+                        ("table_catalog", Value::string("topk")),
+                        ("table_schema", Value::string("public")),
+                        ("table_name", Value::string(name)),
+                        ("table_type", Value::string("BASE TABLE")),
+                        ("table_owner", Value::string("topk")),
+                    ])
+                })
+                .collect(),
+            CatalogTable::Columns => self
+                .columns()
+                .map(|(table, position, column, spec)| {
+                    let pg_type = schema::pg_type(spec);
+                    row([
+                        ("table_catalog", Value::string("topk")),
+                        ("table_schema", Value::string("public")),
+                        ("table_name", Value::string(table)),
+                        ("column_name", Value::string(column)),
+                        ("ordinal_position", Value::i64(position)),
+                        ("column_default", Value::null()),
+                        (
+                            "is_nullable",
+                            Value::string(if spec.required { "NO" } else { "YES" }),
+                        ),
+                        ("data_type", Value::string(pg_type.sql)),
+                        ("udt_name", Value::string(pg_type.name)),
+                        ("column_comment", nullable(column_comment(spec))),
+                    ])
+                })
+                .collect(),
+            CatalogTable::PgNamespace => [
+                (PG_CATALOG_OID, "pg_catalog"),
+                (PUBLIC_OID, "public"),
+                (INFORMATION_SCHEMA_OID, "information_schema"),
+            ]
+            .into_iter()
+            .map(|(oid, name)| {
+                row([
+                    ("oid", Value::i64(oid)),
+                    ("nspname", Value::string(name)),
+                    ("nspowner", Value::i64(OWNER_OID)),
+                ])
+            })
+            .collect(),
+            CatalogTable::PgClass => self
+                .tables
+                .iter()
+                .enumerate()
+                .map(|(i, (name, columns))| {
+                    row([
+                        ("oid", Value::i64(table_oid(i))),
+                        ("relname", Value::string(name)),
+                        ("relnamespace", Value::i64(PUBLIC_OID)),
+                        ("relkind", Value::string("r")),
+                        ("relowner", Value::i64(OWNER_OID)),
+                        ("relnatts", Value::i64(columns.len() as i64)),
+                        ("relhasindex", Value::bool(has_index(columns))),
+                    ])
+                })
+                .collect(),
+            CatalogTable::PgAttribute => self
+                .columns_with_oid()
+                .map(|(oid, position, column, spec)| {
+                    row([
+                        ("attrelid", Value::i64(oid)),
+                        ("attname", Value::string(column)),
+                        ("atttypid", Value::i64(schema::pg_type(spec).oid)),
+                        ("attnum", Value::i64(position)),
+                        ("attnotnull", Value::bool(spec.required)),
+                        ("atthasdef", Value::bool(false)),
+                        ("attisdropped", Value::bool(false)),
+                        ("atttypmod", Value::i64(-1)),
+                    ])
+                })
+                .collect(),
+            CatalogTable::PgType => PG_TYPES
+                .iter()
+                .map(|(pg_type, len, category, elem)| {
+                    row([
+                        ("oid", Value::i64(pg_type.oid)),
+                        ("typname", Value::string(pg_type.name)),
+                        ("typnamespace", Value::i64(PG_CATALOG_OID)),
+                        ("typlen", Value::i64(*len)),
+                        ("typtype", Value::string("b")),
+                        ("typcategory", Value::string(*category)),
+                        ("typelem", Value::i64(*elem)),
+                    ])
+                })
+                .collect(),
+            CatalogTable::PgDescription => self
+                .columns_with_oid()
+                .filter_map(|(oid, position, _, spec)| {
+                    Some(row([
+                        ("objoid", Value::i64(oid)),
+                        ("classoid", Value::i64(PG_CLASS_OID)),
+                        ("objsubid", Value::i64(position)),
+                        ("description", Value::string(column_comment(spec)?)),
+                    ]))
+                })
+                .collect(),
+            CatalogTable::PgTables => self
+                .tables
+                .iter()
+                .map(|(name, columns)| {
+                    row([
+                        ("schemaname", Value::string("public")),
+                        ("tablename", Value::string(name)),
+                        ("tableowner", Value::string("topk")),
+                        ("tablespace", Value::null()),
+                        ("hasindexes", Value::bool(has_index(columns))),
+                        ("hasrules", Value::bool(false)),
+                        ("hastriggers", Value::bool(false)),
+                        ("rowsecurity", Value::bool(false)),
+                    ])
+                })
+                .collect(),
+        }
+    }
+
+    /// `(table, ordinal_position, column, spec)` of every column.
+    fn columns(&self) -> impl Iterator<Item = (&str, i64, &str, &FieldSpec)> {
+        self.tables.iter().flat_map(|(table, columns)| {
+            columns.iter().enumerate().map(move |(i, (column, spec))| {
+                (table.as_str(), i as i64 + 1, column.as_str(), spec)
+            })
+        })
+    }
+
+    /// `(table_oid, attnum, column, spec)` of every column.
+    fn columns_with_oid(&self) -> impl Iterator<Item = (i64, i64, &str, &FieldSpec)> {
+        self.tables
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (_, columns))| {
+                columns.iter().enumerate().map(move |(j, (column, spec))| {
+                    (table_oid(i), j as i64 + 1, column.as_str(), spec)
+                })
+            })
+    }
+}
+
+/// `(type, typlen, typcategory, typelem)` of the types exposed in `pg_type`.
+const PG_TYPES: &[(schema::PgType, i64, &str, i64)] = &[
+    (PG_BOOL, 1, "B", 0),
+    (PG_BYTEA, -1, "U", 0),
+    (PG_INT8, 8, "N", 0),
+    (PG_TEXT, -1, "S", 0),
+    (PG_FLOAT8, 8, "N", 0),
+    (PG_FLOAT4_ARRAY, -1, "A", 700),
+    (PG_JSONB, -1, "U", 0),
+    (
+        schema::PgType {
+            oid: 21,
+            name: "int2",
+            sql: "smallint",
+        },
+        2,
+        "N",
+        0,
+    ),
+    (
+        schema::PgType {
+            oid: 23,
+            name: "int4",
+            sql: "integer",
+        },
+        4,
+        "N",
+        0,
+    ),
+    (
+        schema::PgType {
+            oid: 114,
+            name: "json",
+            sql: "json",
+        },
+        -1,
+        "U",
+        0,
+    ),
+    (
+        schema::PgType {
+            oid: 700,
+            name: "float4",
+            sql: "real",
+        },
+        4,
+        "N",
+        0,
+    ),
+];
+
+fn table_oid(index: usize) -> i64 {
+    FIRST_TABLE_OID + index as i64
+}
+
+fn has_index(columns: &[(String, FieldSpec)]) -> bool {
+    columns.iter().any(|(_, spec)| spec.index.is_some())
+}
+
+/// Index metadata is surfaced as the column comment, eg. `INDEX vector_index(metric = 'cosine')`.
+fn column_comment(spec: &FieldSpec) -> Option<String> {
+    let index = schema::index_sql(spec.index.as_ref()?)?;
+    Some(format!("INDEX {index}"))
+}
+
+fn nullable(value: Option<String>) -> Value {
+    value.map(Value::string).unwrap_or_else(Value::null)
+}
+
+fn row<const N: usize>(fields: [(&str, Value); N]) -> Document {
+    Document {
+        fields: fields
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect::<HashMap<_, _>>(),
+    }
+}
+
+/// Query over catalog tables, evaluated client-side.
+///
+/// Columns are referenced by their qualified name, eg. `c.relname`, where `c` is the alias of
+/// the scanned table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogQuery {
+    /// Scanned tables in join order: the `FROM` table, then each joined table.
+    pub from: Vec<CatalogScan>,
+    /// Output columns as `(name, expression)`, in order.
+    pub projection: Vec<(String, CatalogExpr)>,
+    /// Conjunction of predicates, evaluated after the joins.
+    pub filters: Vec<CatalogFilter>,
+    /// Sort keys as `(expression, ascending)`.
+    pub order_by: Vec<(CatalogExpr, bool)>,
+    /// Number of rows to skip.
+    pub offset: Option<u64>,
+    /// Maximum number of rows to return.
+    pub limit: Option<u64>,
+}
+
+/// Catalog table scanned by a `CatalogQuery`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogScan {
+    pub table: CatalogTable,
+    /// Name qualifying the table's columns: its alias, or its unqualified name.
+    pub alias: String,
+    /// Equi-join keys as pairs of qualified columns. Empty for the `FROM` table.
+    pub on: Vec<(String, String)>,
+    /// `LEFT JOIN`: rows without a match are kept, with the table's columns NULL.
+    pub left: bool,
+}
+
+/// Expression evaluated against a (joined) catalog row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogExpr {
+    /// Qualified column, eg. `c.relname`.
+    Column(String),
+    Literal(Value),
+    /// `CASE column WHEN value THEN result … ELSE default END`
+    Case {
+        column: String,
+        branches: Vec<(Value, Value)>,
+        default: Value,
+    },
+}
+
+impl CatalogExpr {
+    fn eval(&self, row: &Document) -> Value {
+        match self {
+            CatalogExpr::Column(column) => {
+                row.fields.get(column).cloned().unwrap_or_else(Value::null)
+            }
+            CatalogExpr::Literal(value) => value.clone(),
+            CatalogExpr::Case {
+                column,
+                branches,
+                default,
+            } => get(row, column)
+                .and_then(|v| branches.iter().find(|(when, _)| values_eq(v, when)))
+                .map_or_else(|| default.clone(), |(_, then)| then.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CatalogFilter {
+    /// `column = value` (or `<>` when `negated`).
+    Eq {
+        column: String,
+        value: Value,
+        negated: bool,
+    },
+    /// `column [NOT] IN (value, …)`
+    In {
+        column: String,
+        values: Vec<Value>,
+        negated: bool,
+    },
+    /// `column IS [NOT] NULL`
+    IsNull { column: String, negated: bool },
+    /// `column [NOT] LIKE 'pattern'` or `column ~ 'regex'`, both compiled to a regex.
+    Matches {
+        column: String,
+        regex: Regex,
+        negated: bool,
+    },
+}
+
+impl PartialEq for CatalogFilter {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                CatalogFilter::Eq {
+                    column: a,
+                    value: x,
+                    negated: n,
+                },
+                CatalogFilter::Eq {
+                    column: b,
+                    value: y,
+                    negated: m,
+                },
+            ) => a == b && x == y && n == m,
+            (
+                CatalogFilter::In {
+                    column: a,
+                    values: x,
+                    negated: n,
+                },
+                CatalogFilter::In {
+                    column: b,
+                    values: y,
+                    negated: m,
+                },
+            ) => a == b && x == y && n == m,
+            (
+                CatalogFilter::IsNull {
+                    column: a,
+                    negated: n,
+                },
+                CatalogFilter::IsNull {
+                    column: b,
+                    negated: m,
+                },
+            ) => a == b && n == m,
+            (
+                CatalogFilter::Matches {
+                    column: a,
+                    regex: x,
+                    negated: n,
+                },
+                CatalogFilter::Matches {
+                    column: b,
+                    regex: y,
+                    negated: m,
+                },
+            ) => a == b && x.as_str() == y.as_str() && n == m,
+            _ => false,
+        }
+    }
+}
+
+impl CatalogFilter {
+    fn matches(&self, row: &Document) -> bool {
+        let get = |column: &str| get(row, column);
+        match self {
+            CatalogFilter::Eq {
+                column,
+                value,
+                negated,
+            } => get(column).is_some_and(|v| values_eq(v, value) != *negated),
+            CatalogFilter::In {
+                column,
+                values,
+                negated,
+            } => get(column).is_some_and(|v| values.iter().any(|x| values_eq(v, x)) != *negated),
+            CatalogFilter::IsNull { column, negated } => get(column).is_none() != *negated,
+            CatalogFilter::Matches {
+                column,
+                regex,
+                negated,
+            } => get(column)
+                .and_then(|v| v.as_string())
+                .is_some_and(|s| regex.is_match(s) != *negated),
+        }
+    }
+}
+
+impl CatalogQuery {
+    /// Evaluates the query against the rows of the catalog.
+    pub fn execute(&self, catalog: &Catalog) -> Vec<Document> {
+        // Nested loop joins, starting from a single empty row
+        let mut rows = vec![Document::default()];
+        for scan in &self.from {
+            let table = catalog
+                .rows(scan.table)
+                .into_iter()
+                .map(|row| Document {
+                    fields: row
+                        .fields
+                        .into_iter()
+                        .map(|(column, value)| (format!("{}.{column}", scan.alias), value))
+                        .collect(),
+                })
+                .collect::<Vec<_>>();
+
+            rows = rows
+                .into_iter()
+                .flat_map(|row| {
+                    let mut joined = table
+                        .iter()
+                        .map(|other| {
+                            let mut joined = row.clone();
+                            joined.fields.extend(other.fields.clone());
+                            joined
+                        })
+                        .filter(|joined| {
+                            scan.on
+                                .iter()
+                                .all(|(a, b)| match (get(joined, a), get(joined, b)) {
+                                    (Some(a), Some(b)) => values_eq(a, b),
+                                    _ => false,
+                                })
+                        })
+                        .collect::<Vec<_>>();
+                    if joined.is_empty() && scan.left {
+                        joined.push(row);
+                    }
+                    joined
+                })
+                .collect();
+        }
+
+        rows.retain(|row| self.filters.iter().all(|f| f.matches(row)));
+
+        if !self.order_by.is_empty() {
+            rows.sort_by(|a, b| {
+                self.order_by
+                    .iter()
+                    .map(|(expr, asc)| {
+                        let ord = cmp_values(Some(&expr.eval(a)), Some(&expr.eval(b)));
+                        if *asc { ord } else { ord.reverse() }
+                    })
+                    .find(|ord| ord.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        rows.into_iter()
+            .skip(self.offset.unwrap_or(0) as usize)
+            .take(self.limit.map_or(usize::MAX, |l| l as usize))
+            .map(|row| Document {
+                fields: self
+                    .projection
+                    .iter()
+                    .map(|(name, expr)| (name.clone(), expr.eval(&row)))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// Non-NULL value of a column; columns of unmatched `LEFT JOIN` rows are missing.
+fn get<'a>(row: &'a Document, column: &str) -> Option<&'a Value> {
+    row.fields.get(column).filter(|v| v.as_null().is_none())
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    value
+        .as_i64()
+        .map(|n| n as f64)
+        .or_else(|| value.as_f64())
+        .or_else(|| value.as_i32().map(f64::from))
+        .or_else(|| value.as_u32().map(f64::from))
+        .or_else(|| value.as_u64().map(|n| n as f64))
+}
+
+fn values_eq(a: &Value, b: &Value) -> bool {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+/// Orders values with NULLs last, as PostgreSQL does for `ASC`.
+fn cmp_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    let a = a.filter(|v| v.as_null().is_none());
+    let b = b.filter(|v| v.as_null().is_none());
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => match (as_number(a), as_number(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            _ => match (a.as_string(), b.as_string()) {
+                (Some(x), Some(y)) => x.cmp(y),
+                _ => a.as_bool().cmp(&b.as_bool()),
+            },
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use topk_rs::proto::v1::control::{FieldIndex, KeywordIndexType, VectorDistanceMetric};
+
+    use super::*;
+
+    fn catalog() -> Catalog {
+        Catalog::new([
+            Collection {
+                name: "books".to_string(),
+                schema: [
+                    (
+                        "title".to_string(),
+                        FieldSpec::text(true)
+                            .with_index(FieldIndex::keyword(KeywordIndexType::Text)),
+                    ),
+                    (
+                        "embedding".to_string(),
+                        FieldSpec::f32_vector(4, false)
+                            .with_index(FieldIndex::vector(VectorDistanceMetric::Cosine)),
+                    ),
+                ]
+                .into(),
+                ..Default::default()
+            },
+            Collection {
+                name: "authors".to_string(),
+                schema: [("name".to_string(), FieldSpec::text(false))].into(),
+                ..Default::default()
+            },
+        ])
+    }
+
+    fn strings(rows: &[Document], column: &str) -> Vec<String> {
+        rows.iter()
+            .map(|row| row.fields[column].as_string().unwrap().to_string())
+            .collect()
+    }
+
+    #[rstest]
+    #[case("information_schema.tables", Some(CatalogTable::Tables))]
+    #[case("INFORMATION_SCHEMA.COLUMNS", Some(CatalogTable::Columns))]
+    #[case("pg_catalog.pg_class", Some(CatalogTable::PgClass))]
+    #[case("pg_type", Some(CatalogTable::PgType))]
+    #[case("pg_catalog.pg_tables", Some(CatalogTable::PgTables))]
+    #[case("information_schema.views", None)]
+    #[case("public.pg_class", None)]
+    #[case("books", None)]
+    fn from_name(#[case] name: &str, #[case] expected: Option<CatalogTable>) {
+        let name = ObjectName::from(
+            name.split('.')
+                .map(sqlparser::ast::Ident::new)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(CatalogTable::from_name(&name), expected);
+    }
+
+    #[test]
+    fn tables_are_sorted() {
+        let rows = catalog().rows(CatalogTable::Tables);
+        assert_eq!(strings(&rows, "table_name"), ["authors", "books"]);
+    }
+
+    #[test]
+    fn columns_include_id_and_index_comments() {
+        let rows = catalog().rows(CatalogTable::Columns);
+        let books = rows
+            .into_iter()
+            .filter(|row| row.fields["table_name"].as_string() == Some("books"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            strings(&books, "column_name"),
+            ["_id", "embedding", "title"]
+        );
+        assert_eq!(strings(&books, "data_type"), ["text", "real[]", "text"]);
+        assert_eq!(strings(&books, "is_nullable"), ["NO", "YES", "NO"]);
+        assert_eq!(
+            books[1].fields["column_comment"].as_string(),
+            Some("INDEX vector_index(metric = 'cosine')")
+        );
+        assert_eq!(
+            books[2].fields["column_comment"].as_string(),
+            Some("INDEX keyword_index()")
+        );
+        assert!(books[0].fields["column_comment"].as_null().is_some());
+    }
+
+    #[test]
+    fn pg_attribute_references_pg_class() {
+        let catalog = catalog();
+        let classes = catalog.rows(CatalogTable::PgClass);
+        let attributes = catalog.rows(CatalogTable::PgAttribute);
+
+        let books_oid = classes
+            .iter()
+            .find(|row| row.fields["relname"].as_string() == Some("books"))
+            .and_then(|row| row.fields["oid"].as_i64())
+            .unwrap();
+        let books_columns = attributes
+            .iter()
+            .filter(|row| row.fields["attrelid"].as_i64() == Some(books_oid))
+            .count();
+        assert_eq!(books_columns, 3);
+
+        let descriptions = catalog.rows(CatalogTable::PgDescription);
+        assert_eq!(descriptions.len(), 2);
+    }
+
+    fn scan(table: CatalogTable, alias: &str) -> CatalogScan {
+        CatalogScan {
+            table,
+            alias: alias.to_string(),
+            on: vec![],
+            left: false,
+        }
+    }
+
+    #[test]
+    fn execute_filters_sorts_and_projects() {
+        let query = CatalogQuery {
+            from: vec![scan(CatalogTable::Columns, "columns")],
+            projection: vec![(
+                "name".to_string(),
+                CatalogExpr::Column("columns.column_name".to_string()),
+            )],
+            filters: vec![
+                CatalogFilter::Eq {
+                    column: "columns.table_name".to_string(),
+                    value: Value::string("books"),
+                    negated: false,
+                },
+                CatalogFilter::Matches {
+                    column: "columns.column_name".to_string(),
+                    regex: Regex::new("^_").unwrap(),
+                    negated: true,
+                },
+            ],
+            order_by: vec![(
+                CatalogExpr::Column("columns.ordinal_position".to_string()),
+                false,
+            )],
+            offset: None,
+            limit: Some(5),
+        };
+
+        let rows = query.execute(&catalog());
+        assert_eq!(strings(&rows, "name"), ["title", "embedding"]);
+        assert!(rows.iter().all(|row| row.fields.len() == 1));
+    }
+
+    #[test]
+    fn execute_left_join_keeps_unmatched_rows() {
+        let query = CatalogQuery {
+            from: vec![
+                scan(CatalogTable::PgNamespace, "n"),
+                CatalogScan {
+                    on: vec![("c.relnamespace".to_string(), "n.oid".to_string())],
+                    left: true,
+                    ..scan(CatalogTable::PgClass, "c")
+                },
+            ],
+            projection: vec![
+                (
+                    "nspname".to_string(),
+                    CatalogExpr::Column("n.nspname".to_string()),
+                ),
+                (
+                    "relname".to_string(),
+                    CatalogExpr::Column("c.relname".to_string()),
+                ),
+            ],
+            filters: vec![],
+            order_by: vec![
+                (CatalogExpr::Column("n.nspname".to_string()), true),
+                (CatalogExpr::Column("c.relname".to_string()), true),
+            ],
+            offset: None,
+            limit: None,
+        };
+
+        let rows = query.execute(&catalog());
+        assert_eq!(
+            strings(&rows, "nspname"),
+            ["information_schema", "pg_catalog", "public", "public"]
+        );
+        assert!(rows[0].fields["relname"].as_null().is_some());
+        assert_eq!(rows[2].fields["relname"].as_string(), Some("authors"));
+
+        let inner = CatalogQuery {
+            from: vec![
+                query.from[0].clone(),
+                CatalogScan {
+                    left: false,
+                    ..query.from[1].clone()
+                },
+            ],
+            ..query
+        };
+        assert_eq!(
+            strings(&inner.execute(&catalog()), "relname"),
+            ["authors", "books"]
+        );
+    }
+}
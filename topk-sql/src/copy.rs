@@ -0,0 +1,951 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use topk_rs::proto::v1::control::{
+    FieldSpec, field_type::DataType, field_type_list::ListValueType,
+    field_type_matrix::MatrixValueType,
+};
+use topk_rs::proto::v1::data::{Document, Value, value::Value as V};
+
+use crate::expr::{parse_cast, parse_timestamp};
+use crate::{Error, sql_invalid, sql_unsupported};
+
+/// Default maximum number of documents per batch produced by `CopyDecoder`.
+pub const COPY_BATCH_SIZE: usize = 1000;
+
+/// Default maximum (approximate) encoded size of a batch produced by `CopyDecoder`.
+pub const COPY_BATCH_BYTES: usize = 4 * 1024 * 1024;
+
+/// `COPY … WITH (FORMAT <format>)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// RFC 4180 CSV, one row per record.
+    Csv,
+    /// A single JSON array of objects.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+}
+
+impl FromStr for CopyFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(CopyFormat::Csv),
+            "json" => Ok(CopyFormat::Json),
+            "ndjson" | "jsonl" => Ok(CopyFormat::Ndjson),
+            "text" | "binary" => {
+                sql_unsupported!("COPY format `{s}`, expected: csv | json | ndjson")
+            }
+            _ => sql_invalid!("unknown COPY format `{s}`, expected: csv | json | ndjson"),
+        }
+    }
+}
+
+/// Options of a `COPY` statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyOptions {
+    pub format: CopyFormat,
+    /// CSV only: the first record is a header (column names).
+    pub header: bool,
+    /// CSV only: field delimiter.
+    pub delimiter: char,
+    /// CSV only: quote character.
+    pub quote: char,
+    /// CSV only: unquoted string representing NULL.
+    pub null: String,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            format: CopyFormat::Csv,
+            header: false,
+            delimiter: ',',
+            quote: '"',
+            null: String::new(),
+        }
+    }
+}
+
+/// Incremental decoder for `COPY … FROM STDIN`.
+///
+/// Input is pushed in arbitrary chunks and decoded into documents, coerced against the
+/// collection schema (eg. JSON arrays into vectors, ISO strings into timestamps). Documents are
+/// grouped into batches of at most `batch_size` documents or about `batch_bytes` bytes of input,
+/// so that callers can upsert them as they arrive.
+#[derive(Debug)]
+pub struct CopyDecoder {
+    options: CopyOptions,
+    columns: Vec<String>,
+    schema: HashMap<String, FieldSpec>,
+    batch_size: usize,
+    batch_bytes: usize,
+    /// Input that has not been decoded yet.
+    buf: Vec<u8>,
+    /// Offset in `buf` up to which records have been scanned.
+    scan: usize,
+    /// CSV only: whether `buf[..scan]` ends inside a quoted field.
+    in_quotes: bool,
+    /// Line number of the first line in `buf`.
+    line: usize,
+    /// CSV only: whether the header has yet to be skipped.
+    header: bool,
+    batch: Vec<Document>,
+    batch_len: usize,
+    rows: usize,
+    /// Collect invalid records in `errors` instead of failing.
+    skip_invalid: bool,
+    errors: Vec<CopyError>,
+}
+
+/// Record rejected by a `CopyDecoder` in `skip_invalid` mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyError {
+    /// Line of the record, or index of the element of a `FORMAT json` array.
+    pub line: usize,
+    pub message: String,
+}
+
+impl CopyDecoder {
+    /// Creates a decoder for `columns` (may be empty if the input names them, eg. JSON objects
+    /// or a CSV header) of a collection with `schema`.
+    pub fn new(
+        options: CopyOptions,
+        columns: Vec<String>,
+        schema: HashMap<String, FieldSpec>,
+    ) -> Result<Self, Error> {
+        sql_invalid!(
+            options.format == CopyFormat::Csv && !options.header && columns.is_empty(),
+            "COPY … FROM STDIN (FORMAT csv) requires a column list or HEADER"
+        );
+
+        Ok(Self {
+            header: options.format == CopyFormat::Csv && options.header,
+            options,
+            columns,
+            schema,
+            batch_size: COPY_BATCH_SIZE,
+            batch_bytes: COPY_BATCH_BYTES,
+            buf: Vec::new(),
+            scan: 0,
+            in_quotes: false,
+            line: 1,
+            batch: Vec::new(),
+            batch_len: 0,
+            rows: 0,
+            skip_invalid: false,
+            errors: Vec::new(),
+        })
+    }
+
+    /// Overrides the batch limits (`COPY_BATCH_SIZE` and `COPY_BATCH_BYTES`).
+    pub fn with_batch_size(mut self, docs: usize, bytes: usize) -> Self {
+        self.batch_size = docs.max(1);
+        self.batch_bytes = bytes.max(1);
+        self
+    }
+
+    /// Skips invalid records instead of failing on the first one. Skipped records are returned
+    /// by `take_errors` and `finish_with_errors`.
+    pub fn skip_invalid(mut self) -> Self {
+        self.skip_invalid = true;
+        self
+    }
+
+    /// Number of documents decoded so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the records skipped since the last call.
+    pub fn take_errors(&mut self) -> Vec<CopyError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Decodes all complete records in `data` and returns the batches that are full.
+    pub fn push(&mut self, data: &[u8]) -> Result<Vec<Vec<Document>>, Error> {
+        self.buf.extend_from_slice(data);
+
+        // A JSON array is only decoded once complete.
+        if self.options.format == CopyFormat::Json {
+            return Ok(vec![]);
+        }
+
+        let buf = std::mem::take(&mut self.buf);
+        let quote = self.csv_quote();
+        let mut batches = Vec::new();
+        let mut start = 0;
+        for i in self.scan..buf.len() {
+            match buf[i] {
+                b'\n' if !self.in_quotes => {
+                    self.record(&buf[start..i], &mut batches)?;
+                    start = i + 1;
+                }
+                b if Some(b) == quote => self.in_quotes = !self.in_quotes,
+                _ => {}
+            }
+        }
+        self.buf = buf[start..].to_vec();
+        self.scan = self.buf.len();
+
+        Ok(batches)
+    }
+
+    /// Decodes the remaining input and returns the remaining batches.
+    pub fn finish(mut self) -> Result<Vec<Vec<Document>>, Error> {
+        self.flush()
+    }
+
+    /// Like `finish`, also returning the records skipped since the last `take_errors`.
+    pub fn finish_with_errors(mut self) -> Result<(Vec<Vec<Document>>, Vec<CopyError>), Error> {
+        let batches = self.flush()?;
+        Ok((batches, self.errors))
+    }
+
+    fn flush(&mut self) -> Result<Vec<Vec<Document>>, Error> {
+        let mut batches = Vec::new();
+        let buf = std::mem::take(&mut self.buf);
+
+        match self.options.format {
+            CopyFormat::Json => {
+                let docs = match serde_json::from_slice::<serde_json::Value>(&buf)? {
+                    serde_json::Value::Array(docs) => docs,
+                    _ => sql_invalid!("COPY … (FORMAT json) expects an array of objects"),
+                };
+                for (i, doc) in docs.into_iter().enumerate() {
+                    match self.json_document(doc) {
+                        Ok(doc) => self.add(doc, 0, &mut batches),
+                        Err(e) if self.skip_invalid => self.errors.push(CopyError {
+                            line: i,
+                            message: message(e),
+                        }),
+                        Err(e) => {
+                            let message = message(e);
+                            sql_invalid!("element {i}: {message}")
+                        }
+                    }
+                }
+            }
+            CopyFormat::Csv | CopyFormat::Ndjson => {
+                sql_invalid!(
+                    self.in_quotes,
+                    "line {}: unterminated quoted field",
+                    self.line
+                );
+                if !buf.is_empty() {
+                    self.record(&buf, &mut batches)?;
+                }
+            }
+        }
+
+        if !self.batch.is_empty() {
+            batches.push(std::mem::take(&mut self.batch));
+        }
+
+        Ok(batches)
+    }
+
+    fn csv_quote(&self) -> Option<u8> {
+        match self.options.format {
+            CopyFormat::Csv => u8::try_from(self.options.quote).ok(),
+            CopyFormat::Json | CopyFormat::Ndjson => None,
+        }
+    }
+
+    /// Decodes one record (without its trailing newline).
+    fn record(&mut self, record: &[u8], batches: &mut Vec<Vec<Document>>) -> Result<(), Error> {
+        let line = self.line;
+        self.line += 1 + record.iter().filter(|b| **b == b'\n').count();
+
+        let record = record.strip_suffix(b"\r").unwrap_or(record);
+        let doc = std::str::from_utf8(record)
+            .map_err(|e| Error::Invalid(format!("invalid UTF-8: {e}")))
+            .and_then(|text| match self.options.format {
+                CopyFormat::Csv => self.csv_document(text),
+                CopyFormat::Ndjson if text.trim().is_empty() => Ok(None),
+                CopyFormat::Ndjson | CopyFormat::Json => serde_json::from_str(text)
+                    .map_err(Error::from)
+                    .and_then(|json| self.json_document(json).map(Some)),
+            });
+
+        match doc {
+            Ok(Some(doc)) => self.add(doc, record.len(), batches),
+            Ok(None) => {}
+            Err(e) if self.skip_invalid => self.errors.push(CopyError {
+                line,
+                message: message(e),
+            }),
+            Err(e) => {
+                let message = message(e);
+                sql_invalid!("line {line}: {message}")
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add(&mut self, doc: Document, len: usize, batches: &mut Vec<Vec<Document>>) {
+        self.rows += 1;
+        self.batch.push(doc);
+        self.batch_len += len;
+        if self.batch.len() >= self.batch_size || self.batch_len >= self.batch_bytes {
+            batches.push(std::mem::take(&mut self.batch));
+            self.batch_len = 0;
+        }
+    }
+
+    /// Decodes a CSV record, or returns `None` for the header.
+    fn csv_document(&mut self, text: &str) -> Result<Option<Document>, Error> {
+        let cells = split_csv(text, self.options.delimiter, self.options.quote)?;
+
+        if std::mem::take(&mut self.header) {
+            if self.columns.is_empty() {
+                self.columns = cells.into_iter().map(|(name, _)| name).collect();
+            }
+            return Ok(None);
+        }
+
+        sql_invalid!(
+            cells.len() != self.columns.len(),
+            "expected {} fields, got {}",
+            self.columns.len(),
+            cells.len()
+        );
+
+        let mut fields = HashMap::with_capacity(cells.len());
+        for (column, (cell, quoted)) in self.columns.iter().zip(cells) {
+            if !quoted && cell == self.options.null {
+                continue;
+            }
+            let value = coerce_text(self.schema.get(column), cell)
+                .map_err(|e| Error::Invalid(format!("column `{column}`: {}", message(e))))?;
+            fields.insert(column.clone(), value);
+        }
+
+        check_document(&self.schema, &fields)?;
+        Ok(Some(Document { fields }))
+    }
+
+    fn json_document(&self, json: serde_json::Value) -> Result<Document, Error> {
+        let serde_json::Value::Object(object) = json else {
+            sql_invalid!("expected a JSON object");
+        };
+
+        let mut fields = HashMap::with_capacity(object.len());
+        for (column, json) in object {
+            sql_invalid!(
+                !self.columns.is_empty() && !self.columns.contains(&column),
+                "column `{column}` is not in the COPY column list"
+            );
+            if json.is_null() {
+                continue;
+            }
+            let value = coerce_json(self.schema.get(&column), json)
+                .map_err(|e| Error::Invalid(format!("column `{column}`: {}", message(e))))?;
+            fields.insert(column, value);
+        }
+
+        check_document(&self.schema, &fields)?;
+        Ok(Document { fields })
+    }
+}
+
+/// Encoder for `COPY (SELECT …) TO STDOUT`.
+#[derive(Debug)]
+pub struct CopyEncoder {
+    options: CopyOptions,
+    columns: Vec<String>,
+    schema: HashMap<String, FieldSpec>,
+    started: bool,
+    rows: usize,
+}
+
+impl CopyEncoder {
+    /// Creates an encoder writing `columns` in order. If `columns` is empty (`SELECT *`), the
+    /// columns are taken from the first document: `_id` first, then the other fields by name.
+    pub fn new(options: CopyOptions, columns: Vec<String>) -> Self {
+        Self {
+            options,
+            columns,
+            schema: HashMap::new(),
+            started: false,
+            rows: 0,
+        }
+    }
+
+    /// Formats timestamp fields of `schema` as RFC 3339 strings instead of milliseconds.
+    pub fn with_schema(mut self, schema: HashMap<String, FieldSpec>) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Number of documents encoded so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Encodes `docs`, preceded by the CSV header or the opening `[` on the first call.
+    pub fn encode(&mut self, docs: &[Document]) -> Result<Vec<u8>, Error> {
+        let mut out = String::new();
+
+        if !self.started {
+            if self.columns.is_empty()
+                && let Some(doc) = docs.first()
+            {
+                let mut columns = doc.fields.keys().cloned().collect::<Vec<_>>();
+                columns.sort_by(|a, b| (a != "_id", a).cmp(&(b != "_id", b)));
+                self.columns = columns;
+            }
+            out.push_str(&self.begin());
+        }
+
+        for doc in docs {
+            match self.options.format {
+                CopyFormat::Csv => {
+                    let mut cells = Vec::with_capacity(self.columns.len());
+                    for column in &self.columns {
+                        cells.push(self.csv_cell(column, doc.fields.get(column))?);
+                    }
+                    out.push_str(&cells.join(&self.options.delimiter.to_string()));
+                    out.push('\n');
+                }
+                CopyFormat::Json | CopyFormat::Ndjson => {
+                    let mut object = serde_json::Map::with_capacity(self.columns.len());
+                    for column in &self.columns {
+                        let json = match doc.fields.get(column) {
+                            Some(value) => encode_json(self.schema.get(column), value)?,
+                            None => serde_json::Value::Null,
+                        };
+                        object.insert(column.clone(), json);
+                    }
+                    let object = serde_json::to_string(&object)?;
+                    if self.options.format == CopyFormat::Json {
+                        out.push_str(if self.rows == 0 { "\n" } else { ",\n" });
+                        out.push_str(&object);
+                    } else {
+                        out.push_str(&object);
+                        out.push('\n');
+                    }
+                }
+            }
+            self.rows += 1;
+        }
+
+        Ok(out.into_bytes())
+    }
+
+    /// Ends the output (closing `]` for JSON). Writes the header if nothing was encoded.
+    pub fn finish(mut self) -> Vec<u8> {
+        let mut out = self.begin();
+        if self.options.format == CopyFormat::Json {
+            out.push_str(if self.rows == 0 { "]\n" } else { "\n]\n" });
+        }
+        out.into_bytes()
+    }
+
+    fn begin(&mut self) -> String {
+        if std::mem::replace(&mut self.started, true) {
+            return String::new();
+        }
+        match self.options.format {
+            CopyFormat::Csv if self.options.header => {
+                let header = self
+                    .columns
+                    .iter()
+                    .map(|column| self.quote_csv(column))
+                    .collect::<Vec<_>>();
+                format!("{}\n", header.join(&self.options.delimiter.to_string()))
+            }
+            CopyFormat::Json => "[".to_string(),
+            CopyFormat::Csv | CopyFormat::Ndjson => String::new(),
+        }
+    }
+
+    fn csv_cell(&self, column: &str, value: Option<&Value>) -> Result<String, Error> {
+        let text = match value.map(|v| (v, encode_json(self.schema.get(column), v))) {
+            None | Some((_, Ok(serde_json::Value::Null))) => return Ok(self.options.null.clone()),
+            Some((_, Ok(serde_json::Value::String(s)))) => s,
+            Some((_, Ok(json))) => json.to_string(),
+            Some((_, Err(e))) => return Err(e),
+        };
+        Ok(self.quote_csv(&text))
+    }
+
+    fn quote_csv(&self, text: &str) -> String {
+        let quote = self.options.quote;
+        if text == self.options.null || text.contains([self.options.delimiter, quote, '\n', '\r']) {
+            let escaped = text.replace(quote, &format!("{quote}{quote}"));
+            format!("{quote}{escaped}{quote}")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Splits a CSV record into `(cell, quoted)` pairs.
+fn split_csv(text: &str, delimiter: char, quote: char) -> Result<Vec<(String, bool)>, Error> {
+    let mut cells = Vec::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        let mut cell = String::new();
+        let mut quoted = false;
+        if chars.peek() == Some(&quote) {
+            quoted = true;
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some(c) if c == quote => {
+                        if chars.peek() == Some(&quote) {
+                            chars.next();
+                            cell.push(quote);
+                        } else {
+                            break;
+                        }
+                    }
+                    Some(c) => cell.push(c),
+                    None => sql_invalid!("unterminated quoted field"),
+                }
+            }
+            match chars.peek() {
+                None => {}
+                Some(c) if *c == delimiter => {}
+                Some(c) => sql_invalid!("unexpected `{c}` after closing quote"),
+            }
+        } else {
+            while let Some(c) = chars.peek().copied().filter(|c| *c != delimiter) {
+                cell.push(c);
+                chars.next();
+            }
+        }
+        cells.push((cell, quoted));
+        if chars.next().is_none() {
+            return Ok(cells);
+        }
+    }
+}
+
+/// Checks that `_id` is a string and that required fields are present.
+fn check_document(
+    schema: &HashMap<String, FieldSpec>,
+    fields: &HashMap<String, Value>,
+) -> Result<(), Error> {
+    match fields.get("_id").map(|id| id.as_string()) {
+        Some(Some(id)) if !id.is_empty() => {}
+        Some(_) => sql_invalid!("`_id` must be a non-empty string"),
+        None => sql_invalid!("missing `_id`"),
+    }
+    let mut missing = schema
+        .iter()
+        .filter(|(name, spec)| spec.required && !fields.contains_key(*name))
+        .map(|(name, _)| format!("`{name}`"))
+        .collect::<Vec<_>>();
+    missing.sort();
+    sql_invalid!(
+        !missing.is_empty(),
+        "missing required column(s) {}",
+        missing.join(", ")
+    );
+    Ok(())
+}
+
+fn data_type(spec: Option<&FieldSpec>) -> Option<&DataType> {
+    spec.and_then(|spec| spec.data_type.as_ref())
+        .and_then(|t| t.data_type.as_ref())
+}
+
+/// Coerces a CSV cell into a value of the column's type. Undeclared columns are strings.
+fn coerce_text(spec: Option<&FieldSpec>, text: String) -> Result<Value, Error> {
+    match data_type(spec) {
+        None | Some(DataType::Text(_)) => Ok(Value::string(text)),
+        Some(DataType::Integer(_)) => text
+            .trim()
+            .parse::<i64>()
+            .map(Value::i64)
+            .map_err(|e| Error::InvalidLiteral(format!("integer `{text}`: {e}"))),
+        Some(DataType::Float(_)) => text
+            .trim()
+            .parse::<f64>()
+            .map(Value::f64)
+            .map_err(|e| Error::InvalidLiteral(format!("float `{text}`: {e}"))),
+        Some(DataType::Boolean(_)) => match text.trim().to_ascii_lowercase().as_str() {
+            "t" | "true" | "1" | "yes" | "y" | "on" => Ok(Value::bool(true)),
+            "f" | "false" | "0" | "no" | "n" | "off" => Ok(Value::bool(false)),
+            _ => Err(Error::InvalidLiteral(format!("boolean `{text}`"))),
+        },
+        Some(DataType::Timestamp(_)) => parse_timestamp(text.trim()),
+        Some(DataType::Bytes(_)) if !text.starts_with('[') => parse_hex(&text),
+        // Lists, vectors, matrices and structs are JSON-encoded within the cell.
+        Some(_) => coerce_json(spec, serde_json::from_str(&text)?),
+    }
+}
+
+/// Coerces a JSON value into a value of the column's type.
+fn coerce_json(spec: Option<&FieldSpec>, json: serde_json::Value) -> Result<Value, Error> {
+    use serde_json::Value as Json;
+
+    let Some(data_type) = data_type(spec) else {
+        return Ok(Value::try_from(json)?);
+    };
+    let mismatch = |json: &Json| Error::Invalid(format!("unexpected value {json}"));
+
+    match (data_type, json) {
+        (_, Json::Null) => Ok(Value::null()),
+        (DataType::Text(_), Json::String(s)) => Ok(Value::string(s)),
+        (DataType::Integer(_), Json::Number(n)) => n.as_i64().map(Value::i64).ok_or_else(|| {
+            Error::InvalidLiteral(format!("integer `{n}` is out of range or fractional"))
+        }),
+        (DataType::Float(_), Json::Number(n)) => Ok(Value::f64(n.as_f64().unwrap_or(f64::NAN))),
+        (DataType::Boolean(_), Json::Bool(b)) => Ok(Value::bool(b)),
+        (DataType::Timestamp(_), Json::String(s)) => parse_timestamp(&s),
+        (DataType::Timestamp(_), Json::Number(n)) if n.is_i64() => {
+            Ok(Value::i64(n.as_i64().unwrap_or_default()))
+        }
+        (DataType::Bytes(_), Json::String(s)) => parse_hex(&s),
+        (DataType::Bytes(_), Json::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_u64()
+                    .and_then(|b| u8::try_from(b).ok())
+                    .ok_or_else(|| mismatch(item))
+            })
+            .collect::<Result<Vec<u8>, _>>()
+            .map(Value::bytes),
+        (DataType::List(list), Json::Array(items)) => match list.value_type() {
+            ListValueType::String => items
+                .into_iter()
+                .map(|item| match item {
+                    Json::String(s) => Ok(s),
+                    other => Err(mismatch(&other)),
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::list),
+            ListValueType::Integer => items
+                .iter()
+                .map(|item| item.as_i64().ok_or_else(|| mismatch(item)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::list),
+            ListValueType::Float => items
+                .iter()
+                .map(|item| item.as_f64().ok_or_else(|| mismatch(item)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::list),
+            ListValueType::Unspecified => Ok(Value::try_from(Json::Array(items))?),
+        },
+        (DataType::Struct(s), Json::Object(object)) if !s.fields.is_empty() => {
+            let mut fields = HashMap::with_capacity(object.len());
+            for (name, json) in object {
+                if json.is_null() {
+                    continue;
+                }
+                let value = coerce_json(s.fields.get(&name), json)
+                    .map_err(|e| Error::Invalid(format!("field `{name}`: {}", message(e))))?;
+                fields.insert(name, value);
+            }
+            if let Some(name) = s
+                .fields
+                .iter()
+                .find(|(name, spec)| spec.required && !fields.contains_key(*name))
+                .map(|(name, _)| name)
+            {
+                sql_invalid!("missing required field `{name}`");
+            }
+            Ok(Value::r#struct(fields))
+        }
+        (DataType::Struct(_), json @ Json::Object(_)) => Ok(Value::try_from(json)?),
+        (data_type, json) => match cast_type_name(data_type) {
+            // Vectors and matrices accept both JSON values and JSON-encoded strings.
+            Some(type_name) => match json {
+                Json::String(s) => parse_cast(&type_name, &s),
+                json => parse_cast(&type_name, &json.to_string()),
+            },
+            None => Err(mismatch(&json)),
+        },
+    }
+}
+
+/// Type name accepted by `parse_cast` for vector and matrix types.
+fn cast_type_name(data_type: &DataType) -> Option<String> {
+    let name = match data_type {
+        DataType::F32Vector(_) => "f32_vector",
+        DataType::F16Vector(_) => "f16_vector",
+        DataType::F8Vector(_) => "f8_vector",
+        DataType::U8Vector(_) => "u8_vector",
+        DataType::I8Vector(_) => "i8_vector",
+        DataType::BinaryVector(_) => "binary_vector",
+        DataType::F32SparseVector(_) => "f32_sparse_vector",
+        DataType::F16SparseVector(_) => "f16_sparse_vector",
+        DataType::F8SparseVector(_) => "f8_sparse_vector",
+        DataType::U8SparseVector(_) => "u8_sparse_vector",
+        DataType::I8SparseVector(_) => "i8_sparse_vector",
+        DataType::Matrix(m) => match m.value_type() {
+            MatrixValueType::F32 | MatrixValueType::Unspecified => "f32_matrix",
+            MatrixValueType::F16 => "f16_matrix",
+            MatrixValueType::F8 => "f8_matrix",
+            MatrixValueType::U8 => "u8_matrix",
+            MatrixValueType::I8 => "i8_matrix",
+        },
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Encodes a value as JSON, rendering timestamps as RFC 3339 and bytes as `\x` hex.
+fn encode_json(spec: Option<&FieldSpec>, value: &Value) -> Result<serde_json::Value, Error> {
+    if let (Some(DataType::Timestamp(_)), Some(datetime)) = (data_type(spec), value.as_datetime()) {
+        return Ok(serde_json::Value::String(
+            datetime.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+        ));
+    }
+    if let Some(V::Binary(bytes)) = &value.value {
+        let hex = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        return Ok(serde_json::Value::String(format!("\\x{hex}")));
+    }
+    Ok(serde_json::Value::try_from(value.clone())?)
+}
+
+/// Parses PostgreSQL's hex `bytea` format (eg. `\x0aff`).
+fn parse_hex(s: &str) -> Result<Value, Error> {
+    let invalid = || Error::InvalidLiteral(format!("bytes `{s}` (expected \\x-prefixed hex)"));
+    let hex = s.strip_prefix("\\x").ok_or_else(invalid)?;
+    sql_invalid!(
+        hex.len() % 2 != 0,
+        "bytes `{s}` have an odd number of hex digits"
+    );
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(hex.get(i..i + 2).ok_or_else(invalid)?, 16).map_err(|_| invalid())
+        })
+        .collect::<Result<Vec<u8>, _>>()
+        .map(Value::bytes)
+}
+
+/// Error message without the `Invalid: ` prefix, for adding context.
+fn message(e: Error) -> String {
+    match e {
+        Error::Invalid(m) | Error::InvalidLiteral(m) | Error::Unsupported(m) => m,
+        e => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use topk_rs::proto::v1::control::FieldType;
+
+    use super::*;
+
+    fn schema() -> HashMap<String, FieldSpec> {
+        [
+            ("title".to_string(), FieldSpec::text(true)),
+            ("year".to_string(), FieldSpec::integer(false)),
+            ("published".to_string(), FieldSpec::timestamp(false)),
+            ("embedding".to_string(), FieldSpec::f32_vector(3, false)),
+            (
+                "meta".to_string(),
+                FieldSpec {
+                    data_type: Some(FieldType::r#struct([(
+                        "pages".to_string(),
+                        FieldSpec::integer(true),
+                    )])),
+                    required: false,
+                    index: None,
+                },
+            ),
+        ]
+        .into()
+    }
+
+    fn decode(
+        options: CopyOptions,
+        columns: &[&str],
+        chunks: &[&str],
+    ) -> Result<Vec<Vec<Document>>, Error> {
+        let columns = columns.iter().map(|c| c.to_string()).collect();
+        let mut decoder = CopyDecoder::new(options, columns, schema())?.with_batch_size(2, 1 << 20);
+        let mut batches = Vec::new();
+        for chunk in chunks {
+            batches.extend(decoder.push(chunk.as_bytes())?);
+        }
+        batches.extend(decoder.finish()?);
+        Ok(batches)
+    }
+
+    #[test]
+    fn csv_across_chunks() {
+        let options = CopyOptions {
+            header: true,
+            ..Default::default()
+        };
+        let batches = decode(
+            options,
+            &[],
+            &[
+                "_id,title,year,published,embedding\r\n",
+                "1,\"Dune, \"\"the\"\" novel\",1965,1965-08-01,\"[1,2,3]\"\n2,\"multi\nline\",",
+                ",2020-01-01T00:00:00Z,\n3,\"\",,,",
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), [2, 1]);
+        let dune = &batches[0][0].fields;
+        assert_eq!(dune["title"], Value::string("Dune, \"the\" novel"));
+        assert_eq!(dune["year"], Value::i64(1965));
+        assert_eq!(dune["embedding"], Value::list(vec![1f32, 2., 3.]));
+        assert_eq!(batches[0][1].fields["title"], Value::string("multi\nline"));
+        assert!(!batches[0][1].fields.contains_key("year"));
+        // Quoted empty string is not NULL.
+        assert_eq!(batches[1][0].fields["title"], Value::string(""));
+    }
+
+    #[test]
+    fn ndjson_coerces_by_schema() {
+        let options = CopyOptions {
+            format: CopyFormat::Ndjson,
+            ..Default::default()
+        };
+        let batches = decode(
+            options,
+            &[],
+            &[
+                "{\"_id\": \"1\", \"title\": \"a\", \"published\": \"2023-01-01T00:00:00Z\", ",
+                "\"meta\": {\"pages\": 10}, \"tags\": [\"x\"]}\n\n",
+            ],
+        )
+        .unwrap();
+
+        let doc = &batches[0][0].fields;
+        assert_eq!(doc["published"], parse_timestamp("2023-01-01").unwrap());
+        assert_eq!(doc["meta"], Value::r#struct([("pages", Value::i64(10))]));
+        assert_eq!(doc["tags"], Value::list(vec!["x".to_string()]));
+    }
+
+    #[test]
+    fn errors_report_line() {
+        let options = CopyOptions {
+            format: CopyFormat::Ndjson,
+            ..Default::default()
+        };
+        let err = decode(
+            options.clone(),
+            &[],
+            &["{\"_id\": \"1\", \"title\": \"a\"}\n{\"_id\": \"2\", \"title\": \"b\", \"year\": \"x\"}\n"],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid: line 2: column `year`: unexpected value \"x\""
+        );
+
+        let err = decode(options, &[], &["{\"_id\": \"1\"}"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid: line 1: missing required column(s) `title`"
+        );
+
+        let options = CopyOptions {
+            format: CopyFormat::Json,
+            ..Default::default()
+        };
+        let err = decode(
+            options,
+            &[],
+            &["[{\"_id\": \"1\", \"title\": \"a\", \"meta\": {}}]"],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid: element 0: column `meta`: missing required field `pages`"
+        );
+    }
+
+    #[test]
+    fn skip_invalid_collects_errors() {
+        let options = CopyOptions {
+            format: CopyFormat::Ndjson,
+            ..Default::default()
+        };
+        let mut decoder = CopyDecoder::new(options, vec![], schema())
+            .unwrap()
+            .skip_invalid();
+        let batches = decoder
+            .push(b"{\"_id\": \"1\", \"title\": \"a\"}\n{\"_id\": \"2\", \"year\": 1}\nnot json")
+            .unwrap();
+        assert!(batches.is_empty());
+        assert_eq!(
+            decoder.take_errors(),
+            [CopyError {
+                line: 2,
+                message: "missing required column(s) `title`".to_string(),
+            }]
+        );
+
+        let (batches, errors) = decoder.finish_with_errors().unwrap();
+        assert_eq!(batches.concat().len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 3);
+    }
+
+    #[test]
+    fn csv_requires_columns() {
+        let err = CopyDecoder::new(CopyOptions::default(), vec![], schema()).unwrap_err();
+        assert!(err.to_string().contains("requires a column list or HEADER"));
+    }
+
+    #[test]
+    fn encode_round_trip() {
+        let doc = Document {
+            fields: [
+                ("_id".to_string(), Value::string("1")),
+                ("title".to_string(), Value::string("a, \"b\"")),
+                (
+                    "published".to_string(),
+                    parse_timestamp("2023-01-01").unwrap(),
+                ),
+                ("embedding".to_string(), Value::list(vec![1f32, 2., 3.])),
+            ]
+            .into(),
+        };
+
+        for format in [CopyFormat::Csv, CopyFormat::Json, CopyFormat::Ndjson] {
+            let options = CopyOptions {
+                format,
+                header: true,
+                ..Default::default()
+            };
+            let mut encoder = CopyEncoder::new(options.clone(), vec![]).with_schema(schema());
+            let mut out = encoder.encode(std::slice::from_ref(&doc)).unwrap();
+            out.extend(encoder.finish());
+
+            let mut decoder = CopyDecoder::new(options, vec![], schema()).unwrap();
+            let mut docs = decoder.push(&out).unwrap().concat();
+            docs.extend(decoder.finish().unwrap().concat());
+            assert_eq!(docs, std::slice::from_ref(&doc), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn encode_csv() {
+        let options = CopyOptions {
+            header: true,
+            ..Default::default()
+        };
+        let columns = vec!["_id".to_string(), "title".to_string(), "year".to_string()];
+
+        let encoder = CopyEncoder::new(options.clone(), columns.clone());
+        assert_eq!(encoder.finish(), b"_id,title,year\n");
+
+        let mut encoder = CopyEncoder::new(options, columns);
+        let doc = Document {
+            fields: [
+                ("_id".to_string(), Value::string("1")),
+                ("title".to_string(), Value::string("")),
+            ]
+            .into(),
+        };
+        let out = encoder.encode(&[doc]).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "_id,title,year\n1,\"\",\n");
+    }
+}
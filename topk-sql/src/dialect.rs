@@ -1,32 +1,64 @@
 use sqlparser::ast::helpers::attached_token::AttachedToken;
 use sqlparser::ast::{
-    CheckConstraint, ColumnOption, Expr as SqlExpr, Statement as SqlStatement, Update,
-    UpdateTableFromKind,
+    CheckConstraint, ColumnOption, CopyOption, CopySource, CopyTarget, Expr as SqlExpr,
+    Function as SqlFunction, FunctionArg, FunctionArgExpr, FunctionArgumentList, FunctionArguments,
+    Ident, ObjectName, Statement as SqlStatement, Update, UpdateTableFromKind,
 };
 use sqlparser::dialect::{Dialect, PostgreSqlDialect, Precedence};
 use sqlparser::keywords::Keyword;
-use sqlparser::parser::{Parser, ParserError};
+use sqlparser::parser::{IsOptional, Parser, ParserError};
+use sqlparser::tokenizer::Token;
 
 #[derive(Debug)]
 pub struct TopKDialect {
     postgres: PostgreSqlDialect,
+    filter: bool,
 }
 
 impl Default for TopKDialect {
     fn default() -> Self {
         Self {
             postgres: PostgreSqlDialect {},
+            filter: false,
         }
     }
 }
 
+impl TopKDialect {
+    /// Dialect of standalone filter expressions, as typed on a command line: double-quoted
+    /// strings are string literals rather than identifiers, and `starts_with` and `contains`
+    /// are also infix operators (`title starts_with "RFC"`).
+    pub fn filter() -> Self {
+        Self {
+            filter: true,
+            ..Self::default()
+        }
+    }
+
+    /// The infix function operator at the head of `parser`, if any.
+    fn peek_infix_fn(&self, parser: &Parser) -> Option<&'static str> {
+        if !self.filter {
+            return None;
+        }
+        match parser.peek_token().token {
+            Token::Word(w) if w.quote_style.is_none() => INFIX_FNS
+                .iter()
+                .find(|name| w.value.eq_ignore_ascii_case(name))
+                .copied(),
+            _ => None,
+        }
+    }
+}
+
+const INFIX_FNS: [&str; 2] = ["starts_with", "contains"];
+
 impl Dialect for TopKDialect {
     fn identifier_quote_style(&self, identifier: &str) -> Option<char> {
         self.postgres.identifier_quote_style(identifier)
     }
 
     fn is_delimited_identifier_start(&self, ch: char) -> bool {
-        self.postgres.is_delimited_identifier_start(ch)
+        !(self.filter && ch == '"') && self.postgres.is_delimited_identifier_start(ch)
     }
 
     fn is_identifier_start(&self, ch: char) -> bool {
@@ -46,9 +78,27 @@ impl Dialect for TopKDialect {
     }
 
     fn get_next_precedence(&self, parser: &Parser) -> Option<Result<u8, ParserError>> {
+        if self.peek_infix_fn(parser).is_some() {
+            return Some(Ok(self.prec_value(Precedence::Like)));
+        }
         self.postgres.get_next_precedence(parser)
     }
 
+    fn parse_infix(
+        &self,
+        parser: &mut Parser,
+        expr: &SqlExpr,
+        precedence: u8,
+    ) -> Option<Result<SqlExpr, ParserError>> {
+        let name = self.peek_infix_fn(parser)?;
+        parser.next_token();
+        Some(
+            parser
+                .parse_subexpr(precedence)
+                .map(|right| function_call(name, [expr.clone(), right])),
+        )
+    }
+
     fn supports_filter_during_aggregation(&self) -> bool {
         self.postgres.supports_filter_during_aggregation()
     }
@@ -85,6 +135,9 @@ impl Dialect for TopKDialect {
         if parser.parse_keyword(Keyword::UPDATE) {
             return Some(parse_update_statement(parser));
         }
+        if parser.parse_keyword(Keyword::COPY) {
+            return Some(parse_copy_statement(parser));
+        }
 
         self.postgres.parse_statement(parser)
     }
@@ -111,6 +164,27 @@ impl Dialect for TopKDialect {
     }
 }
 
+/// `name(args…)`, for infix operators that lower to function calls.
+fn function_call(name: &str, args: impl IntoIterator<Item = SqlExpr>) -> SqlExpr {
+    SqlExpr::Function(SqlFunction {
+        name: ObjectName::from(vec![Ident::new(name)]),
+        uses_odbc_syntax: false,
+        parameters: FunctionArguments::None,
+        args: FunctionArguments::List(FunctionArgumentList {
+            duplicate_treatment: None,
+            args: args
+                .into_iter()
+                .map(|arg| FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)))
+                .collect(),
+            clauses: vec![],
+        }),
+        filter: None,
+        null_treatment: None,
+        over: None,
+        within_group: vec![],
+    })
+}
+
 // sqlparser's parse_update gates UPDATE … FROM behind a dialect_of! TypeId check that
 // only matches built-in dialects. Pulled out here so parse_statement can intercept UPDATE
 // before the main dispatch and handle FROM correctly.
@@ -145,3 +219,105 @@ fn parse_update_statement(parser: &mut Parser) -> Result<SqlStatement, ParserErr
         limit: None,
     }))
 }
+
+// sqlparser's parse_copy requires `COPY … FROM STDIN` to be followed by `;` and inline
+// tab-separated rows (psql script syntax). Over the wire the rows arrive through the COPY
+// sub-protocol instead, so the statement ends after its options. Legacy options (eg.
+// `CSV HEADER`) are folded into the equivalent `WITH (…)` options.
+fn parse_copy_statement(parser: &mut Parser) -> Result<SqlStatement, ParserError> {
+    let source = if parser.consume_token(&Token::LParen) {
+        let query = parser.parse_query()?;
+        parser.expect_token(&Token::RParen)?;
+        CopySource::Query(query)
+    } else {
+        CopySource::Table {
+            table_name: parser.parse_object_name(false)?,
+            columns: parser.parse_parenthesized_column_list(IsOptional::Optional, false)?,
+        }
+    };
+    let to = match parser.parse_one_of_keywords(&[Keyword::FROM, Keyword::TO]) {
+        Some(Keyword::TO) => true,
+        Some(_) => false,
+        None => return parser.expected("FROM or TO", parser.peek_token()),
+    };
+    let target =
+        match parser.parse_one_of_keywords(&[Keyword::STDIN, Keyword::STDOUT, Keyword::PROGRAM]) {
+            Some(Keyword::STDIN) => CopyTarget::Stdin,
+            Some(Keyword::STDOUT) => CopyTarget::Stdout,
+            Some(_) => CopyTarget::Program {
+                command: parser.parse_literal_string()?,
+            },
+            None => CopyTarget::File {
+                filename: parser.parse_literal_string()?,
+            },
+        };
+
+    let mut options = Vec::new();
+    let _ = parser.parse_keyword(Keyword::WITH);
+    if parser.consume_token(&Token::LParen) {
+        options = parser.parse_comma_separated(|parser| parse_copy_option(parser, false))?;
+        parser.expect_token(&Token::RParen)?;
+    }
+    while !matches!(parser.peek_token().token, Token::EOF | Token::SemiColon) {
+        options.push(parse_copy_option(parser, true)?);
+    }
+
+    Ok(SqlStatement::Copy {
+        source,
+        to,
+        target,
+        options,
+        legacy_options: vec![],
+        values: vec![],
+    })
+}
+
+fn parse_copy_option(parser: &mut Parser, legacy: bool) -> Result<CopyOption, ParserError> {
+    let keywords = [
+        Keyword::FORMAT,
+        Keyword::FREEZE,
+        Keyword::DELIMITER,
+        Keyword::NULL,
+        Keyword::HEADER,
+        Keyword::QUOTE,
+        Keyword::ESCAPE,
+        Keyword::ENCODING,
+        Keyword::CSV,
+        Keyword::BINARY,
+    ];
+    let keyword = parser.parse_one_of_keywords(&keywords);
+    if legacy {
+        let _ = parser.parse_keyword(Keyword::AS);
+    }
+    let flag = |parser: &mut Parser| {
+        !matches!(
+            parser.parse_one_of_keywords(&[Keyword::TRUE, Keyword::FALSE]),
+            Some(Keyword::FALSE)
+        )
+    };
+    let option = match keyword {
+        Some(Keyword::FORMAT) => CopyOption::Format(parser.parse_identifier()?),
+        Some(Keyword::FREEZE) => CopyOption::Freeze(legacy || flag(parser)),
+        Some(Keyword::HEADER) => CopyOption::Header(legacy || flag(parser)),
+        Some(Keyword::DELIMITER) => CopyOption::Delimiter(parse_literal_char(parser)?),
+        Some(Keyword::QUOTE) => CopyOption::Quote(parse_literal_char(parser)?),
+        Some(Keyword::ESCAPE) => CopyOption::Escape(parse_literal_char(parser)?),
+        Some(Keyword::NULL) => CopyOption::Null(parser.parse_literal_string()?),
+        Some(Keyword::ENCODING) => CopyOption::Encoding(parser.parse_literal_string()?),
+        Some(Keyword::CSV) if legacy => CopyOption::Format("csv".into()),
+        Some(Keyword::BINARY) if legacy => CopyOption::Format("binary".into()),
+        _ => return parser.expected("COPY option", parser.peek_token()),
+    };
+    Ok(option)
+}
+
+fn parse_literal_char(parser: &mut Parser) -> Result<char, ParserError> {
+    let s = parser.parse_literal_string()?;
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(ParserError::ParserError(format!(
+            "expected a single character, got '{s}'"
+        ))),
+    }
+}
@@ -0,0 +1,978 @@
+use std::time::Duration;
+
+use serde_json::{Map, Value as Json, json};
+use topk_rs::proto::v1::data::{
+    AggregateExpr, FunctionExpr, List, LogicalExpr, Query, TextExpr, Value, aggregate_expr,
+    function_expr, list, logical_expr, stage, text_expr, value::Value as V,
+};
+
+use crate::schema::{create_table_sql, quote_ident};
+use crate::{
+    CatalogExpr, CatalogFilter, CatalogScan, CopyOptions, Fusion, RowFilter, Statement, Table,
+};
+
+/// Number of list elements shown by non-verbose `EXPLAIN` before eliding the rest.
+const MAX_LIST_ELEMENTS: usize = 4;
+
+/// `EXPLAIN (FORMAT <format>)`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExplainFormat {
+    /// Indented plan, one node per line.
+    #[default]
+    Text,
+    /// Single JSON document.
+    Json,
+}
+
+/// Statistics of an executed `EXPLAIN ANALYZE` statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainAnalyze {
+    /// Time until the last row was received.
+    pub elapsed: Duration,
+    /// Number of documents matched before the final limit (`x-topk-matched-count`).
+    pub matched_count: Option<u64>,
+    /// Number of rows returned.
+    pub rows: u64,
+}
+
+impl Statement {
+    /// Renders the plan of the statement, as returned by `EXPLAIN`.
+    ///
+    /// Queries show their stage pipeline in execution order. `verbose` shows literals (eg. query
+    /// vectors) in full instead of eliding long lists.
+    pub fn explain(
+        &self,
+        format: ExplainFormat,
+        verbose: bool,
+        analyze: Option<&ExplainAnalyze>,
+    ) -> String {
+        let mut node = Explainer { verbose }.statement(self);
+        if let Some(analyze) = analyze {
+            node.props.insert(
+                "analyze".to_string(),
+                json!({
+                    "elapsed_ms": analyze.elapsed.as_secs_f64() * 1000.0,
+                    "matched_count": analyze.matched_count,
+                    "rows": analyze.rows,
+                }),
+            );
+        }
+
+        match format {
+            ExplainFormat::Text => {
+                let mut out = String::new();
+                node.text(0, &mut out);
+                if let Some(analyze) = analyze {
+                    let matched = match analyze.matched_count {
+                        Some(count) => count.to_string(),
+                        None => "n/a".to_string(),
+                    };
+                    out.push_str(&format!(
+                        "Execution: {:.3} ms, matched {matched}, returned {} rows\n",
+                        analyze.elapsed.as_secs_f64() * 1000.0,
+                        analyze.rows
+                    ));
+                }
+                out
+            }
+            ExplainFormat::Json => {
+                serde_json::to_string_pretty(&node.json()).expect("plan is valid JSON")
+            }
+        }
+    }
+}
+
+/// Node of a rendered plan.
+struct Node {
+    /// Label, eg. `Query` or `Filter`.
+    name: String,
+    /// One-line description, shown after the label in text output.
+    detail: String,
+    /// Separator between label and description (`Query on …` vs. `Limit: 5`).
+    sep: &'static str,
+    /// Structured properties for JSON output.
+    props: Map<String, Json>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn new(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            detail: detail.into(),
+            sep: " ",
+            props: Map::new(),
+            children: vec![],
+        }
+    }
+
+    /// Node of a query pipeline, rendered as `Name: detail`.
+    fn stage(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            sep: ": ",
+            ..Self::new(name, detail)
+        }
+    }
+
+    fn prop(mut self, key: &str, value: impl Into<Json>) -> Self {
+        self.props.insert(key.to_string(), value.into());
+        self
+    }
+
+    fn children(mut self, children: Vec<Node>) -> Self {
+        self.children = children;
+        self
+    }
+
+    fn props_table(self, table: &Table) -> Self {
+        let node = self.prop("collection", table.collection());
+        match table {
+            Table::Collection(_) => node,
+            Table::Partition(_, partition) => node.prop("partition", partition.as_str()),
+        }
+    }
+
+    fn text(&self, depth: usize, out: &mut String) {
+        if depth > 0 {
+            out.push_str(&"  ".repeat(depth));
+            out.push_str("-> ");
+        }
+        out.push_str(&self.name);
+        if !self.detail.is_empty() {
+            out.push_str(self.sep);
+            out.push_str(&self.detail);
+        }
+        out.push('\n');
+        for child in &self.children {
+            child.text(depth + 1, out);
+        }
+    }
+
+    fn json(self) -> Json {
+        let mut object = Map::new();
+        object.insert("node".to_string(), Json::String(self.name));
+        object.extend(self.props);
+        if !self.children.is_empty() {
+            let children = self.children.into_iter().map(Node::json).collect();
+            object.insert("plan".to_string(), Json::Array(children));
+        }
+        Json::Object(object)
+    }
+}
+
+struct Explainer {
+    verbose: bool,
+}
+
+impl Explainer {
+    fn statement(&self, stmt: &Statement) -> Node {
+        match stmt {
+            Statement::Select { table, query } => self.query("Query", table, query),
+            Statement::Count { table, query } => self.query("Count", table, query),
+            Statement::Fuse {
+                table,
+                queries,
+                fusion,
+                alias,
+                offset,
+                limit,
+            } => {
+                let fusion = match fusion {
+                    Fusion::Rrf { k } => format!("rrf, k = {k}"),
+                    Fusion::Weighted(weights) => {
+                        let weights = weights
+                            .iter()
+                            .map(|(weight, norm)| format!("{weight} * {norm:?}").to_lowercase())
+                            .collect::<Vec<_>>();
+                        format!("weighted {}", weights.join(" + "))
+                    }
+                };
+                let children = queries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, query)| {
+                        let stages = self.stages(query);
+                        Node::new(format!("Sub-query {}", i + 1), "")
+                            .prop("index", i + 1)
+                            .children(stages)
+                    })
+                    .collect();
+                Node::new(
+                    "Fuse",
+                    format!("on {} ({fusion} AS {alias})", describe(table)),
+                )
+                .props_table(table)
+                .prop("fusion", fusion)
+                .prop("alias", alias.as_str())
+                .prop("offset", *offset)
+                .prop("limit", *limit)
+                .children(children)
+            }
+            Statement::Catalog { query } => {
+                let mut children = Vec::new();
+                for scan in &query.from[1..] {
+                    let join = format!(
+                        "{}JOIN {} ON {}",
+                        if scan.left { "LEFT " } else { "" },
+                        catalog_scan(scan),
+                        scan.on
+                            .iter()
+                            .map(|(a, b)| format!("{a} = {b}"))
+                            .collect::<Vec<_>>()
+                            .join(" AND ")
+                    );
+                    children.push(Node::stage("Join", join.clone()).prop("join", join));
+                }
+                for filter in &query.filters {
+                    let filter = catalog_filter(filter);
+                    children.push(Node::stage("Filter", filter.clone()).prop("expr", filter));
+                }
+                let projection = query
+                    .projection
+                    .iter()
+                    .map(|(name, expr)| alias(&catalog_expr(expr), name))
+                    .collect::<Vec<_>>();
+                children
+                    .push(Node::stage("Select", projection.join(", ")).prop("columns", projection));
+                if !query.order_by.is_empty() {
+                    let keys = query
+                        .order_by
+                        .iter()
+                        .map(|(expr, asc)| format!("{} {}", catalog_expr(expr), order(*asc)))
+                        .collect::<Vec<_>>();
+                    children.push(Node::stage("Sort", keys.join(", ")).prop("keys", keys));
+                }
+                if let Some(offset) = query.offset {
+                    children.push(Node::stage("Offset", offset.to_string()).prop("offset", offset));
+                }
+                if let Some(limit) = query.limit {
+                    children.push(Node::stage("Limit", limit.to_string()).prop("k", limit));
+                }
+                let table = query.from[0].table.name();
+                Node::new(
+                    "Catalog",
+                    format!("scan on {}", catalog_scan(&query.from[0])),
+                )
+                .prop("table", table)
+                .children(children)
+            }
+            Statement::Insert { table, docs } => Node::new(
+                "Insert",
+                format!("into {}: {} document(s)", describe(table), docs.len()),
+            )
+            .props_table(table)
+            .prop("documents", docs.len()),
+            Statement::Update {
+                table,
+                docs,
+                fail_on_missing,
+            } => Node::new(
+                "Update",
+                format!("on {}: {} document(s)", describe(table), docs.len()),
+            )
+            .props_table(table)
+            .prop("documents", docs.len())
+            .prop("fail_on_missing", *fail_on_missing),
+            Statement::Delete { table, filter } => {
+                let (detail, filter) = match filter {
+                    RowFilter::Ids(ids) => {
+                        let ids = ids.iter().map(|id| string(id)).collect::<Vec<_>>();
+                        let detail = format!("_id IN ({})", self.elide(&ids));
+                        (detail.clone(), detail)
+                    }
+                    RowFilter::Expr(expr) => {
+                        let expr = self.logical(expr);
+                        (expr.clone(), expr)
+                    }
+                };
+                Node::new("Delete", format!("from {} where {detail}", describe(table)))
+                    .props_table(table)
+                    .prop("filter", filter)
+            }
+            Statement::DeletePartition { table } => {
+                Node::new("Delete Partition", describe(table)).props_table(table)
+            }
+            Statement::CopyFrom {
+                table,
+                columns,
+                options,
+            } => Node::new(
+                "Copy",
+                format!(
+                    "from STDIN into {} ({})",
+                    describe(table),
+                    copy_format(options)
+                ),
+            )
+            .props_table(table)
+            .prop("columns", columns.clone())
+            .prop("format", copy_format(options)),
+            Statement::CopyTo {
+                stmt,
+                columns,
+                options,
+            } => Node::new("Copy", format!("to STDOUT ({})", copy_format(options)))
+                .prop("columns", columns.clone())
+                .prop("format", copy_format(options))
+                .children(vec![self.statement(stmt)]),
+            Statement::CreateTable {
+                table,
+                schema,
+                if_not_exists,
+            } => {
+                let sql = create_table_sql(&table.to_string(), schema)
+                    .expect("parsed columns have known data types");
+                Node::new("Create Table", describe(table))
+                    .props_table(table)
+                    .prop("if_not_exists", *if_not_exists)
+                    .prop("sql", sql)
+            }
+            Statement::DropTable { table, if_exists } => Node::new("Drop Table", describe(table))
+                .props_table(table)
+                .prop("if_exists", *if_exists),
+            Statement::AlterTable {
+                table,
+                changes,
+                if_exists,
+            } => {
+                let changes = changes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+                let children = changes
+                    .iter()
+                    .map(|change| {
+                        Node::stage("Change", change.as_str()).prop("sql", change.as_str())
+                    })
+                    .collect();
+                Node::new("Alter Table", describe(table))
+                    .props_table(table)
+                    .prop("if_exists", *if_exists)
+                    .children(children)
+            }
+            Statement::ShowCreateTable { table } => {
+                Node::new("Show Create Table", describe(table)).props_table(table)
+            }
+            Statement::Explain { stmt, .. } => {
+                Node::new("Explain", "").children(vec![self.statement(stmt)])
+            }
+            Statement::Set { variable, value } => {
+                let value = self.literal(value);
+                Node::new("Set", format!("{} = {value}", variable.as_str()))
+                    .prop("variable", variable.as_str())
+                    .prop("value", value)
+            }
+            Statement::Show { variable } => {
+                Node::new("Show", variable.as_str()).prop("variable", variable.as_str())
+            }
+            Statement::Begin => Node::new("Begin", "(ignored)"),
+            Statement::Commit => Node::new("Commit", "(ignored)"),
+            Statement::Rollback => Node::new("Rollback", "(ignored)"),
+            Statement::Discard => Node::new("Discard", "(ignored)"),
+        }
+    }
+
+    fn query(&self, name: &str, table: &Table, query: &Query) -> Node {
+        Node::new(name, format!("on {}", describe(table)))
+            .props_table(table)
+            .children(self.stages(query))
+    }
+
+    fn stages(&self, query: &Query) -> Vec<Node> {
+        query
+            .stages
+            .iter()
+            .filter_map(|stage| stage.stage.as_ref())
+            .map(|stage| self.stage(stage))
+            .collect()
+    }
+
+    fn stage(&self, stage: &stage::Stage) -> Node {
+        match stage {
+            stage::Stage::Select(select) => {
+                let mut exprs = select
+                    .exprs
+                    .iter()
+                    .map(|(name, expr)| {
+                        let expr = match &expr.expr {
+                            Some(stage::select_stage::select_expr::Expr::LogicalExpr(e)) => {
+                                self.logical(e)
+                            }
+                            Some(stage::select_stage::select_expr::Expr::FunctionExpr(e)) => {
+                                self.function(e)
+                            }
+                            None => "NULL".to_string(),
+                        };
+                        (name.as_str(), expr)
+                    })
+                    .collect::<Vec<_>>();
+                exprs.sort();
+                let detail = exprs
+                    .iter()
+                    .map(|(name, expr)| alias(expr, name))
+                    .collect::<Vec<_>>();
+                let exprs = exprs
+                    .into_iter()
+                    .map(|(name, expr)| (name.to_string(), Json::String(expr)))
+                    .collect::<Map<_, _>>();
+                Node::stage("Select", detail.join(", ")).prop("exprs", exprs)
+            }
+            stage::Stage::Filter(filter) => {
+                let expr = match filter.expr.as_ref().and_then(|e| e.expr.as_ref()) {
+                    Some(stage::filter_stage::filter_expr::Expr::LogicalExpr(e)) => self.logical(e),
+                    Some(stage::filter_stage::filter_expr::Expr::TextExpr(e)) => self.text(e),
+                    None => "TRUE".to_string(),
+                };
+                Node::stage("Filter", expr.clone()).prop("expr", expr)
+            }
+            #[allow(deprecated)]
+            stage::Stage::TopK(topk) => {
+                let expr = topk
+                    .expr
+                    .as_ref()
+                    .map(|e| self.logical(e))
+                    .unwrap_or_default();
+                Node::stage(
+                    "TopK",
+                    format!("{expr} {} LIMIT {}", order(topk.asc), topk.k),
+                )
+                .prop("expr", expr)
+                .prop("k", topk.k)
+                .prop("asc", topk.asc)
+            }
+            stage::Stage::Count(_) => Node::stage("Count", ""),
+            #[allow(deprecated)]
+            stage::Stage::Rerank(rerank) => {
+                let mut detail = Vec::new();
+                if let Some(model) = &rerank.model {
+                    detail.push(format!("model = {}", string(model)));
+                }
+                if let Some(query) = &rerank.query {
+                    detail.push(format!("query = {}", string(query)));
+                }
+                if !rerank.fields.is_empty() {
+                    detail.push(format!("fields = [{}]", rerank.fields.join(", ")));
+                }
+                Node::stage("Rerank", detail.join(", "))
+                    .prop("model", rerank.model.clone())
+                    .prop("query", rerank.query.clone())
+                    .prop("fields", rerank.fields.clone())
+                    .prop("topk_multiple", rerank.topk_multiple)
+            }
+            stage::Stage::Limit(limit) => {
+                Node::stage("Limit", limit.k.to_string()).prop("k", limit.k)
+            }
+            stage::Stage::Offset(offset) => {
+                Node::stage("Offset", offset.offset.to_string()).prop("offset", offset.offset)
+            }
+            stage::Stage::Sort(sort) => {
+                #[allow(deprecated)]
+                let legacy = sort.expr.as_ref().map(|expr| (expr, sort.asc));
+                let keys = sort
+                    .exprs
+                    .iter()
+                    .filter_map(|e| {
+                        let asc = e.order() != stage::sort_stage::SortOrder::Desc;
+                        e.expr.as_ref().map(|expr| (expr, asc))
+                    })
+                    .chain(legacy)
+                    .map(|(expr, asc)| format!("{} {}", self.logical(expr), order(asc)))
+                    .collect::<Vec<_>>();
+                Node::stage("Sort", keys.join(", ")).prop("keys", keys)
+            }
+            stage::Stage::Fetch(fetch) => {
+                Node::stage("Fetch", fetch.fields.join(", ")).prop("fields", fetch.fields.clone())
+            }
+            stage::Stage::GroupBy(group_by) => {
+                let mut keys = group_by
+                    .keys
+                    .iter()
+                    .map(|(name, expr)| alias(&self.logical(expr), name))
+                    .collect::<Vec<_>>();
+                keys.sort();
+                let mut aggs = group_by
+                    .aggs
+                    .iter()
+                    .map(|(name, agg)| alias(&aggregate(agg), name))
+                    .collect::<Vec<_>>();
+                aggs.sort();
+                Node::stage(
+                    "Group By",
+                    format!("{} AGGREGATE {}", keys.join(", "), aggs.join(", ")),
+                )
+                .prop("keys", keys)
+                .prop("aggs", aggs)
+            }
+        }
+    }
+
+    fn logical(&self, expr: &LogicalExpr) -> String {
+        use logical_expr::{binary_op, ternary_op, unary_op};
+
+        let Some(expr) = &expr.expr else {
+            return "NULL".to_string();
+        };
+
+        match expr {
+            logical_expr::Expr::Field(field) => quote_ident(field),
+            logical_expr::Expr::Literal(value) => self.literal(value),
+            logical_expr::Expr::UnaryOp(op) => {
+                let x = self.operand(op.expr.as_deref());
+                match op.op() {
+                    unary_op::Op::Not => format!("NOT {x}"),
+                    unary_op::Op::IsNull => format!("{x} IS NULL"),
+                    unary_op::Op::IsNotNull => format!("{x} IS NOT NULL"),
+                    unary_op::Op::Abs => format!("abs({})", self.arg(op.expr.as_deref())),
+                    unary_op::Op::Ln => format!("ln({})", self.arg(op.expr.as_deref())),
+                    unary_op::Op::Exp => format!("exp({})", self.arg(op.expr.as_deref())),
+                    unary_op::Op::Sqrt => format!("sqrt({})", self.arg(op.expr.as_deref())),
+                    unary_op::Op::Square => format!("square({})", self.arg(op.expr.as_deref())),
+                    unary_op::Op::Unspecified => format!("?({x})"),
+                }
+            }
+            logical_expr::Expr::BinaryOp(op) => {
+                let infix = |sym: &str| {
+                    format!(
+                        "{} {sym} {}",
+                        self.operand(op.left.as_deref()),
+                        self.operand(op.right.as_deref())
+                    )
+                };
+                let call = |name: &str| {
+                    format!(
+                        "{name}({}, {})",
+                        self.arg(op.left.as_deref()),
+                        self.arg(op.right.as_deref())
+                    )
+                };
+                match op.op() {
+                    binary_op::Op::Add => infix("+"),
+                    binary_op::Op::Sub => infix("-"),
+                    binary_op::Op::Mul => infix("*"),
+                    binary_op::Op::Div => infix("/"),
+                    binary_op::Op::Gt => infix(">"),
+                    binary_op::Op::Lt => infix("<"),
+                    binary_op::Op::Gte => infix(">="),
+                    binary_op::Op::Lte => infix("<="),
+                    binary_op::Op::Eq => infix("="),
+                    binary_op::Op::Neq => infix("<>"),
+                    binary_op::Op::And => infix("AND"),
+                    binary_op::Op::Or => infix("OR"),
+                    binary_op::Op::In => infix("IN"),
+                    binary_op::Op::StartsWith => call("starts_with"),
+                    binary_op::Op::Contains => call("contains"),
+                    binary_op::Op::MatchAll => call("match_all"),
+                    binary_op::Op::MatchAny => call("match_any"),
+                    binary_op::Op::Coalesce => call("coalesce"),
+                    binary_op::Op::Min => call("least"),
+                    binary_op::Op::Max => call("greatest"),
+                    // `date_part(part, x)`, with the part on the right of the op.
+                    binary_op::Op::DatePart => format!(
+                        "date_part({}, {})",
+                        self.arg(op.right.as_deref()),
+                        self.arg(op.left.as_deref())
+                    ),
+                    binary_op::Op::Unspecified => call("?"),
+                }
+            }
+            logical_expr::Expr::TernaryOp(op) => {
+                let (x, y, z) = (
+                    self.arg(op.x.as_deref()),
+                    self.arg(op.y.as_deref()),
+                    self.arg(op.z.as_deref()),
+                );
+                match op.op() {
+                    ternary_op::Op::Choose => format!("CASE WHEN {x} THEN {y} ELSE {z} END"),
+                    ternary_op::Op::RegexpMatch => format!("regexp_like({x}, {y}, {z})"),
+                    ternary_op::Op::Elapsed => format!("elapsed({x}, {y}, {z})"),
+                    ternary_op::Op::Saturate => format!("saturate({x}, {y}, {z})"),
+                    ternary_op::Op::Decay => format!("decay({x}, {y}, {z})"),
+                    ternary_op::Op::Unspecified => format!("?({x}, {y}, {z})"),
+                }
+            }
+            logical_expr::Expr::NaryOp(op) => {
+                let sep = match op.op() {
+                    logical_expr::nary_op::Op::Any => " OR ",
+                    _ => " AND ",
+                };
+                op.exprs
+                    .iter()
+                    .map(|e| self.operand(Some(e)))
+                    .collect::<Vec<_>>()
+                    .join(sep)
+            }
+        }
+    }
+
+    /// Operand of an infix operator, parenthesized unless atomic.
+    fn operand(&self, expr: Option<&LogicalExpr>) -> String {
+        match expr.and_then(|e| e.expr.as_ref()) {
+            Some(
+                logical_expr::Expr::BinaryOp(_)
+                | logical_expr::Expr::NaryOp(_)
+                | logical_expr::Expr::UnaryOp(_),
+            ) => format!("({})", self.arg(expr)),
+            _ => self.arg(expr),
+        }
+    }
+
+    fn arg(&self, expr: Option<&LogicalExpr>) -> String {
+        match expr {
+            Some(expr) => self.logical(expr),
+            None => "NULL".to_string(),
+        }
+    }
+
+    fn function(&self, expr: &FunctionExpr) -> String {
+        let Some(func) = &expr.func else {
+            return "NULL".to_string();
+        };
+
+        match func {
+            function_expr::Func::VectorDistance(f) => {
+                let mut args = vec![quote_ident(&f.field), self.value(f.query.as_ref())];
+                if f.skip_refine {
+                    args.push("skip_refine => true".to_string());
+                }
+                format!("vector_distance({})", args.join(", "))
+            }
+            function_expr::Func::Bm25Score(f) => {
+                let mut args = Vec::new();
+                if let Some(b) = f.b {
+                    args.push(format!("b => {b}"));
+                }
+                if let Some(k1) = f.k1 {
+                    args.push(format!("k1 => {k1}"));
+                }
+                format!("bm25_score({})", args.join(", "))
+            }
+            function_expr::Func::SemanticSimilarity(f) => format!(
+                "semantic_similarity({}, {})",
+                quote_ident(&f.field),
+                string(&f.query)
+            ),
+            function_expr::Func::MultiVectorDistance(f) => {
+                let mut args = vec![quote_ident(&f.field), self.value(f.query.as_ref())];
+                if let Some(candidates) = f.candidates {
+                    args.push(format!("candidates => {candidates}"));
+                }
+                format!("multi_vector_distance({})", args.join(", "))
+            }
+        }
+    }
+
+    fn text(&self, expr: &TextExpr) -> String {
+        let Some(expr) = &expr.expr else {
+            return "TRUE".to_string();
+        };
+
+        match expr {
+            text_expr::Expr::Terms(terms) => {
+                let name = if terms.should { "should" } else { "match" };
+                let terms = terms
+                    .terms
+                    .iter()
+                    .map(|term| {
+                        let mut args = vec![string(&term.token)];
+                        if let Some(field) = &term.field {
+                            args.push(quote_ident(field));
+                        }
+                        if term.weight != 1.0 {
+                            args.push(format!("weight => {}", term.weight));
+                        }
+                        format!("{name}({})", args.join(", "))
+                    })
+                    .collect::<Vec<_>>();
+                let joined = terms.join(if terms_all(expr) { " AND " } else { " OR " });
+                if terms.len() > 1 {
+                    format!("({joined})")
+                } else {
+                    joined
+                }
+            }
+            text_expr::Expr::And(and) => format!(
+                "{} AND {}",
+                self.text_operand(and.left.as_deref()),
+                self.text_operand(and.right.as_deref())
+            ),
+            text_expr::Expr::Or(or) => format!(
+                "({} OR {})",
+                self.text_operand(or.left.as_deref()),
+                self.text_operand(or.right.as_deref())
+            ),
+        }
+    }
+
+    fn text_operand(&self, expr: Option<&TextExpr>) -> String {
+        match expr {
+            Some(expr) => self.text(expr),
+            None => "TRUE".to_string(),
+        }
+    }
+
+    fn value(&self, value: Option<&Value>) -> String {
+        match value {
+            Some(value) => self.literal(value),
+            None => "NULL".to_string(),
+        }
+    }
+
+    /// Renders a literal in SQL syntax, eliding long lists unless `verbose`.
+    fn literal(&self, value: &Value) -> String {
+        match &value.value {
+            None | Some(V::Null(_)) => "NULL".to_string(),
+            Some(V::String(s)) => string(s),
+            Some(V::Bool(b)) => b.to_string().to_uppercase(),
+            Some(V::Binary(bytes)) => {
+                let hex = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+                format!("'\\x{hex}'")
+            }
+            // Shortest representation of each float rather than its f64 widening.
+            Some(V::List(List {
+                values: Some(list::Values::F32(values)),
+            })) => format!("[{}]", self.elide(&floats(values.values.iter().copied()))),
+            Some(V::List(List {
+                values: Some(list::Values::F16(values)),
+            })) => format!(
+                "[{}]",
+                self.elide(&floats(values.as_ref().iter().map(|v| f32::from(*v))))
+            ),
+            Some(V::List(List {
+                values: Some(list::Values::F8(values)),
+            })) => format!(
+                "[{}]",
+                self.elide(&floats(values.as_ref().iter().map(|v| f32::from(*v))))
+            ),
+            _ => match Json::try_from(value.clone()) {
+                Ok(Json::Array(items)) => {
+                    let items = items
+                        .iter()
+                        .map(|item| match item {
+                            Json::String(s) => string(s),
+                            item => item.to_string(),
+                        })
+                        .collect::<Vec<_>>();
+                    format!("[{}]", self.elide(&items))
+                }
+                Ok(json) => json.to_string(),
+                Err(_) => format!("{value:?}"),
+            },
+        }
+    }
+
+    fn elide(&self, items: &[String]) -> String {
+        if self.verbose || items.len() <= MAX_LIST_ELEMENTS {
+            return items.join(", ");
+        }
+        format!(
+            "{}, … ({} values)",
+            items[..MAX_LIST_ELEMENTS].join(", "),
+            items.len()
+        )
+    }
+}
+
+fn terms_all(expr: &text_expr::Expr) -> bool {
+    matches!(expr, text_expr::Expr::Terms(terms) if terms.all)
+}
+
+fn describe(table: &Table) -> String {
+    match table {
+        Table::Collection(collection) => format!("Collection {}", quote_ident(collection)),
+        Table::Partition(collection, partition) => format!(
+            "Partition {} of Collection {}",
+            quote_ident(partition),
+            quote_ident(collection)
+        ),
+    }
+}
+
+fn aggregate(agg: &AggregateExpr) -> String {
+    match &agg.op {
+        Some(aggregate_expr::Op::Count(count)) => match &count.field {
+            Some(field) => format!("count({})", quote_ident(field)),
+            None => "count(*)".to_string(),
+        },
+        Some(aggregate_expr::Op::Sum(sum)) => format!("sum({})", quote_ident(&sum.field)),
+        Some(aggregate_expr::Op::Min(min)) => format!("min({})", quote_ident(&min.field)),
+        Some(aggregate_expr::Op::Max(max)) => format!("max({})", quote_ident(&max.field)),
+        Some(aggregate_expr::Op::Avg(avg)) => format!("avg({})", quote_ident(&avg.field)),
+        None => "NULL".to_string(),
+    }
+}
+
+/// Catalog table with its alias, eg. `pg_catalog.pg_class c`.
+fn catalog_scan(scan: &CatalogScan) -> String {
+    if scan.alias == <&'static str>::from(scan.table) {
+        scan.table.name()
+    } else {
+        format!("{} {}", scan.table.name(), scan.alias)
+    }
+}
+
+fn catalog_expr(expr: &CatalogExpr) -> String {
+    let literal = |value: &Value| Explainer { verbose: true }.literal(value);
+    match expr {
+        CatalogExpr::Column(column) => column.clone(),
+        CatalogExpr::Literal(value) => literal(value),
+        CatalogExpr::Case {
+            column,
+            branches,
+            default,
+        } => {
+            let mut out = format!("CASE {column}");
+            for (when, then) in branches {
+                out.push_str(&format!(" WHEN {} THEN {}", literal(when), literal(then)));
+            }
+            if default.as_null().is_none() {
+                out.push_str(&format!(" ELSE {}", literal(default)));
+            }
+            out.push_str(" END");
+            out
+        }
+    }
+}
+
+fn catalog_filter(filter: &CatalogFilter) -> String {
+    let not = |negated: bool| if negated { "NOT " } else { "" };
+    let literal = |value: &Value| Explainer { verbose: true }.literal(value);
+    match filter {
+        CatalogFilter::Eq {
+            column,
+            value,
+            negated,
+        } => {
+            let op = if *negated { "<>" } else { "=" };
+            format!("{column} {op} {}", literal(value))
+        }
+        CatalogFilter::In {
+            column,
+            values,
+            negated,
+        } => {
+            let values = values.iter().map(literal).collect::<Vec<_>>();
+            format!("{column} {}IN ({})", not(*negated), values.join(", "))
+        }
+        CatalogFilter::IsNull { column, negated } => {
+            format!("{column} IS {}NULL", not(*negated))
+        }
+        CatalogFilter::Matches {
+            column,
+            regex,
+            negated,
+        } => {
+            let op = if *negated { "!~" } else { "~" };
+            format!("{column} {op} {}", string(regex.as_str()))
+        }
+    }
+}
+
+fn copy_format(options: &CopyOptions) -> String {
+    format!("{:?}", options.format).to_lowercase()
+}
+
+/// `expr AS name`, or just `expr` if it is the field `name` itself.
+fn alias(expr: &str, name: &str) -> String {
+    if expr == quote_ident(name) {
+        expr.to_string()
+    } else {
+        format!("{expr} AS {}", quote_ident(name))
+    }
+}
+
+fn order(asc: bool) -> &'static str {
+    if asc { "ASC" } else { "DESC" }
+}
+
+fn floats(values: impl Iterator<Item = f32>) -> Vec<String> {
+    values.map(|v| v.to_string()).collect()
+}
+
+fn string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use topk_rs::proto::v1::data::stage::select_stage::SelectExpr;
+    use topk_rs::proto::v1::data::{Document, Stage};
+
+    use super::*;
+
+    fn books() -> Table {
+        Table::Collection("books".to_string())
+    }
+
+    #[test]
+    fn query_pipeline() {
+        let stmt = Statement::Select {
+            table: books(),
+            query: Query::new(vec![
+                Stage::filter(
+                    LogicalExpr::field("author")
+                        .eq(LogicalExpr::literal(Value::string("Tolkien")))
+                        .and(
+                            LogicalExpr::field("published_year")
+                                .gt(LogicalExpr::literal(Value::i64(1950))),
+                        ),
+                ),
+                Stage::select([
+                    ("_id", SelectExpr::logical(LogicalExpr::field("_id"))),
+                    (
+                        "dist",
+                        SelectExpr::function(FunctionExpr::vector_distance(
+                            "embedding",
+                            Value::list(vec![0.1f32; 8]),
+                            false,
+                        )),
+                    ),
+                ]),
+                Stage::sort(LogicalExpr::field("dist")),
+                Stage::limit(5),
+            ]),
+        };
+
+        assert_eq!(
+            stmt.explain(ExplainFormat::Text, false, None),
+            "Query on Collection books\n\
+             \x20 -> Filter: (author = 'Tolkien') AND (published_year > 1950)\n\
+             \x20 -> Select: _id, vector_distance(embedding, [0.1, 0.1, 0.1, 0.1, … (8 values)]) AS dist\n\
+             \x20 -> Sort: dist DESC\n\
+             \x20 -> Limit: 5\n"
+        );
+
+        let verbose = stmt.explain(ExplainFormat::Text, true, None);
+        assert!(!verbose.contains('…'), "{verbose}");
+    }
+
+    #[test]
+    fn json_and_analyze() {
+        let stmt = Statement::Insert {
+            table: Table::Partition("books".to_string(), "p1".to_string()),
+            docs: vec![Document::default(); 3],
+        };
+        let analyze = ExplainAnalyze {
+            elapsed: Duration::from_micros(1500),
+            matched_count: None,
+            rows: 0,
+        };
+
+        let text = stmt.explain(ExplainFormat::Text, false, Some(&analyze));
+        assert_eq!(
+            text,
+            "Insert into Partition p1 of Collection books: 3 document(s)\n\
+             Execution: 1.500 ms, matched n/a, returned 0 rows\n"
+        );
+
+        let json: Json =
+            serde_json::from_str(&stmt.explain(ExplainFormat::Json, false, Some(&analyze)))
+                .unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "node": "Insert",
+                "collection": "books",
+                "partition": "p1",
+                "documents": 3,
+                "analyze": {"elapsed_ms": 1.5, "matched_count": null, "rows": 0},
+            })
+        );
+    }
+}
@@ -6,7 +6,7 @@ use topk_rs::proto::v1::data::{
 
 use super::typed::{ElemType, TypedValues, coerce_i64s};
 use crate::expr::Expr;
-use crate::expr::regexp;
+use crate::expr::{rank, regexp};
 use crate::ext::{SqlExprExt, SqlFunctionExt};
 use crate::{Error, FromSql, sql_invalid, sql_unsupported};
 
@@ -16,6 +16,10 @@ impl TryFrom<SqlFunction> for Expr {
     fn try_from(func: SqlFunction) -> Result<Self, Self::Error> {
         let name = func.name();
         let key = name.to_ascii_lowercase();
+        if key == "rrf" {
+            let (args, kwargs) = func.named_args()?;
+            return Ok(Self::Rank(rank::rrf(args, kwargs, &name)?));
+        }
         let args = func.args()?;
 
         Ok(match key.as_str() {
@@ -160,6 +164,12 @@ impl TryFrom<SqlFunction> for Expr {
                 let value = Value::from_sql(value)?;
                 Self::Logical(field.contains(value))
             }
+            "starts_with" => {
+                let [field, value]: [SqlExpr; 2] = exact(args, &name)?;
+                let field = LogicalExpr::from_sql(field)?;
+                let value = Value::from_sql(value)?;
+                Self::Logical(field.starts_with(value))
+            }
             "vector_distance" => {
                 let (field, query, skip_refine) = match args.len() {
                     2 => {
@@ -222,6 +232,7 @@ impl TryFrom<SqlFunction> for Expr {
                 }
                 n => sql_invalid!("{name}: expected 0 or 2 args, got {n}"),
             },
+            "normalize" => Self::Rank(rank::normalize(args, &name)?),
             "semantic_similarity" => {
                 let [field, query]: [SqlExpr; 2] = exact(args, &name)?;
                 let query = query.as_string().ok_or_else(|| {
@@ -403,7 +414,7 @@ impl FromSql<SqlFunction> for Value {
         let name = func.name();
         match Expr::try_from(func)? {
             Expr::Literal(v) => Ok(v),
-            Expr::Logical(_) | Expr::Text(_) | Expr::Function(_) => {
+            Expr::Logical(_) | Expr::Text(_) | Expr::Function(_) | Expr::Rank(_) => {
                 sql_unsupported!("`{name}` does not produce a Value")
             }
         }
@@ -424,6 +435,10 @@ impl FromSql<SqlFunction> for LogicalExpr {
                 "`{name}` is a search function — only valid at the top of a SELECT projection \
                  item (e.g. `SELECT {name}(…) AS s FROM c ORDER BY s LIMIT k`)"
             ),
+            Expr::Rank(_) => sql_unsupported!(
+                "`{name}` is a rank fusion function — only valid in a SELECT projection item \
+                 (e.g. `SELECT rrf(bm25_score(), …) AS s FROM c ORDER BY s DESC LIMIT k`)"
+            ),
         }
     }
 }
@@ -4,15 +4,21 @@ mod aggregate;
 mod filter;
 mod function;
 mod logical;
+mod rank;
 mod regexp;
 mod select;
 mod typed;
 mod value;
 
+pub(crate) use rank::contains_rank_fn;
+pub use rank::{FUSE_SCORE, Fusion, Normalization, RankExpr, resolve_score_orders, score_order};
+pub(crate) use value::{parse_cast, parse_timestamp};
+
 #[derive(Clone, Debug)]
 pub enum Expr {
     Literal(Value),
     Logical(LogicalExpr),
     Text(TextExpr),
     Function(FunctionExpr),
+    Rank(RankExpr),
 }
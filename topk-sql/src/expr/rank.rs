@@ -0,0 +1,588 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{BinaryOperator, Expr as SqlExpr, visit_expressions};
+use topk_rs::proto::v1::control::field_index::Index;
+use topk_rs::proto::v1::control::{FieldSpec, VectorDistanceMetric};
+use topk_rs::proto::v1::data::function_expr::Func;
+use topk_rs::proto::v1::data::stage::select_stage::{SelectExpr, select_expr};
+use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
+use topk_rs::proto::v1::data::stage;
+use topk_rs::proto::v1::data::{Document, FunctionExpr, Query, Value};
+
+use super::Expr;
+use crate::ext::{SqlExprExt, SqlFunctionExt};
+use crate::{Error, FromSql, sql_invalid, sql_unsupported};
+
+/// Field each fusion sub-query projects its score into.
+pub const FUSE_SCORE: &str = "_fuse_score";
+
+/// Default `k` of `rrf(…)`, same as Elasticsearch's `rank_constant`.
+const RRF_K: f32 = 60.0;
+
+/// Hybrid ranking expression (`rrf(…)`, `normalize(…)`).
+///
+/// Every scoring expression runs as its own sub-query and the resulting
+/// rankings are fused client-side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankExpr {
+    /// Scoring expression of each sub-query.
+    pub scores: Vec<SelectExpr>,
+    /// How the sub-query rankings are fused.
+    pub fusion: Fusion,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fusion {
+    /// Reciprocal rank fusion, `Σ 1 / (k + rank)`.
+    Rrf { k: f32 },
+    /// Weighted sum of normalized scores, one `(weight, normalization)` per sub-query.
+    Weighted(Vec<(f32, Normalization)>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// `(s - min) / (max - min)`
+    MinMax,
+    /// `(s - mean) / stddev`
+    ZScore,
+}
+
+impl std::str::FromStr for Normalization {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "minmax" => Ok(Normalization::MinMax),
+            "zscore" => Ok(Normalization::ZScore),
+            other => sql_invalid!("unknown normalization '{other}'; expected 'minmax' or 'zscore'"),
+        }
+    }
+}
+
+/// `rrf(score_expr, …, k => 60)`
+pub(super) fn rrf(
+    args: Vec<SqlExpr>,
+    mut kwargs: HashMap<String, SqlExpr>,
+    name: &str,
+) -> Result<RankExpr, Error> {
+    sql_invalid!(
+        args.is_empty(),
+        "{name}: expected at least 1 scoring expression"
+    );
+
+    let k = match kwargs.remove("k") {
+        Some(k) => {
+            let k = Value::from_sql(k)?;
+            k.as_f64()
+                .or_else(|| k.as_i64().map(|n| n as f64))
+                .filter(|k| *k >= 0.0)
+                .ok_or_else(|| Error::Invalid(format!("{name}: k must be a non-negative number")))?
+                as f32
+        }
+        None => RRF_K,
+    };
+    if let Some(key) = kwargs.keys().next() {
+        sql_invalid!("{name}: unknown argument `{key}`");
+    }
+
+    Ok(RankExpr {
+        scores: args
+            .into_iter()
+            .map(score_expr)
+            .collect::<Result<Vec<_>, _>>()?,
+        fusion: Fusion::Rrf { k },
+    })
+}
+
+/// `normalize(score_expr, 'minmax'|'zscore')`
+pub(super) fn normalize(args: Vec<SqlExpr>, name: &str) -> Result<RankExpr, Error> {
+    let [score, method]: [SqlExpr; 2] = args
+        .try_into()
+        .map_err(|v: Vec<_>| Error::Invalid(format!("{name}: expected 2 args, got {}", v.len())))?;
+    let method = method
+        .as_string()
+        .ok_or_else(|| Error::Invalid(format!("{name}: method must be a string literal")))?;
+
+    Ok(RankExpr {
+        scores: vec![score_expr(score)?],
+        fusion: Fusion::Weighted(vec![(1.0, method.parse()?)]),
+    })
+}
+
+fn score_expr(expr: SqlExpr) -> Result<SelectExpr, Error> {
+    sql_unsupported!(contains_rank_fn(&expr), "nested rank fusion functions");
+    SelectExpr::from_sql(expr)
+}
+
+/// True if `expr` calls `rrf(…)` or `normalize(…)` anywhere.
+pub(crate) fn contains_rank_fn(expr: &SqlExpr) -> bool {
+    visit_expressions(expr, |expr| match expr {
+        SqlExpr::Function(f)
+            if matches!(f.name().to_ascii_lowercase().as_str(), "rrf" | "normalize") =>
+        {
+            ControlFlow::Break(())
+        }
+        _ => ControlFlow::Continue(()),
+    })
+    .is_break()
+}
+
+/// Order in which a fusion score ranks documents best first: ascending for `vector_distance`
+/// over a field indexed with the euclidean or hamming metric (lower is closer), descending for
+/// every other score.
+pub fn score_order(score: &SelectExpr, schema: &HashMap<String, FieldSpec>) -> SortOrder {
+    let Some(select_expr::Expr::FunctionExpr(FunctionExpr {
+        func: Some(Func::VectorDistance(distance)),
+    })) = &score.expr
+    else {
+        return SortOrder::Desc;
+    };
+
+    let metric = schema
+        .get(&distance.field)
+        .and_then(|spec| spec.index.as_ref())
+        .and_then(|index| match &index.index {
+            Some(Index::VectorIndex(index)) => Some(index.metric()),
+            _ => None,
+        });
+    match metric {
+        Some(VectorDistanceMetric::Euclidean | VectorDistanceMetric::Hamming) => SortOrder::Asc,
+        _ => SortOrder::Desc,
+    }
+}
+
+/// Sorts the sub-queries of a `Statement::Fuse` by the [`score_order`] of their score in the
+/// collection `schema`, and returns those orders for [`Fusion::fuse`].
+///
+/// Sub-queries are lowered without a schema, ranking their score descending.
+pub fn resolve_score_orders(
+    queries: &mut [Query],
+    schema: &HashMap<String, FieldSpec>,
+) -> Vec<SortOrder> {
+    queries
+        .iter_mut()
+        .map(|query| {
+            let order = query
+                .stages
+                .iter()
+                .find_map(|stage| match &stage.stage {
+                    Some(stage::Stage::Select(select)) => select.exprs.get(FUSE_SCORE),
+                    _ => None,
+                })
+                .map(|score| score_order(score, schema))
+                .unwrap_or(SortOrder::Desc);
+
+            for stage in &mut query.stages {
+                if let Some(stage::Stage::Sort(sort)) = &mut stage.stage {
+                    for expr in &mut sort.exprs {
+                        expr.order = order as i32;
+                    }
+                }
+            }
+            order
+        })
+        .collect()
+}
+
+/// Parses a weighted fusion, eg. `0.3 * normalize(a, 'minmax') + 0.7 * normalize(b, 'minmax')`.
+impl FromSql<SqlExpr> for RankExpr {
+    fn from_sql(expr: SqlExpr) -> Result<RankExpr, Error> {
+        match expr {
+            SqlExpr::Function(func) => {
+                let name = func.name();
+                match Expr::try_from(func)? {
+                    Expr::Rank(rank) => Ok(rank),
+                    _ => sql_unsupported!("`{name}` cannot be combined with rank fusion functions"),
+                }
+            }
+            SqlExpr::Nested(inner) => RankExpr::from_sql(*inner),
+            SqlExpr::BinaryOp {
+                left,
+                op: BinaryOperator::Plus,
+                right,
+            } => {
+                let mut left = RankExpr::from_sql(*left)?;
+                let right = RankExpr::from_sql(*right)?;
+                match (&mut left.fusion, right.fusion) {
+                    (Fusion::Weighted(l), Fusion::Weighted(r)) => l.extend(r),
+                    _ => sql_unsupported!("`rrf(…)` cannot be combined with other scores"),
+                }
+                left.scores.extend(right.scores);
+                Ok(left)
+            }
+            SqlExpr::BinaryOp {
+                left,
+                op: BinaryOperator::Multiply,
+                right,
+            } => {
+                let (weight, rank) = match (weight(&left), weight(&right)) {
+                    (Some(w), None) => (w, RankExpr::from_sql(*right)?),
+                    (None, Some(w)) => (w, RankExpr::from_sql(*left)?),
+                    _ => sql_unsupported!(
+                        "rank fusion weights must be numeric literals (eg. `0.3 * normalize(…)`)"
+                    ),
+                };
+                match rank.fusion {
+                    Fusion::Weighted(terms) => Ok(RankExpr {
+                        scores: rank.scores,
+                        fusion: Fusion::Weighted(
+                            terms.into_iter().map(|(w, n)| (w * weight, n)).collect(),
+                        ),
+                    }),
+                    Fusion::Rrf { .. } => sql_unsupported!("weighted `rrf(…)`"),
+                }
+            }
+            other => sql_unsupported!("expression in rank fusion: {other}"),
+        }
+    }
+}
+
+fn weight(expr: &SqlExpr) -> Option<f32> {
+    let value = Value::from_sql(expr.clone()).ok()?;
+    value
+        .as_f64()
+        .or_else(|| value.as_i64().map(|n| n as f64))
+        .map(|w| w as f32)
+}
+
+impl Fusion {
+    /// Fuses the results of the sub-queries (in the order of `RankExpr::scores`), whose
+    /// scores rank documents best first in `orders` (see `resolve_score_orders`).
+    ///
+    /// Documents are merged by `_id`, their `FUSE_SCORE` field is replaced by the
+    /// fused score under `alias`, and they are returned best first.
+    pub fn fuse(
+        &self,
+        results: Vec<Vec<Document>>,
+        orders: &[SortOrder],
+        alias: &str,
+    ) -> Result<Vec<Document>, Error> {
+        sql_invalid!(
+            orders.len() != results.len(),
+            "expected {} score orders, got {}",
+            results.len(),
+            orders.len()
+        );
+
+        let mut by_id: HashMap<String, Document> = HashMap::new();
+        let mut groups: Vec<Vec<(String, f64)>> = Vec::with_capacity(results.len());
+        for (docs, order) in results.into_iter().zip(orders) {
+            let mut members = Vec::with_capacity(docs.len());
+            for mut doc in docs {
+                let id = doc
+                    .id()
+                    .map_err(|e| Error::Internal(e.to_string()))?
+                    .to_string();
+                let score = doc.fields.remove(FUSE_SCORE).as_ref().and_then(as_score);
+                // Negated distances rank and normalize like scores, higher being better
+                let score = match order {
+                    SortOrder::Asc => score.map(|score| -score),
+                    _ => score,
+                };
+                if let Some(score) = score {
+                    members.push((id.clone(), score));
+                }
+                let entry = by_id.entry(id).or_default();
+                for (k, v) in doc.fields {
+                    entry.fields.entry(k).or_insert(v);
+                }
+            }
+            groups.push(members);
+        }
+
+        let totals = self.consume(groups)?;
+
+        let mut fused: Vec<(f64, Document)> = by_id
+            .into_iter()
+            .map(|(id, doc)| (totals.get(&id).copied().unwrap_or(0.0), doc))
+            .collect();
+        fused.sort_by(|a, b| {
+            score_desc(
+                (a.0, a.1.id().unwrap_or_default()),
+                (b.0, b.1.id().unwrap_or_default()),
+            )
+        });
+
+        Ok(fused
+            .into_iter()
+            .map(|(score, mut doc)| {
+                doc.fields
+                    .insert(alias.to_string(), Value::f32(score as f32));
+                doc
+            })
+            .collect())
+    }
+
+    fn consume(&self, groups: Vec<Vec<(String, f64)>>) -> Result<HashMap<String, f64>, Error> {
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        match self {
+            Fusion::Rrf { k } => {
+                for mut ranked in groups {
+                    ranked.sort_by(|a, b| score_desc((a.1, &a.0), (b.1, &b.0)));
+                    for (position, (id, _)) in ranked.into_iter().enumerate() {
+                        *totals.entry(id).or_insert(0.0) +=
+                            1.0 / (*k as f64 + (position + 1) as f64);
+                    }
+                }
+            }
+            Fusion::Weighted(terms) => {
+                sql_invalid!(
+                    terms.len() != groups.len(),
+                    "expected {} sub-query results, got {}",
+                    terms.len(),
+                    groups.len()
+                );
+                for ((weight, normalization), group) in terms.iter().zip(groups) {
+                    for (id, score) in normalization.apply(group) {
+                        *totals.entry(id).or_insert(0.0) += *weight as f64 * score;
+                    }
+                }
+            }
+        }
+        Ok(totals)
+    }
+}
+
+impl Normalization {
+    fn apply(&self, group: Vec<(String, f64)>) -> Vec<(String, f64)> {
+        if group.is_empty() {
+            return group;
+        }
+        match self {
+            Normalization::MinMax => {
+                let min = group.iter().map(|(_, s)| *s).fold(f64::INFINITY, f64::min);
+                let max = group
+                    .iter()
+                    .map(|(_, s)| *s)
+                    .fold(f64::NEG_INFINITY, f64::max);
+                let range = max - min;
+                group
+                    .into_iter()
+                    // All scores equal: every document is equally (and fully) relevant.
+                    .map(|(id, s)| (id, if range > 0.0 { (s - min) / range } else { 1.0 }))
+                    .collect()
+            }
+            Normalization::ZScore => {
+                let n = group.len() as f64;
+                let mean = group.iter().map(|(_, s)| *s).sum::<f64>() / n;
+                let std = (group.iter().map(|(_, s)| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+                group
+                    .into_iter()
+                    .map(|(id, s)| (id, if std > 0.0 { (s - mean) / std } else { 0.0 }))
+                    .collect()
+            }
+        }
+    }
+}
+
+fn as_score(value: &Value) -> Option<f64> {
+    value
+        .as_f32()
+        .map(f64::from)
+        .or_else(|| value.as_f64())
+        .or_else(|| value.as_i64().map(|n| n as f64))
+        .or_else(|| value.as_i32().map(f64::from))
+        .or_else(|| value.as_u64().map(|n| n as f64))
+        .or_else(|| value.as_u32().map(f64::from))
+}
+
+/// Orders by score descending, ties broken by id ascending.
+fn score_desc(a: (f64, &str), b: (f64, &str)) -> Ordering {
+    b.0.partial_cmp(&a.0)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.1.cmp(b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use topk_rs::doc;
+
+    use super::*;
+
+    fn scored(ids: &[(&str, f32)]) -> Vec<Document> {
+        ids.iter()
+            .map(|(id, score)| doc!("_id" => *id, FUSE_SCORE => *score))
+            .collect()
+    }
+
+    fn ranking(docs: &[Document], alias: &str) -> Vec<(String, f32)> {
+        docs.iter()
+            .map(|doc| {
+                (
+                    doc.id().unwrap().to_string(),
+                    doc.fields.get(alias).and_then(Value::as_f32).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rrf_sums_reciprocal_ranks() {
+        let fused = Fusion::Rrf { k: 60.0 }
+            .fuse(
+                vec![
+                    scored(&[("a", 9.0), ("b", 5.0), ("c", 1.0)]),
+                    scored(&[("c", 0.9), ("a", 0.8)]),
+                ],
+                &[SortOrder::Desc, SortOrder::Desc],
+                "score",
+            )
+            .unwrap();
+
+        let expected = [
+            ("a", 1.0 / 61.0 + 1.0 / 62.0),
+            ("c", 1.0 / 63.0 + 1.0 / 61.0),
+            ("b", 1.0 / 62.0),
+        ];
+        let actual = ranking(&fused, "score");
+        assert_eq!(actual.len(), expected.len());
+        for ((id, score), (expected_id, expected_score)) in actual.iter().zip(expected) {
+            assert_eq!(id, expected_id);
+            assert!((*score as f64 - expected_score).abs() < 1e-6);
+        }
+        assert!(fused.iter().all(|doc| !doc.fields.contains_key(FUSE_SCORE)));
+    }
+
+    #[test]
+    fn rrf_breaks_ties_by_id() {
+        let fused = Fusion::Rrf { k: 1.0 }
+            .fuse(
+                vec![scored(&[("b", 1.0)]), scored(&[("a", 1.0)])],
+                &[SortOrder::Desc, SortOrder::Desc],
+                "s",
+            )
+            .unwrap();
+        assert_eq!(
+            ranking(&fused, "s"),
+            vec![("a".to_string(), 0.5), ("b".to_string(), 0.5)]
+        );
+    }
+
+    #[test]
+    fn fusion_ranks_ascending_scores_lowest_first() {
+        // Euclidean distances, closest first
+        let distances = scored(&[("a", 0.1), ("b", 0.5), ("c", 2.0)]);
+
+        let fused = Fusion::Rrf { k: 60.0 }
+            .fuse(vec![distances.clone()], &[SortOrder::Asc], "s")
+            .unwrap();
+        let ids: Vec<_> = ranking(&fused, "s").into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+
+        let fused = Fusion::Weighted(vec![(1.0, Normalization::MinMax)])
+            .fuse(vec![distances], &[SortOrder::Asc], "s")
+            .unwrap();
+        assert_eq!(
+            ranking(&fused, "s"),
+            vec![
+                ("a".to_string(), 1.0),
+                ("b".to_string(), 0.7894737),
+                ("c".to_string(), 0.0),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::minmax(Normalization::MinMax, &[2.0, 4.0, 6.0], &[0.0, 0.5, 1.0])]
+    #[case::minmax_constant(Normalization::MinMax, &[3.0, 3.0], &[1.0, 1.0])]
+    #[case::zscore(Normalization::ZScore, &[1.0, 3.0], &[-1.0, 1.0])]
+    #[case::zscore_constant(Normalization::ZScore, &[3.0, 3.0], &[0.0, 0.0])]
+    fn normalize_scores(
+        #[case] normalization: Normalization,
+        #[case] scores: &[f64],
+        #[case] expected: &[f64],
+    ) {
+        let group = scores
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i.to_string(), *s))
+            .collect();
+        let actual: Vec<f64> = normalization
+            .apply(group)
+            .into_iter()
+            .map(|(_, s)| s)
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn weighted_fusion() {
+        let fused = Fusion::Weighted(vec![
+            (0.3, Normalization::MinMax),
+            (0.7, Normalization::MinMax),
+        ])
+        .fuse(
+            vec![
+                scored(&[("a", 10.0), ("b", 0.0)]),
+                scored(&[("b", 0.9), ("c", 0.1)]),
+            ],
+            &[SortOrder::Desc, SortOrder::Desc],
+            "score",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ranking(&fused, "score"),
+            vec![
+                ("b".to_string(), 0.7),
+                ("a".to_string(), 0.3),
+                ("c".to_string(), 0.0),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::rrf("rrf(bm25_score(), semantic_similarity(bio, 'x'))", 2, Fusion::Rrf { k: 60.0 })]
+    #[case::rrf_k("rrf(bm25_score(), k => 10)", 1, Fusion::Rrf { k: 10.0 })]
+    #[case::normalize(
+        "normalize(bm25_score(), 'zscore')",
+        1,
+        Fusion::Weighted(vec![(1.0, Normalization::ZScore)])
+    )]
+    #[case::weighted(
+        "0.3 * normalize(bm25_score(), 'minmax') + normalize(rating, 'MINMAX') * 0.7",
+        2,
+        Fusion::Weighted(vec![(0.3, Normalization::MinMax), (0.7, Normalization::MinMax)])
+    )]
+    fn parse(#[case] sql: &str, #[case] scores: usize, #[case] fusion: Fusion) {
+        let expr = sqlparser::parser::Parser::new(&crate::dialect::TopKDialect::default())
+            .try_with_sql(sql)
+            .unwrap()
+            .parse_expr()
+            .unwrap();
+        let rank = RankExpr::from_sql(expr).unwrap();
+        assert_eq!(rank.scores.len(), scores);
+        assert_eq!(rank.fusion, fusion);
+    }
+
+    #[rstest]
+    #[case::rrf_no_args("rrf()", "Invalid: rrf: expected at least 1 scoring expression")]
+    #[case::rrf_unknown_kwarg("rrf(bm25_score(), x => 1)", "Invalid: rrf: unknown argument `x`")]
+    #[case::normalize_method(
+        "normalize(bm25_score(), 'l2')",
+        "Invalid: unknown normalization 'l2'; expected 'minmax' or 'zscore'"
+    )]
+    #[case::rrf_plus_normalize(
+        "rrf(bm25_score()) + normalize(rating, 'minmax')",
+        "Unsupported: `rrf(…)` cannot be combined with other scores"
+    )]
+    #[case::non_literal_weight(
+        "rating * normalize(bm25_score(), 'minmax')",
+        "Unsupported: rank fusion weights must be numeric literals (eg. `0.3 * normalize(…)`)"
+    )]
+    #[case::nested(
+        "rrf(normalize(rating, 'minmax'))",
+        "Unsupported: nested rank fusion functions"
+    )]
+    fn parse_error(#[case] sql: &str, #[case] expected: &str) {
+        let expr = sqlparser::parser::Parser::new(&crate::dialect::TopKDialect::default())
+            .try_with_sql(sql)
+            .unwrap()
+            .parse_expr()
+            .unwrap();
+        assert_eq!(RankExpr::from_sql(expr).unwrap_err().to_string(), expected);
+    }
+}
@@ -15,6 +15,11 @@ impl FromSql<SqlExpr> for SelectExpr {
                 Expr::Text(_) => {
                     sql_unsupported!("`match` is a text filter function — only valid in WHERE")
                 }
+                Expr::Rank(_) => {
+                    sql_unsupported!(
+                        "rank fusion functions must be the whole SELECT projection item"
+                    )
+                }
             },
             SqlExpr::Cast { expr, .. } => Ok(SelectExpr::from_sql(*expr)?),
             other => Ok(SelectExpr::logical(LogicalExpr::from_sql(other)?)),
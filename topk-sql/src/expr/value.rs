@@ -145,7 +145,7 @@ pub fn parse_number(repr: &str) -> Result<Value, Error> {
         .map_err(|e| Error::InvalidLiteral(format!("invalid integer literal `{repr}`: {e}")))
 }
 
-fn parse_timestamp(s: &str) -> Result<Value, Error> {
+pub(crate) fn parse_timestamp(s: &str) -> Result<Value, Error> {
     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
         return Ok(Value::timestamp(dt));
     }
@@ -157,7 +157,7 @@ fn parse_timestamp(s: &str) -> Result<Value, Error> {
     )))
 }
 
-fn parse_cast(type_name: &str, s: &str) -> Result<Value, Error> {
+pub(crate) fn parse_cast(type_name: &str, s: &str) -> Result<Value, Error> {
     if let Some(elem_type) = ElemType::from_dense_type_name(type_name) {
         let floats: Vec<f64> = serde_json::from_str(s)?;
         return Ok(elem_type.from_floats(floats, type_name)?.into_list_value());
@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 use sqlparser::ast::{
     Expr as SqlExpr, Function as SqlFunction, FunctionArg, FunctionArgExpr, FunctionArguments,
 };
 
+use super::SqlExprExt;
 use crate::Error;
 
 pub trait SqlFunctionExt {
@@ -11,6 +14,9 @@ pub trait SqlFunctionExt {
     /// Get the arguments of the function.
     fn args(&self) -> Result<Vec<SqlExpr>, Error>;
 
+    /// Get the positional and named (`name => value`) arguments of the function.
+    fn named_args(&self) -> Result<(Vec<SqlExpr>, HashMap<String, SqlExpr>), Error>;
+
     fn is_count(&self) -> bool;
 
     fn matches_args<F>(&self, check: F) -> bool
@@ -42,6 +48,57 @@ impl SqlFunctionExt for SqlFunction {
         }
     }
 
+    fn named_args(&self) -> Result<(Vec<SqlExpr>, HashMap<String, SqlExpr>), Error> {
+        let list = match &self.args {
+            FunctionArguments::None => return Ok((Vec::new(), HashMap::new())),
+            FunctionArguments::Subquery(_) => {
+                return Err(Error::Unsupported("function call shape".to_string()));
+            }
+            FunctionArguments::List(list) => list,
+        };
+
+        let mut args = Vec::new();
+        let mut kwargs = HashMap::new();
+        for arg in &list.args {
+            let (name, arg) = match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) => {
+                    args.push(e.clone());
+                    continue;
+                }
+                FunctionArg::Named {
+                    name,
+                    arg: FunctionArgExpr::Expr(e),
+                    ..
+                } => (name.value.clone(), e),
+                FunctionArg::ExprNamed {
+                    name,
+                    arg: FunctionArgExpr::Expr(e),
+                    ..
+                } => match name.as_ident() {
+                    Some(name) => (name, e),
+                    None => {
+                        return Err(Error::Unsupported(format!(
+                            "function argument name: {name}"
+                        )));
+                    }
+                },
+                _ => {
+                    return Err(Error::Unsupported(
+                        "wildcard or qualified function argument".to_string(),
+                    ));
+                }
+            };
+            if kwargs
+                .insert(name.to_ascii_lowercase(), arg.clone())
+                .is_some()
+            {
+                return Err(Error::Invalid(format!("duplicate argument `{name}`")));
+            }
+        }
+
+        Ok((args, kwargs))
+    }
+
     fn is_count(&self) -> bool {
         self.name.0.len() == 1
             && self.name.0[0]
@@ -1,12 +1,12 @@
 use std::ops::ControlFlow;
 
 use sqlparser::ast::{
-    AssignmentTarget, Expr as SqlExpr, FromTable, ObjectName, SelectItem, SetExpr,
+    AssignmentTarget, CopySource, Expr as SqlExpr, FromTable, ObjectName, SelectItem, SetExpr,
     Statement as SqlStatement, TableObject, Value as SqlValue, visit_expressions, visit_relations,
 };
 
 use super::{SqlExprExt, TableFactorExt};
-use crate::{Error, Table};
+use crate::{CatalogTable, Error, Table};
 
 pub trait SqlStatementExt {
     fn projection(&self) -> Option<&[SelectItem]>;
@@ -52,6 +52,8 @@ impl SqlStatementExt for SqlStatement {
             SqlStatement::Query(q) => match q.body.as_ref() {
                 SetExpr::Select(s) => {
                     match s.from.first().and_then(|from| from.relation.table_name()) {
+                        // Catalog tables are synthesized client-side, not backed by a collection.
+                        Some(name) if CatalogTable::from_name(name).is_some() => return Ok(None),
                         Some(name) => name,
                         None => return Ok(None),
                     }
@@ -75,6 +77,10 @@ impl SqlStatementExt for SqlStatement {
                     None => return Ok(None),
                 }
             }
+            SqlStatement::Copy {
+                source: CopySource::Table { table_name, .. },
+                ..
+            } => table_name,
             _ => return Ok(None),
         };
 
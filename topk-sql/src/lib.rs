@@ -2,8 +2,18 @@ use std::ops::ControlFlow;
 use std::sync::LazyLock;
 
 use regex::Regex;
-use sqlparser::ast::{self, visit_expressions};
+use sqlparser::ast::{self, Visit, visit_expressions};
 use sqlparser::parser::ParserError;
+use sqlparser::tokenizer::Token;
+use topk_rs::proto::v1::data::stage::filter_stage::FilterExpr;
+
+mod catalog;
+pub use catalog::{Catalog, CatalogExpr, CatalogFilter, CatalogQuery, CatalogScan, CatalogTable};
+
+mod copy;
+pub use copy::{
+    COPY_BATCH_BYTES, COPY_BATCH_SIZE, CopyDecoder, CopyEncoder, CopyError, CopyFormat, CopyOptions,
+};
 
 mod dialect;
 use dialect::TopKDialect;
@@ -13,8 +23,19 @@ pub use ext::{
     ObjectNameExt, SelectItemExt, SqlExprExt, SqlFunctionExt, SqlStatementExt, TableFactorExt,
 };
 
+mod explain;
+pub use explain::{ExplainAnalyze, ExplainFormat};
+
 mod expr;
-pub use expr::Expr;
+pub use expr::{
+    Expr, FUSE_SCORE, Fusion, Normalization, RankExpr, resolve_score_orders, score_order,
+};
+
+mod migration;
+pub use migration::{MigrationPlan, MigrationStep, STAGING_SUFFIX, SchemaChange};
+
+mod schema;
+pub use schema::{create_table_sql, data_type_sql, index_sql};
 
 mod stmt;
 pub use stmt::{RowFilter, Statement, Variable};
@@ -67,32 +88,7 @@ pub fn parse_sql(sql: &str) -> Result<Vec<ast::Statement>, Error> {
     // Validate
     let mut diag = Vec::new();
     for stmt in stmts.iter() {
-        let _: ControlFlow<()> = visit_expressions(stmt, |expr| {
-            // Validate unsupported expressions.
-            match expr {
-                ast::Expr::ILike { .. } => {
-                    diag.push("ILIKE: TopK has no case-insensitive matching primitive");
-                }
-                ast::Expr::IsDistinctFrom(_, _) => {
-                    diag.push("IS DISTINCT FROM: not supported");
-                }
-                ast::Expr::IsNotDistinctFrom(_, _) => {
-                    diag.push("IS NOT DISTINCT FROM: not supported");
-                }
-                ast::Expr::Subquery(_) | ast::Expr::Exists { .. } => {
-                    diag.push("Subqueries are not supported");
-                }
-                ast::Expr::InSubquery { .. } => {
-                    diag.push("IN (SELECT …): not supported");
-                }
-                ast::Expr::InUnnest { .. } => {
-                    diag.push("IN UNNEST(…): not supported");
-                }
-                _ => {}
-            }
-
-            ControlFlow::Continue(())
-        });
+        unsupported_exprs(stmt, &mut diag);
     }
 
     if !diag.is_empty() {
@@ -102,6 +98,63 @@ pub fn parse_sql(sql: &str) -> Result<Vec<ast::Statement>, Error> {
     Ok(stmts)
 }
 
+/// Parse a SQL condition, as in `SELECT … WHERE <condition>`, into filter expressions.
+pub fn parse_filter(sql: &str) -> Result<Vec<FilterExpr>, Error> {
+    parse_condition(sql, &TopKDialect::default())
+}
+
+/// Parse a filter typed on a command line into filter expressions.
+///
+/// Unlike [`parse_filter`], strings may be quoted with `"` (so quoted column names are not
+/// supported) and `starts_with` and `contains` are also infix operators:
+/// `title starts_with "RFC"`.
+pub fn parse_cli_filter(sql: &str) -> Result<Vec<FilterExpr>, Error> {
+    parse_condition(sql, &TopKDialect::filter())
+}
+
+fn parse_condition(sql: &str, dialect: &TopKDialect) -> Result<Vec<FilterExpr>, Error> {
+    let mut parser = sqlparser::parser::Parser::new(dialect).try_with_sql(sql)?;
+    let expr = parser.parse_expr()?;
+    parser.expect_token(&Token::EOF)?;
+
+    let mut diag = Vec::new();
+    unsupported_exprs(&expr, &mut diag);
+    if !diag.is_empty() {
+        return Err(Error::Unsupported(diag.join("; ")));
+    }
+
+    Vec::<FilterExpr>::from_sql(expr)
+}
+
+/// Collects the expressions of `node` that TopK cannot evaluate.
+fn unsupported_exprs<V: Visit>(node: &V, diag: &mut Vec<&'static str>) {
+    let _: ControlFlow<()> = visit_expressions(node, |expr| {
+        match expr {
+            ast::Expr::ILike { .. } => {
+                diag.push("ILIKE: TopK has no case-insensitive matching primitive");
+            }
+            ast::Expr::IsDistinctFrom(_, _) => {
+                diag.push("IS DISTINCT FROM: not supported");
+            }
+            ast::Expr::IsNotDistinctFrom(_, _) => {
+                diag.push("IS NOT DISTINCT FROM: not supported");
+            }
+            ast::Expr::Subquery(_) | ast::Expr::Exists { .. } => {
+                diag.push("Subqueries are not supported");
+            }
+            ast::Expr::InSubquery { .. } => {
+                diag.push("IN (SELECT …): not supported");
+            }
+            ast::Expr::InUnnest { .. } => {
+                diag.push("IN UNNEST(…): not supported");
+            }
+            _ => {}
+        }
+
+        ControlFlow::Continue(())
+    });
+}
+
 /// Convert a parsed SQL batch into typed statements.
 ///
 /// Returns pairs of `(Statement, Option<ast::Statement>)` where the raw SQL is
@@ -151,6 +204,7 @@ fn rewrite_partition_syntax(sql: &str) -> std::borrow::Cow<'_, str> {
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
+    use topk_rs::proto::v1::data::LogicalExpr;
 
     use super::*;
 
@@ -179,6 +233,70 @@ mod tests {
     fn rewrite_partition(#[case] sql: &str, #[case] expected: &str) {
         assert_eq!(rewrite_partition_syntax(sql), expected);
     }
+
+    #[test]
+    fn parse_filter_matches_where() {
+        let Statement::Select { query, .. } = Statement::try_from(
+            parse_sql("SELECT _id FROM t WHERE year >= 2023 AND team IN ('infra', 'ml')")
+                .unwrap()
+                .remove(0),
+        )
+        .unwrap() else {
+            panic!("expected SELECT");
+        };
+        let expected = query
+            .stages
+            .into_iter()
+            .filter_map(|stage| match stage.stage {
+                Some(topk_rs::proto::v1::data::stage::Stage::Filter(filter)) => filter.expr,
+                _ => None,
+            });
+
+        assert_eq!(
+            parse_filter("year >= 2023 AND team IN ('infra', 'ml')").unwrap(),
+            expected.collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parse_filter_quoted_column() {
+        assert_eq!(
+            parse_filter(r#""a b" = 1"#).unwrap(),
+            vec![FilterExpr::logical(LogicalExpr::field("a b").eq(1_i64))]
+        );
+    }
+
+    #[test]
+    fn parse_cli_filter_infix_functions() {
+        assert_eq!(
+            parse_cli_filter(
+                r#"year >= 2023 and team in ("infra","ml") and title starts_with "RFC""#
+            )
+            .unwrap(),
+            parse_filter("year >= 2023 AND team IN ('infra', 'ml') AND starts_with(title, 'RFC')")
+                .unwrap()
+        );
+        assert_eq!(
+            parse_cli_filter(r#"not tags contains "draft" or title starts_with "A""#).unwrap(),
+            parse_filter("NOT contains(tags, 'draft') OR starts_with(title, 'A')").unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("year >= 2023 LIMIT 1")]
+    #[case("year >= 2023; DROP TABLE t")]
+    #[case("title ILIKE 'a%'")]
+    #[case("title starts_with 'A'")]
+    fn parse_filter_rejects(#[case] sql: &str) {
+        assert!(parse_filter(sql).is_err(), "{sql}");
+    }
+
+    #[rstest]
+    #[case("year >= 2023 LIMIT 1")]
+    #[case("title starts_with")]
+    fn parse_cli_filter_rejects(#[case] sql: &str) {
+        assert!(parse_cli_filter(sql).is_err(), "{sql}");
+    }
 }
 
 #[macro_export]
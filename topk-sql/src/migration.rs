@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use topk_rs::proto::v1::control::FieldSpec;
+
+use crate::schema::{column_sql, create_table_sql, quote_ident};
+use crate::{Error, sql_invalid};
+
+/// Suffix of the staging collection used to rebuild a collection with a new schema.
+pub const STAGING_SUFFIX: &str = "__migration";
+
+/// Single `ALTER TABLE` operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaChange {
+    /// `ADD COLUMN [IF NOT EXISTS] <column_def>`
+    AddColumn {
+        name: String,
+        spec: FieldSpec,
+        if_not_exists: bool,
+    },
+    /// `DROP COLUMN [IF EXISTS] <name>`
+    DropColumn { name: String, if_exists: bool },
+}
+
+/// Step of a `MigrationPlan`, executed in order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationStep {
+    /// Create `table` with `schema`.
+    CreateTable {
+        table: String,
+        schema: HashMap<String, FieldSpec>,
+    },
+    /// Read every document of `from` and upsert it into `to`, removing `drop_fields`.
+    CopyDocuments {
+        from: String,
+        to: String,
+        drop_fields: Vec<String>,
+    },
+    /// Check that `to` has as many documents as `from`, stopping the migration otherwise.
+    VerifyCount { from: String, to: String },
+    /// Drop `table` and all of its documents.
+    DropTable { table: String },
+}
+
+/// Plan for applying `ALTER TABLE` to a collection.
+///
+/// Collection schemas are immutable, so a schema change rebuilds the collection through a
+/// staging collection: the documents are copied (and validated) into the new schema, and the
+/// original collection is only dropped and recreated once the copy has been verified. The
+/// staging collection is only dropped once the copy back has been verified too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationPlan {
+    /// Collection being altered.
+    pub table: String,
+    /// Schema of the collection after the migration.
+    pub schema: HashMap<String, FieldSpec>,
+    /// Steps to execute, in order. Empty if the changes are no-ops.
+    pub steps: Vec<MigrationStep>,
+    /// Explanation of the consequences of each change.
+    pub notes: Vec<String>,
+}
+
+impl MigrationPlan {
+    /// Plans `changes` against the `current` schema of `table`.
+    pub fn new(
+        table: &str,
+        current: &HashMap<String, FieldSpec>,
+        changes: &[SchemaChange],
+    ) -> Result<Self, Error> {
+        let mut schema = current.clone();
+        let mut drop_fields = Vec::new();
+        let mut notes = Vec::new();
+
+        for change in changes {
+            match change {
+                SchemaChange::AddColumn {
+                    name,
+                    spec,
+                    if_not_exists,
+                } => {
+                    sql_invalid!(
+                        name == "_id",
+                        "column `_id` is implicit and cannot be added"
+                    );
+                    if schema.contains_key(name) {
+                        sql_invalid!(!if_not_exists, "column `{name}` already exists");
+                        notes.push(format!("`{name}` already exists, skipping."));
+                        continue;
+                    }
+                    if spec.required {
+                        notes.push(format!(
+                            "`{name}` is NOT NULL: copying fails for documents without a value \
+                             for `{name}`, before `{table}` is dropped."
+                        ));
+                    }
+                    if spec.index.is_some() {
+                        notes.push(format!(
+                            "`{name}` is indexed: the index is built as documents are copied."
+                        ));
+                    } else if !spec.required {
+                        notes.push(format!(
+                            "`{name}` is nullable and not indexed: undeclared fields can already \
+                             be written, declaring it only adds type checking."
+                        ));
+                    }
+                    schema.insert(name.clone(), spec.clone());
+                }
+                SchemaChange::DropColumn { name, if_exists } => {
+                    sql_invalid!(name == "_id", "column `_id` cannot be dropped");
+                    if schema.remove(name).is_none() {
+                        sql_invalid!(!if_exists, "column `{name}` does not exist");
+                        notes.push(format!("`{name}` does not exist, skipping."));
+                        continue;
+                    }
+                    notes.push(format!(
+                        "`{name}` is removed from every document, along with its index."
+                    ));
+                    drop_fields.push(name.clone());
+                }
+            }
+        }
+
+        if &schema == current {
+            return Ok(Self {
+                table: table.to_string(),
+                schema,
+                steps: vec![],
+                notes,
+            });
+        }
+
+        // Fail before any step runs if the new schema cannot be recreated.
+        create_table_sql(table, &schema)?;
+
+        let staging = format!("{table}{STAGING_SUFFIX}");
+        notes.push(format!(
+            "`{table}` is unavailable from steps 4 to 6. Pause writes to `{table}` during the \
+             migration: documents inserted or deleted after step 2 starts fail step 3, but \
+             updates are not detected."
+        ));
+        notes.push(format!(
+            "To roll back before step 4, drop `{staging}`: `{table}` is unchanged. From step 4 \
+             on, `{staging}` holds every document: finish from step 5 and keep `{staging}` \
+             until step 7 passes."
+        ));
+
+        let steps = vec![
+            MigrationStep::CreateTable {
+                table: staging.clone(),
+                schema: schema.clone(),
+            },
+            MigrationStep::CopyDocuments {
+                from: table.to_string(),
+                to: staging.clone(),
+                drop_fields,
+            },
+            MigrationStep::VerifyCount {
+                from: table.to_string(),
+                to: staging.clone(),
+            },
+            MigrationStep::DropTable {
+                table: table.to_string(),
+            },
+            MigrationStep::CreateTable {
+                table: table.to_string(),
+                schema: schema.clone(),
+            },
+            MigrationStep::CopyDocuments {
+                from: staging.clone(),
+                to: table.to_string(),
+                drop_fields: vec![],
+            },
+            MigrationStep::VerifyCount {
+                from: staging.clone(),
+                to: table.to_string(),
+            },
+            MigrationStep::DropTable { table: staging },
+        ];
+
+        Ok(Self {
+            table: table.to_string(),
+            schema,
+            steps,
+            notes,
+        })
+    }
+}
+
+impl MigrationStep {
+    /// SQL equivalent of the step, if it has one.
+    ///
+    /// Schemas of planned steps always render, see `MigrationPlan::new`.
+    pub fn sql(&self) -> Option<String> {
+        match self {
+            MigrationStep::CreateTable { table, schema } => create_table_sql(table, schema).ok(),
+            MigrationStep::CopyDocuments { .. } => None,
+            MigrationStep::VerifyCount { from, to } => Some(format!(
+                "SELECT COUNT(*) FROM {};\nSELECT COUNT(*) FROM {};",
+                quote_ident(from),
+                quote_ident(to)
+            )),
+            MigrationStep::DropTable { table } => {
+                Some(format!("DROP TABLE {};", quote_ident(table)))
+            }
+        }
+    }
+}
+
+impl Display for MigrationStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationStep::CreateTable { table, .. } => {
+                write!(f, "create `{table}` with the new schema")
+            }
+            MigrationStep::CopyDocuments {
+                from,
+                to,
+                drop_fields,
+            } => {
+                write!(f, "copy all documents from `{from}` into `{to}`")?;
+                if !drop_fields.is_empty() {
+                    let fields = drop_fields
+                        .iter()
+                        .map(|field| format!("`{field}`"))
+                        .collect::<Vec<_>>();
+                    write!(f, ", removing {}", fields.join(", "))?;
+                }
+                Ok(())
+            }
+            MigrationStep::VerifyCount { from, to } => {
+                write!(f, "check that `{to}` has as many documents as `{from}`")
+            }
+            MigrationStep::DropTable { table } => write!(f, "drop `{table}`"),
+        }
+    }
+}
+
+impl Display for MigrationPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.steps.is_empty() {
+            writeln!(f, "-- `{}` is unchanged, nothing to do.", self.table)?;
+        }
+        for note in &self.notes {
+            writeln!(f, "-- {note}")?;
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "-- Step {}: {step}", i + 1)?;
+            if let Some(sql) = step.sql() {
+                writeln!(f, "{sql}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaChange::AddColumn {
+                name,
+                spec,
+                if_not_exists,
+            } => {
+                let if_not_exists = if *if_not_exists { "IF NOT EXISTS " } else { "" };
+                let column = column_sql(name, spec).expect("parsed columns have known data types");
+                write!(f, "ADD COLUMN {if_not_exists}{column}")
+            }
+            SchemaChange::DropColumn { name, if_exists } => {
+                let if_exists = if *if_exists { "IF EXISTS " } else { "" };
+                write!(f, "DROP COLUMN {if_exists}{}", quote_ident(name))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use topk_rs::proto::v1::control::{FieldIndex, VectorDistanceMetric};
+
+    use super::*;
+
+    fn schema() -> HashMap<String, FieldSpec> {
+        [
+            ("title".to_string(), FieldSpec::text(true)),
+            ("year".to_string(), FieldSpec::integer(false)),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn add_and_drop_rebuild_through_staging() {
+        let plan = MigrationPlan::new(
+            "books",
+            &schema(),
+            &[
+                SchemaChange::AddColumn {
+                    name: "embedding".to_string(),
+                    spec: FieldSpec::f32_vector(4, false)
+                        .with_index(FieldIndex::vector(VectorDistanceMetric::Cosine)),
+                    if_not_exists: false,
+                },
+                SchemaChange::DropColumn {
+                    name: "year".to_string(),
+                    if_exists: false,
+                },
+            ],
+        )
+        .unwrap();
+
+        let mut columns = plan.schema.keys().cloned().collect::<Vec<_>>();
+        columns.sort();
+        assert_eq!(columns, ["embedding", "title"]);
+        assert_eq!(plan.steps.len(), 8);
+        assert_eq!(
+            plan.steps[1],
+            MigrationStep::CopyDocuments {
+                from: "books".to_string(),
+                to: "books__migration".to_string(),
+                drop_fields: vec!["year".to_string()],
+            }
+        );
+        assert_eq!(
+            plan.steps[2],
+            MigrationStep::VerifyCount {
+                from: "books".to_string(),
+                to: "books__migration".to_string(),
+            }
+        );
+        assert_eq!(
+            plan.steps[3],
+            MigrationStep::DropTable {
+                table: "books".to_string()
+            }
+        );
+        assert_eq!(
+            plan.steps[6],
+            MigrationStep::VerifyCount {
+                from: "books__migration".to_string(),
+                to: "books".to_string(),
+            }
+        );
+        assert_eq!(
+            plan.steps[7],
+            MigrationStep::DropTable {
+                table: "books__migration".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn noop_changes_have_no_steps() {
+        let plan = MigrationPlan::new(
+            "books",
+            &schema(),
+            &[
+                SchemaChange::AddColumn {
+                    name: "title".to_string(),
+                    spec: FieldSpec::text(true),
+                    if_not_exists: true,
+                },
+                SchemaChange::DropColumn {
+                    name: "missing".to_string(),
+                    if_exists: true,
+                },
+            ],
+        )
+        .unwrap();
+
+        assert!(plan.steps.is_empty());
+        assert!(plan.to_string().contains("nothing to do"));
+    }
+
+    #[test]
+    fn unknown_data_type_cannot_be_recreated() {
+        let mut current = schema();
+        current.insert(
+            "legacy".to_string(),
+            FieldSpec {
+                data_type: None,
+                required: false,
+                index: None,
+            },
+        );
+        let add = SchemaChange::AddColumn {
+            name: "isbn".to_string(),
+            spec: FieldSpec::text(false),
+            if_not_exists: false,
+        };
+        let err = MigrationPlan::new("books", &current, &[add]).unwrap_err();
+        assert!(err.to_string().contains("`legacy`"), "{err}");
+
+        let drop = SchemaChange::DropColumn {
+            name: "legacy".to_string(),
+            if_exists: false,
+        };
+        let plan = MigrationPlan::new("books", &current, &[drop]).unwrap();
+        assert_eq!(plan.steps.len(), 8);
+    }
+
+    #[test]
+    fn invalid_changes() {
+        let err = MigrationPlan::new(
+            "books",
+            &schema(),
+            &[SchemaChange::DropColumn {
+                name: "missing".to_string(),
+                if_exists: false,
+            }],
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Invalid: column `missing` does not exist");
+
+        let err = MigrationPlan::new(
+            "books",
+            &schema(),
+            &[SchemaChange::AddColumn {
+                name: "title".to_string(),
+                spec: FieldSpec::text(false),
+                if_not_exists: false,
+            }],
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Invalid: column `title` already exists");
+    }
+}
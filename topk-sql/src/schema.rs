@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use sqlparser::keywords::ALL_KEYWORDS;
+use topk_rs::proto::v1::control::{
+    FieldIndex, FieldSpec, KeywordIndexType, MultiVectorDistanceMetric, MultiVectorQuantization,
+    VectorDistanceMetric, field_index::Index, field_type::DataType, field_type_list::ListValueType,
+    field_type_matrix::MatrixValueType,
+};
+
+use crate::{Error, sql_unsupported};
+
+/// PostgreSQL type a TopK field is exposed as over the wire and in the catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PgType {
+    /// `pg_type.oid`
+    pub oid: i64,
+    /// `pg_type.typname` (eg. `int8`, `_float4`)
+    pub name: &'static str,
+    /// `information_schema.columns.data_type` (eg. `bigint`, `real[]`)
+    pub sql: &'static str,
+}
+
+pub(crate) const PG_BOOL: PgType = PgType {
+    oid: 16,
+    name: "bool",
+    sql: "boolean",
+};
+pub(crate) const PG_BYTEA: PgType = PgType {
+    oid: 17,
+    name: "bytea",
+    sql: "bytea",
+};
+pub(crate) const PG_INT8: PgType = PgType {
+    oid: 20,
+    name: "int8",
+    sql: "bigint",
+};
+pub(crate) const PG_TEXT: PgType = PgType {
+    oid: 25,
+    name: "text",
+    sql: "text",
+};
+pub(crate) const PG_FLOAT8: PgType = PgType {
+    oid: 701,
+    name: "float8",
+    sql: "double precision",
+};
+pub(crate) const PG_FLOAT4_ARRAY: PgType = PgType {
+    oid: 1021,
+    name: "_float4",
+    sql: "real[]",
+};
+pub(crate) const PG_JSONB: PgType = PgType {
+    oid: 3802,
+    name: "jsonb",
+    sql: "jsonb",
+};
+
+/// Maps a field to the PostgreSQL type its values are returned as.
+pub(crate) fn pg_type(spec: &FieldSpec) -> PgType {
+    let Some(data_type) = spec.data_type.as_ref().and_then(|t| t.data_type.as_ref()) else {
+        return PG_JSONB;
+    };
+
+    match data_type {
+        DataType::Text(_) => PG_TEXT,
+        // Timestamps are returned as milliseconds since UNIX epoch.
+        DataType::Integer(_) | DataType::Timestamp(_) => PG_INT8,
+        DataType::Float(_) => PG_FLOAT8,
+        DataType::Boolean(_) => PG_BOOL,
+        DataType::Bytes(_) => PG_BYTEA,
+        DataType::F32Vector(_)
+        | DataType::F16Vector(_)
+        | DataType::F8Vector(_)
+        | DataType::U8Vector(_)
+        | DataType::I8Vector(_)
+        | DataType::BinaryVector(_) => PG_FLOAT4_ARRAY,
+        DataType::F32SparseVector(_)
+        | DataType::F16SparseVector(_)
+        | DataType::F8SparseVector(_)
+        | DataType::U8SparseVector(_)
+        | DataType::I8SparseVector(_)
+        | DataType::List(_)
+        | DataType::Struct(_)
+        | DataType::Matrix(_) => PG_JSONB,
+    }
+}
+
+/// Renders an index as the `INDEX …` clause method accepted by `CREATE TABLE`
+/// (eg. `vector_index(metric = 'cosine')`).
+pub fn index_sql(index: &FieldIndex) -> Option<String> {
+    let sql = match index.index.as_ref()? {
+        Index::KeywordIndex(keyword) => match keyword.index_type() {
+            KeywordIndexType::Exact => "keyword_index(type = 'exact')".to_string(),
+            KeywordIndexType::Text | KeywordIndexType::Unspecified => "keyword_index()".to_string(),
+        },
+        Index::VectorIndex(vector) => {
+            let metric = match vector.metric() {
+                VectorDistanceMetric::Cosine => "cosine",
+                VectorDistanceMetric::Euclidean => "euclidean",
+                VectorDistanceMetric::DotProduct => "dot_product",
+                VectorDistanceMetric::Hamming => "hamming",
+                VectorDistanceMetric::Unspecified => "unspecified",
+            };
+            match vector.exact {
+                Some(exact) => format!("vector_index(metric = '{metric}', exact = '{exact}')"),
+                None => format!("vector_index(metric = '{metric}')"),
+            }
+        }
+        Index::SemanticIndex(_) => "semantic_index()".to_string(),
+        Index::NgramIndex(_) => "ngram_index()".to_string(),
+        Index::MultiVectorIndex(multi) => {
+            let metric = match multi.metric() {
+                MultiVectorDistanceMetric::Maxsim => "maxsim",
+                MultiVectorDistanceMetric::Unspecified => "unspecified",
+            };
+            let mut options = vec![format!("metric = '{metric}'")];
+            if multi.quantization.is_some() {
+                let quantization = match multi.quantization() {
+                    MultiVectorQuantization::Binary1bit => "1bit",
+                    MultiVectorQuantization::Binary2bit => "2bit",
+                    MultiVectorQuantization::Scalar => "scalar",
+                    MultiVectorQuantization::Unspecified => "unspecified",
+                };
+                options.push(format!("quantization = '{quantization}'"));
+            }
+            if let Some(width) = multi.width {
+                options.push(format!("width = '{width}'"));
+            }
+            if let Some(top_k) = multi.top_k {
+                options.push(format!("top_k = '{top_k}'"));
+            }
+            if multi.skip_smve {
+                options.push("skip_smve = 'true'".to_string());
+            }
+            format!("multi_vector_index({})", options.join(", "))
+        }
+    };
+
+    Some(sql)
+}
+
+/// Renders a collection schema as a `CREATE TABLE` statement that parses back into the same
+/// schema. Columns are sorted by name; `_id` is implicit and never rendered.
+///
+/// Fails if a column has a data type unknown to this client, which has no SQL equivalent.
+pub fn create_table_sql(table: &str, schema: &HashMap<String, FieldSpec>) -> Result<String, Error> {
+    let mut columns = schema.iter().collect::<Vec<_>>();
+    columns.sort_by(|a, b| a.0.cmp(b.0));
+
+    let columns = columns
+        .into_iter()
+        .map(|(name, spec)| Ok(format!("    {}", column_sql(name, spec)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(format!(
+        "CREATE TABLE {} (\n{}\n);",
+        quote_ident(table),
+        columns.join(",\n")
+    ))
+}
+
+/// Renders a column definition, eg. `title TEXT NOT NULL INDEX keyword_index()`.
+pub(crate) fn column_sql(name: &str, spec: &FieldSpec) -> Result<String, Error> {
+    sql_unsupported!(
+        spec.data_type
+            .as_ref()
+            .and_then(|t| t.data_type.as_ref())
+            .is_none(),
+        "column `{name}` has a data type unknown to this client"
+    );
+
+    let mut sql = format!("{} {}", quote_ident(name), data_type_sql(spec)?);
+    if spec.required {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(index) = spec.index.as_ref().and_then(index_sql) {
+        sql.push_str(" INDEX ");
+        sql.push_str(&index);
+    }
+    Ok(sql)
+}
+
+/// Renders a column type as accepted by `CREATE TABLE` (eg. `TEXT[]`, `f32_vector(768)`).
+///
+/// Fails if the data type is unset, ie. unknown to this client.
+pub fn data_type_sql(spec: &FieldSpec) -> Result<String, Error> {
+    let Some(data_type) = spec.data_type.as_ref().and_then(|t| t.data_type.as_ref()) else {
+        sql_unsupported!("data type unknown to this client");
+    };
+
+    let sql = match data_type {
+        DataType::Text(_) => "TEXT".to_string(),
+        DataType::Integer(_) => "BIGINT".to_string(),
+        DataType::Float(_) => "DOUBLE PRECISION".to_string(),
+        DataType::Boolean(_) => "BOOLEAN".to_string(),
+        DataType::Bytes(_) => "BYTEA".to_string(),
+        DataType::Timestamp(_) => "TIMESTAMP".to_string(),
+        DataType::List(list) => match list.value_type() {
+            ListValueType::String => "TEXT[]".to_string(),
+            ListValueType::Integer => "BIGINT[]".to_string(),
+            ListValueType::Float => "DOUBLE PRECISION[]".to_string(),
+            ListValueType::Unspecified => "JSONB[]".to_string(),
+        },
+        DataType::F32Vector(v) => format!("f32_vector({})", v.dimension),
+        DataType::F16Vector(v) => format!("f16_vector({})", v.dimension),
+        DataType::F8Vector(v) => format!("f8_vector({})", v.dimension),
+        DataType::U8Vector(v) => format!("u8_vector({})", v.dimension),
+        DataType::I8Vector(v) => format!("i8_vector({})", v.dimension),
+        DataType::BinaryVector(v) => format!("binary_vector({})", v.dimension),
+        DataType::F32SparseVector(_) => "f32_sparse_vector".to_string(),
+        DataType::F16SparseVector(_) => "f16_sparse_vector".to_string(),
+        DataType::F8SparseVector(_) => "f8_sparse_vector".to_string(),
+        DataType::U8SparseVector(_) => "u8_sparse_vector".to_string(),
+        DataType::I8SparseVector(_) => "i8_sparse_vector".to_string(),
+        DataType::Matrix(m) => {
+            let value_type = match m.value_type() {
+                MatrixValueType::F32 | MatrixValueType::Unspecified => "f32",
+                MatrixValueType::F16 => "f16",
+                MatrixValueType::F8 => "f8",
+                MatrixValueType::U8 => "u8",
+                MatrixValueType::I8 => "i8",
+            };
+            format!("{value_type}_matrix({})", m.dimension)
+        }
+        DataType::Struct(s) if s.fields.is_empty() => "JSONB".to_string(),
+        // Struct fields are nested column definitions, each passed as a string literal.
+        DataType::Struct(s) => {
+            let mut fields = s.fields.iter().collect::<Vec<_>>();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            let fields = fields
+                .into_iter()
+                .map(|(name, spec)| {
+                    Ok(format!("'{}'", column_sql(name, spec)?.replace('\'', "''")))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            format!("struct({})", fields.join(", "))
+        }
+    };
+    Ok(sql)
+}
+
+/// Quotes an identifier unless it is a lowercase, non-keyword name.
+pub(crate) fn quote_ident(name: &str) -> String {
+    let simple = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if simple && !ALL_KEYWORDS.contains(&name.to_ascii_uppercase().as_str()) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
@@ -0,0 +1,66 @@
+use sqlparser::ast::{AlterTable, AlterTableOperation};
+use topk_rs::proto::v1::control::FieldSpec;
+
+use crate::{Error, FromSql, SchemaChange, Statement, Table, sql_invalid, sql_unsupported};
+
+impl TryFrom<AlterTable> for Statement {
+    type Error = Error;
+
+    fn try_from(alter: AlterTable) -> Result<Statement, Error> {
+        sql_unsupported!(alter.table_type.is_some(), "ALTER {} TABLE", alter.name);
+        sql_unsupported!(alter.on_cluster.is_some(), "ALTER TABLE … ON CLUSTER");
+        sql_unsupported!(alter.location.is_some(), "ALTER TABLE … SET LOCATION");
+
+        let table = Table::new(alter.name)?;
+        sql_invalid!(
+            !matches!(table, Table::Collection(_)),
+            "ALTER TABLE requires a collection name"
+        );
+
+        let mut changes = Vec::with_capacity(alter.operations.len());
+        for operation in alter.operations {
+            match operation {
+                AlterTableOperation::AddColumn {
+                    if_not_exists,
+                    column_def,
+                    column_position,
+                    ..
+                } => {
+                    sql_unsupported!(
+                        column_position.is_some(),
+                        "ADD COLUMN … FIRST/AFTER (columns are unordered)"
+                    );
+                    let name = column_def.name.value.clone();
+                    changes.push(SchemaChange::AddColumn {
+                        name,
+                        spec: FieldSpec::from_sql(column_def)?,
+                        if_not_exists,
+                    });
+                }
+                AlterTableOperation::DropColumn {
+                    column_names,
+                    if_exists,
+                    drop_behavior,
+                    ..
+                } => {
+                    sql_unsupported!(drop_behavior.is_some(), "DROP COLUMN … CASCADE/RESTRICT");
+                    changes.extend(
+                        column_names
+                            .into_iter()
+                            .map(|name| SchemaChange::DropColumn {
+                                name: name.value,
+                                if_exists,
+                            }),
+                    );
+                }
+                other => sql_unsupported!("ALTER TABLE operation: {other}"),
+            }
+        }
+
+        Ok(Statement::AlterTable {
+            table,
+            changes,
+            if_exists: alter.if_exists,
+        })
+    }
+}
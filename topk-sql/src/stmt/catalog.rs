@@ -0,0 +1,470 @@
+use regex::Regex;
+use sqlparser::ast::{
+    BinaryOperator, Expr as SqlExpr, Function, JoinConstraint, JoinOperator, LimitClause, OrderBy,
+    OrderByKind, SelectItem, SelectItemQualifiedWildcardKind, TableFactor, TableWithJoins,
+    Value as SqlValue,
+};
+use topk_rs::proto::v1::data::Value;
+
+use crate::{
+    CatalogExpr, CatalogFilter, CatalogQuery, CatalogScan, CatalogTable, Error, FromSql,
+    SqlExprExt, Statement, sql_invalid, sql_unsupported,
+};
+
+/// Lowers a `SELECT` over `information_schema` / `pg_catalog` tables into a `CatalogQuery`,
+/// which is evaluated client-side against rows generated from the collection schemas.
+pub(super) fn lower(
+    from: TableWithJoins,
+    projection: Vec<SelectItem>,
+    selection: Option<SqlExpr>,
+    group_by: Vec<SqlExpr>,
+    order_by: Option<OrderBy>,
+    limit_clause: Option<LimitClause>,
+) -> Result<Statement, Error> {
+    let mut scans = vec![scan(from.relation)?];
+    sql_unsupported!(!group_by.is_empty(), "GROUP BY on {}", names(&scans));
+
+    for join in from.joins {
+        let (constraint, left) = match join.join_operator {
+            JoinOperator::Join(constraint) | JoinOperator::Inner(constraint) => (constraint, false),
+            JoinOperator::Left(constraint) | JoinOperator::LeftOuter(constraint) => {
+                (constraint, true)
+            }
+            _ => sql_unsupported!(
+                "JOIN other than [INNER] JOIN and LEFT [OUTER] JOIN on catalog tables"
+            ),
+        };
+        let JoinConstraint::On(on) = constraint else {
+            sql_unsupported!("JOIN without ON on catalog tables");
+        };
+
+        let joined = scan(join.relation)?;
+        sql_invalid!(
+            scans.iter().any(|scan| scan.alias == joined.alias),
+            "table name `{}` specified more than once",
+            joined.alias
+        );
+        scans.push(joined);
+
+        let mut keys = Vec::new();
+        lower_join_keys(&scans, on, &mut keys)?;
+        let joined = scans.last_mut().expect("joined table was pushed");
+        joined.on = keys;
+        joined.left = left;
+    }
+
+    let mut columns = Vec::with_capacity(projection.len());
+    for item in projection {
+        match item {
+            SelectItem::Wildcard(_) => {
+                for scan in &scans {
+                    columns.extend(wildcard(scan));
+                }
+            }
+            SelectItem::QualifiedWildcard(SelectItemQualifiedWildcardKind::ObjectName(name), _) => {
+                let qualifier = name
+                    .0
+                    .last()
+                    .and_then(|part| part.as_ident())
+                    .map(|ident| ident.value.to_ascii_lowercase())
+                    .unwrap_or_default();
+                let scan = find_scan(&scans, &qualifier)?;
+                columns.extend(wildcard(scan));
+            }
+            SelectItem::QualifiedWildcard(kind, _) => sql_unsupported!("SELECT {kind}.*"),
+            SelectItem::UnnamedExpr(expr) => {
+                columns.push((output_name(&expr), lower_expr(&scans, &expr)?));
+            }
+            SelectItem::ExprWithAlias { expr, alias } => {
+                columns.push((alias.value, lower_expr(&scans, &expr)?));
+            }
+        }
+    }
+
+    let mut filters = Vec::new();
+    if let Some(selection) = selection {
+        lower_filter(&scans, selection, &mut filters)?;
+    }
+
+    let mut sort = Vec::new();
+    if let Some(order_by) = order_by {
+        let OrderByKind::Expressions(exprs) = order_by.kind else {
+            sql_unsupported!("ORDER BY ALL");
+        };
+        for entry in exprs {
+            let key = match &entry.expr {
+                SqlExpr::Value(v) if matches!(v.value, SqlValue::Number(_, _)) => {
+                    let position = entry.expr.as_u64().unwrap_or_default() as usize;
+                    match position.checked_sub(1).and_then(|i| columns.get(i)) {
+                        Some((_, expr)) => expr.clone(),
+                        None => sql_invalid!("ORDER BY position {position} is not in select list"),
+                    }
+                }
+                // Output aliases take precedence over source columns, as in PostgreSQL.
+                expr => match expr.as_ident().and_then(|name| {
+                    columns
+                        .iter()
+                        .find(|(alias, _)| alias.eq_ignore_ascii_case(&name))
+                }) {
+                    Some((_, expr)) => expr.clone(),
+                    None => CatalogExpr::Column(resolve_column(&scans, expr)?),
+                },
+            };
+            sort.push((key, entry.options.asc.unwrap_or(true)));
+        }
+    }
+
+    let (limit, offset) = match limit_clause {
+        Some(LimitClause::LimitOffset { limit, offset, .. }) => {
+            let limit = limit
+                .map(|expr| {
+                    expr.as_u64().ok_or_else(|| {
+                        Error::Invalid("LIMIT must be a positive integer".to_string())
+                    })
+                })
+                .transpose()?;
+            let offset = offset
+                .map(|o| {
+                    o.value.as_u64().ok_or_else(|| {
+                        Error::Invalid("OFFSET must be a positive integer".to_string())
+                    })
+                })
+                .transpose()?;
+            (limit, offset)
+        }
+        Some(LimitClause::OffsetCommaLimit { .. }) => sql_unsupported!("LIMIT offset, limit"),
+        None => (None, None),
+    };
+
+    Ok(Statement::Catalog {
+        query: CatalogQuery {
+            from: scans,
+            projection: columns,
+            filters,
+            order_by: sort,
+            offset,
+            limit,
+        },
+    })
+}
+
+/// Resolves a `FROM` or `JOIN` relation to a catalog table.
+fn scan(relation: TableFactor) -> Result<CatalogScan, Error> {
+    let TableFactor::Table {
+        name, alias, args, ..
+    } = relation
+    else {
+        sql_unsupported!("JOIN of catalog tables with {relation}");
+    };
+    sql_unsupported!(args.is_some(), "table-valued function in FROM");
+    let Some(table) = CatalogTable::from_name(&name) else {
+        sql_unsupported!("JOIN of catalog tables with {name}");
+    };
+
+    let alias = match alias {
+        Some(alias) => {
+            sql_unsupported!(
+                !alias.columns.is_empty(),
+                "column aliases on {}",
+                table.name()
+            );
+            alias.name.value.to_ascii_lowercase()
+        }
+        None => <&'static str>::from(table).to_string(),
+    };
+
+    Ok(CatalogScan {
+        table,
+        alias,
+        on: vec![],
+        left: false,
+    })
+}
+
+fn find_scan<'a>(scans: &'a [CatalogScan], qualifier: &str) -> Result<&'a CatalogScan, Error> {
+    match scans.iter().find(|scan| scan.alias == qualifier) {
+        Some(scan) => Ok(scan),
+        None => sql_invalid!("missing FROM-clause entry for table `{qualifier}`"),
+    }
+}
+
+/// Qualified names of the scanned tables, for error messages.
+fn names(scans: &[CatalogScan]) -> String {
+    scans
+        .iter()
+        .map(|scan| scan.table.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn wildcard(scan: &CatalogScan) -> impl Iterator<Item = (String, CatalogExpr)> + '_ {
+    scan.table.columns().iter().map(|column| {
+        (
+            column.to_string(),
+            CatalogExpr::Column(format!("{}.{column}", scan.alias)),
+        )
+    })
+}
+
+/// Resolves a (possibly qualified) column reference to the qualified column of a scanned table.
+fn resolve_column(scans: &[CatalogScan], expr: &SqlExpr) -> Result<String, Error> {
+    let (scan, column) = match expr {
+        SqlExpr::Identifier(ident) => {
+            let column = ident.value.to_ascii_lowercase();
+            let mut owners = scans
+                .iter()
+                .filter(|scan| scan.table.columns().contains(&column.as_str()));
+            match (owners.next(), owners.next()) {
+                (Some(scan), None) => (scan, column),
+                (Some(_), Some(_)) => sql_invalid!("column reference `{column}` is ambiguous"),
+                (None, _) => {
+                    let tables = names(scans);
+                    sql_invalid!("column `{column}` does not exist in {tables}")
+                }
+            }
+        }
+        SqlExpr::CompoundIdentifier(parts) if parts.len() >= 2 => {
+            let qualifier = parts[parts.len() - 2].value.to_ascii_lowercase();
+            let column = parts[parts.len() - 1].value.to_ascii_lowercase();
+            let scan = find_scan(scans, &qualifier)?;
+            sql_invalid!(
+                !scan.table.columns().contains(&column.as_str()),
+                "column `{column}` does not exist in {}",
+                scan.table.name()
+            );
+            (scan, column)
+        }
+        SqlExpr::Nested(inner) => return resolve_column(scans, inner),
+        other => {
+            let tables = names(scans);
+            sql_unsupported!("expression on {tables}: {other}")
+        }
+    };
+    Ok(format!("{}.{column}", scan.alias))
+}
+
+/// Lowers an `ON` clause into equi-join keys between the last scanned table and the preceding
+/// ones.
+fn lower_join_keys(
+    scans: &[CatalogScan],
+    expr: SqlExpr,
+    keys: &mut Vec<(String, String)>,
+) -> Result<(), Error> {
+    match expr {
+        SqlExpr::Nested(inner) => lower_join_keys(scans, *inner, keys)?,
+        SqlExpr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            lower_join_keys(scans, *left, keys)?;
+            lower_join_keys(scans, *right, keys)?;
+        }
+        SqlExpr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq,
+            right,
+        } if is_column(&left) && is_column(&right) => {
+            let left = resolve_column(scans, &left)?;
+            let right = resolve_column(scans, &right)?;
+            let joined = format!("{}.", scans.last().expect("joined table").alias);
+            sql_unsupported!(
+                left.starts_with(&joined) == right.starts_with(&joined),
+                "join condition `{left} = {right}`: only equalities between the joined table and a preceding one are supported"
+            );
+            keys.push((left, right));
+        }
+        other => sql_unsupported!(
+            "join condition `{other}`: only equi-joins are supported on catalog tables"
+        ),
+    }
+
+    Ok(())
+}
+
+fn is_column(expr: &SqlExpr) -> bool {
+    match expr {
+        SqlExpr::Identifier(_) | SqlExpr::CompoundIdentifier(_) => true,
+        SqlExpr::Nested(inner) => is_column(inner),
+        _ => false,
+    }
+}
+
+/// Lowers a select list expression: a column, a literal, a simple `CASE` over a column, or
+/// `pg_get_userbyid`.
+fn lower_expr(scans: &[CatalogScan], expr: &SqlExpr) -> Result<CatalogExpr, Error> {
+    match expr {
+        SqlExpr::Value(_) => Ok(CatalogExpr::Literal(Value::from_sql(expr.clone())?)),
+        SqlExpr::Case {
+            operand: Some(operand),
+            conditions,
+            else_result,
+            ..
+        } => Ok(CatalogExpr::Case {
+            column: resolve_column(scans, operand)?,
+            branches: conditions
+                .iter()
+                .map(|when| {
+                    Ok((
+                        Value::from_sql(when.condition.clone())?,
+                        Value::from_sql(when.result.clone())?,
+                    ))
+                })
+                .collect::<Result<_, Error>>()?,
+            default: else_result
+                .as_deref()
+                .map(|expr| Value::from_sql(expr.clone()))
+                .transpose()?
+                .unwrap_or_else(Value::null),
+        }),
+        SqlExpr::Case { operand: None, .. } => {
+            let tables = names(scans);
+            sql_unsupported!("CASE without operand on {tables}")
+        }
+        // Every catalog object is owned by the same role, see `OWNER_OID`.
+        SqlExpr::Function(f) if function_name(f).as_deref() == Some("pg_get_userbyid") => {
+            Ok(CatalogExpr::Literal(Value::string("topk")))
+        }
+        SqlExpr::Nested(inner) => lower_expr(scans, inner),
+        expr => Ok(CatalogExpr::Column(resolve_column(scans, expr)?)),
+    }
+}
+
+/// Output name of an unaliased select list expression, as PostgreSQL names it.
+fn output_name(expr: &SqlExpr) -> String {
+    match expr {
+        SqlExpr::Identifier(ident) => ident.value.to_ascii_lowercase(),
+        SqlExpr::CompoundIdentifier(parts) => parts
+            .last()
+            .map(|p| p.value.to_ascii_lowercase())
+            .unwrap_or_default(),
+        SqlExpr::Function(f) => function_name(f).unwrap_or_default(),
+        SqlExpr::Case { .. } => "case".to_string(),
+        SqlExpr::Nested(inner) => output_name(inner),
+        _ => "?column?".to_string(),
+    }
+}
+
+/// Unqualified, lowercase name of a function, eg. `pg_table_is_visible`.
+fn function_name(f: &Function) -> Option<String> {
+    Some(f.name.0.last()?.as_ident()?.value.to_ascii_lowercase())
+}
+
+/// Lowers a `WHERE` clause into a conjunction of `CatalogFilter`s.
+fn lower_filter(
+    scans: &[CatalogScan],
+    expr: SqlExpr,
+    filters: &mut Vec<CatalogFilter>,
+) -> Result<(), Error> {
+    match expr {
+        SqlExpr::Nested(inner) => lower_filter(scans, *inner, filters)?,
+        SqlExpr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            lower_filter(scans, *left, filters)?;
+            lower_filter(scans, *right, filters)?;
+        }
+        // All catalog tables are in the search path.
+        SqlExpr::Function(f) if function_name(&f).as_deref() == Some("pg_table_is_visible") => {}
+        SqlExpr::BinaryOp { left, op, right } => {
+            // Accept both `column op literal` and `literal op column` for symmetric operators.
+            let (column, literal) = match resolve_column(scans, &left) {
+                Ok(column) => (column, *right),
+                Err(_) if matches!(op, BinaryOperator::Eq | BinaryOperator::NotEq) => {
+                    (resolve_column(scans, &right)?, *left)
+                }
+                Err(e) => return Err(e),
+            };
+            let filter = match op {
+                BinaryOperator::Eq | BinaryOperator::NotEq => CatalogFilter::Eq {
+                    column,
+                    value: Value::from_sql(literal)?,
+                    negated: op == BinaryOperator::NotEq,
+                },
+                BinaryOperator::PGRegexMatch | BinaryOperator::PGRegexNotMatch => {
+                    CatalogFilter::Matches {
+                        column,
+                        regex: regex(&pattern(literal)?)?,
+                        negated: op == BinaryOperator::PGRegexNotMatch,
+                    }
+                }
+                other => {
+                    let tables = names(scans);
+                    sql_unsupported!("operator `{other}` on {tables}")
+                }
+            };
+            filters.push(filter);
+        }
+        SqlExpr::InList {
+            expr,
+            list,
+            negated,
+        } => filters.push(CatalogFilter::In {
+            column: resolve_column(scans, &expr)?,
+            values: list
+                .into_iter()
+                .map(Value::from_sql)
+                .collect::<Result<_, _>>()?,
+            negated,
+        }),
+        SqlExpr::IsNull(expr) => filters.push(CatalogFilter::IsNull {
+            column: resolve_column(scans, &expr)?,
+            negated: false,
+        }),
+        SqlExpr::IsNotNull(expr) => filters.push(CatalogFilter::IsNull {
+            column: resolve_column(scans, &expr)?,
+            negated: true,
+        }),
+        SqlExpr::Like {
+            negated,
+            any,
+            expr,
+            pattern: like,
+            escape_char,
+        } => {
+            sql_unsupported!(any || escape_char.is_some(), "LIKE ANY / LIKE … ESCAPE");
+            filters.push(CatalogFilter::Matches {
+                column: resolve_column(scans, &expr)?,
+                regex: regex(&like_to_regex(&pattern(*like)?))?,
+                negated,
+            });
+        }
+        other => {
+            let tables = names(scans);
+            sql_unsupported!("WHERE clause on {tables}: {other}")
+        }
+    }
+
+    Ok(())
+}
+
+fn pattern(expr: SqlExpr) -> Result<String, Error> {
+    expr.as_string()
+        .ok_or_else(|| Error::Invalid(format!("pattern must be a string literal, got {expr}")))
+}
+
+fn regex(pattern: &str) -> Result<Regex, Error> {
+    Regex::new(pattern).map_err(|e| Error::Invalid(format!("invalid pattern: {e}")))
+}
+
+/// Translates a `LIKE` pattern (`%`, `_`, `\` escapes) into an anchored regex.
+fn like_to_regex(like: &str) -> String {
+    let mut out = String::from("^");
+    let mut chars = like.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => out.push_str(".*"),
+            '_' => out.push('.'),
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    out.push_str(&regex::escape(&next.to_string()));
+                }
+            }
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    out.push('$');
+    out
+}
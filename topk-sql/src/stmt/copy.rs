@@ -0,0 +1,131 @@
+use sqlparser::ast::{CopyOption, CopySource, CopyTarget, SetExpr, Statement as SqlStatement};
+
+use crate::{
+    CopyFormat, CopyOptions, Error, SelectItemExt, Statement, Table, sql_invalid, sql_unsupported,
+};
+
+pub(crate) fn try_from_sql(stmt: SqlStatement) -> Result<Statement, Error> {
+    let SqlStatement::Copy {
+        source,
+        to,
+        target,
+        options,
+        // `TopKDialect` folds legacy options (eg. `CSV HEADER`) into `options`
+        legacy_options: _,
+        values,
+    } = stmt
+    else {
+        sql_unsupported!("not a COPY statement");
+    };
+
+    sql_unsupported!(
+        !values.is_empty(),
+        "inline COPY data, stream rows over the COPY protocol instead"
+    );
+    match (&target, to) {
+        (CopyTarget::Stdin, false) | (CopyTarget::Stdout, true) => {}
+        (CopyTarget::File { .. } | CopyTarget::Program { .. }, _) => {
+            sql_unsupported!("COPY … FILE / PROGRAM, use FROM STDIN or TO STDOUT")
+        }
+        (_, true) => sql_invalid!("COPY … TO requires STDOUT"),
+        (_, false) => sql_invalid!("COPY … FROM requires STDIN"),
+    }
+
+    let options = parse_options(options)?;
+
+    match source {
+        CopySource::Table {
+            table_name,
+            columns,
+        } => {
+            sql_unsupported!(
+                to,
+                "COPY <table> TO STDOUT, use COPY (SELECT … FROM {table_name}) TO STDOUT"
+            );
+
+            let table = Table::new(table_name)?;
+            let columns = columns.into_iter().map(|c| c.value).collect::<Vec<_>>();
+            for (i, c) in columns.iter().enumerate() {
+                sql_invalid!(
+                    columns[..i].contains(c),
+                    "column `{c}` specified more than once"
+                );
+            }
+            sql_invalid!(
+                !columns.is_empty() && !columns.iter().any(|c| c == "_id"),
+                "COPY column list must include `_id`"
+            );
+            sql_invalid!(
+                options.format == CopyFormat::Csv && !options.header && columns.is_empty(),
+                "COPY … FROM STDIN (FORMAT csv) requires a column list or HEADER"
+            );
+
+            Ok(Statement::CopyFrom {
+                table,
+                columns,
+                options,
+            })
+        }
+        CopySource::Query(query) => {
+            sql_invalid!(!to, "COPY (SELECT …) requires TO STDOUT");
+
+            // Output columns in projection order; empty for `SELECT *` (see `CopyEncoder::new`).
+            let columns = match query.body.as_ref() {
+                SetExpr::Select(select) if !select.projection.iter().any(|p| p.is_wildcard()) => {
+                    select
+                        .projection
+                        .iter()
+                        .map(|item| item.projection_name())
+                        .collect::<Result<_, _>>()?
+                }
+                _ => vec![],
+            };
+
+            let stmt = Statement::try_from(*query)?;
+            Ok(Statement::CopyTo {
+                stmt: Box::new(stmt),
+                columns,
+                options,
+            })
+        }
+    }
+}
+
+fn parse_options(options: Vec<CopyOption>) -> Result<CopyOptions, Error> {
+    let mut out = CopyOptions::default();
+    let mut escape = None;
+    for option in options {
+        match option {
+            CopyOption::Format(format) => out.format = format.value.parse()?,
+            CopyOption::Header(header) => out.header = header,
+            CopyOption::Delimiter(delimiter) => out.delimiter = delimiter,
+            CopyOption::Quote(quote) => out.quote = quote,
+            CopyOption::Escape(c) => escape = Some(c),
+            CopyOption::Null(null) => out.null = null,
+            CopyOption::Encoding(encoding) => sql_unsupported!(
+                !encoding.eq_ignore_ascii_case("utf8") && !encoding.eq_ignore_ascii_case("utf-8"),
+                "COPY encoding `{encoding}`, only UTF8 is supported"
+            ),
+            CopyOption::Freeze(_) => {}
+            other => sql_unsupported!("COPY option: {other}"),
+        }
+    }
+
+    if out.format != CopyFormat::Csv {
+        sql_invalid!(out.header, "HEADER is only supported with FORMAT csv");
+    }
+    sql_unsupported!(
+        escape.is_some_and(|escape| escape != out.quote),
+        "COPY ESCAPE different from QUOTE"
+    );
+    sql_invalid!(
+        out.delimiter == out.quote || ['\r', '\n'].contains(&out.delimiter),
+        "COPY delimiter must differ from the quote and newline characters"
+    );
+    sql_invalid!(
+        !out.delimiter.is_ascii() || !out.quote.is_ascii(),
+        "COPY delimiter and quote must be ASCII characters"
+    );
+
+    Ok(out)
+}
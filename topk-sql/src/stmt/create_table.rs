@@ -5,15 +5,18 @@ use sqlparser::ast::{
     CreateTable as SqlCreateTable, DataType, Expr as SqlExpr, Function, FunctionArg,
     FunctionArgExpr, FunctionArguments,
 };
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
 use topk_rs::proto::v1::control::{
     FieldIndex, FieldSpec, FieldType, KeywordIndexType, MultiVectorDistanceMetric,
-    MultiVectorQuantization, VectorDistanceMetric, field_type_list::ListValueType,
-    field_type_matrix::MatrixValueType,
+    MultiVectorQuantization, VectorDistanceMetric, field_index::Index,
+    field_type_list::ListValueType, field_type_matrix::MatrixValueType,
 };
 
+use crate::dialect::TopKDialect;
 use crate::{
     Error, FromSql, SqlExprExt, Statement, Table, parse_args, parse_kwargs, sql_invalid,
-    sql_unsupported, util::Kwargs
+    sql_unsupported, util::Kwargs,
 };
 
 impl TryFrom<SqlCreateTable> for Statement {
@@ -32,7 +35,7 @@ impl TryFrom<SqlCreateTable> for Statement {
             .columns
             .into_iter()
             .map(|column| {
-                let name = column.name.to_string();
+                let name = column.name.value.clone();
                 FieldSpec::from_sql(column).map(|spec| (name, spec))
             })
             .collect::<Result<_, Error>>()?;
@@ -98,6 +101,7 @@ impl FromSql<DataType> for FieldType {
                 Float(_) | Float4 | Float8 | Real | DoublePrecision => {
                     Ok(FieldType::list(ListValueType::Float))
                 }
+                JSON | JSONB => Ok(FieldType::list(ListValueType::Unspecified)),
                 dt => sql_unsupported!("list element type: {dt}"),
             },
 
@@ -166,6 +170,20 @@ impl FromSql<DataType> for FieldType {
                         Ok(FieldType::matrix(dim, MatrixValueType::I8))
                     }
 
+                    // Struct with nested column definitions, eg. `struct('title TEXT NOT NULL')`
+                    "struct" => {
+                        sql_invalid!(args.is_empty(), "struct requires at least one field");
+                        let fields = args
+                            .iter()
+                            .map(|field| {
+                                let column = parse_column_def(field)?;
+                                let name = column.name.value.clone();
+                                FieldSpec::from_sql(column).map(|spec| (name, spec))
+                            })
+                            .collect::<Result<Vec<_>, Error>>()?;
+                        Ok(FieldType::r#struct(fields))
+                    }
+
                     _ => sql_unsupported!("data type: {name}"),
                 }
             }
@@ -175,6 +193,23 @@ impl FromSql<DataType> for FieldType {
     }
 }
 
+/// Parses a nested struct field definition (eg. `title TEXT NOT NULL`).
+fn parse_column_def(sql: &str) -> Result<ColumnDef, Error> {
+    let dialect = TopKDialect::default();
+    let mut parser = Parser::new(&dialect).try_with_sql(sql)?;
+    let column = parser.parse_column_def().map_err(|e| {
+        Error::Invalid(format!(
+            "struct fields must be column definitions in string literals \
+             (eg. struct('title TEXT NOT NULL')), got `{sql}`: {e}"
+        ))
+    })?;
+    sql_invalid!(
+        parser.peek_token().token != Token::EOF,
+        "unexpected input after struct field definition `{sql}`"
+    );
+    Ok(column)
+}
+
 impl FromSql<Function> for FieldIndex {
     fn from_sql(func: Function) -> Result<Self, Error> {
         let method = func.name.to_string().to_ascii_lowercase();
@@ -228,20 +263,29 @@ impl FromSql<Function> for FieldIndex {
                 FieldIndex::ngram()
             }
             "vector_index" => {
-                let (metric,) = parse_kwargs!(&opts; metric: VectorDistanceMetric)?;
-                FieldIndex::vector(metric)
+                let (metric, exact) =
+                    parse_kwargs!(&opts; metric: VectorDistanceMetric; exact: bool)?;
+                let mut index = FieldIndex::vector(metric);
+                if let Some(Index::VectorIndex(vector)) = index.index.as_mut() {
+                    vector.exact = exact;
+                }
+                index
             }
             "multi_vector_index" => {
-                let (metric, quantization, width, top_k) = parse_kwargs!(
+                let (metric, quantization, width, top_k, skip_smve) = parse_kwargs!(
                     &opts;
                     metric: MultiVectorDistanceMetric;
-                    quantization: MultiVectorQuantization, width: u32, top_k: u32
+                    quantization: MultiVectorQuantization, width: u32, top_k: u32, skip_smve: bool
                 )?;
                 sql_invalid!(
                     metric != MultiVectorDistanceMetric::Maxsim,
                     "multi_vector_index metric must be 'maxsim'"
                 );
-                FieldIndex::multi_vector(metric, quantization, width, top_k)
+                let index = FieldIndex::multi_vector(metric, quantization, width, top_k);
+                match skip_smve {
+                    Some(true) => index.skip_smve(),
+                    _ => index,
+                }
             }
             _ => sql_unsupported!(
                 "unknown index method `{method}`, expected: keyword_index | semantic_index | ngram_index | vector_index | multi_vector_index"
@@ -1,28 +1,83 @@
-use sqlparser::ast::Statement as SqlStatement;
+use sqlparser::ast::{AnalyzeFormat, AnalyzeFormatKind, Statement as SqlStatement, UtilityOption};
 
-use crate::{Error, Statement, sql_unsupported};
+use crate::{Error, ExplainFormat, SqlExprExt, Statement, sql_invalid, sql_unsupported};
 
 pub(crate) fn try_from_sql(stmt: SqlStatement) -> Result<Statement, Error> {
     match stmt {
         SqlStatement::Explain {
-            analyze,
-            verbose,
+            mut analyze,
+            mut verbose,
             query_plan,
             statement,
             format,
             options,
             ..
         } => {
-            sql_unsupported!(analyze, "EXPLAIN with ANALYZE");
             sql_unsupported!(query_plan, "EXPLAIN with QUERY PLAN");
-            sql_unsupported!(format.is_some(), "EXPLAIN with FORMAT");
-            sql_unsupported!(options.is_some(), "EXPLAIN with OPTIONS");
+
+            let mut format = match format {
+                Some(AnalyzeFormatKind::Keyword(f) | AnalyzeFormatKind::Assignment(f)) => {
+                    explain_format(f)?
+                }
+                None => ExplainFormat::Text,
+            };
+
+            // `EXPLAIN (ANALYZE, VERBOSE false, FORMAT JSON) …`
+            for UtilityOption { name, arg } in options.unwrap_or_default() {
+                let option = name.value.to_ascii_uppercase();
+                let flag = || match &arg {
+                    None => Ok(true),
+                    Some(arg) => arg.as_bool().ok_or_else(|| {
+                        Error::Invalid(format!("EXPLAIN option {option} expects a boolean"))
+                    }),
+                };
+                match option.as_str() {
+                    "ANALYZE" => analyze = flag()?,
+                    "VERBOSE" => verbose = flag()?,
+                    "FORMAT" => {
+                        format = match arg.as_ref().and_then(|arg| arg.as_ident()) {
+                            Some(f) if f.eq_ignore_ascii_case("text") => ExplainFormat::Text,
+                            Some(f) if f.eq_ignore_ascii_case("json") => ExplainFormat::Json,
+                            _ => sql_unsupported!("EXPLAIN format, expected: TEXT | JSON"),
+                        }
+                    }
+                    // Cost model and buffer statistics do not apply, accepted for client compat.
+                    "COSTS" | "BUFFERS" | "TIMING" | "SUMMARY" | "SETTINGS" | "WAL" => {
+                        flag()?;
+                    }
+                    _ => sql_unsupported!("EXPLAIN option {option}"),
+                }
+            }
+
+            let stmt = Statement::try_from(*statement)?;
+            sql_invalid!(
+                analyze
+                    && !matches!(
+                        stmt,
+                        Statement::Select { .. }
+                            | Statement::Count { .. }
+                            | Statement::Fuse { .. }
+                            | Statement::Catalog { .. }
+                    ),
+                "EXPLAIN ANALYZE is only supported for SELECT, it would execute the {}",
+                stmt.as_str()
+            );
 
             Ok(Statement::Explain {
-                stmt: Box::new(Statement::try_from(*statement)?),
+                stmt: Box::new(stmt),
                 verbose,
+                analyze,
+                format,
             })
         }
         _ => sql_unsupported!("not an EXPLAIN statement"),
     }
 }
+
+fn explain_format(format: AnalyzeFormat) -> Result<ExplainFormat, Error> {
+    match format {
+        AnalyzeFormat::TEXT => Ok(ExplainFormat::Text),
+        AnalyzeFormat::JSON => Ok(ExplainFormat::Json),
+        other => sql_unsupported!("EXPLAIN FORMAT {other}, expected: TEXT | JSON"),
+    }
+}
@@ -5,8 +5,15 @@ use strum_macros::IntoStaticStr;
 use topk_rs::proto::v1::control::FieldSpec;
 use topk_rs::proto::v1::data::{Document, LogicalExpr, Query, Value};
 
-use crate::{Error, FromSql, SqlExprExt, Table, sql_invalid, sql_unsupported};
+use crate::expr::Fusion;
+use crate::{
+    CatalogQuery, CopyOptions, Error, ExplainFormat, FromSql, SchemaChange, SqlExprExt, Table,
+    sql_invalid, sql_unsupported,
+};
 
+mod alter_table;
+mod catalog;
+mod copy;
 mod create_table;
 mod delete;
 mod drop;
@@ -34,6 +41,26 @@ pub enum Statement {
         /// `topk_rs::Query` to execute.
         query: Query,
     },
+    Fuse {
+        /// Table name (`<collection>` OR `<collection>.<partition>`).
+        table: Table,
+        /// `topk_rs::Query` per scoring expression, each ranked by `FUSE_SCORE` (descending
+        /// until resolved against the schema with `resolve_score_orders`).
+        queries: Vec<Query>,
+        /// How to fuse the sub-query results (see `Fusion::fuse`).
+        fusion: Fusion,
+        /// Output name of the fused score.
+        alias: String,
+        /// Number of fused rows to skip.
+        offset: u64,
+        /// Number of fused rows to return.
+        limit: u64,
+    },
+    Catalog {
+        /// Query over synthetic `information_schema` / `pg_catalog` tables, evaluated
+        /// client-side (see `CatalogQuery::execute`).
+        query: CatalogQuery,
+    },
     Insert {
         /// Table name (`<collection>` OR `<collection>.<partition>`).
         table: Table,
@@ -58,6 +85,22 @@ pub enum Statement {
         /// Table name (`<collection>.<partition>`).
         table: Table,
     },
+    CopyFrom {
+        /// Table name (`<collection>` OR `<collection>.<partition>`).
+        table: Table,
+        /// Columns of each CSV record, empty if given by the header or the JSON objects.
+        columns: Vec<String>,
+        /// Input format (see `CopyDecoder`).
+        options: CopyOptions,
+    },
+    CopyTo {
+        /// Query whose results are exported.
+        stmt: Box<Statement>,
+        /// Output columns in order, empty for `SELECT *` (see `CopyEncoder::new`).
+        columns: Vec<String>,
+        /// Output format (see `CopyEncoder`).
+        options: CopyOptions,
+    },
 
     CreateTable {
         /// Table name (`<collection>`).
@@ -73,12 +116,28 @@ pub enum Statement {
         /// Silently ignore if the table does not exist.
         if_exists: bool,
     },
+    AlterTable {
+        /// Table name (`<collection>`).
+        table: Table,
+        /// Changes to apply, in order (see `MigrationPlan::new`).
+        changes: Vec<SchemaChange>,
+        /// Silently ignore if the table does not exist.
+        if_exists: bool,
+    },
+    ShowCreateTable {
+        /// Table name (`<collection>`).
+        table: Table,
+    },
 
     Explain {
         /// Statement to explain.
         stmt: Box<Statement>,
         /// Whether to include verbose information.
         verbose: bool,
+        /// Whether to execute the statement and report its statistics (see `ExplainAnalyze`).
+        analyze: bool,
+        /// Output format (see `Statement::explain`).
+        format: ExplainFormat,
     },
     Set {
         /// Variable to set (eg. `consistency_level`)
@@ -110,13 +169,17 @@ impl Statement {
         match self {
             Statement::Select { table, .. }
             | Statement::Count { table, .. }
+            | Statement::Fuse { table, .. }
             | Statement::Insert { table, .. }
             | Statement::Update { table, .. }
             | Statement::Delete { table, .. }
             | Statement::DeletePartition { table }
+            | Statement::CopyFrom { table, .. }
             | Statement::CreateTable { table, .. }
-            | Statement::DropTable { table, .. } => Some(table),
-            Statement::Explain { stmt, .. } => stmt.table(),
+            | Statement::DropTable { table, .. }
+            | Statement::AlterTable { table, .. }
+            | Statement::ShowCreateTable { table } => Some(table),
+            Statement::Explain { stmt, .. } | Statement::CopyTo { stmt, .. } => stmt.table(),
             _ => None,
         }
     }
@@ -131,16 +194,20 @@ impl TryFrom<SqlStatement> for Statement {
             SqlStatement::Commit { .. } => Ok(Statement::Commit),
             SqlStatement::Rollback { .. } => Ok(Statement::Rollback),
             SqlStatement::Set(set) => Statement::try_from(set),
-            SqlStatement::ShowVariable { .. } => show::try_from_sql(stmt),
+            SqlStatement::ShowVariable { .. } | SqlStatement::ShowCreate { .. } => {
+                show::try_from_sql(stmt)
+            }
             SqlStatement::Discard { .. } => Ok(Statement::Discard),
 
             SqlStatement::Query(q) => Statement::try_from(*q),
             SqlStatement::Insert(insert) => Statement::try_from(insert),
             SqlStatement::Update(update) => Statement::try_from(update),
             SqlStatement::Delete(delete) => Statement::try_from(delete),
+            SqlStatement::Copy { .. } => copy::try_from_sql(stmt),
             SqlStatement::Explain { .. } => explain::try_from_sql(stmt),
             SqlStatement::CreateTable(ct) => Statement::try_from(ct),
             SqlStatement::Drop { .. } => drop::try_from_sql(stmt),
+            SqlStatement::AlterTable(alter) => Statement::try_from(alter),
             other => Err(Error::Unsupported(format!("statement: {other:?}"))),
         }
     }
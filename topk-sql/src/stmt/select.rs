@@ -10,9 +10,11 @@ use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
 use topk_rs::proto::v1::data::stage::{filter_stage::FilterExpr, select_stage::SelectExpr};
 use topk_rs::proto::v1::data::{AggregateExpr, LogicalExpr, Query, Stage};
 
+use super::catalog;
+use crate::expr::{FUSE_SCORE, RankExpr, contains_rank_fn};
 use crate::{
-    Error, FromSql, SelectItemExt, SqlExprExt, SqlFunctionExt, Table, sql_invalid, sql_unsupported,
-    stmt::Statement,
+    CatalogTable, Error, FromSql, SelectItemExt, SqlExprExt, SqlFunctionExt, Table, sql_invalid,
+    sql_unsupported, stmt::Statement,
 };
 
 fn is_aggregate_fn(func: &SqlFunction) -> bool {
@@ -157,6 +159,20 @@ impl TryFrom<SqlQuery> for Statement {
         sql_invalid!(select.from.is_empty(), "SELECT requires a FROM clause");
         sql_unsupported!(select.from.len() != 1, "multiple tables in FROM");
         let first = select.from.swap_remove(0);
+
+        if let TableFactor::Table { name, .. } = &first.relation
+            && let Some(catalog) = CatalogTable::from_name(name)
+        {
+            sql_unsupported!(select.having.is_some(), "HAVING on {}", catalog.name());
+            return catalog::lower(
+                first,
+                select.projection,
+                select.selection,
+                group_by_exprs,
+                query.order_by,
+                query.limit_clause,
+            );
+        }
         sql_unsupported!(!first.joins.is_empty(), "JOIN");
 
         let table = match first.relation {
@@ -177,6 +193,7 @@ impl TryFrom<SqlQuery> for Statement {
         }
 
         let mut post_group_projection = None;
+        let mut fused = None;
 
         if group_by_exprs.is_empty() {
             let has_aggregate = select.projection.iter().any(
@@ -205,6 +222,7 @@ impl TryFrom<SqlQuery> for Statement {
             }
 
             let mut projection = Vec::with_capacity(select.projection.len());
+            let mut rank = None;
             for item in select.projection {
                 if item.is_wildcard() {
                     sql_unsupported!("SELECT *");
@@ -212,10 +230,25 @@ impl TryFrom<SqlQuery> for Statement {
                 let expr = item
                     .expr()
                     .expect("non-wildcard select item has an expression");
+                if contains_rank_fn(expr) {
+                    sql_unsupported!(rank.is_some(), "multiple rank fusion items in SELECT");
+                    rank = Some((item.projection_name()?, RankExpr::from_sql(expr.clone())?));
+                    continue;
+                }
                 projection.push((item.projection_name()?, SelectExpr::from_sql(expr.clone())?));
             }
-            stages.push(Stage::select(projection));
+            match rank {
+                Some((alias, rank)) => fused = Some((alias, rank, projection)),
+                None => stages.push(Stage::select(projection)),
+            }
         } else {
+            sql_unsupported!(
+                select
+                    .projection
+                    .iter()
+                    .any(|item| item.expr().is_some_and(contains_rank_fn)),
+                "rank fusion functions with GROUP BY"
+            );
             let (group_stages, projection) =
                 lower_group_by(select.projection, group_by_exprs, select.having)?;
             stages.extend(group_stages);
@@ -276,6 +309,10 @@ impl TryFrom<SqlQuery> for Statement {
             None => (None, None),
         };
 
+        if let Some((alias, rank, projection)) = fused {
+            return lower_fusion(table, stages, projection, alias, rank, sort, limit, offset);
+        }
+
         match (sort, limit) {
             (Some(exprs), Some(k)) => {
                 stages.push(Stage::sort(exprs));
@@ -304,3 +341,58 @@ impl TryFrom<SqlQuery> for Statement {
         })
     }
 }
+
+/// Lowers `SELECT …, rrf(…) AS s FROM t WHERE … ORDER BY s DESC LIMIT k` into one
+/// sub-query per scoring expression, each sharing the `WHERE` filters and projection.
+#[allow(clippy::too_many_arguments)]
+fn lower_fusion(
+    table: Table,
+    filters: Vec<Stage>,
+    projection: Vec<(String, SelectExpr)>,
+    alias: String,
+    rank: RankExpr,
+    sort: Option<Vec<(LogicalExpr, SortOrder)>>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+) -> Result<Statement, Error> {
+    let Some(limit) = limit else {
+        sql_invalid!("rank fusion requires a LIMIT")
+    };
+    if let Some(sort) = sort {
+        sql_unsupported!(
+            sort != [(LogicalExpr::field(alias.as_str()), SortOrder::Desc)],
+            "ORDER BY in a rank fusion query must be `{alias} DESC`"
+        );
+    }
+    let offset = offset.unwrap_or(0);
+    // Rank at least 10 documents per sub-query, so that small pages still fuse candidates
+    // ranked highly by only one of them.
+    let window = (offset + limit).max(10);
+
+    let queries = rank
+        .scores
+        .into_iter()
+        .map(|score| {
+            let mut stages = filters.clone();
+            let mut exprs = projection.clone();
+            exprs.push((FUSE_SCORE.to_string(), score));
+            stages.push(Stage::select(exprs));
+            // Descending until resolved against the schema, see `resolve_score_orders`
+            stages.push(Stage::sort((
+                LogicalExpr::field(FUSE_SCORE),
+                SortOrder::Desc,
+            )));
+            stages.push(Stage::limit(window));
+            Query { stages }
+        })
+        .collect();
+
+    Ok(Statement::Fuse {
+        table,
+        queries,
+        fusion: rank.fusion,
+        alias,
+        offset,
+        limit,
+    })
+}
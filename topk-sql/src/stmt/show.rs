@@ -1,6 +1,6 @@
-use sqlparser::ast::{ObjectName, ObjectNamePart, Statement as SqlStatement};
+use sqlparser::ast::{ObjectName, ObjectNamePart, ShowCreateObject, Statement as SqlStatement};
 
-use crate::{Error, FromSql, Statement, sql_unsupported, stmt::Variable};
+use crate::{Error, FromSql, Statement, Table, sql_invalid, sql_unsupported, stmt::Variable};
 
 pub(crate) fn try_from_sql(stmt: SqlStatement) -> Result<Statement, Error> {
     match stmt {
@@ -12,6 +12,18 @@ pub(crate) fn try_from_sql(stmt: SqlStatement) -> Result<Statement, Error> {
                     .collect(),
             ))?,
         }),
+        SqlStatement::ShowCreate { obj_type, obj_name } => {
+            sql_unsupported!(
+                obj_type != ShowCreateObject::Table,
+                "SHOW CREATE {obj_type}"
+            );
+            let table = Table::new(obj_name)?;
+            sql_invalid!(
+                !matches!(table, Table::Collection(_)),
+                "SHOW CREATE TABLE requires a collection name"
+            );
+            Ok(Statement::ShowCreateTable { table })
+        }
         _ => sql_unsupported!("SHOW requires a variable name"),
     }
 }
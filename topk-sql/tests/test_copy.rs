@@ -0,0 +1,87 @@
+use rstest::rstest;
+use topk_sql::{CopyFormat, CopyOptions, Statement, Table};
+
+fn convert(sql: &str) -> Result<Statement, topk_sql::Error> {
+    let mut stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql)?)?;
+    Ok(stmts.remove(0).0)
+}
+
+#[test]
+fn copy_from_lowers_options() {
+    let stmt = convert(
+        "COPY books (_id, title) FROM STDIN WITH (FORMAT csv, HEADER true, DELIMITER ';', NULL 'NA')",
+    )
+    .unwrap();
+
+    assert_eq!(
+        stmt,
+        Statement::CopyFrom {
+            table: Table::Collection("books".to_string()),
+            columns: vec!["_id".to_string(), "title".to_string()],
+            options: CopyOptions {
+                format: CopyFormat::Csv,
+                header: true,
+                delimiter: ';',
+                quote: '"',
+                null: "NA".to_string(),
+            },
+        }
+    );
+    assert_eq!(stmt.table(), Some(&Table::Collection("books".to_string())));
+}
+
+#[test]
+fn copy_from_legacy_options() {
+    let stmt = convert("COPY books FROM STDIN CSV HEADER DELIMITER AS '|';").unwrap();
+    let Statement::CopyFrom { options, .. } = stmt else {
+        panic!("expected CopyFrom, got {stmt:?}");
+    };
+    assert_eq!(options.format, CopyFormat::Csv);
+    assert!(options.header);
+    assert_eq!(options.delimiter, '|');
+}
+
+#[test]
+fn copy_to_lowers_query() {
+    let stmt = convert(
+        "COPY (SELECT _id, title AS name FROM books WHERE year > 2000) TO STDOUT WITH (FORMAT ndjson)",
+    )
+    .unwrap();
+
+    let Statement::CopyTo {
+        stmt,
+        columns,
+        options,
+    } = stmt
+    else {
+        panic!("expected CopyTo, got {stmt:?}");
+    };
+    assert!(matches!(*stmt, Statement::Select { .. }));
+    assert_eq!(columns, ["_id", "name"]);
+    assert_eq!(options.format, CopyFormat::Ndjson);
+
+    let stmt =
+        convert("COPY (SELECT * FROM information_schema.tables) TO STDOUT WITH (FORMAT json)")
+            .unwrap();
+    assert!(matches!(stmt, Statement::CopyTo { columns, .. } if columns.is_empty()));
+}
+
+#[rstest]
+#[case::legacy_binary("COPY books FROM STDIN BINARY", "COPY format `binary`")]
+#[case::table_to_stdout("COPY books TO STDOUT", "use COPY (SELECT")]
+#[case::file("COPY books FROM '/tmp/books.csv'", "FILE / PROGRAM")]
+#[case::csv_without_columns("COPY books FROM STDIN WITH (FORMAT csv)", "column list or HEADER")]
+#[case::missing_id(
+    "COPY books (title) FROM STDIN WITH (FORMAT csv)",
+    "must include `_id`"
+)]
+#[case::text_format("COPY books (_id) FROM STDIN WITH (FORMAT text)", "COPY format `text`")]
+#[case::header_json(
+    "COPY books FROM STDIN WITH (FORMAT json, HEADER true)",
+    "only supported with FORMAT csv"
+)]
+#[case::escape("COPY books (_id) FROM STDIN WITH (FORMAT csv, ESCAPE '\\')", "ESCAPE")]
+fn copy_rejected(#[case] sql: &str, #[case] message: &str) {
+    let err = convert(sql).unwrap_err();
+    assert!(err.to_string().contains(message), "{err}");
+}
@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use rstest::rstest;
+use topk_rs::proto::v1::control::{FieldSpec, FieldType};
 use topk_rs::{doc, proto::v1::data::Document};
 
 mod common;
@@ -177,3 +178,155 @@ async fn create_table_if_not_exists(
 
     assert_eq!(rows, expected);
 }
+
+fn parse_schema(
+    sql: &str,
+) -> std::collections::HashMap<String, topk_rs::proto::v1::control::FieldSpec> {
+    let mut stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql).unwrap()).unwrap();
+    match stmts.pop().expect("expected one statement").0 {
+        topk_sql::Statement::CreateTable { schema, .. } => schema,
+        other => panic!("expected CREATE TABLE, got {other:?}"),
+    }
+}
+
+#[rstest]
+#[case::scalars(
+    "CREATE TABLE books (a TEXT NOT NULL, b BIGINT, c FLOAT, d BOOLEAN, e BYTEA, f TIMESTAMP)"
+)]
+#[case::lists(
+    "CREATE TABLE books (tags TEXT[], ids INTEGER[], scores FLOAT[] NOT NULL, values JSONB[])"
+)]
+#[case::vectors(
+    "CREATE TABLE books (
+        a f32_vector(4) INDEX vector_index(metric = 'cosine'),
+        b f16_vector(8) INDEX vector_index(metric = 'dot_product', exact = 'true'),
+        c u8_vector(4), d i8_vector(4), e f8_vector(4),
+        f binary_vector(16) INDEX vector_index(metric = 'hamming'),
+        g f32_sparse_vector INDEX vector_index(metric = 'dot_product'),
+        h u8_sparse_vector, i i8_sparse_vector, j f16_sparse_vector, k f8_sparse_vector
+    )"
+)]
+#[case::matrices(
+    "CREATE TABLE books (
+        a f32_matrix(8) INDEX multi_vector_index(metric = 'maxsim'),
+        b f16_matrix(8) INDEX multi_vector_index(metric = 'maxsim', quantization = '1bit', width = '32', top_k = '4', skip_smve = 'true'),
+        c u8_matrix(8), d i8_matrix(8), e f8_matrix(8)
+    )"
+)]
+#[case::text_indexes(
+    "CREATE TABLE books (
+        a TEXT INDEX keyword_index(),
+        b TEXT INDEX keyword_index(type = 'exact'),
+        c TEXT INDEX semantic_index(),
+        d TEXT NOT NULL INDEX ngram_index()
+    )"
+)]
+#[case::structs(
+    "CREATE TABLE books (
+        meta JSONB,
+        author struct('name TEXT NOT NULL INDEX keyword_index()', 'born struct(''year BIGINT'')') NOT NULL,
+        extra struct('values JSONB[]')
+    )"
+)]
+#[case::quoted_names(r#"CREATE TABLE books ("Title" TEXT, "select" BIGINT, "a b" FLOAT)"#)]
+fn show_create_table_round_trip(#[case] sql: &str) {
+    let schema = parse_schema(sql);
+    let rendered = topk_sql::create_table_sql("books", &schema).unwrap();
+    assert_eq!(parse_schema(&rendered), schema, "{rendered}");
+}
+
+#[rstest]
+#[case::column(FieldSpec {
+    data_type: Some(FieldType { data_type: None }),
+    required: false,
+    index: None,
+})]
+#[case::missing(FieldSpec {
+    data_type: None,
+    required: true,
+    index: None,
+})]
+#[case::struct_field(FieldSpec {
+    data_type: Some(FieldType::r#struct([(
+        "created_at",
+        FieldSpec {
+            data_type: Some(FieldType { data_type: None }),
+            required: false,
+            index: None,
+        },
+    )])),
+    required: false,
+    index: None,
+})]
+fn show_create_table_unknown_data_type(#[case] spec: FieldSpec) {
+    let schema = [("meta".to_string(), spec)].into_iter().collect();
+    let err = topk_sql::create_table_sql("books", &schema).unwrap_err();
+    assert!(err.to_string().contains("unknown to this client"), "{err}");
+}
+
+#[test]
+fn show_create_table_format() {
+    let schema = parse_schema(
+        "CREATE TABLE books (title TEXT NOT NULL INDEX keyword_index(), \
+         embedding f32_vector(4) INDEX vector_index(metric = 'cosine'))",
+    );
+    assert_eq!(
+        topk_sql::create_table_sql("books", &schema).unwrap(),
+        "CREATE TABLE books (\n    \
+             embedding f32_vector(4) INDEX vector_index(metric = 'cosine'),\n    \
+             title TEXT NOT NULL INDEX keyword_index()\n\
+         );"
+    );
+
+    let mut stmts =
+        topk_sql::convert_sql(topk_sql::parse_sql("SHOW CREATE TABLE books").unwrap()).unwrap();
+    assert_eq!(
+        stmts.pop().unwrap().0,
+        topk_sql::Statement::ShowCreateTable {
+            table: topk_sql::Table::Collection("books".into())
+        }
+    );
+}
+
+#[test]
+fn alter_table_plans_migration() {
+    let sql = "ALTER TABLE books ADD COLUMN year BIGINT NOT NULL, DROP COLUMN IF EXISTS isbn";
+    let mut stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql).unwrap()).unwrap();
+    let topk_sql::Statement::AlterTable { table, changes, .. } = stmts.pop().unwrap().0 else {
+        panic!("expected ALTER TABLE");
+    };
+    assert_eq!(table, topk_sql::Table::Collection("books".into()));
+    assert_eq!(changes.len(), 2);
+
+    let current = parse_schema("CREATE TABLE books (title TEXT NOT NULL, isbn TEXT)");
+    let plan = topk_sql::MigrationPlan::new("books", &current, &changes).unwrap();
+    assert_eq!(
+        plan.schema,
+        parse_schema("CREATE TABLE books (title TEXT NOT NULL, year BIGINT NOT NULL)")
+    );
+
+    let explained = plan.to_string();
+    assert!(explained.contains("-- Step 1: create `books__migration` with the new schema"));
+    assert!(explained.contains(
+        "-- Step 2: copy all documents from `books` into `books__migration`, removing `isbn`"
+    ));
+    assert!(explained.contains(
+        "-- Step 3: check that `books__migration` has as many documents as `books`\n\
+         SELECT COUNT(*) FROM books;\n\
+         SELECT COUNT(*) FROM books__migration;\n\
+         -- Step 4: drop `books`\n\
+         DROP TABLE books;"
+    ));
+    assert!(explained.contains("To roll back before step 4, drop `books__migration`"));
+}
+
+#[rstest]
+#[case::rename("ALTER TABLE books RENAME COLUMN a TO b", "ALTER TABLE operation")]
+#[case::partition("ALTER TABLE books$p ADD COLUMN a TEXT", "requires a collection name")]
+#[case::cascade("ALTER TABLE books DROP COLUMN a CASCADE", "CASCADE/RESTRICT")]
+fn alter_table_rejected(#[case] sql: &str, #[case] expected: &str) {
+    let err = topk_sql::parse_sql(sql)
+        .and_then(topk_sql::convert_sql)
+        .unwrap_err();
+    assert!(err.to_string().contains(expected), "{err}");
+}
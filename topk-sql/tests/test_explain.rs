@@ -1,4 +1,7 @@
+use std::time::Duration;
+
 use rstest::rstest;
+use topk_sql::{ExplainAnalyze, ExplainFormat, Statement};
 
 mod common;
 use common::{BooksContext, Scope};
@@ -42,3 +45,119 @@ async fn explain(#[case] query: &str, #[case] fragments: &[&str]) {
         );
     }
 }
+
+fn convert(sql: &str) -> Result<topk_sql::Statement, topk_sql::Error> {
+    let mut stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql)?)?;
+    Ok(stmts.remove(0).0)
+}
+
+#[rstest]
+#[case::text(
+    "EXPLAIN SELECT _id FROM books LIMIT 5",
+    false,
+    false,
+    ExplainFormat::Text
+)]
+#[case::analyze(
+    "EXPLAIN ANALYZE SELECT _id FROM books LIMIT 5",
+    true,
+    false,
+    ExplainFormat::Text
+)]
+#[case::options(
+    "EXPLAIN (ANALYZE, VERBOSE, COSTS false, FORMAT JSON) SELECT _id FROM books LIMIT 5",
+    true,
+    true,
+    ExplainFormat::Json
+)]
+#[case::options_off(
+    "EXPLAIN (ANALYZE false, FORMAT TEXT) SELECT _id FROM books LIMIT 5",
+    false,
+    false,
+    ExplainFormat::Text
+)]
+fn explain_options(
+    #[case] sql: &str,
+    #[case] analyze: bool,
+    #[case] verbose: bool,
+    #[case] format: ExplainFormat,
+) {
+    let stmt = convert(sql).unwrap();
+    let Statement::Explain {
+        stmt,
+        verbose: actual_verbose,
+        analyze: actual_analyze,
+        format: actual_format,
+    } = stmt
+    else {
+        panic!("expected Explain, got {stmt:?}");
+    };
+    assert!(matches!(*stmt, Statement::Select { .. }));
+    assert_eq!(
+        (actual_analyze, actual_verbose, actual_format),
+        (analyze, verbose, format)
+    );
+}
+
+#[test]
+fn explain_renders_stage_pipeline() {
+    let stmt = convert(
+        "SELECT _id, title FROM books WHERE author = 'Tolkien' ORDER BY published_year DESC LIMIT 5",
+    )
+    .unwrap();
+
+    assert_eq!(
+        stmt.explain(ExplainFormat::Text, false, None),
+        "Query on Collection books\n\
+         \x20 -> Filter: author = 'Tolkien'\n\
+         \x20 -> Select: _id, title\n\
+         \x20 -> Sort: published_year DESC\n\
+         \x20 -> Limit: 5\n"
+    );
+
+    let analyze = ExplainAnalyze {
+        elapsed: Duration::from_millis(12),
+        matched_count: Some(42),
+        rows: 5,
+    };
+    let json: serde_json::Value =
+        serde_json::from_str(&stmt.explain(ExplainFormat::Json, false, Some(&analyze))).unwrap();
+    assert_eq!(json["node"], "Query");
+    assert_eq!(json["collection"], "books");
+    assert_eq!(json["plan"][0]["node"], "Filter");
+    assert_eq!(json["plan"][0]["expr"], "author = 'Tolkien'");
+    assert_eq!(json["plan"][3]["k"], 5);
+    assert_eq!(json["analyze"]["matched_count"], 42);
+    assert_eq!(json["analyze"]["rows"], 5);
+}
+
+#[test]
+fn explain_renders_catalog_join() {
+    let stmt = convert(
+        "SELECT c.relname, n.nspname AS namespace FROM pg_class c \
+         LEFT JOIN pg_namespace n ON n.oid = c.relnamespace \
+         WHERE n.nspname <> 'pg_catalog' ORDER BY 1",
+    )
+    .unwrap();
+
+    assert_eq!(
+        stmt.explain(ExplainFormat::Text, false, None),
+        "Catalog scan on pg_catalog.pg_class c\n\
+         \x20 -> Join: LEFT JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace\n\
+         \x20 -> Filter: n.nspname <> 'pg_catalog'\n\
+         \x20 -> Select: c.relname AS relname, n.nspname AS namespace\n\
+         \x20 -> Sort: c.relname ASC\n"
+    );
+}
+
+#[rstest]
+#[case::analyze_write(
+    "EXPLAIN ANALYZE DELETE FROM books WHERE _id = 'hobbit'",
+    "only supported for SELECT"
+)]
+#[case::unknown_option("EXPLAIN (GENERIC_PLAN) SELECT _id FROM books", "GENERIC_PLAN")]
+#[case::yaml("EXPLAIN (FORMAT YAML) SELECT _id FROM books", "TEXT | JSON")]
+fn explain_rejected(#[case] sql: &str, #[case] message: &str) {
+    let err = convert(sql).unwrap_err();
+    assert!(err.to_string().contains(message), "{err}");
+}
@@ -1,5 +1,7 @@
 use rstest::rstest;
+use topk_rs::proto::v1::control::{Collection, FieldSpec};
 use topk_rs::proto::v1::data::{Document, value};
+use topk_sql::{Catalog, CatalogExpr, CatalogTable};
 
 mod common;
 use common::{BooksContext, Scope};
@@ -62,3 +64,110 @@ fn strings(docs: &[Document], field: &str) -> Vec<String> {
         })
         .collect()
 }
+
+fn catalog_query(sql: &str) -> topk_sql::CatalogQuery {
+    let mut stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql).unwrap()).unwrap();
+    let (stmt, _) = stmts.pop().expect("expected one statement");
+
+    let topk_sql::Statement::Catalog { query } = stmt else {
+        panic!("expected a catalog statement, got {stmt:?}");
+    };
+    query
+}
+
+#[test]
+fn catalog_select_lowers_to_catalog_query() {
+    let query = catalog_query(
+        "SELECT c.column_name AS name, data_type FROM information_schema.columns c \
+         WHERE c.table_name = 'books' AND column_name NOT LIKE '\\_%' \
+         ORDER BY ordinal_position DESC LIMIT 10",
+    );
+
+    assert_eq!(query.from.len(), 1);
+    assert_eq!(query.from[0].table, CatalogTable::Columns);
+    assert_eq!(query.from[0].alias, "c");
+    assert_eq!(
+        query.projection,
+        [
+            ("name".to_string(), column("c.column_name")),
+            ("data_type".to_string(), column("c.data_type")),
+        ]
+    );
+    assert_eq!(query.filters.len(), 2);
+    assert_eq!(query.order_by, [(column("c.ordinal_position"), false)]);
+    assert_eq!(query.limit, Some(10));
+}
+
+/// The query run by psql's `\d`.
+#[test]
+fn psql_list_relations() {
+    let query = catalog_query(
+        r#"SELECT n.nspname as "Schema",
+  c.relname as "Name",
+  CASE c.relkind WHEN 'r' THEN 'table' WHEN 'v' THEN 'view' WHEN 'm' THEN 'materialized view' WHEN 'i' THEN 'index' WHEN 'S' THEN 'sequence' WHEN 't' THEN 'TOAST table' WHEN 'f' THEN 'foreign table' WHEN 'p' THEN 'partitioned table' WHEN 'I' THEN 'partitioned index' END as "Type",
+  pg_catalog.pg_get_userbyid(c.relowner) as "Owner"
+FROM pg_catalog.pg_class c
+     LEFT JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+WHERE c.relkind IN ('r','p','v','m','S','f','')
+      AND n.nspname <> 'pg_catalog'
+      AND n.nspname !~ '^pg_toast'
+      AND n.nspname <> 'information_schema'
+  AND pg_catalog.pg_table_is_visible(c.oid)
+ORDER BY 1,2;"#,
+    );
+
+    assert_eq!(
+        query.from[1].on,
+        [("n.oid".to_string(), "c.relnamespace".to_string())]
+    );
+    assert!(query.from[1].left);
+
+    let catalog = Catalog::new(["books", "authors"].map(|name| Collection {
+        name: name.to_string(),
+        schema: [("title".to_string(), FieldSpec::text(true))].into(),
+        ..Default::default()
+    }));
+    let rows = query.execute(&catalog);
+
+    let rows = rows
+        .iter()
+        .map(|row| {
+            ["Schema", "Name", "Type", "Owner"]
+                .map(|column| row.fields[column].as_string().unwrap())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            ["public", "authors", "table", "topk"],
+            ["public", "books", "table", "topk"],
+        ]
+    );
+}
+
+fn column(name: &str) -> CatalogExpr {
+    CatalogExpr::Column(name.to_string())
+}
+
+#[rstest]
+#[case::unknown_column("SELECT relname, bogus FROM pg_class", "column `bogus` does not exist")]
+#[case::non_equi_join(
+    "SELECT a.attname FROM pg_attribute a JOIN pg_class c ON a.attrelid > c.oid",
+    "only equi-joins"
+)]
+#[case::ambiguous_column(
+    "SELECT oid FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace",
+    "column reference `oid` is ambiguous"
+)]
+#[case::join_collection(
+    "SELECT c.relname FROM pg_class c JOIN books b ON b._id = c.relname",
+    "JOIN of catalog tables with books"
+)]
+#[case::group_by(
+    "SELECT table_name FROM information_schema.columns GROUP BY table_name",
+    "GROUP BY"
+)]
+fn catalog_select_rejected(#[case] sql: &str, #[case] message: &str) {
+    let err = topk_sql::convert_sql(topk_sql::parse_sql(sql).unwrap()).unwrap_err();
+    assert!(err.to_string().contains(message), "{err}");
+}
@@ -598,7 +598,10 @@ fn order_by_multiple_keys_lowers_to_multi_expr_sort() {
         sort.exprs[0].order,
         stage::sort_stage::SortOrder::Desc as i32
     );
-    assert_eq!(sort.exprs[1].order, stage::sort_stage::SortOrder::Asc as i32);
+    assert_eq!(
+        sort.exprs[1].order,
+        stage::sort_stage::SortOrder::Asc as i32
+    );
 }
 
 #[rstest]
@@ -982,6 +985,162 @@ async fn semantic_similarity_search() {
     }
 }
 
+#[test]
+fn rrf_lowers_to_one_query_per_score() {
+    let sql = "SELECT _id, title, rrf(bm25_score(), semantic_similarity(bio, 'quest'), k => 20) AS score \
+               FROM books WHERE match('rings', title) ORDER BY score DESC LIMIT 5 OFFSET 2";
+    let mut stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql).unwrap()).unwrap();
+    let (stmt, _) = stmts.pop().expect("expected one statement");
+
+    let topk_sql::Statement::Fuse {
+        queries,
+        fusion,
+        alias,
+        offset,
+        limit,
+        ..
+    } = stmt
+    else {
+        panic!("expected a fuse statement, got {stmt:?}");
+    };
+
+    assert_eq!(fusion, topk_sql::Fusion::Rrf { k: 20.0 });
+    assert_eq!((alias.as_str(), offset, limit), ("score", 2, 5));
+    assert_eq!(queries.len(), 2);
+    for query in &queries {
+        let kinds = query
+            .stages
+            .iter()
+            .map(|s| match s.stage.as_ref().unwrap() {
+                stage::Stage::Filter(_) => "filter",
+                stage::Stage::Select(_) => "select",
+                stage::Stage::Sort(_) => "sort",
+                stage::Stage::Limit(_) => "limit",
+                other => panic!("unexpected stage {other:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, ["filter", "select", "sort", "limit"]);
+
+        let Some(stage::Stage::Select(select)) = query.stages[1].stage.as_ref() else {
+            panic!("expected a select stage");
+        };
+        assert!(select.exprs.contains_key("title"));
+        assert!(select.exprs.contains_key(topk_sql::FUSE_SCORE));
+        assert!(!select.exprs.contains_key("score"));
+    }
+}
+
+#[test]
+fn weighted_normalize_lowers_to_weighted_fusion() {
+    let sql = "SELECT _id, 0.3 * normalize(bm25_score(), 'minmax') \
+               + 0.7 * normalize(vector_distance(embedding, f32_vector(ARRAY[1, 0, 0, 0])), 'zscore') AS s \
+               FROM books WHERE match('rings') LIMIT 3";
+    let mut stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql).unwrap()).unwrap();
+    let (stmt, _) = stmts.pop().expect("expected one statement");
+
+    let topk_sql::Statement::Fuse {
+        queries, fusion, ..
+    } = stmt
+    else {
+        panic!("expected a fuse statement, got {stmt:?}");
+    };
+
+    assert_eq!(queries.len(), 2);
+    assert_eq!(
+        fusion,
+        topk_sql::Fusion::Weighted(vec![
+            (0.3, topk_sql::Normalization::MinMax),
+            (0.7, topk_sql::Normalization::ZScore),
+        ])
+    );
+}
+
+#[test]
+fn rrf_ranks_euclidean_distance_ascending() {
+    use topk_rs::proto::v1::control::{FieldIndex, FieldSpec, VectorDistanceMetric};
+    use topk_rs::proto::v1::data::stage::sort_stage::SortOrder;
+
+    let sql = "SELECT _id, rrf(vector_distance(embedding, f32_vector(ARRAY[1, 0])), bm25_score()) AS s \
+               FROM books WHERE match('rings') ORDER BY s DESC LIMIT 3";
+    let mut stmts = topk_sql::convert_sql(topk_sql::parse_sql(sql).unwrap()).unwrap();
+    let (stmt, _) = stmts.pop().expect("expected one statement");
+    let topk_sql::Statement::Fuse {
+        mut queries,
+        fusion,
+        alias,
+        ..
+    } = stmt
+    else {
+        panic!("expected a fuse statement, got {stmt:?}");
+    };
+
+    let schema = [
+        (
+            "embedding".to_string(),
+            FieldSpec::f32_vector(2, true)
+                .with_index(FieldIndex::vector(VectorDistanceMetric::Euclidean)),
+        ),
+        ("title".to_string(), FieldSpec::text(true)),
+    ]
+    .into_iter()
+    .collect();
+    let orders = topk_sql::resolve_score_orders(&mut queries, &schema);
+    assert_eq!(orders, [SortOrder::Asc, SortOrder::Desc]);
+
+    for (query, order) in queries.iter().zip(&orders) {
+        let Some(stage::Stage::Sort(sort)) = query.stages[2].stage.as_ref() else {
+            panic!("expected a sort stage");
+        };
+        assert_eq!(sort.exprs[0].order, *order as i32);
+    }
+
+    // "near" is closest to the query vector and ranked second by BM25, "far" is ranked first
+    // by BM25 but is the farthest
+    let scored = |docs: &[(&str, f32)]| -> Vec<Document> {
+        docs.iter()
+            .map(|(id, score)| doc!("_id" => *id, topk_sql::FUSE_SCORE => *score))
+            .collect()
+    };
+    let fused = fusion
+        .fuse(
+            vec![
+                scored(&[("near", 0.1), ("mid", 0.5), ("far", 3.0)]),
+                scored(&[("far", 9.0), ("near", 8.0), ("mid", 1.0)]),
+            ],
+            &orders,
+            &alias,
+        )
+        .unwrap();
+    let ranking: Vec<_> = fused.iter().map(|doc| doc.id().unwrap()).collect();
+    assert_eq!(ranking, ["near", "far", "mid"]);
+}
+
+#[rstest]
+#[case::without_limit(
+    "SELECT rrf(bm25_score()) AS s FROM books WHERE match('rings')",
+    "Invalid: rank fusion requires a LIMIT"
+)]
+#[case::order_by_other(
+    "SELECT rrf(bm25_score()) AS s FROM books WHERE match('rings') ORDER BY rating DESC LIMIT 3",
+    "Unsupported: ORDER BY in a rank fusion query must be `s DESC`"
+)]
+#[case::multiple(
+    "SELECT rrf(bm25_score()) AS a, rrf(rating) AS b FROM books LIMIT 3",
+    "Unsupported: multiple rank fusion items in SELECT"
+)]
+#[case::in_where(
+    "SELECT _id FROM books WHERE rrf(rating) > 0 LIMIT 3",
+    "Unsupported: `rrf` is a rank fusion function \u{2014} only valid in a SELECT projection item (e.g. `SELECT rrf(bm25_score(), \u{2026}) AS s FROM c ORDER BY s DESC LIMIT k`)"
+)]
+#[case::group_by(
+    "SELECT genre, rrf(rating) AS s, COUNT(*) AS c FROM books GROUP BY genre",
+    "Unsupported: rank fusion functions with GROUP BY"
+)]
+fn rank_fusion_rejected(#[case] sql: &str, #[case] expected: &str) {
+    let err = topk_sql::convert_sql(topk_sql::parse_sql(sql).unwrap()).unwrap_err();
+    assert_eq!(err.to_string(), expected);
+}
+
 #[rstest]
 #[case::select_star("SELECT * FROM {{table}}", "Unsupported: SELECT *")]
 #[case::missing_alias(